@@ -12,8 +12,8 @@ use anyhow::bail;
 use anyhow::ensure;
 use holochain_conductor_api::AdminRequest;
 use holochain_conductor_api::AdminResponse;
+use holochain_conductor_api::AllowedOrigins;
 use holochain_conductor_api::AppStatusFilter;
-use holochain_conductor_api::InterfaceDriver;
 use holochain_conductor_api::{AdminInterfaceConfig, InstalledAppInfo};
 use holochain_p2p::kitsune_p2p::agent_store::AgentInfoSigned;
 use holochain_types::prelude::DnaHash;
@@ -231,6 +231,22 @@ pub async fn call(holochain_path: &Path, req: Call) -> anyhow::Result<()> {
         running,
         call,
     } = req;
+    let cmds = connect(holochain_path, running, existing).await?;
+    for mut cmd in cmds {
+        call_inner(&mut cmd.0, call.clone()).await?;
+    }
+    Ok(())
+}
+
+/// Connect to the admin interfaces of the `running` ports or, if there are none,
+/// of the existing sandboxes, running any conductors that are not already running.
+/// The handles of any conductors that were started must be kept
+/// until the calls are done.
+pub(crate) async fn connect(
+    holochain_path: &Path,
+    running: Vec<u16>,
+    existing: Existing,
+) -> anyhow::Result<Vec<(CmdRunner, Option<tokio::process::Child>)>> {
     let cmds = if running.is_empty() {
         let paths = if existing.is_empty() {
             crate::save::load(std::env::current_dir()?)?
@@ -267,10 +283,7 @@ pub async fn call(holochain_path: &Path, req: Call) -> anyhow::Result<()> {
         }
         cmds
     };
-    for mut cmd in cmds {
-        call_inner(&mut cmd.0, call.clone()).await?;
-    }
-    Ok(())
+    Ok(cmds)
 }
 
 async fn call_inner(cmd: &mut CmdRunner, call: AdminRequestCli) -> anyhow::Result<()> {
@@ -398,9 +411,7 @@ pub async fn add_admin_interface(cmd: &mut CmdRunner, args: AddAdminWs) -> anyho
     let port = args.port.unwrap_or(0);
     let resp = cmd
         .command(AdminRequest::AddAdminInterfaces(vec![
-            AdminInterfaceConfig {
-                driver: InterfaceDriver::Websocket { port },
-            },
+            AdminInterfaceConfig::websocket(port),
         ]))
         .await?;
     ensure!(
@@ -615,7 +626,11 @@ pub async fn disable_app(cmd: &mut CmdRunner, args: DisableApp) -> anyhow::Resul
 /// Calls [`AdminRequest::AttachAppInterface`] and adds another app interface.
 pub async fn attach_app_interface(cmd: &mut CmdRunner, args: AddAppWs) -> anyhow::Result<u16> {
     let resp = cmd
-        .command(AdminRequest::AttachAppInterface { port: args.port })
+        .command(AdminRequest::AttachAppInterface {
+            port: args.port,
+            allowed_origins: AllowedOrigins::Any,
+            require_authentication: false,
+        })
         .await?;
     tracing::debug!(?resp);
     match resp {
@@ -68,6 +68,12 @@ pub enum HcSandboxSubcommand {
     /// Make a call to a conductor's admin interface.
     Call(crate::calls::Call),
 
+    /// Call a zome function in an installed app and print the response as JSON.
+    ///
+    /// The payload is given as JSON or YAML and the call is made
+    /// by the agent of the cell with the given role.
+    ZomeCall(crate::zome_call::ZomeCall),
+
     /// List sandboxes found in `$(pwd)/.hc`.
     List {
         /// Show more verbose information.
@@ -149,6 +155,9 @@ impl HcSandbox {
             HcSandboxSubcommand::Call(call) => {
                 crate::calls::call(&self.holochain_path, call).await?
             }
+            HcSandboxSubcommand::ZomeCall(zome_call) => {
+                crate::zome_call::zome_call(&self.holochain_path, zome_call).await?
+            }
             // HcSandboxSubcommand::Task => todo!("Running custom tasks is coming soon"),
             HcSandboxSubcommand::List { verbose } => {
                 crate::save::list(std::env::current_dir()?, verbose)?
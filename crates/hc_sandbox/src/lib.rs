@@ -73,6 +73,15 @@
 //! ```shell
 //! hc sandbox call list-cells
 //! ```
+//! #### Zome call
+//! Calls a zome function in an installed app through the conductor's
+//! app interface, attaching one if there is none.
+//! The payload is given as JSON or YAML and the response is printed as JSON.
+//! The call is made as the cell's own agent.
+//!
+//! ```shell
+//! hc sandbox zome-call my-app my-role my_zome my_fn --payload '{"content": "hello"}'
+//! ```
 //! #### List and Clean
 //! These commands allow you to list the persisted sandboxes
 //! in the current directory (from the`.hcXXX`) file.
@@ -140,6 +149,7 @@ pub mod generate;
 pub mod run;
 pub mod sandbox;
 pub mod save;
+pub mod zome_call;
 pub use cli::HcSandbox;
 
 mod ports;
@@ -33,6 +33,7 @@ pub async fn get_admin_ports(paths: Vec<PathBuf>) -> anyhow::Result<Vec<u16>> {
             if let Some(ai) = config.admin_interfaces {
                 if let Some(AdminInterfaceConfig {
                     driver: InterfaceDriver::Websocket { port },
+                    ..
                 }) = ai.get(0)
                 {
                     ports.push(*port)
@@ -48,6 +49,11 @@ pub(crate) async fn get_admin_api(port: u16) -> WebsocketResult<WebsocketSender>
     websocket_client_by_port(port).await.map(|p| p.0)
 }
 
+pub(crate) async fn get_app_api(port: u16) -> WebsocketResult<WebsocketSender> {
+    tracing::debug!(port);
+    websocket_client_by_port(port).await.map(|p| p.0)
+}
+
 async fn websocket_client_by_port(
     port: u16,
 ) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
@@ -62,6 +68,7 @@ pub(crate) fn random_admin_port(config: &mut ConductorConfig) {
     match config.admin_interfaces.as_mut().and_then(|i| i.first_mut()) {
         Some(AdminInterfaceConfig {
             driver: InterfaceDriver::Websocket { port },
+            ..
         }) => {
             if *port != 0 {
                 *port = 0;
@@ -69,27 +76,21 @@ pub(crate) fn random_admin_port(config: &mut ConductorConfig) {
         }
         None => {
             let port = 0;
-            config.admin_interfaces = Some(vec![AdminInterfaceConfig {
-                driver: InterfaceDriver::Websocket { port },
-            }]);
+            config.admin_interfaces = Some(vec![AdminInterfaceConfig::websocket(port)]);
         }
     }
 }
 
 pub(crate) fn set_admin_port(config: &mut ConductorConfig, port: u16) {
-    let p = port;
-    let port = AdminInterfaceConfig {
-        driver: InterfaceDriver::Websocket { port },
-    };
     match config
         .admin_interfaces
         .as_mut()
         .and_then(|ai| ai.get_mut(0))
     {
         Some(admin_interface) => {
-            *admin_interface = port;
+            admin_interface.driver = InterfaceDriver::Websocket { port };
         }
-        None => config.admin_interfaces = Some(vec![port]),
+        None => config.admin_interfaces = Some(vec![AdminInterfaceConfig::websocket(port)]),
     }
-    msg!("Admin port set to: {}", p);
+    msg!("Admin port set to: {}", port);
 }
@@ -0,0 +1,232 @@
+//! Helpers for calling zome functions through a conductor's app interface.
+//!
+//! The cell to call is found by its app and role through the admin interface.
+//! An app interface is attached if the conductor doesn't have one yet.
+use std::path::Path;
+
+use anyhow::anyhow;
+use anyhow::bail;
+use anyhow::Context;
+use holochain_conductor_api::AdminRequest;
+use holochain_conductor_api::AdminResponse;
+use holochain_conductor_api::AppRequest;
+use holochain_conductor_api::AppResponse;
+use holochain_conductor_api::IssueAppAuthenticationTokenPayload;
+use holochain_types::prelude::AppRoleId;
+use holochain_types::prelude::CellId;
+use holochain_types::prelude::ExternIO;
+use holochain_types::prelude::FunctionName;
+use holochain_types::prelude::InstalledAppId;
+use holochain_types::prelude::ZomeName;
+use holochain_websocket::WebsocketSender;
+use structopt::StructOpt;
+
+use crate::calls::attach_app_interface;
+use crate::calls::list_app_ws;
+use crate::calls::list_apps;
+use crate::calls::AddAppWs;
+use crate::calls::ListApps;
+use crate::cmds::Existing;
+use crate::expect_match;
+use crate::ports::get_app_api;
+use crate::CmdRunner;
+
+#[doc(hidden)]
+#[derive(Debug, StructOpt)]
+pub struct ZomeCall {
+    /// The InstalledAppId of the app containing the cell.
+    pub app_id: InstalledAppId,
+    /// The AppRoleId of the cell within the app.
+    pub role_id: AppRoleId,
+    /// The zome containing the function.
+    pub zome_name: String,
+    /// The name of the function to call.
+    pub fn_name: String,
+    #[structopt(short, long, default_value = "null")]
+    /// The input to the function as JSON or YAML.
+    /// For example `--payload '{"content": "hello"}'`.
+    pub payload: String,
+    #[structopt(short, long, conflicts_with_all = &["existing_paths", "indices"], value_delimiter = ",")]
+    /// Ports to running conductor admin interfaces.
+    /// If this is empty existing sandboxes will be used.
+    /// Cannot be combined with existing sandboxes.
+    pub running: Vec<u16>,
+    #[structopt(flatten)]
+    pub existing: Existing,
+}
+
+#[doc(hidden)]
+pub async fn zome_call(holochain_path: &Path, args: ZomeCall) -> anyhow::Result<()> {
+    let ZomeCall {
+        app_id,
+        role_id,
+        zome_name,
+        fn_name,
+        payload,
+        running,
+        existing,
+    } = args;
+    let payload = parse_payload(&payload)?;
+    let cmds = crate::calls::connect(holochain_path, running, existing).await?;
+    for mut cmd in cmds {
+        let response = call_zome(
+            &mut cmd.0,
+            &app_id,
+            &role_id,
+            zome_name.clone().into(),
+            fn_name.clone().into(),
+            payload.clone(),
+        )
+        .await?;
+        println!("{}", extern_io_to_json(&response)?);
+    }
+    Ok(())
+}
+
+/// Call a zome function in the cell with the given role in an installed app.
+///
+/// The call is made over the first app interface of the conductor,
+/// which is attached if there is none, and the connection is authenticated
+/// with a token issued for the app. The cell's own agent is the provenance
+/// of the call so it is authorized by the author grant.
+pub async fn call_zome(
+    cmd: &mut CmdRunner,
+    app_id: &InstalledAppId,
+    role_id: &AppRoleId,
+    zome_name: ZomeName,
+    fn_name: FunctionName,
+    payload: ExternIO,
+) -> anyhow::Result<ExternIO> {
+    let cell_id = find_cell(cmd, app_id, role_id).await?;
+
+    let port = match list_app_ws(cmd).await?.first() {
+        Some(port) => *port,
+        None => attach_app_interface(cmd, AddAppWs { port: None }).await?,
+    };
+    let mut app = get_app_api(port).await?;
+    authenticate(cmd, &mut app, app_id).await?;
+
+    let provenance = cell_id.agent_pubkey().clone();
+    let call = holochain_conductor_api::ZomeCall {
+        cell_id,
+        zome_name,
+        fn_name,
+        payload,
+        cap_secret: None,
+        provenance,
+    };
+    let response: AppResponse = app.request(AppRequest::ZomeCall(Box::new(call))).await?;
+    match response {
+        AppResponse::ZomeCall(output) => Ok(*output),
+        AppResponse::Error(error) => bail!("Zome call failed: {:?}", error),
+        _ => bail!("Zome call failed, got: {:?}", response),
+    }
+}
+
+/// Find the cell with the given role in an installed app.
+async fn find_cell(
+    cmd: &mut CmdRunner,
+    app_id: &InstalledAppId,
+    role_id: &AppRoleId,
+) -> anyhow::Result<CellId> {
+    let apps = list_apps(cmd, ListApps { status: None }).await?;
+    let app = apps
+        .into_iter()
+        .find(|app| app.installed_app_id == *app_id)
+        .ok_or_else(|| anyhow!("App {} is not installed", app_id))?;
+    app.cell_data
+        .into_iter()
+        .find(|cell| cell.as_role_id() == role_id)
+        .map(|cell| cell.into_id())
+        .ok_or_else(|| anyhow!("App {} has no cell with role {}", app_id, role_id))
+}
+
+/// Authenticate an app interface connection with a token issued for the app.
+/// This is harmless for interfaces which don't require authentication.
+async fn authenticate(
+    cmd: &mut CmdRunner,
+    app: &mut WebsocketSender,
+    app_id: &InstalledAppId,
+) -> anyhow::Result<()> {
+    let resp = cmd
+        .command(AdminRequest::IssueAppAuthenticationToken(
+            IssueAppAuthenticationTokenPayload::for_app(app_id.clone()),
+        ))
+        .await?;
+    let issued = expect_match!(resp => AdminResponse::AppAuthenticationTokenIssued, "Failed to issue app authentication token");
+    let response: AppResponse = app
+        .request(AppRequest::Authenticate {
+            token: issued.token,
+        })
+        .await?;
+    match response {
+        AppResponse::Authenticated { .. } => Ok(()),
+        _ => bail!("Failed to authenticate app interface, got: {:?}", response),
+    }
+}
+
+/// Parse a JSON or YAML payload into the msgpack input of a zome function.
+pub fn parse_payload(payload: &str) -> anyhow::Result<ExternIO> {
+    // JSON is a subset of YAML so both can be parsed the same way.
+    let value: serde_yaml::Value =
+        serde_yaml::from_str(payload).context("Failed to parse the payload as JSON or YAML")?;
+    Ok(ExternIO::encode(value)?)
+}
+
+/// Decode the msgpack output of a zome function into pretty printed JSON.
+/// Binary data, such as hashes, is shown as an array of bytes.
+pub fn extern_io_to_json(output: &ExternIO) -> anyhow::Result<String> {
+    let mut json = Vec::new();
+    let mut deserializer = rmp_serde::Deserializer::new(output.as_bytes());
+    let mut serializer = serde_json::Serializer::pretty(&mut json);
+    serde_transcode::transcode(&mut deserializer, &mut serializer)?;
+    Ok(String::from_utf8(json)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holochain_types::prelude::ActionHash;
+    use serde_json::json;
+
+    #[test]
+    fn parses_json_and_yaml_payloads() {
+        let expected = json!({ "content": "hello", "count": 3, "tags": ["a", "b"] });
+
+        let payload =
+            parse_payload(r#"{"content": "hello", "count": 3, "tags": ["a", "b"]}"#).unwrap();
+        assert_eq!(payload.decode::<serde_json::Value>().unwrap(), expected);
+
+        let payload = parse_payload("content: hello\ncount: 3\ntags:\n  - a\n  - b").unwrap();
+        assert_eq!(payload.decode::<serde_json::Value>().unwrap(), expected);
+
+        // - The default payload is the unit input of functions without arguments.
+        let payload = parse_payload("null").unwrap();
+        payload.decode::<()>().unwrap();
+        assert_eq!(payload, ExternIO::encode(()).unwrap());
+
+        assert!(parse_payload("{").is_err());
+    }
+
+    #[test]
+    fn outputs_are_printed_as_json() {
+        let output = ExternIO::encode(json!({ "content": "hello", "count": 3 })).unwrap();
+        assert_eq!(
+            extern_io_to_json(&output).unwrap(),
+            "{\n  \"content\": \"hello\",\n  \"count\": 3\n}"
+        );
+
+        assert_eq!(
+            extern_io_to_json(&ExternIO::encode(()).unwrap()).unwrap(),
+            "null"
+        );
+
+        // - Hashes and other binary data are shown as arrays of bytes.
+        let hash = ActionHash::from_raw_36(vec![7; 36]);
+        let output = ExternIO::encode(&hash).unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&extern_io_to_json(&output).unwrap()).unwrap();
+        let bytes: Vec<u8> = serde_json::from_value(json).unwrap();
+        assert_eq!(bytes, hash.get_raw_39());
+    }
+}
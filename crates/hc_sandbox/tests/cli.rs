@@ -94,3 +94,24 @@ async fn run_multiple_on_same_port() {
     let _hc_admin2 = cmd.spawn().expect("Failed to spawn holochain");
     tokio::time::sleep(std::time::Duration::from_secs(4)).await;
 }
+
+/// The payload of a zome call is parsed before connecting to any conductor
+#[tokio::test(flavor = "multi_thread")]
+async fn zome_call_rejects_invalid_payload() {
+    let port: u16 = pick_unused_port().expect("No ports free");
+    let cmd = std::process::Command::cargo_bin("hc-sandbox").unwrap();
+    let mut cmd = Command::from(cmd);
+    cmd.arg("zome-call")
+        .arg(format!("--running={}", port))
+        .arg("--payload={")
+        .args(&["app", "role", "zome", "fn_name"])
+        .kill_on_drop(true);
+    let output = cmd.output().await.expect("Failed to run hc-sandbox");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Failed to parse the payload as JSON or YAML"),
+        "unexpected error: {}",
+        stderr
+    );
+}
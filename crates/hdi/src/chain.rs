@@ -0,0 +1,35 @@
+use crate::prelude::*;
+
+/// MUST get a range of an agent's source chain, walking back from the
+/// `chain_top` of the [`ChainFilter`].
+///
+/// The range is linked by action hashes, so it can't contain a fork of the
+/// chain and every agent that can get the range will get exactly the same
+/// actions. The actions are returned from the chain top down.
+///
+/// The filter can stop the range early:
+///
+/// - [`ChainFilter::take`] returns at most this many actions
+/// - [`ChainFilter::until`] stops at (and includes) one of the given actions
+/// - If neither is given the range goes all the way to the genesis action
+///
+/// The actions are NOT guaranteed to be valid, only to exist and be linked.
+///
+/// `must_get_agent_activity` is available in contexts such as validation where both determinism and network access is desirable.
+/// Note that during validation only the data already held locally is used, so if the range
+/// isn't complete the op will wait for it to arrive. Validating the rules of an agent's chain is
+/// best done on the `RegisterAgentActivity` ops, where the authority holds the agent's chain.
+///
+/// If the range fails to be returned:
+///
+/// - Callbacks will return early with `UnresolvedDependencies`
+/// - Zome calls will receive a `WasmError` from the host
+pub fn must_get_agent_activity(
+    author: AgentPubKey,
+    chain_filter: ChainFilter,
+) -> ExternResult<Vec<SignedActionHashed>> {
+    HDI.with(|h| {
+        h.borrow()
+            .must_get_agent_activity(MustGetAgentActivityInput::new(author, chain_filter))
+    })
+}
@@ -32,6 +32,10 @@ pub trait HdiT: Send + Sync {
         &self,
         must_get_valid_record_input: MustGetValidRecordInput,
     ) -> ExternResult<Record>;
+    fn must_get_agent_activity(
+        &self,
+        must_get_agent_activity_input: MustGetAgentActivityInput,
+    ) -> ExternResult<Vec<SignedActionHashed>>;
     // Info
     fn dna_info(&self, dna_info_input: ()) -> ExternResult<DnaInfo>;
     fn zome_info(&self, zome_info_input: ()) -> ExternResult<ZomeInfo>;
@@ -77,6 +81,12 @@ impl HdiT for ErrHdi {
     fn must_get_valid_record(&self, _: MustGetValidRecordInput) -> ExternResult<Record> {
         Self::err()
     }
+    fn must_get_agent_activity(
+        &self,
+        _: MustGetAgentActivityInput,
+    ) -> ExternResult<Vec<SignedActionHashed>> {
+        Self::err()
+    }
     fn dna_info(&self, _: ()) -> ExternResult<DnaInfo> {
         Self::err()
     }
@@ -143,6 +153,15 @@ impl HdiT for HostHdi {
             must_get_valid_record_input,
         )
     }
+    fn must_get_agent_activity(
+        &self,
+        must_get_agent_activity_input: MustGetAgentActivityInput,
+    ) -> ExternResult<Vec<SignedActionHashed>> {
+        host_call::<MustGetAgentActivityInput, Vec<SignedActionHashed>>(
+            __must_get_agent_activity,
+            must_get_agent_activity_input,
+        )
+    }
     fn dna_info(&self, _: ()) -> ExternResult<DnaInfo> {
         host_call::<(), DnaInfo>(__dna_info, ())
     }
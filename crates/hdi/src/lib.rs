@@ -88,6 +88,13 @@ pub use hdk_derive::hdk_link_types;
 /// For example, an agent could choose to 'block' another agent and ignore all their updates.
 pub mod entry;
 
+/// Deterministically read ranges of agents' source chains.
+///
+/// A range is bounded by action hashes rather than sequence numbers or timestamps, so it
+/// identifies exactly one set of actions and can be safely used in validation to check rules
+/// that depend on an agent's history, e.g. "no more than N posts per day".
+pub mod chain;
+
 pub mod hash;
 
 /// Maps a Rust function to an extern that WASM can expose to the Holochain host.
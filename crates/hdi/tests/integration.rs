@@ -434,6 +434,13 @@ fn set_zome_types(entries: &[(u8, u8)], links: &[(u8, u8)]) {
             todo!()
         }
 
+        fn must_get_agent_activity(
+            &self,
+            must_get_agent_activity_input: MustGetAgentActivityInput,
+        ) -> ExternResult<Vec<SignedActionHashed>> {
+            todo!()
+        }
+
         fn dna_info(&self, dna_info_input: ()) -> ExternResult<DnaInfo> {
             todo!()
         }
@@ -198,3 +198,46 @@ pub fn update_cap_grant(
         chain_top_ordering: ChainTopOrdering::default(),
     })
 }
+
+/// List the capability grants on the local source chain which pass the query.
+///
+/// Only grants which haven't been updated or deleted are returned, in the
+/// order they were committed. Grants committed earlier in the same zome
+/// call are not included.
+///
+/// An empty [`CapGrantQuery`] returns every live grant. The returned
+/// [`CapGrantInfo`] includes the action hash of each grant, which can be
+/// passed to [`update_cap_grant`] or [`delete_cap_grant`] to change or
+/// revoke access.
+///
+/// ```ignore
+/// // Every grant giving access to `get_post`.
+/// let grants = capability_grants(CapGrantQuery {
+///     function: Some((zome_info()?.name, "get_post".into())),
+///     ..Default::default()
+/// })?;
+/// ```
+pub fn capability_grants(query: CapGrantQuery) -> ExternResult<Vec<CapGrantInfo>> {
+    HDK.with(|h| h.borrow().capability_grants(query))
+}
+
+/// List the capability claims on the local source chain which pass the query.
+///
+/// Only claims which haven't been updated or deleted are returned, in the
+/// order they were committed. Claims committed earlier in the same zome
+/// call are not included.
+///
+/// There is no guarantee that any returned claim is still honoured by its grantor.
+/// See [`create_cap_claim`].
+pub fn capability_claims(query: CapClaimQuery) -> ExternResult<Vec<CapClaimInfo>> {
+    HDK.with(|h| h.borrow().capability_claims(query))
+}
+
+/// Get the capability that authorized the current zome call.
+///
+/// The [`CapInfo`] contains the agent that made the call and the grant that
+/// allowed it. Calls made by the chain author are covered by the implicit
+/// [`CapGrant::ChainAuthor`] grant which has no action hash.
+pub fn capability_info() -> ExternResult<CapInfo> {
+    HDK.with(|h| h.borrow().capability_info(()))
+}
@@ -1,5 +1,7 @@
 use crate::prelude::*;
 
+pub use hdi::chain::*;
+
 /// Query the _actions_ of a remote agent's chain.
 ///
 /// The agent activity is only the actions of their source chain.
@@ -30,3 +32,16 @@ pub fn get_agent_activity(
 pub fn query(filter: ChainQueryFilter) -> ExternResult<Vec<Record>> {
     HDK.with(|h| h.borrow().query(filter))
 }
+
+/// Get the validation receipts received for each of the ops produced by an action.
+///
+/// Validators send a signed receipt back to the author once they have validated
+/// and integrated one of the author's ops, so the receipts show how many peers
+/// hold each op. Only receipts that have reached this agent's conductor are returned.
+///
+/// Ops which haven't received any receipts yet are returned with an empty list of receipts.
+pub fn get_validation_receipts(
+    input: GetValidationReceiptsInput,
+) -> ExternResult<Vec<ValidationReceiptSet>> {
+    HDK.with(|h| h.borrow().get_validation_receipts(input))
+}
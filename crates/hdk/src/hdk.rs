@@ -21,12 +21,20 @@ thread_local!(pub static HDK: RefCell<Rc<dyn HdkT>> = RefCell::new(Rc::new(HostH
 /// set_hdk(mock_hdk);
 /// ```
 pub trait HdkT: HdiT {
+    // Capability
+    fn capability_claims(&self, query: CapClaimQuery) -> ExternResult<Vec<CapClaimInfo>>;
+    fn capability_grants(&self, query: CapGrantQuery) -> ExternResult<Vec<CapGrantInfo>>;
+    fn capability_info(&self, capability_info_input: ()) -> ExternResult<CapInfo>;
     // Chain
     fn get_agent_activity(
         &self,
         get_agent_activity_input: GetAgentActivityInput,
     ) -> ExternResult<AgentActivity>;
     fn query(&self, filter: ChainQueryFilter) -> ExternResult<Vec<Record>>;
+    fn get_validation_receipts(
+        &self,
+        input: GetValidationReceiptsInput,
+    ) -> ExternResult<Vec<ValidationReceiptSet>>;
     // Ed25519
     fn sign(&self, sign: Sign) -> ExternResult<Signature>;
     fn sign_ephemeral(&self, sign_ephemeral: SignEphemeral) -> ExternResult<EphemeralSignatures>;
@@ -52,6 +60,7 @@ pub trait HdkT: HdiT {
         &self,
         get_links_input: Vec<GetLinksInput>,
     ) -> ExternResult<Vec<LinkDetails>>;
+    fn count_links(&self, query: LinkQuery) -> ExternResult<usize>;
     // P2P
     fn call(&self, call: Vec<Call>) -> ExternResult<Vec<ZomeCallResponse>>;
     fn emit_signal(&self, app_signal: AppSignal) -> ExternResult<()>;
@@ -91,12 +100,20 @@ mockall::mock! {
     pub HdkT {}
 
     impl HdkT for HdkT {
+        // Capability
+        fn capability_claims(&self, query: CapClaimQuery) -> ExternResult<Vec<CapClaimInfo>>;
+        fn capability_grants(&self, query: CapGrantQuery) -> ExternResult<Vec<CapGrantInfo>>;
+        fn capability_info(&self, capability_info_input: ()) -> ExternResult<CapInfo>;
         // Chain
         fn get_agent_activity(
             &self,
             get_agent_activity_input: GetAgentActivityInput,
         ) -> ExternResult<AgentActivity>;
         fn query(&self, filter: ChainQueryFilter) -> ExternResult<Vec<Record>>;
+        fn get_validation_receipts(
+            &self,
+            input: GetValidationReceiptsInput,
+        ) -> ExternResult<Vec<ValidationReceiptSet>>;
         // Ed25519
         fn sign(&self, sign: Sign) -> ExternResult<Signature>;
         fn sign_ephemeral(&self, sign_ephemeral: SignEphemeral) -> ExternResult<EphemeralSignatures>;
@@ -122,6 +139,7 @@ mockall::mock! {
             &self,
             get_links_input: Vec<GetLinksInput>,
         ) -> ExternResult<Vec<LinkDetails>>;
+        fn count_links(&self, query: LinkQuery) -> ExternResult<usize>;
         // P2P
         fn call(&self, call: Vec<Call>) -> ExternResult<Vec<ZomeCallResponse>>;
         fn emit_signal(&self, app_signal: AppSignal) -> ExternResult<()>;
@@ -169,6 +187,10 @@ mockall::mock! {
             &self,
             must_get_valid_record_input: MustGetValidRecordInput,
         ) -> ExternResult<Record>;
+        fn must_get_agent_activity(
+            &self,
+            must_get_agent_activity_input: MustGetAgentActivityInput,
+        ) -> ExternResult<Vec<SignedActionHashed>>;
         // Info
         fn dna_info(&self, dna_info_input: ()) -> ExternResult<DnaInfo>;
         fn zome_info(&self, zome_info_input: ()) -> ExternResult<ZomeInfo>;
@@ -230,6 +252,13 @@ impl HdiT for ErrHdk {
         Self::err()
     }
 
+    fn must_get_agent_activity(
+        &self,
+        _must_get_agent_activity_input: MustGetAgentActivityInput,
+    ) -> ExternResult<Vec<SignedActionHashed>> {
+        Self::err()
+    }
+
     fn dna_info(&self, _dna_info_input: ()) -> ExternResult<DnaInfo> {
         Self::err()
     }
@@ -259,12 +288,28 @@ impl HdiT for ErrHdk {
 
 /// Every call is an error for the ErrHdk.
 impl HdkT for ErrHdk {
+    // Capability
+    fn capability_claims(&self, _: CapClaimQuery) -> ExternResult<Vec<CapClaimInfo>> {
+        Self::err()
+    }
+    fn capability_grants(&self, _: CapGrantQuery) -> ExternResult<Vec<CapGrantInfo>> {
+        Self::err()
+    }
+    fn capability_info(&self, _: ()) -> ExternResult<CapInfo> {
+        Self::err()
+    }
     fn get_agent_activity(&self, _: GetAgentActivityInput) -> ExternResult<AgentActivity> {
         Self::err()
     }
     fn query(&self, _: ChainQueryFilter) -> ExternResult<Vec<Record>> {
         Self::err()
     }
+    fn get_validation_receipts(
+        &self,
+        _: GetValidationReceiptsInput,
+    ) -> ExternResult<Vec<ValidationReceiptSet>> {
+        Self::err()
+    }
     fn sign(&self, _: Sign) -> ExternResult<Signature> {
         Self::err()
     }
@@ -312,6 +357,9 @@ impl HdkT for ErrHdk {
     fn get_link_details(&self, _: Vec<GetLinksInput>) -> ExternResult<Vec<LinkDetails>> {
         Self::err()
     }
+    fn count_links(&self, _: LinkQuery) -> ExternResult<usize> {
+        Self::err()
+    }
     // P2P
     fn call(&self, _: Vec<Call>) -> ExternResult<Vec<ZomeCallResponse>> {
         Self::err()
@@ -409,6 +457,12 @@ impl HdiT for HostHdk {
     ) -> ExternResult<Record> {
         HostHdi::new().must_get_valid_record(must_get_valid_record_input)
     }
+    fn must_get_agent_activity(
+        &self,
+        must_get_agent_activity_input: MustGetAgentActivityInput,
+    ) -> ExternResult<Vec<SignedActionHashed>> {
+        HostHdi::new().must_get_agent_activity(must_get_agent_activity_input)
+    }
     fn dna_info(&self, _: ()) -> ExternResult<DnaInfo> {
         HostHdi::new().dna_info(())
     }
@@ -438,6 +492,15 @@ impl HdiT for HostHdk {
 /// Elsewhere in the hdk are more high level wrappers around this basic trait.
 #[cfg(all(not(feature = "mock"), target_arch = "wasm32"))]
 impl HdkT for HostHdk {
+    fn capability_claims(&self, query: CapClaimQuery) -> ExternResult<Vec<CapClaimInfo>> {
+        host_call::<CapClaimQuery, Vec<CapClaimInfo>>(__capability_claims, query)
+    }
+    fn capability_grants(&self, query: CapGrantQuery) -> ExternResult<Vec<CapGrantInfo>> {
+        host_call::<CapGrantQuery, Vec<CapGrantInfo>>(__capability_grants, query)
+    }
+    fn capability_info(&self, _: ()) -> ExternResult<CapInfo> {
+        host_call::<(), CapInfo>(__capability_info, ())
+    }
     fn get_agent_activity(
         &self,
         get_agent_activity_input: GetAgentActivityInput,
@@ -450,6 +513,15 @@ impl HdkT for HostHdk {
     fn query(&self, filter: ChainQueryFilter) -> ExternResult<Vec<Record>> {
         host_call::<ChainQueryFilter, Vec<Record>>(__query, filter)
     }
+    fn get_validation_receipts(
+        &self,
+        input: GetValidationReceiptsInput,
+    ) -> ExternResult<Vec<ValidationReceiptSet>> {
+        host_call::<GetValidationReceiptsInput, Vec<ValidationReceiptSet>>(
+            __get_validation_receipts,
+            input,
+        )
+    }
     fn sign(&self, sign: Sign) -> ExternResult<Signature> {
         host_call::<Sign, Signature>(__sign, sign)
     }
@@ -502,6 +574,9 @@ impl HdkT for HostHdk {
     ) -> ExternResult<Vec<LinkDetails>> {
         host_call::<Vec<GetLinksInput>, Vec<LinkDetails>>(__get_link_details, get_links_input)
     }
+    fn count_links(&self, query: LinkQuery) -> ExternResult<usize> {
+        host_call::<LinkQuery, usize>(__count_links, query)
+    }
     fn call(&self, call: Vec<Call>) -> ExternResult<Vec<ZomeCallResponse>> {
         host_call::<Vec<Call>, Vec<ZomeCallResponse>>(__call, call)
     }
@@ -131,6 +131,9 @@ pub fn delete_link(address: ActionHash) -> ExternResult<ActionHash> {
 /// This is mostly identical to `get_link_details` but returns only creates that have not been
 /// deleted c.f. get_link_details that returns all the creates and all the deletes together.
 ///
+/// Links are ordered by the time they were created. To filter links by author or time, or to
+/// get them a page at a time, use [ `get_links_with` ].
+///
 /// See [ `get_link_details` ].
 pub fn get_links(
     base: impl Into<AnyLinkableHash>,
@@ -148,6 +151,43 @@ pub fn get_links(
         .unwrap())
 }
 
+/// Get the links matching a [ `GetLinksInput` ], which can also filter the links by author
+/// and the time they were created, and get them a page at a time.
+///
+/// ```ignore
+/// let page = get_links_with(GetLinksInput::new(base, link_type_filter, None).limit(100))?;
+/// // The next page starts after the last link of this page.
+/// let next = get_links_with(
+///     GetLinksInput::new(base, link_type_filter, None)
+///         .limit(100)
+///         .cursor(page.last().unwrap()),
+/// )?;
+/// ```
+///
+/// See [ `get_links` ].
+pub fn get_links_with(input: GetLinksInput) -> ExternResult<Vec<Link>> {
+    Ok(HDK
+        .with(|h| h.borrow().get_links(vec![input]))?
+        .into_iter()
+        .next()
+        .unwrap())
+}
+
+/// Count the links on a base without getting them.
+///
+/// The query can filter the links by type, tag prefix, author and the time they were created.
+/// Deleted links are not counted.
+///
+/// Authorities only send the hashes of the matching links so this is much cheaper than
+/// [ `get_links` ] for bases with many links.
+///
+/// ```ignore
+/// let posts = count_links(LinkQuery::new(all_posts_hash, LinkTypes::AllPosts.try_into_filter()?))?;
+/// ```
+pub fn count_links(query: LinkQuery) -> ExternResult<usize> {
+    HDK.with(|h| h.borrow().count_links(query))
+}
+
 /// Get all link creates and deletes that reference a base entry hash, optionally filtered by tag
 ///
 /// Tag filtering is a simple bytes prefix.
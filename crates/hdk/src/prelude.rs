@@ -4,6 +4,8 @@ pub use crate::capability::delete_cap_grant;
 pub use crate::capability::generate_cap_secret;
 pub use crate::capability::update_cap_grant;
 pub use crate::chain::get_agent_activity;
+pub use crate::chain::get_validation_receipts;
+pub use crate::chain::must_get_agent_activity;
 pub use crate::chain::query;
 pub use crate::countersigning::accept_countersigning_preflight_request;
 pub use crate::countersigning::session_times_from_millis;
@@ -39,10 +41,12 @@ pub use crate::info::agent_info;
 pub use crate::info::call_info;
 pub use crate::info::dna_info;
 pub use crate::info::zome_info;
+pub use crate::link::count_links;
 pub use crate::link::create_link;
 pub use crate::link::delete_link;
 pub use crate::link::get_link_details;
 pub use crate::link::get_links;
+pub use crate::link::get_links_with;
 pub use crate::link::LinkTypeFilterExt;
 pub use crate::map_extern;
 pub use crate::map_extern::ExternResult;
@@ -127,10 +131,13 @@ macro_rules! holochain_externs {
             __get_details,
             __get_links,
             __get_link_details,
+            __count_links,
+            __get_validation_receipts,
             __get_agent_activity,
             __must_get_entry,
             __must_get_valid_record,
             __must_get_action,
+            __must_get_agent_activity,
             __accept_countersigning_preflight_request,
             __query,
             __call_remote,
@@ -11,7 +11,6 @@ use holochain::conductor::handle::DevSettingsDelta;
 use holochain::sweettest::*;
 use holochain_conductor_api::conductor::ConductorConfig;
 use holochain_conductor_api::AdminInterfaceConfig;
-use holochain_conductor_api::InterfaceDriver;
 use holochain_test_wasm_common::AnchorInput;
 use holochain_test_wasm_common::ManyAnchorInput;
 use holochain_wasm_test_utils::TestWasm;
@@ -183,9 +182,7 @@ async fn setup() -> (Producer, Consumer, Others) {
         network.tuning_params = Arc::new(tuning);
         ConductorConfig {
             network: Some(network),
-            admin_interfaces: Some(vec![AdminInterfaceConfig {
-                driver: InterfaceDriver::Websocket { port: 0 },
-            }]),
+            admin_interfaces: Some(vec![AdminInterfaceConfig::websocket(0)]),
             ..Default::default()
         }
     };
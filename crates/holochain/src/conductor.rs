@@ -14,12 +14,14 @@
 // TODO: clean up allow(missing_docs) once parent is fully documented
 
 pub mod api;
+pub mod app_auth_token_store;
 mod cell;
 #[allow(clippy::module_inception)]
 #[allow(missing_docs)]
 pub mod conductor;
 #[allow(missing_docs)]
 pub mod config;
+pub mod db_key;
 pub mod entry_def_store;
 #[allow(missing_docs)]
 pub mod error;
@@ -28,6 +30,7 @@ pub mod interactive;
 pub mod interface;
 pub mod kitsune_host_impl;
 pub mod manager;
+pub mod metrics;
 pub mod p2p_agent_store;
 pub mod paths;
 #[allow(missing_docs)]
@@ -137,6 +137,28 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     .await?;
                 Ok(AdminResponse::CloneCellCreated(cell_id))
             }
+            ArchiveCloneCell(payload) => {
+                self.conductor_handle
+                    .clone()
+                    .archive_clone_cell(*payload)
+                    .await?;
+                Ok(AdminResponse::CloneCellArchived)
+            }
+            RestoreCloneCell(payload) => {
+                let cell_id = self
+                    .conductor_handle
+                    .clone()
+                    .restore_clone_cell(*payload)
+                    .await?;
+                Ok(AdminResponse::CloneCellRestored(cell_id))
+            }
+            DeleteArchivedCloneCells(payload) => {
+                self.conductor_handle
+                    .clone()
+                    .delete_archived_clone_cells(*payload)
+                    .await?;
+                Ok(AdminResponse::ArchivedCloneCellsDeleted)
+            }
             InstallApp(payload) => {
                 trace!(?payload.dnas);
                 let InstallAppPayload {
@@ -281,15 +303,29 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     .await?;
                 Ok(AdminResponse::AppStarted(app.status().is_running()))
             }
-            AttachAppInterface { port } => {
+            AttachAppInterface {
+                port,
+                allowed_origins,
+                require_authentication,
+            } => {
                 let port = port.unwrap_or(0);
                 let port = self
                     .conductor_handle
                     .clone()
-                    .add_app_interface(port)
+                    .add_app_interface(port, allowed_origins, require_authentication)
                     .await?;
                 Ok(AdminResponse::AppInterfaceAttached { port })
             }
+            IssueAppAuthenticationToken(payload) => {
+                let issued = self
+                    .conductor_handle
+                    .issue_app_authentication_token(payload)
+                    .await?;
+                Ok(AdminResponse::AppAuthenticationTokenIssued(issued))
+            }
+            Authenticate { .. } => Ok(AdminResponse::Error(ExternalApiWireError::InternalError(
+                "Authentication can only be made over an admin interface connection".to_string(),
+            ))),
             ListAppInterfaces => {
                 let interfaces = self.conductor_handle.list_app_interfaces().await?;
                 Ok(AdminResponse::AppInterfacesListed(interfaces))
@@ -312,6 +348,45 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                 let dump = self.conductor_handle.dump_network_metrics(dna_hash).await?;
                 Ok(AdminResponse::NetworkMetricsDumped(dump))
             }
+            DumpValidationReceipts {
+                cell_id,
+                action_hash,
+            } => {
+                let sets = self
+                    .conductor_handle
+                    .dump_validation_receipts(&cell_id, action_hash)
+                    .await?;
+                Ok(AdminResponse::ValidationReceiptsDumped(sets))
+            }
+            DumpWorkflowQueues { cell_id } => {
+                let dump = self.conductor_handle.dump_workflow_queues(&cell_id).await?;
+                Ok(AdminResponse::WorkflowQueuesDumped(dump))
+            }
+            RequeueOpsForValidation { cell_id, op_hashes } => {
+                let requeued = self
+                    .conductor_handle
+                    .requeue_ops_for_validation(&cell_id, Some(op_hashes))
+                    .await?;
+                Ok(AdminResponse::OpsRequeued(requeued))
+            }
+            RequeueAbandonedOps { cell_id } => {
+                let requeued = self
+                    .conductor_handle
+                    .requeue_ops_for_validation(&cell_id, None)
+                    .await?;
+                Ok(AdminResponse::OpsRequeued(requeued))
+            }
+            ListCountersigningSessions => {
+                let sessions = self.conductor_handle.list_countersigning_sessions().await?;
+                Ok(AdminResponse::CountersigningSessionsListed(sessions))
+            }
+            AbandonCountersigningSession { cell_id } => {
+                let session = self
+                    .conductor_handle
+                    .abandon_countersigning_session(&cell_id)
+                    .await?;
+                Ok(AdminResponse::CountersigningSessionAbandoned(session))
+            }
             AddAgentInfo { agent_infos } => {
                 self.conductor_handle.add_agent_infos(agent_infos).await?;
                 Ok(AdminResponse::AgentInfoAdded)
@@ -348,6 +423,21 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     .await?;
                 Ok(AdminResponse::RecordsAdded)
             }
+            ExportSourceChain { cell_id } => {
+                let archive = self.conductor_handle.export_source_chain(&cell_id).await?;
+                Ok(AdminResponse::SourceChainExported(Box::new(archive)))
+            }
+            ImportSourceChain { archive, validate } => {
+                self.conductor_handle
+                    .clone()
+                    .import_source_chain(*archive, validate)
+                    .await?;
+                Ok(AdminResponse::SourceChainImported)
+            }
+            RotateDatabaseKey => {
+                self.conductor_handle.rotate_db_key().await?;
+                Ok(AdminResponse::DatabaseKeyRotated)
+            }
         }
     }
 }
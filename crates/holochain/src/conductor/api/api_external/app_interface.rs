@@ -100,8 +100,23 @@ impl AppInterfaceApi for RealAppInterfaceApi {
                     Err(e) => Ok(AppResponse::Error(e.into())),
                 }
             }
-            AppRequest::SignalSubscription(_) => Ok(AppResponse::Unimplemented(request)),
-            AppRequest::Crypto(_) => Ok(AppResponse::Unimplemented(request)),
+            AppRequest::SignalSubscription(_) => {
+                Ok(AppResponse::Error(ExternalApiWireError::InternalError(
+                    "Signal subscriptions can only be made over an app interface connection"
+                        .to_string(),
+                )))
+            }
+            AppRequest::Crypto(request) => Ok(AppResponse::Crypto(Box::new(
+                self.conductor_handle.app_crypto(*request).await?,
+            ))),
+            AppRequest::Authenticate { token } => {
+                match self.conductor_handle.authenticate_app_token(&token) {
+                    Ok(installed_app_id) => Ok(AppResponse::Authenticated { installed_app_id }),
+                    Err(e) => Ok(AppResponse::Error(ExternalApiWireError::Unauthenticated(
+                        e.to_string(),
+                    ))),
+                }
+            }
         }
     }
 }
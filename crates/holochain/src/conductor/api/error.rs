@@ -73,6 +73,10 @@ pub enum ConductorApiError {
     #[error("KeystoreError: {0}")]
     KeystoreError(#[from] holochain_keystore::KeystoreError),
 
+    /// An agent key was used on behalf of an app which has no cell with that agent.
+    #[error("The agent key {1} is not the agent of any cell of the app {0}")]
+    AgentNotInApp(InstalledAppId, AgentPubKey),
+
     /// Cell error
     #[error(transparent)]
     CellError(#[from] CellError),
@@ -106,6 +110,10 @@ pub enum ConductorApiError {
     #[error(transparent)]
     RusqliteError(#[from] rusqlite::Error),
 
+    /// A source chain archive failed its integrity checks.
+    #[error(transparent)]
+    ChainArchiveError(#[from] holochain_types::chain_archive::ChainArchiveError),
+
     /// Other
     #[error("Other: {0}")]
     Other(Box<dyn std::error::Error + Send + Sync>),
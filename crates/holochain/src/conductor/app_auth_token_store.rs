@@ -0,0 +1,146 @@
+//! In-memory store of the tokens which app interface connections
+//! can authenticate with.
+//!
+//! Tokens are issued through the admin interface for a single app, and
+//! are lost when the conductor restarts.
+
+use super::error::ConductorError;
+use super::error::ConductorResult;
+use holochain_conductor_api::AppAuthenticationToken;
+use holochain_types::prelude::*;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Number of random bytes in a token.
+const TOKEN_BYTES: usize = 32;
+
+#[derive(Clone, Debug)]
+struct TokenInfo {
+    installed_app_id: InstalledAppId,
+    expires_at: Option<Timestamp>,
+    single_use: bool,
+}
+
+impl TokenInfo {
+    fn is_expired(&self, now: Timestamp) -> bool {
+        self.expires_at
+            .map_or(false, |expires_at| now >= expires_at)
+    }
+}
+
+/// Issues and checks app authentication tokens.
+#[derive(Debug, Default)]
+pub struct AppAuthTokenStore {
+    tokens: HashMap<AppAuthenticationToken, TokenInfo>,
+}
+
+impl AppAuthTokenStore {
+    /// Issue a new random token for an app.
+    /// A zero `expiry` means the token never expires.
+    /// Returns the token and when it expires.
+    pub fn issue_token(
+        &mut self,
+        installed_app_id: InstalledAppId,
+        expiry: Duration,
+        single_use: bool,
+    ) -> (AppAuthenticationToken, Option<Timestamp>) {
+        let now = Timestamp::now();
+        self.remove_expired(now);
+        let token: AppAuthenticationToken = (0..TOKEN_BYTES).map(|_| rand::random()).collect();
+        let expires_at = if expiry.is_zero() {
+            None
+        } else {
+            Some(now.saturating_add(&expiry))
+        };
+        self.tokens.insert(
+            token.clone(),
+            TokenInfo {
+                installed_app_id,
+                expires_at,
+                single_use,
+            },
+        );
+        (token, expires_at)
+    }
+
+    /// Check a token, returning the app it was issued for.
+    /// Single use tokens are revoked by this.
+    pub fn authenticate_token(
+        &mut self,
+        token: &AppAuthenticationToken,
+    ) -> ConductorResult<InstalledAppId> {
+        let now = Timestamp::now();
+        self.remove_expired(now);
+        let info = self
+            .tokens
+            .get(token)
+            .cloned()
+            .ok_or(ConductorError::AppAuthenticationFailed)?;
+        if info.single_use {
+            self.tokens.remove(token);
+        }
+        Ok(info.installed_app_id)
+    }
+
+    /// Revoke all tokens issued for an app.
+    pub fn revoke_app_tokens(&mut self, installed_app_id: &InstalledAppId) {
+        self.tokens
+            .retain(|_, info| &info.installed_app_id != installed_app_id);
+    }
+
+    fn remove_expired(&mut self, now: Timestamp) {
+        self.tokens.retain(|_, info| !info.is_expired(now));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use matches::assert_matches;
+
+    #[test]
+    fn tokens_authenticate_their_app() {
+        let mut store = AppAuthTokenStore::default();
+        let (token, expires_at) =
+            store.issue_token("app".to_string(), Duration::from_secs(30), false);
+        assert!(expires_at.is_some());
+        assert_eq!(store.authenticate_token(&token).unwrap(), "app");
+        // Multi use tokens can be used again.
+        assert_eq!(store.authenticate_token(&token).unwrap(), "app");
+        assert_matches!(
+            store.authenticate_token(&vec![0; TOKEN_BYTES]),
+            Err(ConductorError::AppAuthenticationFailed)
+        );
+    }
+
+    #[test]
+    fn single_use_tokens_are_revoked_after_use() {
+        let mut store = AppAuthTokenStore::default();
+        let (token, _) = store.issue_token("app".to_string(), Duration::ZERO, true);
+        assert_eq!(store.authenticate_token(&token).unwrap(), "app");
+        assert_matches!(
+            store.authenticate_token(&token),
+            Err(ConductorError::AppAuthenticationFailed)
+        );
+    }
+
+    #[test]
+    fn expired_and_revoked_tokens_fail() {
+        let mut store = AppAuthTokenStore::default();
+        let (expiring, _) = store.issue_token("app".to_string(), Duration::from_millis(1), false);
+        let (forever, expires_at) = store.issue_token("app".to_string(), Duration::ZERO, false);
+        assert!(expires_at.is_none());
+        std::thread::sleep(Duration::from_millis(10));
+        assert_matches!(
+            store.authenticate_token(&expiring),
+            Err(ConductorError::AppAuthenticationFailed)
+        );
+        assert_eq!(store.authenticate_token(&forever).unwrap(), "app");
+
+        store.revoke_app_tokens(&"app".to_string());
+        assert_matches!(
+            store.authenticate_token(&forever),
+            Err(ConductorError::AppAuthenticationFailed)
+        );
+    }
+}
@@ -356,7 +356,8 @@ impl Cell {
             | QueryAgentInfoSignedNearBasis { .. }
             | QueryPeerDensity { .. }
             | Publish { .. }
-            | FetchOpData { .. } => {
+            | FetchOpData { .. }
+            | PublishWarrants { .. } => {
                 // These events are aggregated over a set of cells, so need to be handled at the conductor level.
                 unreachable!()
             }
@@ -447,6 +448,22 @@ impl Cell {
                 .instrument(debug_span!("cell_handle_get_links"))
                 .await;
             }
+            CountLinks {
+                span_context: _,
+                respond,
+                query,
+                ..
+            } => {
+                async {
+                    let res = self
+                        .handle_count_links(query)
+                        .await
+                        .map_err(holochain_p2p::HolochainP2pError::other);
+                    respond.respond(Ok(async move { res }.boxed().into()));
+                }
+                .instrument(debug_span!("cell_handle_count_links"))
+                .await;
+            }
             GetAgentActivity {
                 span_context: _,
                 respond,
@@ -536,6 +553,7 @@ impl Cell {
                     &self.space.countersigning_workspace,
                     self.queue_triggers.countersigning.clone(),
                 )
+                .await
                 .map_err(Box::new)?;
                 Ok(())
             }
@@ -645,14 +663,18 @@ impl Cell {
             .map_err(Into::into)
     }
 
-    #[instrument(skip(self, _dht_hash, _options))]
+    #[instrument(skip(self, options))]
     /// a remote node is asking us for metadata
     async fn handle_get_meta(
         &self,
-        _dht_hash: holo_hash::AnyDhtHash,
-        _options: holochain_p2p::event::GetMetaOptions,
+        dht_hash: holo_hash::AnyDhtHash,
+        options: holochain_p2p::event::GetMetaOptions,
     ) -> CellResult<MetadataSet> {
-        unimplemented!()
+        debug!(id = ?self.id());
+        let db = self.space.dht_db.clone();
+        authority::handle_get_meta(db.into(), dht_hash, options)
+            .await
+            .map_err(Into::into)
     }
 
     #[instrument(skip(self, options))]
@@ -672,6 +694,16 @@ impl Cell {
             .map_err(Into::into)
     }
 
+    #[instrument(skip(self))]
+    /// a remote node is asking us for the hashes of the links on a base
+    async fn handle_count_links(&self, query: WireLinkKey) -> CellResult<CountLinksResponse> {
+        debug!(id = ?self.id());
+        let db = self.space.dht_db.clone();
+        authority::handle_count_links(db.into(), query)
+            .await
+            .map_err(Into::into)
+    }
+
     #[instrument(skip(self, options))]
     async fn handle_get_agent_activity(
         &self,
@@ -838,6 +870,9 @@ impl Cell {
             }
         };
 
+        let zome_name = invocation.zome.zome_name().clone();
+        let fn_name = invocation.fn_name.clone();
+        let histograms = ribosome.histograms();
         let args = CallZomeWorkflowArgs {
             cell_id: self.id.clone(),
             ribosome,
@@ -846,7 +881,8 @@ impl Cell {
             conductor_handle,
             is_root_zome_call,
         };
-        Ok(call_zome_workflow(
+        let start = std::time::Instant::now();
+        let result = call_zome_workflow(
             workspace_lock,
             self.holochain_p2p_cell.clone(),
             keystore,
@@ -854,8 +890,16 @@ impl Cell {
             self.queue_triggers.publish_dht_ops.clone(),
             self.queue_triggers.integrate_dht_ops.clone(),
         )
-        .await
-        .map_err(Box::new)?)
+        .await;
+        if let Some(histograms) = histograms {
+            histograms.record_zome_call_duration(
+                self.id.dna_hash(),
+                &zome_name,
+                &fn_name,
+                start.elapsed(),
+            );
+        }
+        Ok(result.map_err(Box::new)?)
     }
 
     /// Check if each Zome's init callback has been run, and if not, run it.
@@ -11,6 +11,7 @@
 
 pub use self::share::RwShare;
 use super::api::RealAppInterfaceApi;
+use super::app_auth_token_store::AppAuthTokenStore;
 use super::config::AdminInterfaceConfig;
 use super::config::InterfaceDriver;
 use super::entry_def_store::get_entry_defs;
@@ -26,6 +27,7 @@ use super::manager::keep_alive_task;
 use super::manager::ManagedTaskAdd;
 use super::manager::ManagedTaskHandle;
 use super::manager::TaskManagerRunHandle;
+use super::metrics;
 use super::paths::DatabaseRootPath;
 use super::ribosome_store::RibosomeStore;
 use super::space::Space;
@@ -55,6 +57,7 @@ use futures::future::TryFutureExt;
 use futures::stream::StreamExt;
 use holo_hash::DnaHash;
 use holochain_conductor_api::conductor::KeystoreConfig;
+use holochain_conductor_api::config::AllowedOrigins;
 use holochain_conductor_api::AppStatusFilter;
 use holochain_conductor_api::FullIntegrationStateDump;
 use holochain_conductor_api::InstalledAppInfo;
@@ -64,6 +67,8 @@ use holochain_keystore::lair_keystore::spawn_new_lair_keystore;
 use holochain_keystore::test_keystore::spawn_legacy_test_keystore;
 use holochain_keystore::test_keystore::spawn_test_keystore;
 use holochain_keystore::MetaLairClient;
+use holochain_p2p::dht_arc::DhtArcSet;
+use holochain_p2p::AgentPubKeyExt;
 use holochain_sqlite::prelude::*;
 use holochain_sqlite::sql::sql_cell::state_dump;
 use holochain_state::mutations;
@@ -153,6 +158,9 @@ where
     /// Collection app interface data, keyed by id
     app_interfaces: RwShare<HashMap<AppInterfaceId, AppInterfaceRuntime>>,
 
+    /// The tokens which app interface connections can authenticate with
+    pub(super) app_auth_token_store: RwShare<AppAuthTokenStore>,
+
     /// The channels and handles needed to interact with the task_manager task.
     /// If this is None, then the task manager has not yet been initialized.
     pub(super) task_manager: RwShare<Option<TaskManagerClient>>,
@@ -298,18 +306,24 @@ impl Conductor {
         });
 
         // Closure to process each admin config item
-        let spawn_from_config = |AdminInterfaceConfig { driver, .. }| {
+        let spawn_from_config = |AdminInterfaceConfig {
+                                     driver,
+                                     allowed_origins,
+                                     auth,
+                                 }| {
             let admin_api = admin_api.clone();
             let stop_tx = stop_tx.clone();
             async move {
                 match driver {
                     InterfaceDriver::Websocket { port } => {
-                        let (listener_handle, listener) = spawn_websocket_listener(port).await?;
+                        let (listener_handle, listener) =
+                            spawn_websocket_listener(port, allowed_origins).await?;
                         let port = listener_handle.local_addr().port().unwrap_or(port);
                         let handle: ManagedTaskHandle = spawn_admin_interface_task(
                             listener_handle,
                             listener,
                             admin_api.clone(),
+                            auth,
                             stop_tx.subscribe(),
                         )?;
                         InterfaceResult::Ok((port, handle))
@@ -358,6 +372,8 @@ impl Conductor {
     pub(super) async fn add_app_interface_via_handle(
         &self,
         port: either::Either<u16, AppInterfaceId>,
+        allowed_origins: AllowedOrigins,
+        require_authentication: bool,
         handle: ConductorHandle,
     ) -> ConductorResult<u16> {
         let interface_id = match port {
@@ -376,9 +392,16 @@ impl Conductor {
                 .task_stop_broadcaster()
                 .subscribe()
         });
-        let (port, task) = spawn_app_interface_task(port, app_api, signal_tx.clone(), stop_rx)
-            .await
-            .map_err(Box::new)?;
+        let (port, task) = spawn_app_interface_task(
+            port,
+            allowed_origins.clone(),
+            require_authentication,
+            app_api,
+            signal_tx.clone(),
+            stop_rx,
+        )
+        .await
+        .map_err(Box::new)?;
         // TODO: RELIABILITY: Handle this task by restarting it if it fails and log the error
         self.manage_task(ManagedTaskAdd::ignore(
             task,
@@ -397,7 +420,11 @@ impl Conductor {
             app_interfaces.insert(interface_id.clone(), interface);
             Ok(())
         })?;
-        let config = AppInterfaceConfig::websocket(port);
+        let config = AppInterfaceConfig {
+            allowed_origins,
+            require_authentication,
+            ..AppInterfaceConfig::websocket(port)
+        };
         self.update_state(|mut state| {
             state.app_interfaces.insert(interface_id, config);
             Ok(state)
@@ -454,10 +481,15 @@ impl Conductor {
         &self,
         handle: ConductorHandle,
     ) -> ConductorResult<()> {
-        for id in self.get_state().await?.app_interfaces.keys().cloned() {
+        for (id, config) in self.get_state().await?.app_interfaces {
             tracing::debug!("Starting up app interface: {:?}", id);
             let _ = self
-                .add_app_interface_via_handle(either::Right(id), handle.clone())
+                .add_app_interface_via_handle(
+                    either::Right(id),
+                    config.allowed_origins,
+                    config.require_authentication,
+                    handle.clone(),
+                )
                 .await?;
         }
         Ok(())
@@ -794,6 +826,80 @@ impl Conductor {
         Ok(cell_id)
     }
 
+    /// Move a clone cell of an app to the archive
+    pub(super) async fn archive_clone_cell_in_app(
+        &self,
+        ArchiveCloneCellPayload {
+            app_id,
+            role_id,
+            clone_cell_id,
+        }: ArchiveCloneCellPayload,
+    ) -> ConductorResult<()> {
+        self.update_state(move |mut state| {
+            let app = state
+                .installed_apps_mut()
+                .get_mut(&app_id)
+                .ok_or_else(|| ConductorError::AppNotInstalled(app_id.clone()))?;
+            app.archive_clone(&role_id, &clone_cell_id)?;
+            Ok(state)
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Move an archived clone cell back into its app
+    pub(super) async fn restore_clone_cell_in_app(
+        &self,
+        ArchiveCloneCellPayload {
+            app_id,
+            role_id,
+            clone_cell_id,
+        }: ArchiveCloneCellPayload,
+    ) -> ConductorResult<()> {
+        self.update_state(move |mut state| {
+            let app = state
+                .installed_apps_mut()
+                .get_mut(&app_id)
+                .ok_or_else(|| ConductorError::AppNotInstalled(app_id.clone()))?;
+            app.restore_clone(&role_id, &clone_cell_id)?;
+            Ok(state)
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Remove all archived clone cells of an app role from the state,
+    /// returning the removed cells.
+    pub(super) async fn delete_archived_clone_cells_in_app(
+        &self,
+        DeleteArchivedCloneCellsPayload { app_id, role_id }: DeleteArchivedCloneCellsPayload,
+    ) -> ConductorResult<HashSet<CellId>> {
+        let (_, deleted) = self
+            .update_state_prime(move |mut state| {
+                let app = state
+                    .installed_apps_mut()
+                    .get_mut(&app_id)
+                    .ok_or_else(|| ConductorError::AppNotInstalled(app_id.clone()))?;
+                let deleted = app.delete_archived_clones(&role_id)?;
+                Ok((state, deleted))
+            })
+            .await?;
+        Ok(deleted)
+    }
+
+    /// Delete the databases of a dna, refusing while any cell of it
+    /// is still in the conductor and could be using them.
+    pub(super) async fn delete_space(&self, dna_hash: &DnaHash) -> ConductorResult<()> {
+        if self
+            .list_cell_ids(None)
+            .iter()
+            .any(|cell_id| cell_id.dna_hash() == dna_hash)
+        {
+            return Err(ConductorError::SpaceInUse(dna_hash.clone()));
+        }
+        self.spaces.delete_space(dna_hash).await
+    }
+
     pub(super) async fn load_wasms_into_dna_files(
         &self,
     ) -> ConductorResult<(
@@ -1291,6 +1397,7 @@ impl Conductor {
             config,
             shutting_down: Arc::new(AtomicBool::new(false)),
             app_interfaces: RwShare::new(HashMap::new()),
+            app_auth_token_store: RwShare::new(AppAuthTokenStore::default()),
             task_manager: RwShare::new(None),
             admin_websocket_ports: RwShare::new(Vec::new()),
             ribosome_store,
@@ -1322,6 +1429,168 @@ impl Conductor {
         Ok(())
     }
 
+    pub(crate) async fn evict_cache_dbs(&self) -> ConductorResult<()> {
+        let max_age = self.config.cache.max_age();
+        let max_bytes = self.config.cache.max_bytes;
+        for db in self.spaces.get_from_spaces(|s| s.cache_db.clone()) {
+            let evicted = holochain_state::cache::evict_cache(&db, max_age, max_bytes).await?;
+            if evicted > 0 {
+                tracing::debug!(evicted, "Evicted actions from cache database");
+            }
+        }
+        Ok(())
+    }
+
+    /// Delete the DHT data outside the storage arcs of the local agents
+    /// of every space with cells on this conductor.
+    pub(crate) async fn collect_arc_garbage(&self) -> ConductorResult<()> {
+        let mut agents_by_dna: HashMap<DnaHash, Vec<AgentPubKey>> = HashMap::new();
+        for cell_id in self.list_cell_ids(None) {
+            let (dna_hash, agent) = cell_id.into_dna_and_agent();
+            agents_by_dna.entry(dna_hash).or_default().push(agent);
+        }
+        for (dna_hash, agents) in agents_by_dna {
+            let infos = self
+                .spaces
+                .p2p_agents_db(&dna_hash)?
+                .async_reader({
+                    let agents = agents.clone();
+                    move |txn| {
+                        agents
+                            .iter()
+                            .map(|agent| txn.p2p_get_agent(&agent.to_kitsune()))
+                            .collect::<DatabaseResult<Option<Vec<_>>>>()
+                    }
+                })
+                .await?;
+            // The arcs aren't known until every local agent has joined the network.
+            let local_arcs = match infos {
+                Some(infos) => infos.iter().fold(DhtArcSet::new_empty(), |arcs, info| {
+                    arcs.union(&DhtArcSet::from(info.storage_arc.inner()))
+                }),
+                None => continue,
+            };
+            let collected = self
+                .spaces
+                .collect_arc_garbage(&dna_hash, local_arcs, agents, &self.config.arc_gc)
+                .await?;
+            if collected > 0 {
+                tracing::debug!(
+                    ?dna_hash,
+                    collected,
+                    "Deleted DHT ops outside of the local arcs"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Render the metrics of every space on this conductor
+    /// in the Prometheus text format.
+    pub(crate) async fn render_metrics(&self) -> ConductorResult<String> {
+        use holochain_p2p::HolochainP2pSender;
+        let mut spaces = Vec::new();
+        for space in self.spaces.get_from_spaces(|s| s.clone()) {
+            let mut metrics = metrics::SpaceMetrics::read(&space).await?;
+            metrics.gossip = self
+                .holochain_p2p
+                .gossip_totals(metrics.dna_hash.clone())
+                .await?;
+            spaces.push(metrics);
+        }
+        let histograms = self.ribosome_store.share_ref(|s| s.histograms());
+        Ok(metrics::render(&spaces, &histograms))
+    }
+
+    /// Start serving metrics over HTTP if the config asks for it
+    /// and register the server with the TaskManager.
+    pub(super) async fn start_metrics_server_via_handle(
+        &self,
+        handle: ConductorHandle,
+    ) -> ConductorResult<()> {
+        let config = match &self.config.metrics {
+            Some(config) => config,
+            None => return Ok(()),
+        };
+        let stop_rx = self.task_manager.share_ref(|tm| {
+            tm.as_ref()
+                .expect("Task manager not initialized")
+                .task_stop_broadcaster()
+                .subscribe()
+        });
+        let task = metrics::spawn_metrics_server(config.socket_addr(), handle, stop_rx)?;
+        self.manage_task(ManagedTaskAdd::ignore(task, "metrics server"))
+            .await
+    }
+
+    /// Spawn the tasks which periodically tidy up the databases
+    /// and register them with the TaskManager.
+    /// Tasks which are disabled by the config are not spawned.
+    pub(super) async fn add_db_maintenance_tasks_via_handle(
+        &self,
+        handle: ConductorHandle,
+    ) -> ConductorResult<()> {
+        if self.config.cache.is_bounded() {
+            let handle = handle.clone();
+            self.manage_periodic_task(
+                "cache eviction",
+                self.config.cache.eviction_interval(),
+                move || {
+                    let handle = handle.clone();
+                    async move { handle.evict_cache_dbs().await }
+                },
+            )
+            .await?;
+        }
+        if self.config.arc_gc.enabled {
+            self.manage_periodic_task(
+                "arc garbage collection",
+                self.config.arc_gc.interval(),
+                move || {
+                    let handle = handle.clone();
+                    async move { handle.collect_arc_garbage().await }
+                },
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Spawn a task which runs `f` every `interval` until the conductor
+    /// shuts down and register it with the TaskManager.
+    /// Errors are logged and don't stop the task.
+    async fn manage_periodic_task<F, Fut>(
+        &self,
+        name: &'static str,
+        interval: std::time::Duration,
+        mut f: F,
+    ) -> ConductorResult<()>
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = ConductorResult<()>> + Send,
+    {
+        let mut stop_rx = self.task_manager.share_ref(|tm| {
+            tm.as_ref()
+                .expect("Task manager not initialized")
+                .task_stop_broadcaster()
+                .subscribe()
+        });
+        let task = tokio::task::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = stop_rx.recv() => break,
+                    _ = tokio::time::sleep(interval) => {
+                        if let Err(e) = f().await {
+                            tracing::error!("{} failed: {:?}", name, e);
+                        }
+                    }
+                }
+            }
+            Ok(())
+        });
+        self.manage_task(ManagedTaskAdd::ignore(task, name)).await
+    }
+
     pub(super) async fn get_state(&self) -> ConductorResult<ConductorState> {
         self.spaces
             .conductor_db
@@ -1402,6 +1671,7 @@ mod builder {
     use holochain_p2p::dht::ArqStrat;
 
     use super::*;
+    use crate::conductor::db_key::DbKeyManager;
     use crate::conductor::handle::DevSettings;
     use crate::conductor::kitsune_host_impl::KitsuneHostImpl;
     use crate::conductor::ribosome_store::RibosomeStore;
@@ -1460,6 +1730,7 @@ mod builder {
 
             tracing::info!(?self.config);
 
+            let db_passphrase = self.passphrase.clone();
             let keystore = if let Some(keystore) = self.keystore {
                 keystore
             } else {
@@ -1516,7 +1787,19 @@ mod builder {
             let strat =
                 ArqStrat::from_params(network_config.tuning_params.gossip_redundancy_target);
 
-            let spaces = Spaces::new(&config)?;
+            let db_key = match &config.db_encryption {
+                Some(db_encryption) => Some(Arc::new(
+                    DbKeyManager::init(
+                        config.environment_path.as_ref(),
+                        db_encryption,
+                        &keystore,
+                        db_passphrase,
+                    )
+                    .await?,
+                )),
+                None => None,
+            };
+            let spaces = Spaces::new_with_db_key(&config, db_key)?;
             let host = KitsuneHostImpl::new(
                 spaces.clone(),
                 ribosome_store.clone(),
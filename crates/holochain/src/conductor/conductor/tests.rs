@@ -13,7 +13,9 @@ use crate::{
 };
 use ::fixt::prelude::*;
 use holochain_conductor_api::InstalledAppInfoStatus;
-use holochain_conductor_api::{AdminRequest, AdminResponse, AppRequest, AppResponse, ZomeCall};
+use holochain_conductor_api::{
+    AdminRequest, AdminResponse, AppRequest, AppResponse, ExternalApiWireError, ZomeCall,
+};
 use holochain_keystore::crude_mock_keystore::spawn_crude_mock_keystore;
 use holochain_keystore::crude_mock_keystore::spawn_real_or_mock_keystore;
 use holochain_state::prelude::{test_keystore, *};
@@ -156,6 +158,144 @@ async fn can_add_clone_cell_to_app() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn can_archive_restore_and_delete_clone_cells() {
+    let db_dir = test_db_dir();
+    let keystore = test_keystore();
+    let holochain_p2p = holochain_p2p::stub_network().await;
+
+    let agent = fixt!(AgentPubKey);
+    let dna = fake_valid_dna_file("");
+    let cell_id = CellId::new(dna.dna_hash().to_owned(), agent.clone());
+
+    let ribosome_store = RibosomeStore::new();
+    let (post_commit_sender, _post_commit_receiver) =
+        tokio::sync::mpsc::channel(POST_COMMIT_CHANNEL_BOUND);
+    let spaces = Spaces::new(&ConductorConfig {
+        environment_path: db_dir.path().to_path_buf().into(),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let conductor = Conductor::new(
+        Default::default(),
+        ribosome_store,
+        keystore,
+        holochain_p2p,
+        spaces,
+        post_commit_sender,
+    )
+    .await
+    .unwrap();
+
+    let app_id = "app".to_string();
+    let role_id = "role_id".to_string();
+    let role = AppRoleAssignment::new(cell_id.clone(), true, 1);
+    let app = InstalledAppCommon::new(app_id.clone(), agent, vec![(role_id.clone(), role)]);
+    conductor.register_phenotype(RealRibosome::empty(dna));
+    conductor
+        .update_state(move |mut state| {
+            state
+                .installed_apps_mut()
+                .insert(RunningApp::from(app).into());
+            Ok(state)
+        })
+        .await
+        .unwrap();
+
+    let clone_cell_id = conductor
+        .add_clone_cell_to_app(app_id.clone(), role_id.clone(), ().into())
+        .await
+        .unwrap();
+    let archive = ArchiveCloneCellPayload {
+        app_id: app_id.clone(),
+        role_id: role_id.clone(),
+        clone_cell_id: clone_cell_id.clone(),
+    };
+
+    // Only clone cells can be archived
+    matches::assert_matches!(
+        conductor
+            .archive_clone_cell_in_app(ArchiveCloneCellPayload {
+                clone_cell_id: cell_id.clone(),
+                ..archive.clone()
+            })
+            .await,
+        Err(ConductorError::AppError(AppError::CloneCellNotFound(_)))
+    );
+
+    conductor
+        .archive_clone_cell_in_app(archive.clone())
+        .await
+        .unwrap();
+    let state = conductor.get_state().await.unwrap();
+    let app = state.get_app(&app_id).unwrap();
+    assert_eq!(app.cloned_cells().count(), 0);
+    assert_eq!(
+        app.archived_clone_cells().collect::<Vec<_>>(),
+        vec![&clone_cell_id]
+    );
+
+    // The archived clone still uses up the only slot
+    matches::assert_matches!(
+        conductor
+            .add_clone_cell_to_app(app_id.clone(), role_id.clone(), ().into())
+            .await,
+        Err(ConductorError::AppError(AppError::CloneLimitExceeded(1, _)))
+    );
+
+    conductor
+        .restore_clone_cell_in_app(archive.clone())
+        .await
+        .unwrap();
+    let state = conductor.get_state().await.unwrap();
+    assert_eq!(
+        state
+            .get_app(&app_id)
+            .unwrap()
+            .cloned_cells()
+            .collect::<Vec<_>>(),
+        vec![&clone_cell_id]
+    );
+
+    // Deleting the archived clone frees its slot and removes its databases
+    let authored_path = db_dir
+        .path()
+        .join(DbKindAuthored(Arc::new(clone_cell_id.dna_hash().clone())).filename());
+    conductor
+        .spaces
+        .get_or_create_space(clone_cell_id.dna_hash())
+        .unwrap();
+    assert!(authored_path.exists());
+    conductor.archive_clone_cell_in_app(archive).await.unwrap();
+    let deleted = conductor
+        .delete_archived_clone_cells_in_app(DeleteArchivedCloneCellsPayload {
+            app_id: app_id.clone(),
+            role_id: role_id.clone(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(deleted, hashset! { clone_cell_id.clone() });
+    conductor
+        .delete_space(clone_cell_id.dna_hash())
+        .await
+        .unwrap();
+    assert!(!authored_path.exists());
+    let state = conductor.get_state().await.unwrap();
+    assert_eq!(
+        state
+            .get_app(&app_id)
+            .unwrap()
+            .archived_clone_cells()
+            .count(),
+        0
+    );
+    conductor
+        .add_clone_cell_to_app(app_id, role_id, ().into())
+        .await
+        .unwrap();
+}
+
 /// App can't be installed if another app is already installed under the
 /// same InstalledAppId
 #[tokio::test(flavor = "multi_thread")]
@@ -210,6 +350,48 @@ async fn app_ids_are_unique() {
     );
 }
 
+/// An app can't be removed while another app reuses one of its cells
+#[test]
+fn apps_with_dependents_cant_be_removed() {
+    let cell_id = fake_cell_id(1);
+    let agent = cell_id.agent_pubkey().clone();
+    let owner = InstalledAppCommon::new_legacy(
+        "owner",
+        vec![InstalledCell::new(cell_id.clone(), "role".to_string())],
+    )
+    .unwrap();
+    let dependent = InstalledAppCommon::new(
+        "dependent",
+        agent,
+        vec![(
+            "role".to_string(),
+            AppRoleAssignment::new_existing(cell_id, 0),
+        )],
+    );
+
+    let mut state = ConductorState::default();
+    state.add_app(owner).unwrap();
+    state.add_app(dependent).unwrap();
+
+    assert_eq!(
+        state.dependents_of(&"owner".to_string()).unwrap(),
+        vec!["dependent".to_string()]
+    );
+    assert!(state
+        .dependents_of(&"dependent".to_string())
+        .unwrap()
+        .is_empty());
+    assert_matches!(
+        state.remove_app(&"owner".to_string()),
+        Err(ConductorError::AppHasDependents(id, dependents))
+        if id == "owner" && dependents == vec!["dependent".to_string()]
+    );
+
+    // Once the dependent is gone the owner can be removed
+    state.remove_app(&"dependent".to_string()).unwrap();
+    state.remove_app(&"owner".to_string()).unwrap();
+}
+
 /// App can't be installed if it contains duplicate AppRoleIds
 #[tokio::test(flavor = "multi_thread")]
 async fn app_role_ids_are_unique() {
@@ -486,7 +668,11 @@ async fn test_signing_error_during_genesis_doesnt_bork_interfaces() {
     let (cell1,) = app1.into_tuple();
     let (cell2,) = app2.into_tuple();
 
-    let app_port = conductor.inner_handle().add_app_interface(0).await.unwrap();
+    let app_port = conductor
+        .inner_handle()
+        .add_app_interface(0, AllowedOrigins::Any, false)
+        .await
+        .unwrap();
     let (mut app_client, _) = websocket_client_by_port(app_port).await.unwrap();
     let (mut admin_client, _) = conductor.admin_ws_client().await;
 
@@ -1009,3 +1195,224 @@ async fn test_init_concurrency() {
     assert_eq!(num_calls_clone.fetch_add(0, Ordering::SeqCst), 100);
     assert_eq!(num_inits_clone.fetch_add(0, Ordering::SeqCst), 1);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn app_crypto_requests_use_app_agent_keys() {
+    use holochain_conductor_api::{CryptoRequest, CryptoResponse};
+
+    observability::test_run().ok();
+    let dnas = [mk_dna(simple_create_entry_zome()).await.unwrap().0];
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let (alice, bob) = SweetAgents::two(conductor.keystore()).await;
+    conductor
+        .setup_app_for_agents("app-", &[alice.clone(), bob.clone()], &dnas)
+        .await
+        .unwrap();
+    let alice_app = format!("app-{}", alice);
+    let bob_app = format!("app-{}", bob);
+    let handle = conductor.inner_handle();
+    let data = b"arbitrary bytes".to_vec();
+
+    // - Alice's app can sign with alice's key
+    let response = handle
+        .app_crypto(CryptoRequest::Sign {
+            installed_app_id: alice_app.clone(),
+            payload: Sign::new_raw(alice.clone(), data.clone()),
+        })
+        .await
+        .unwrap();
+    let signature = match response {
+        CryptoResponse::Signature(signature) => signature,
+        other => panic!("unexpected response {:?}", other),
+    };
+    assert!(
+        alice
+            .verify_signature_raw(&signature, data.clone().into())
+            .await
+    );
+
+    // - Alice's app can't sign with bob's key
+    let err = handle
+        .app_crypto(CryptoRequest::Sign {
+            installed_app_id: alice_app.clone(),
+            payload: Sign::new_raw(bob.clone(), data.clone()),
+        })
+        .await
+        .unwrap_err();
+    assert_matches!(err, ConductorApiError::AgentNotInApp(_, key) if key == bob);
+
+    // - Alice encrypts to bob and bob decrypts
+    let keystore = conductor.keystore();
+    let alice_x25519 =
+        X25519PubKey::from(*keystore.agent_x25519_pub_key(alice.clone()).await.unwrap());
+    let bob_x25519 = X25519PubKey::from(*keystore.agent_x25519_pub_key(bob.clone()).await.unwrap());
+    let response = handle
+        .app_crypto(CryptoRequest::Encrypt {
+            installed_app_id: alice_app.clone(),
+            agent_key: alice.clone(),
+            recipient: bob_x25519,
+            data: data.clone().into(),
+        })
+        .await
+        .unwrap();
+    let encrypted_data = match response {
+        CryptoResponse::Encrypted(encrypted_data) => encrypted_data,
+        other => panic!("unexpected response {:?}", other),
+    };
+    assert_ne!(encrypted_data.as_encrypted_data_ref(), data.as_slice());
+
+    let response = handle
+        .app_crypto(CryptoRequest::Decrypt {
+            installed_app_id: bob_app.clone(),
+            agent_key: bob.clone(),
+            sender: alice_x25519,
+            encrypted_data: encrypted_data.clone(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(response, CryptoResponse::Decrypted(data.into()));
+
+    // - Alice's app can't decrypt as bob
+    let err = handle
+        .app_crypto(CryptoRequest::Decrypt {
+            installed_app_id: alice_app,
+            agent_key: bob,
+            sender: alice_x25519,
+            encrypted_data,
+        })
+        .await
+        .unwrap_err();
+    assert_matches!(err, ConductorApiError::AgentNotInApp(_, _));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn app_interface_authentication() {
+    use holochain_conductor_api::{CryptoRequest, IssueAppAuthenticationTokenPayload};
+
+    observability::test_run().ok();
+    let dnas = [mk_dna(simple_create_entry_zome()).await.unwrap().0];
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let (alice, bob) = SweetAgents::two(conductor.keystore()).await;
+    conductor
+        .setup_app_for_agents("app-", &[alice.clone(), bob.clone()], &dnas)
+        .await
+        .unwrap();
+    let alice_app = format!("app-{}", alice);
+    let bob_app = format!("app-{}", bob);
+    let handle = conductor.inner_handle();
+
+    // - Tokens can't be issued for apps which aren't installed
+    let err = handle
+        .issue_app_authentication_token(IssueAppAuthenticationTokenPayload::for_app(
+            "no-such-app".to_string(),
+        ))
+        .await
+        .unwrap_err();
+    assert_matches!(err, ConductorError::AppNotInstalled(_));
+
+    let app_port = handle
+        .clone()
+        .add_app_interface(0, AllowedOrigins::Any, true)
+        .await
+        .unwrap();
+    let (mut app_client, _) = websocket_client_by_port(app_port).await.unwrap();
+
+    // - Requests are rejected until the connection authenticates
+    let response: AppResponse = app_client
+        .request(AppRequest::AppInfo {
+            installed_app_id: alice_app.clone(),
+        })
+        .await
+        .unwrap();
+    assert_matches!(
+        response,
+        AppResponse::Error(ExternalApiWireError::Unauthenticated(_))
+    );
+
+    let issued = handle
+        .issue_app_authentication_token(IssueAppAuthenticationTokenPayload::for_app(
+            alice_app.clone(),
+        ))
+        .await
+        .unwrap();
+    assert!(issued.expires_at.is_some());
+    let response: AppResponse = app_client
+        .request(AppRequest::Authenticate {
+            token: issued.token.clone(),
+        })
+        .await
+        .unwrap();
+    assert_matches!(response, AppResponse::Authenticated { installed_app_id } if installed_app_id == alice_app);
+
+    // - The connection can only make requests for its own app
+    let response: AppResponse = app_client
+        .request(AppRequest::AppInfo {
+            installed_app_id: alice_app.clone(),
+        })
+        .await
+        .unwrap();
+    assert_matches!(response, AppResponse::AppInfo(Some(_)));
+    let response: AppResponse = app_client
+        .request(AppRequest::AppInfo {
+            installed_app_id: bob_app.clone(),
+        })
+        .await
+        .unwrap();
+    assert_matches!(
+        response,
+        AppResponse::Error(ExternalApiWireError::Unauthorized(_))
+    );
+
+    // - Crypto requests need an authenticated connection
+    // even when the interface doesn't require authentication
+    let open_port = handle
+        .clone()
+        .add_app_interface(0, AllowedOrigins::Any, false)
+        .await
+        .unwrap();
+    let (mut open_client, _) = websocket_client_by_port(open_port).await.unwrap();
+    let sign = || {
+        AppRequest::Crypto(Box::new(CryptoRequest::Sign {
+            installed_app_id: alice_app.clone(),
+            payload: Sign::new_raw(alice.clone(), b"arbitrary bytes".to_vec()),
+        }))
+    };
+    let response: AppResponse = open_client.request(sign()).await.unwrap();
+    assert_matches!(
+        response,
+        AppResponse::Error(ExternalApiWireError::Unauthenticated(_))
+    );
+    let response: AppResponse = app_client.request(sign()).await.unwrap();
+    assert_matches!(response, AppResponse::Crypto(_));
+
+    // - Single use tokens can't be used again
+    let (mut other_client, _) = websocket_client_by_port(app_port).await.unwrap();
+    let response: AppResponse = other_client
+        .request(AppRequest::Authenticate {
+            token: issued.token,
+        })
+        .await
+        .unwrap();
+    assert_matches!(
+        response,
+        AppResponse::Error(ExternalApiWireError::Unauthenticated(_))
+    );
+
+    // - Reusable tokens are revoked when their app is uninstalled
+    let issued = handle
+        .issue_app_authentication_token(IssueAppAuthenticationTokenPayload {
+            installed_app_id: bob_app.clone(),
+            expiry_seconds: 0,
+            single_use: false,
+        })
+        .await
+        .unwrap();
+    assert!(issued.expires_at.is_none());
+    handle.authenticate_app_token(&issued.token).unwrap();
+    handle.authenticate_app_token(&issued.token).unwrap();
+    handle.clone().uninstall_app(&bob_app).await.unwrap();
+    assert_matches!(
+        handle.authenticate_app_token(&issued.token),
+        Err(ConductorError::AppAuthenticationFailed)
+    );
+}
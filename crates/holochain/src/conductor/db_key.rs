@@ -0,0 +1,284 @@
+//! Management of the key used for at-rest encryption of the conductor's databases.
+//!
+//! The database key is derived in two steps:
+//! - A master key which never changes, derived either from a secret held by
+//!   the keystore or from the passphrase the conductor was started with.
+//! - The actual database key, a keyed hash of a random salt with the master key.
+//!
+//! Both salts are stored in the database root directory. Rotating the key
+//! only replaces the second salt, so no secret material ever touches the disk.
+
+use super::error::ConductorError;
+use super::error::ConductorResult;
+use holochain_conductor_api::conductor::DbEncryptionConfig;
+use holochain_keystore::MetaLairClient;
+use holochain_sqlite::key::DbKey;
+use std::path::Path;
+use std::path::PathBuf;
+use tokio::sync::RwLockReadGuard;
+
+const SALT_FILE: &str = "db_key.salt";
+const NEXT_SALT_FILE: &str = "db_key.salt.next";
+const SALT_BYTES: usize = sodoken::hash::argon2id::SALTBYTES;
+const KEY_CONTEXT: &[u8] = b"holochain-db-key";
+
+/// The salts for both steps of the key derivation,
+/// stored together as a single file.
+#[derive(Clone, Copy)]
+struct Salts {
+    master: [u8; SALT_BYTES],
+    key: [u8; SALT_BYTES],
+}
+
+impl Salts {
+    async fn random() -> ConductorResult<Self> {
+        Ok(Self {
+            master: random_salt().await?,
+            key: random_salt().await?,
+        })
+    }
+
+    fn read(path: &Path) -> ConductorResult<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let bytes = std::fs::read(path)?;
+        if bytes.len() != SALT_BYTES * 2 {
+            return Err(ConductorError::other(format!(
+                "Database key salt file {} is corrupt",
+                path.display()
+            )));
+        }
+        let mut master = [0; SALT_BYTES];
+        let mut key = [0; SALT_BYTES];
+        master.copy_from_slice(&bytes[..SALT_BYTES]);
+        key.copy_from_slice(&bytes[SALT_BYTES..]);
+        Ok(Some(Self { master, key }))
+    }
+
+    fn write(&self, path: &Path) -> ConductorResult<()> {
+        use std::io::Write;
+        let mut file = std::fs::File::create(path)?;
+        file.write_all(&self.master)?;
+        file.write_all(&self.key)?;
+        file.sync_all()?;
+        Ok(())
+    }
+}
+
+/// Holds the master key of this conductor and the current database key.
+pub struct DbKeyManager {
+    root: PathBuf,
+    master: [u8; 32],
+    current: tokio::sync::RwLock<(Salts, DbKey)>,
+}
+
+impl DbKeyManager {
+    /// Derive the database key for the databases below `root`.
+    ///
+    /// This also completes a key rotation that was interrupted and encrypts
+    /// any databases that were written without encryption, so it must run
+    /// before any database is opened.
+    pub async fn init(
+        root: &Path,
+        config: &DbEncryptionConfig,
+        keystore: &MetaLairClient,
+        passphrase: Option<sodoken::BufRead>,
+    ) -> ConductorResult<Self> {
+        std::fs::create_dir_all(root)?;
+        let salt_path = root.join(SALT_FILE);
+        let salts = match Salts::read(&salt_path)? {
+            Some(salts) => salts,
+            None => {
+                let salts = Salts::random().await?;
+                salts.write(&salt_path)?;
+                salts
+            }
+        };
+
+        let master = match config {
+            DbEncryptionConfig::Keystore => keystore.get_or_create_db_master_key().await?,
+            DbEncryptionConfig::Passphrase => {
+                let passphrase = passphrase.ok_or_else(|| {
+                    ConductorError::other("passphrase required for database encryption")
+                })?;
+                let hash = sodoken::BufWriteSized::<32>::new_mem_locked()?;
+                sodoken::hash::argon2id::hash(
+                    hash.clone(),
+                    passphrase,
+                    sodoken::BufReadSized::new_no_lock(salts.master),
+                    sodoken::hash::argon2id::OPSLIMIT_MODERATE,
+                    sodoken::hash::argon2id::MEMLIMIT_MODERATE,
+                )
+                .await?;
+                let mut master = [0; 32];
+                master.copy_from_slice(&*hash.read_lock());
+                master
+            }
+        };
+
+        let mut salts = salts;
+        let mut key = derive_key(&master, &salts.key).await?;
+
+        let next_salt_path = root.join(NEXT_SALT_FILE);
+        if let Some(next) = Salts::read(&next_salt_path)? {
+            tracing::warn!("Completing an interrupted database key rotation");
+            let next_key = derive_key(&master, &next.key).await?;
+            holochain_sqlite::key::rekey_database_dir(root, &key, &next_key).await?;
+            std::fs::rename(&next_salt_path, &salt_path)?;
+            salts = next;
+            key = next_key;
+        }
+
+        {
+            let (root, key) = (root.to_owned(), key.clone());
+            tokio::task::spawn_blocking(move || {
+                holochain_sqlite::key::encrypt_database_dir(&root, &key)
+            })
+            .await??;
+        }
+
+        Ok(Self {
+            root: root.to_owned(),
+            master,
+            current: tokio::sync::RwLock::new((salts, key)),
+        })
+    }
+
+    /// The key to open databases with.
+    /// Hold on to it until the databases are opened so the key
+    /// can't be rotated in between.
+    /// Fails instead of waiting if the key is being rotated.
+    pub fn current_key(&self) -> ConductorResult<RwLockReadGuard<'_, DbKey>> {
+        let current = self
+            .current
+            .try_read()
+            .map_err(|_| ConductorError::other("the database key is being rotated"))?;
+        Ok(RwLockReadGuard::map(current, |(_, key)| key))
+    }
+
+    /// Replace the database key with a freshly derived one and
+    /// re-encrypt every database with it.
+    ///
+    /// The new salt is written to disk before any database is touched
+    /// so an interrupted rotation is completed on the next startup.
+    pub async fn rotate(&self) -> ConductorResult<()> {
+        let mut current = self.current.write().await;
+        let (salts, old_key) = current.clone();
+        let next = Salts {
+            master: salts.master,
+            key: random_salt().await?,
+        };
+        let next_key = derive_key(&self.master, &next.key).await?;
+        let next_salt_path = self.root.join(NEXT_SALT_FILE);
+        next.write(&next_salt_path)?;
+        holochain_sqlite::key::rekey_database_dir(&self.root, &old_key, &next_key).await?;
+        std::fs::rename(&next_salt_path, self.root.join(SALT_FILE))?;
+        *current = (next, next_key);
+        tracing::info!("Database key rotated");
+        Ok(())
+    }
+}
+
+async fn random_salt() -> ConductorResult<[u8; SALT_BYTES]> {
+    let salt = sodoken::BufWriteSized::<SALT_BYTES>::new_no_lock();
+    sodoken::random::bytes_buf(salt.clone()).await?;
+    let mut out = [0; SALT_BYTES];
+    out.copy_from_slice(&*salt.read_lock());
+    Ok(out)
+}
+
+async fn derive_key(master: &[u8; 32], salt: &[u8; SALT_BYTES]) -> ConductorResult<DbKey> {
+    let hash = sodoken::BufWriteSized::<32>::new_mem_locked()?;
+    let mut message = KEY_CONTEXT.to_vec();
+    message.extend_from_slice(salt);
+    sodoken::hash::blake2b::hash_with_key(hash.clone(), message, master.to_vec()).await?;
+    let mut key = [0; 32];
+    key.copy_from_slice(&*hash.read_lock());
+    Ok(DbKey::from_bytes(key))
+}
+
+#[cfg(all(test, feature = "db-encryption"))]
+mod tests {
+    use super::*;
+    use holochain_keystore::test_keystore::spawn_test_keystore;
+    use holochain_sqlite::conn::DbSyncLevel;
+    use holochain_sqlite::db::DbKindWasm;
+    use holochain_sqlite::db::DbWrite;
+    use holochain_sqlite::key::database_files;
+    use holochain_sqlite::key::key_opens;
+
+    fn passphrase(p: &str) -> Option<sodoken::BufRead> {
+        Some(sodoken::BufRead::from(p.as_bytes().to_vec()))
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn rotated_key_is_used_after_restart() {
+        let td = tempfile::tempdir().unwrap();
+        let keystore = spawn_test_keystore().await.unwrap();
+        let config = DbEncryptionConfig::Keystore;
+
+        let manager = DbKeyManager::init(td.path(), &config, &keystore, None)
+            .await
+            .unwrap();
+        let key = manager.current_key().unwrap().clone();
+        DbWrite::open_with_key(
+            td.path(),
+            DbKindWasm,
+            DbSyncLevel::default(),
+            Some(key.clone()),
+        )
+        .unwrap();
+        let files = database_files(td.path()).unwrap();
+        assert!(!files.is_empty());
+        assert!(key_opens(&files[0], &key).unwrap());
+
+        manager.rotate().await.unwrap();
+        let rotated = manager.current_key().unwrap().clone();
+        assert_ne!(rotated, key);
+        assert!(key_opens(&files[0], &rotated).unwrap());
+        assert!(!key_opens(&files[0], &key).unwrap());
+
+        // - The rotated key is derived again on the next startup.
+        let restarted = DbKeyManager::init(td.path(), &config, &keystore, None)
+            .await
+            .unwrap();
+        assert_eq!(*restarted.current_key().unwrap(), rotated);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn wrong_passphrase_derives_a_key_which_does_not_open_databases() {
+        let td = tempfile::tempdir().unwrap();
+        let keystore = spawn_test_keystore().await.unwrap();
+        let config = DbEncryptionConfig::Passphrase;
+
+        let manager = DbKeyManager::init(td.path(), &config, &keystore, passphrase("right"))
+            .await
+            .unwrap();
+        let key = manager.current_key().unwrap().clone();
+        DbWrite::open_with_key(
+            td.path(),
+            DbKindWasm,
+            DbSyncLevel::default(),
+            Some(key.clone()),
+        )
+        .unwrap();
+        let file = database_files(td.path()).unwrap().remove(0);
+
+        let reopened = DbKeyManager::init(td.path(), &config, &keystore, passphrase("right"))
+            .await
+            .unwrap();
+        assert_eq!(*reopened.current_key().unwrap(), key);
+
+        let wrong = DbKeyManager::init(td.path(), &config, &keystore, passphrase("wrong"))
+            .await
+            .unwrap();
+        assert_ne!(*wrong.current_key().unwrap(), key);
+        assert!(!key_opens(&file, &wrong.current_key().unwrap()).unwrap());
+
+        // - A passphrase is required.
+        assert!(DbKeyManager::init(td.path(), &config, &keystore, None)
+            .await
+            .is_err());
+    }
+}
@@ -83,12 +83,21 @@ pub enum ConductorError {
     #[error("Tried to access an app that was not installed: {0}")]
     AppNotInstalled(InstalledAppId),
 
+    #[error("Can't remove app {0} because these apps reuse its cells: {1:?}")]
+    AppHasDependents(InstalledAppId, Vec<InstalledAppId>),
+
+    #[error("The app authentication token is invalid or has expired")]
+    AppAuthenticationFailed,
+
     #[error("Tried to install an app using an already-used InstalledAppId: {0}")]
     AppAlreadyInstalled(InstalledAppId),
 
     #[error("Tried to perform an operation on an app that was not running: {0}")]
     AppNotRunning(InstalledAppId),
 
+    #[error("Can't delete the databases of dna {0} while cells of it are in the conductor")]
+    SpaceInUse(DnaHash),
+
     #[error(transparent)]
     HolochainP2pError(#[from] holochain_p2p::HolochainP2pError),
 
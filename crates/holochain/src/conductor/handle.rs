@@ -35,10 +35,12 @@
 //! types for testing. If we did not have a way of hiding this type genericity,
 //! code which interacted with the Conductor would also have to be highly generic.
 
+use super::api::error::ConductorApiError;
 use super::api::error::ConductorApiResult;
 use super::api::ZomeCall;
 use super::conductor::CellStatus;
 use super::config::AdminInterfaceConfig;
+use super::error::ConductorError;
 use super::error::ConductorResult;
 use super::interface::SignalBroadcaster;
 use super::manager::spawn_task_manager;
@@ -61,14 +63,21 @@ use crate::core::queue_consumer::QueueConsumerMap;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitArgs;
 use crate::core::ribosome::real_ribosome::RealRibosome;
 use crate::core::ribosome::RibosomeT;
+use crate::core::warrant::block_warranted_agents;
 use crate::core::workflow::ZomeCallResult;
 use derive_more::From;
 use futures::future::FutureExt;
 use futures::StreamExt;
 use holochain_conductor_api::conductor::ConductorConfig;
+use holochain_conductor_api::config::AllowedOrigins;
+use holochain_conductor_api::AppAuthenticationToken;
+use holochain_conductor_api::AppAuthenticationTokenIssued;
 use holochain_conductor_api::AppStatusFilter;
+use holochain_conductor_api::CryptoRequest;
+use holochain_conductor_api::CryptoResponse;
 use holochain_conductor_api::FullStateDump;
 use holochain_conductor_api::InstalledAppInfo;
+use holochain_conductor_api::IssueAppAuthenticationTokenPayload;
 use holochain_conductor_api::JsonDump;
 use holochain_keystore::MetaLairClient;
 use holochain_p2p::actor::HolochainP2pRefToDna;
@@ -81,7 +90,11 @@ use holochain_state::prelude::SourceChainError;
 use holochain_state::prelude::SourceChainResult;
 use holochain_state::prelude::StateMutationError;
 use holochain_state::prelude::StateMutationResult;
+use holochain_state::prelude::StateQueryResult;
 use holochain_state::source_chain;
+use holochain_state::validation_receipts;
+use holochain_state::workflow_queues;
+use holochain_state::workflow_queues::WorkflowQueuesDump;
 use holochain_types::prelude::*;
 use kitsune_p2p::agent_store::AgentInfoSigned;
 use kitsune_p2p_types::config::JOIN_NETWORK_TIMEOUT;
@@ -129,7 +142,12 @@ pub trait ConductorHandleT: Send + Sync {
     ) -> ConductorResult<()>;
 
     /// Add an app interface
-    async fn add_app_interface(self: Arc<Self>, port: u16) -> ConductorResult<u16>;
+    async fn add_app_interface(
+        self: Arc<Self>,
+        port: u16,
+        allowed_origins: AllowedOrigins,
+        require_authentication: bool,
+    ) -> ConductorResult<u16>;
 
     /// List the app interfaces currently installed.
     async fn list_app_interfaces(&self) -> ConductorResult<Vec<u16>>;
@@ -208,14 +226,42 @@ pub trait ConductorHandleT: Send + Sync {
     /// Prune expired agent_infos from the p2p agents database
     async fn prune_p2p_agents_db(&self) -> ConductorResult<()>;
 
+    /// Evict the least recently fetched data from the cache databases
+    /// until they are within the limits of the cache config
+    async fn evict_cache_dbs(&self) -> ConductorResult<()>;
+
+    /// Delete the DHT data which has been outside the storage arcs
+    /// of the local agents for the grace period of the arc GC config
+    async fn collect_arc_garbage(&self) -> ConductorResult<()>;
+
+    /// Render the metrics of this conductor in the Prometheus text format
+    async fn render_metrics(&self) -> ConductorResult<String>;
+
     /// Create a new Cell in an existing App based on an existing DNA
     async fn create_clone_cell(
         self: Arc<Self>,
         payload: CreateCloneCellPayload,
     ) -> ConductorResult<CellId>;
 
-    /// Destroy a cloned Cell
-    async fn destroy_clone_cell(self: Arc<Self>, cell_id: CellId) -> ConductorResult<()>;
+    /// Archive a clone cell of an app, stopping the cell but keeping its data
+    async fn archive_clone_cell(
+        self: Arc<Self>,
+        payload: ArchiveCloneCellPayload,
+    ) -> ConductorResult<()>;
+
+    /// Restore an archived clone cell to its app, starting the cell again
+    /// if the app is running
+    async fn restore_clone_cell(
+        self: Arc<Self>,
+        payload: ArchiveCloneCellPayload,
+    ) -> ConductorResult<CellId>;
+
+    /// Permanently delete all archived clone cells of an app role,
+    /// including the databases of their DNAs
+    async fn delete_archived_clone_cells(
+        self: Arc<Self>,
+        payload: DeleteArchivedCloneCellsPayload,
+    ) -> ConductorResult<()>;
 
     /// Install Cells into ConductorState based on installation info, and run
     /// genesis on all new source chains
@@ -326,6 +372,47 @@ pub trait ConductorHandleT: Send + Sync {
     /// Dump the network metrics
     async fn dump_network_metrics(&self, dna_hash: Option<DnaHash>) -> ConductorApiResult<String>;
 
+    /// Dump the validation receipts received by a cell, grouped by op.
+    /// If no action hash is given, the ops of all the cell's actions are dumped.
+    async fn dump_validation_receipts(
+        &self,
+        cell_id: &CellId,
+        action_hash: Option<ActionHash>,
+    ) -> ConductorApiResult<Vec<ValidationReceiptSet>>;
+
+    /// Dump the workflow queues of a cell and the ops in them
+    /// which are waiting on dependencies.
+    async fn dump_workflow_queues(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorApiResult<WorkflowQueuesDump>;
+
+    /// Put ops of a cell back into the sys validation queue.
+    ///
+    /// If `op_hashes` is `None` every abandoned op is re-queued.
+    /// Returns the hashes of the ops which were re-queued.
+    async fn requeue_ops_for_validation(
+        &self,
+        cell_id: &CellId,
+        op_hashes: Option<Vec<DhtOpHash>>,
+    ) -> ConductorApiResult<Vec<DhtOpHash>>;
+
+    /// List the countersigning sessions the chains of the cells on this
+    /// conductor are locked for.
+    async fn list_countersigning_sessions(
+        &self,
+    ) -> ConductorApiResult<Vec<CountersigningSessionInfo>>;
+
+    /// Abandon the countersigning session a cell's chain is locked for
+    /// and unlock the chain.
+    async fn abandon_countersigning_session(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorApiResult<Option<CountersigningSessionInfo>>;
+
+    /// Re-encrypt every database with a newly derived key.
+    async fn rotate_db_key(&self) -> ConductorResult<()>;
+
     /// Access the broadcast Sender which will send a Signal across every
     /// attached app interface
     async fn signal_broadcaster(&self) -> SignalBroadcaster;
@@ -336,6 +423,22 @@ pub trait ConductorHandleT: Send + Sync {
         installed_app_id: &InstalledAppId,
     ) -> ConductorResult<Option<InstalledAppInfo>>;
 
+    /// Sign, encrypt or decrypt data with the agent keys of an installed app
+    async fn app_crypto(&self, request: CryptoRequest) -> ConductorApiResult<CryptoResponse>;
+
+    /// Issue a token which an app interface connection can use to
+    /// authenticate as the given app
+    async fn issue_app_authentication_token(
+        &self,
+        payload: IssueAppAuthenticationTokenPayload,
+    ) -> ConductorResult<AppAuthenticationTokenIssued>;
+
+    /// Check an app authentication token, returning the app it was issued for
+    fn authenticate_app_token(
+        &self,
+        token: &AppAuthenticationToken,
+    ) -> ConductorResult<InstalledAppId>;
+
     /// Add signed agent info to the conductor
     async fn add_agent_infos(&self, agent_infos: Vec<AgentInfoSigned>) -> ConductorApiResult<()>;
 
@@ -361,6 +464,22 @@ pub trait ConductorHandleT: Send + Sync {
         records: Vec<Record>,
     ) -> ConductorApiResult<()>;
 
+    /// Export the full source chain of a cell, signed by the cell's agent.
+    async fn export_source_chain(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorApiResult<SignedSourceChainArchive>;
+
+    /// Import an exported source chain into a cell which has no chain yet.
+    ///
+    /// The integrity of the archive is always checked.
+    /// If `validate` is true the records are also validated by the DNA.
+    async fn import_source_chain(
+        self: Arc<Self>,
+        archive: SignedSourceChainArchive,
+        validate: bool,
+    ) -> ConductorApiResult<()>;
+
     /// Retrieve the authored environment for this dna. FOR TESTING ONLY.
     #[cfg(any(test, feature = "test_utils"))]
     fn get_authored_db(&self, cell_id: &DnaHash) -> ConductorApiResult<DbWrite<DbKindAuthored>>;
@@ -553,6 +672,14 @@ impl ConductorHandleT for ConductorHandleImpl {
             .startup_app_interfaces_via_handle(self.clone())
             .await?;
 
+        self.conductor
+            .add_db_maintenance_tasks_via_handle(self.clone())
+            .await?;
+
+        self.conductor
+            .start_metrics_server_via_handle(self.clone())
+            .await?;
+
         // We don't care what fx are returned here, since all cells need to
         // be spun up
         let _ = self.conductor.start_paused_apps().await?;
@@ -560,9 +687,19 @@ impl ConductorHandleT for ConductorHandleImpl {
         self.process_app_status_fx(AppStatusFx::SpinUp, None).await
     }
 
-    async fn add_app_interface(self: Arc<Self>, port: u16) -> ConductorResult<u16> {
+    async fn add_app_interface(
+        self: Arc<Self>,
+        port: u16,
+        allowed_origins: AllowedOrigins,
+        require_authentication: bool,
+    ) -> ConductorResult<u16> {
         self.conductor
-            .add_app_interface_via_handle(either::Left(port), self.clone())
+            .add_app_interface_via_handle(
+                either::Left(port),
+                allowed_origins,
+                require_authentication,
+                self.clone(),
+            )
             .await
     }
 
@@ -775,6 +912,7 @@ impl ConductorHandleT for ConductorHandleImpl {
             | Get { .. }
             | GetMeta { .. }
             | GetLinks { .. }
+            | CountLinks { .. }
             | GetAgentActivity { .. }
             | ValidationReceiptReceived { .. } => {
                 let cell_id = CellId::new(event.dna_hash().clone(), event.target_agents().clone());
@@ -806,6 +944,33 @@ impl ConductorHandleT for ConductorHandleImpl {
                 .instrument(debug_span!("handle_publish"))
                 .await;
             }
+            PublishWarrants {
+                dna_hash,
+                respond,
+                warrants,
+                ..
+            } => {
+                use holochain_p2p::HolochainP2pSender;
+                async {
+                    let res = match self
+                        .conductor
+                        .spaces
+                        .handle_publish_warrants(&dna_hash, warrants)
+                        .await
+                    {
+                        Ok(proven) if !proven.is_empty() => self
+                            .holochain_p2p()
+                            .block_agents(dna_hash, proven)
+                            .await
+                            .map_err(holochain_p2p::HolochainP2pError::other),
+                        Ok(_) => Ok(()),
+                        Err(e) => Err(holochain_p2p::HolochainP2pError::other(e)),
+                    };
+                    respond.respond(Ok(async move { res }.boxed().into()));
+                }
+                .instrument(debug_span!("handle_publish_warrants"))
+                .await;
+            }
             FetchOpData {
                 respond,
                 query,
@@ -890,6 +1055,18 @@ impl ConductorHandleT for ConductorHandleImpl {
         self.conductor.prune_p2p_agents_db().await
     }
 
+    async fn evict_cache_dbs(&self) -> ConductorResult<()> {
+        self.conductor.evict_cache_dbs().await
+    }
+
+    async fn collect_arc_garbage(&self) -> ConductorResult<()> {
+        self.conductor.collect_arc_garbage().await
+    }
+
+    async fn render_metrics(&self) -> ConductorResult<String> {
+        self.conductor.render_metrics().await
+    }
+
     async fn create_clone_cell(
         self: Arc<Self>,
         payload: CreateCloneCellPayload,
@@ -916,8 +1093,60 @@ impl ConductorHandleT for ConductorHandleImpl {
         Ok(cell_id)
     }
 
-    async fn destroy_clone_cell(self: Arc<Self>, _cell_id: CellId) -> ConductorResult<()> {
-        todo!()
+    async fn archive_clone_cell(
+        self: Arc<Self>,
+        payload: ArchiveCloneCellPayload,
+    ) -> ConductorResult<()> {
+        self.conductor.archive_clone_cell_in_app(payload).await?;
+
+        // Remove the cell, which is no longer part of the app
+        self.process_app_status_fx(AppStatusFx::SpinDown, None)
+            .await?;
+        Ok(())
+    }
+
+    async fn restore_clone_cell(
+        self: Arc<Self>,
+        payload: ArchiveCloneCellPayload,
+    ) -> ConductorResult<CellId> {
+        let app_id = payload.app_id.clone();
+        let cell_id = payload.clone_cell_id.clone();
+        self.conductor.restore_clone_cell_in_app(payload).await?;
+
+        // Start the cell again if the app is running
+        self.process_app_status_fx(
+            AppStatusFx::SpinUp,
+            Some(vec![app_id].into_iter().collect()),
+        )
+        .await?;
+        Ok(cell_id)
+    }
+
+    async fn delete_archived_clone_cells(
+        self: Arc<Self>,
+        payload: DeleteArchivedCloneCellsPayload,
+    ) -> ConductorResult<()> {
+        let deleted = self
+            .conductor
+            .delete_archived_clone_cells_in_app(payload)
+            .await?;
+
+        // A DNA's databases are shared by all cells of that DNA,
+        // so they can only be deleted if no other cell still uses them.
+        let state = self.conductor.get_state().await?;
+        let dnas_in_use: HashSet<&DnaHash> = state
+            .installed_apps()
+            .values()
+            .flat_map(|app| app.all_cells().chain(app.archived_clone_cells()))
+            .map(|cell_id| cell_id.dna_hash())
+            .collect();
+        for cell_id in deleted {
+            if !dnas_in_use.contains(cell_id.dna_hash()) {
+                self.conductor.delete_space(cell_id.dna_hash()).await?;
+                tracing::info!(?cell_id, "Deleted archived clone cell");
+            }
+        }
+        Ok(())
     }
 
     async fn install_app(
@@ -969,8 +1198,9 @@ impl ConductorHandleT for ConductorHandleImpl {
 
         let installed_app_id =
             installed_app_id.unwrap_or_else(|| bundle.manifest().app_name().to_owned());
+        let gamut = self.get_dna_gamut().await?;
         let ops = bundle
-            .resolve_cells(agent_key.clone(), DnaGamut::placeholder(), membrane_proofs)
+            .resolve_cells(agent_key.clone(), gamut, membrane_proofs)
             .await?;
 
         let cells_to_create = ops.cells_to_create();
@@ -1132,6 +1362,11 @@ impl ConductorHandleT for ConductorHandleImpl {
         let app = self.conductor.remove_app_from_db(installed_app_id).await?;
         tracing::debug!(msg = "Removed app from db.", app = ?app);
 
+        // Tokens issued for the app must not outlive it
+        self.conductor
+            .app_auth_token_store
+            .share_mut(|store| store.revoke_app_tokens(installed_app_id));
+
         // Remove cells which may now be dangling due to the removed app
         self_clone
             .process_app_status_fx(AppStatusFx::SpinDown, None)
@@ -1237,6 +1472,118 @@ impl ConductorHandleT for ConductorHandleImpl {
             .map_err(super::api::error::ConductorApiError::other)
     }
 
+    async fn dump_validation_receipts(
+        &self,
+        cell_id: &CellId,
+        action_hash: Option<ActionHash>,
+    ) -> ConductorApiResult<Vec<ValidationReceiptSet>> {
+        let dht_db = self.conductor.get_or_create_dht_db(cell_id.dna_hash())?;
+        let author = cell_id.agent_pubkey().clone();
+        Ok(dht_db
+            .async_reader(move |txn| {
+                validation_receipts::list_receipt_sets(&txn, action_hash.as_ref(), Some(&author))
+            })
+            .await?)
+    }
+
+    async fn dump_workflow_queues(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorApiResult<WorkflowQueuesDump> {
+        let now = Timestamp::now();
+        let dht_db = self.conductor.get_or_create_dht_db(cell_id.dna_hash())?;
+        let (mut queues, blocked_ops) = dht_db
+            .async_reader(move |txn| {
+                StateQueryResult::Ok((
+                    workflow_queues::dht_queues(&txn, now)?,
+                    workflow_queues::blocked_ops(&txn)?,
+                ))
+            })
+            .await?;
+        let authored_db = self
+            .conductor
+            .get_or_create_authored_db(cell_id.dna_hash())?;
+        let author = cell_id.agent_pubkey().clone();
+        queues.push(
+            authored_db
+                .async_reader(move |txn| workflow_queues::publish_queue(&txn, &author, now))
+                .await?,
+        );
+        Ok(WorkflowQueuesDump {
+            queues,
+            blocked_ops,
+        })
+    }
+
+    async fn requeue_ops_for_validation(
+        &self,
+        cell_id: &CellId,
+        op_hashes: Option<Vec<DhtOpHash>>,
+    ) -> ConductorApiResult<Vec<DhtOpHash>> {
+        let cell = self.conductor.cell_by_id(cell_id)?;
+        let requeued = self
+            .conductor
+            .spaces
+            .requeue_ops_for_validation(cell_id.dna_hash(), op_hashes)
+            .await?;
+        if !requeued.is_empty() {
+            cell.triggers()
+                .sys_validation
+                .trigger(&"requeue_ops_for_validation");
+        }
+        Ok(requeued)
+    }
+
+    async fn list_countersigning_sessions(
+        &self,
+    ) -> ConductorApiResult<Vec<CountersigningSessionInfo>> {
+        let mut sessions = Vec::new();
+        for cell_id in self.conductor.list_cell_ids(None) {
+            let authored_db = self
+                .conductor
+                .get_or_create_authored_db(cell_id.dna_hash())?;
+            let session = authored_db
+                .async_reader(move |txn| {
+                    holochain_state::countersigning::countersigning_session_info(&txn, &cell_id)
+                })
+                .await?;
+            sessions.extend(session);
+        }
+        Ok(sessions)
+    }
+
+    async fn abandon_countersigning_session(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorApiResult<Option<CountersigningSessionInfo>> {
+        let authored_db = self
+            .conductor
+            .get_or_create_authored_db(cell_id.dna_hash())?;
+        let cell_id = cell_id.clone();
+        let session = authored_db
+            .async_commit(move |txn| {
+                holochain_state::countersigning::abandon_countersigning_session(txn, &cell_id)
+            })
+            .await?;
+        if let Some(session) = &session {
+            tracing::warn!(
+                cell_id = ?session.cell_id,
+                entry_hash = ?session.entry_hash,
+                "Abandoned countersigning session"
+            );
+        }
+        Ok(session)
+    }
+
+    async fn rotate_db_key(&self) -> ConductorResult<()> {
+        match &self.conductor.spaces.db_key {
+            Some(db_key) => db_key.rotate().await,
+            None => Err(ConductorError::other(
+                "Database encryption is not enabled in the conductor config",
+            )),
+        }
+    }
+
     async fn signal_broadcaster(&self) -> SignalBroadcaster {
         self.conductor.signal_broadcaster()
     }
@@ -1252,6 +1599,106 @@ impl ConductorHandleT for ConductorHandleImpl {
             .get_app_info(installed_app_id))
     }
 
+    async fn issue_app_authentication_token(
+        &self,
+        payload: IssueAppAuthenticationTokenPayload,
+    ) -> ConductorResult<AppAuthenticationTokenIssued> {
+        let IssueAppAuthenticationTokenPayload {
+            installed_app_id,
+            expiry_seconds,
+            single_use,
+        } = payload;
+        // Tokens may only be issued for apps which are installed.
+        self.conductor
+            .get_state()
+            .await?
+            .get_app(&installed_app_id)?;
+        let (token, expires_at) = self.conductor.app_auth_token_store.share_mut(|store| {
+            store.issue_token(
+                installed_app_id,
+                std::time::Duration::from_secs(expiry_seconds),
+                single_use,
+            )
+        });
+        Ok(AppAuthenticationTokenIssued { token, expires_at })
+    }
+
+    fn authenticate_app_token(
+        &self,
+        token: &AppAuthenticationToken,
+    ) -> ConductorResult<InstalledAppId> {
+        self.conductor
+            .app_auth_token_store
+            .share_mut(|store| store.authenticate_token(token))
+    }
+
+    async fn app_crypto(&self, request: CryptoRequest) -> ConductorApiResult<CryptoResponse> {
+        let state = self.conductor.get_state().await?;
+        let app = state.get_app(request.installed_app_id())?;
+        // Only the agents of the app's own cells may be used.
+        let check_agent = |agent_key: &AgentPubKey| {
+            if app
+                .all_cells()
+                .any(|cell_id| cell_id.agent_pubkey() == agent_key)
+            {
+                Ok(())
+            } else {
+                Err(ConductorApiError::AgentNotInApp(
+                    app.id().clone(),
+                    agent_key.clone(),
+                ))
+            }
+        };
+        let keystore = self.keystore();
+        match request {
+            CryptoRequest::Sign { payload, .. } => {
+                check_agent(&payload.key)?;
+                let Sign { key, data } = payload;
+                let signature = keystore.sign(key, data.into_vec().into()).await?;
+                Ok(CryptoResponse::Signature(signature))
+            }
+            CryptoRequest::Encrypt {
+                agent_key,
+                recipient,
+                data,
+                ..
+            } => {
+                check_agent(&agent_key)?;
+                let sender = keystore.agent_x25519_pub_key(agent_key).await?;
+                let mut recipient_key = [0; 32];
+                recipient_key.copy_from_slice(recipient.as_ref());
+                let (nonce, cipher) = keystore
+                    .crypto_box_xsalsa(sender, recipient_key.into(), data.as_ref().into())
+                    .await?;
+                Ok(CryptoResponse::Encrypted(
+                    XSalsa20Poly1305EncryptedData::new(nonce.into(), cipher.to_vec()),
+                ))
+            }
+            CryptoRequest::Decrypt {
+                agent_key,
+                sender,
+                encrypted_data,
+                ..
+            } => {
+                check_agent(&agent_key)?;
+                let recipient = keystore.agent_x25519_pub_key(agent_key).await?;
+                let mut sender_key = [0; 32];
+                sender_key.copy_from_slice(sender.as_ref());
+                let mut nonce = [0; 24];
+                nonce.copy_from_slice(encrypted_data.as_nonce_ref().as_ref());
+                let data = keystore
+                    .crypto_box_xsalsa_open(
+                        sender_key.into(),
+                        recipient,
+                        nonce,
+                        encrypted_data.as_encrypted_data_ref().into(),
+                    )
+                    .await?;
+                Ok(CryptoResponse::Decrypted(data.to_vec().into()))
+            }
+        }
+    }
+
     async fn add_agent_infos(&self, agent_infos: Vec<AgentInfoSigned>) -> ConductorApiResult<()> {
         let mut space_map = HashMap::new();
         for agent_info_signed in agent_infos {
@@ -1466,6 +1913,60 @@ impl ConductorHandleT for ConductorHandleImpl {
         Ok(())
     }
 
+    async fn export_source_chain(
+        &self,
+        cell_id: &CellId,
+    ) -> ConductorApiResult<SignedSourceChainArchive> {
+        let authored_db = self
+            .conductor
+            .get_or_create_authored_db(cell_id.dna_hash())?;
+        let records =
+            source_chain::export_records(authored_db.into(), cell_id.agent_pubkey().clone())
+                .await?;
+        if records.is_empty() {
+            return Err(SourceChainError::ChainEmpty.into());
+        }
+        Ok(SourceChainArchive::new(cell_id.clone(), records)
+            .sign(self.conductor.keystore())
+            .await?)
+    }
+
+    async fn import_source_chain(
+        self: Arc<Self>,
+        archive: SignedSourceChainArchive,
+        validate: bool,
+    ) -> ConductorApiResult<()> {
+        archive.verify().await?;
+        let cell_id = archive.archive.cell_id();
+        if self.get_dna_def(cell_id.dna_hash()).is_none() {
+            return Err(ConductorApiError::DnaMissing(cell_id.dna_hash().clone()));
+        }
+
+        // Only restore into an empty chain so the archive can't fork it.
+        let authored_db = self
+            .conductor
+            .get_or_create_authored_db(cell_id.dna_hash())?;
+        let chain_head = authored_db
+            .async_reader({
+                let author = Arc::new(cell_id.agent_pubkey().clone());
+                move |txn| holochain_state::prelude::chain_head_db(&txn, author)
+            })
+            .await;
+        match chain_head {
+            Err(SourceChainError::ChainEmpty) => (),
+            Err(e) => return Err(e.into()),
+            Ok(_) => {
+                return Err(ConductorApiError::other(format!(
+                    "Can't import a source chain for {:?} because it already has a chain",
+                    cell_id
+                )))
+            }
+        }
+
+        self.insert_records_into_source_chain(cell_id, false, validate, archive.archive.records)
+            .await
+    }
+
     #[cfg(any(test, feature = "test_utils"))]
     fn get_authored_db(&self, dna_hash: &DnaHash) -> ConductorApiResult<DbWrite<DbKindAuthored>> {
         Ok(self.conductor.get_or_create_authored_db(dna_hash)?)
@@ -1611,6 +2112,19 @@ impl ConductorHandleImpl {
         Ok(last.1)
     }
 
+    /// All registered DNAs and the cells of all installed apps,
+    /// to resolve the roles of a new app against.
+    async fn get_dna_gamut(&self) -> ConductorResult<DnaGamut> {
+        let state = self.conductor.get_state().await?;
+        let cells: Vec<CellId> = state
+            .installed_apps()
+            .values()
+            .flat_map(|app| app.all_cells().cloned().collect::<Vec<_>>())
+            .collect();
+        let dnas = self.conductor.ribosome_store().share_ref(|ds| ds.list());
+        Ok(DnaGamut::new(cells).with_dnas(dnas))
+    }
+
     /// Create any Cells which are missing for any running apps, then initialize
     /// and join them. (Joining could take a while.)
     pub(super) async fn create_and_add_initialized_cells_for_running_apps(
@@ -1684,7 +2198,13 @@ impl ConductorHandleImpl {
                         tracing::info!(cell_id = ?cell_id, "Timed out trying to join the network");
                         Err(cell_id)
                     }
-                    Ok(Ok(_)) => Ok(cell_id),
+                    Ok(Ok(_)) => {
+                        // Stop talking to anyone we hold proven warrants for.
+                        if let Err(e) = block_warranted_agents(cell.dht_db(), &network).await {
+                            tracing::warn!(error = ?e, cell_id = ?cell_id, "Failed to block warranted agents");
+                        }
+                        Ok(cell_id)
+                    }
                 }
             });
 
@@ -3,11 +3,22 @@
 
 use super::error::InterfaceError;
 use super::error::InterfaceResult;
+use crate::conductor::api::AdminRequest;
+use crate::conductor::api::AdminResponse;
+use crate::conductor::api::AppAuthenticationToken;
+use crate::conductor::api::AppRequest;
+use crate::conductor::api::AppResponse;
+use crate::conductor::api::ExternalApiWireError;
 use crate::conductor::conductor::StopReceiver;
 use crate::conductor::interface::*;
 use crate::conductor::manager::ManagedTaskHandle;
 use crate::conductor::manager::ManagedTaskResult;
+use holochain_conductor_api::config::AdminAuthConfig;
+use holochain_conductor_api::config::AllowedOrigins;
+use holochain_conductor_api::signal_subscription::SignalFilterSet;
+use holochain_conductor_api::signal_subscription::SignalSubscription;
 use holochain_serialized_bytes::SerializedBytes;
+use holochain_types::app::InstalledAppId;
 use holochain_types::signal::Signal;
 use holochain_websocket::ListenerHandle;
 use holochain_websocket::ListenerItem;
@@ -16,11 +27,18 @@ use holochain_websocket::WebsocketListener;
 use holochain_websocket::WebsocketMessage;
 use holochain_websocket::WebsocketReceiver;
 use holochain_websocket::WebsocketSender;
+use holochain_zome_types::cell::CellId;
+use parking_lot::Mutex;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::convert::TryFrom;
 
 use std::sync::atomic::AtomicIsize;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
 use tokio::sync::broadcast;
 use tokio_stream::StreamExt;
 use tracing::*;
@@ -35,6 +53,7 @@ const MAX_CONNECTIONS: isize = 400;
 /// Create a WebsocketListener to be used in interfaces
 pub async fn spawn_websocket_listener(
     port: u16,
+    allowed_origins: AllowedOrigins,
 ) -> InterfaceResult<(
     ListenerHandle,
     impl futures::stream::Stream<Item = ListenerItem>,
@@ -42,7 +61,7 @@ pub async fn spawn_websocket_listener(
     trace!("Initializing Admin interface");
     let listener = WebsocketListener::bind_with_handle(
         url2!("ws://127.0.0.1:{}", port),
-        Arc::new(WebsocketConfig::default()),
+        Arc::new(WebsocketConfig::default().allowed_origins(allowed_origins.into())),
     )
     .await?;
     trace!("LISTENING AT: {}", listener.0.local_addr());
@@ -50,13 +69,20 @@ pub async fn spawn_websocket_listener(
 }
 
 /// Create an Admin Interface, which only receives AdminRequest messages
-/// from the external client
-pub fn spawn_admin_interface_task<A: InterfaceApi>(
+/// from the external client.
+/// If `auth` is set, each connection must authenticate with its token
+/// before any other request is handled.
+pub fn spawn_admin_interface_task<A>(
     handle: ListenerHandle,
     listener: impl futures::stream::Stream<Item = ListenerItem> + Send + 'static,
     api: A,
+    auth: Option<AdminAuthConfig>,
     mut stop_rx: StopReceiver,
-) -> InterfaceResult<ManagedTaskHandle> {
+) -> InterfaceResult<ManagedTaskHandle>
+where
+    A: InterfaceApi<ApiRequest = AdminRequest, ApiResponse = AdminResponse>,
+{
+    let auth = auth.map(Arc::new);
     Ok(tokio::task::spawn(async move {
         // Task that will kill the listener and all child connections.
         tokio::task::spawn(
@@ -77,6 +103,7 @@ pub fn spawn_admin_interface_task<A: InterfaceApi>(
                     tokio::task::spawn(recv_incoming_admin_msgs(
                         api.clone(),
                         rx_from_iface,
+                        auth.clone().map(AdminSession::new),
                         num_connections.clone(),
                     ));
                 }
@@ -90,9 +117,15 @@ pub fn spawn_admin_interface_task<A: InterfaceApi>(
 }
 
 /// Create an App Interface, which includes the ability to receive signals
-/// from Cells via a broadcast channel
-pub async fn spawn_app_interface_task<A: InterfaceApi>(
+/// from Cells via a broadcast channel.
+/// If `require_authentication` is set, each connection must authenticate
+/// with an app authentication token before any other request is handled.
+pub async fn spawn_app_interface_task<
+    A: InterfaceApi<ApiRequest = AppRequest, ApiResponse = AppResponse>,
+>(
     port: u16,
+    allowed_origins: AllowedOrigins,
+    require_authentication: bool,
     api: A,
     signal_broadcaster: broadcast::Sender<Signal>,
     mut stop_rx: StopReceiver,
@@ -100,7 +133,7 @@ pub async fn spawn_app_interface_task<A: InterfaceApi>(
     trace!("Initializing App interface");
     let (handle, mut listener) = WebsocketListener::bind_with_handle(
         url2!("ws://127.0.0.1:{}", port),
-        Arc::new(WebsocketConfig::default()),
+        Arc::new(WebsocketConfig::default().allowed_origins(allowed_origins.into())),
     )
     .await?;
     trace!("LISTENING AT: {}", handle.local_addr());
@@ -123,6 +156,7 @@ pub async fn spawn_app_interface_task<A: InterfaceApi>(
                         rx_from_iface,
                         rx_from_cell,
                         tx_to_iface,
+                        AppConnectionAuth::new(require_authentication),
                     );
                 }
                 Err(err) => {
@@ -138,18 +172,26 @@ pub async fn spawn_app_interface_task<A: InterfaceApi>(
 
 /// Polls for messages coming in from the external client.
 /// Used by Admin interface.
-async fn recv_incoming_admin_msgs<A: InterfaceApi>(
+async fn recv_incoming_admin_msgs<A>(
     api: A,
     rx_from_iface: WebsocketReceiver,
+    session: Option<AdminSession>,
     num_connections: Arc<AtomicIsize>,
-) {
+) where
+    A: InterfaceApi<ApiRequest = AdminRequest, ApiResponse = AdminResponse>,
+{
     use futures::stream::StreamExt;
 
     rx_from_iface
         .for_each_concurrent(4096, move |msg| {
             let api = api.clone();
+            let session = session.clone();
             async move {
-                if let Err(e) = handle_incoming_message(msg, api.clone()).await {
+                let result = match session {
+                    Some(session) => handle_incoming_admin_message(msg, api, session).await,
+                    None => handle_incoming_message(msg, api).await,
+                };
+                if let Err(e) = result {
                     error!(error = &e as &dyn std::error::Error)
                 }
             }
@@ -158,19 +200,289 @@ async fn recv_incoming_admin_msgs<A: InterfaceApi>(
     num_connections.fetch_sub(1, Ordering::SeqCst);
 }
 
+/// The authentication state of a single connection to an Admin interface
+/// which requires authentication.
+#[derive(Clone)]
+struct AdminSession {
+    auth: Arc<AdminAuthConfig>,
+    authenticated_at: Arc<Mutex<Option<Instant>>>,
+}
+
+impl AdminSession {
+    fn new(auth: Arc<AdminAuthConfig>) -> Self {
+        Self {
+            auth,
+            authenticated_at: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Check a token against the configured one, starting a new session if it matches.
+    fn authenticate(&self, token: &str) -> bool {
+        let matches = constant_time_eq(self.auth.token.as_bytes(), token.as_bytes());
+        if matches {
+            *self.authenticated_at.lock() = Some(Instant::now());
+        }
+        matches
+    }
+
+    /// Whether the connection has authenticated and its session has not expired.
+    fn is_authenticated(&self) -> bool {
+        match *self.authenticated_at.lock() {
+            Some(authenticated_at) => self.auth.session_duration_seconds.map_or(true, |seconds| {
+                authenticated_at.elapsed() < Duration::from_secs(seconds.into())
+            }),
+            None => false,
+        }
+    }
+}
+
+/// Compare two byte strings without short circuiting on the first difference,
+/// so that the time taken doesn't reveal how much of a token was correct.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Handles messages on Admin interfaces which require authentication
+async fn handle_incoming_admin_message<A>(
+    ws_msg: WebsocketMessage,
+    api: A,
+    session: AdminSession,
+) -> InterfaceResult<()>
+where
+    A: InterfaceApi<ApiRequest = AdminRequest, ApiResponse = AdminResponse>,
+{
+    let (bytes, respond) = ws_msg;
+    let response = match bytes.try_into() {
+        Ok(AdminRequest::Authenticate { token }) => {
+            if session.authenticate(&token) {
+                AdminResponse::Authenticated
+            } else {
+                AdminResponse::Error(ExternalApiWireError::Unauthenticated(
+                    "The admin authentication token is invalid".to_string(),
+                ))
+            }
+        }
+        _ if !session.is_authenticated() => {
+            AdminResponse::Error(ExternalApiWireError::Unauthenticated(
+                "The connection must authenticate before making admin requests".to_string(),
+            ))
+        }
+        request => api.handle_request(request).await?,
+    };
+    Ok(respond.respond(response.try_into()?).await?)
+}
+
+/// Look up the cells of an app through the api.
+/// If that fails, the error response for the client is returned instead.
+async fn lookup_app_cells<A>(
+    api: &A,
+    installed_app_id: &InstalledAppId,
+) -> InterfaceResult<Result<HashSet<CellId>, AppResponse>>
+where
+    A: InterfaceApi<ApiRequest = AppRequest, ApiResponse = AppResponse>,
+{
+    let request = AppRequest::AppInfo {
+        installed_app_id: installed_app_id.clone(),
+    };
+    match api.handle_request(Ok(request)).await? {
+        AppResponse::AppInfo(Some(info)) => Ok(Ok(info
+            .cell_data
+            .into_iter()
+            .map(|cell| cell.into_id())
+            .collect())),
+        AppResponse::AppInfo(None) => Ok(Err(AppResponse::Error(
+            ExternalApiWireError::InternalError(format!(
+                "App {} is not installed",
+                installed_app_id
+            )),
+        ))),
+        other => Ok(Err(other)),
+    }
+}
+
+/// The app which a single App interface connection has authenticated as.
+///
+/// Once authenticated, a connection can only make requests for its own app
+/// and only receives signals from its own app's cells.
+/// Crypto requests use the app's agent keys, so they always need an
+/// authenticated connection, even when authentication isn't required.
+#[derive(Clone)]
+struct AppConnectionAuth {
+    /// Whether requests are rejected until the connection authenticates
+    required: bool,
+    app: Arc<RwLock<Option<(InstalledAppId, HashSet<CellId>)>>>,
+}
+
+impl AppConnectionAuth {
+    fn new(required: bool) -> Self {
+        Self {
+            required,
+            app: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Authenticate the connection with a token issued through the admin interface.
+    async fn authenticate<A>(
+        &self,
+        api: &A,
+        token: AppAuthenticationToken,
+    ) -> InterfaceResult<AppResponse>
+    where
+        A: InterfaceApi<ApiRequest = AppRequest, ApiResponse = AppResponse>,
+    {
+        let response = api
+            .handle_request(Ok(AppRequest::Authenticate { token }))
+            .await?;
+        if let AppResponse::Authenticated { installed_app_id } = &response {
+            if let Err(error) = self.refresh_cells(api, installed_app_id).await? {
+                return Ok(error);
+            }
+        }
+        Ok(response)
+    }
+
+    /// Update the cached cells of the authenticated app.
+    async fn refresh_cells<A>(
+        &self,
+        api: &A,
+        installed_app_id: &InstalledAppId,
+    ) -> InterfaceResult<Result<(), AppResponse>>
+    where
+        A: InterfaceApi<ApiRequest = AppRequest, ApiResponse = AppResponse>,
+    {
+        Ok(lookup_app_cells(api, installed_app_id)
+            .await?
+            .map(|cells| *self.app.write() = Some((installed_app_id.clone(), cells))))
+    }
+
+    fn has_cell(&self, cell_id: &CellId) -> bool {
+        self.app
+            .read()
+            .as_ref()
+            .map_or(false, |(_, cells)| cells.contains(cell_id))
+    }
+
+    /// Check that the connection may make a request,
+    /// returning the error response if it may not.
+    async fn check<A>(&self, api: &A, request: &AppRequest) -> InterfaceResult<Option<AppResponse>>
+    where
+        A: InterfaceApi<ApiRequest = AppRequest, ApiResponse = AppResponse>,
+    {
+        let installed_app_id = match self.app.read().as_ref() {
+            Some((installed_app_id, _)) => installed_app_id.clone(),
+            None if self.required || matches!(request, AppRequest::Crypto(_)) => {
+                return Ok(Some(AppResponse::Error(
+                    ExternalApiWireError::Unauthenticated(
+                        "The connection must authenticate before making app requests".to_string(),
+                    ),
+                )))
+            }
+            None => return Ok(None),
+        };
+        let allowed = match request {
+            AppRequest::AppInfo {
+                installed_app_id: id,
+            } => *id == installed_app_id,
+            AppRequest::SignalSubscription(subscription) => {
+                *subscription.installed_app_id() == installed_app_id
+            }
+            AppRequest::Crypto(crypto) => *crypto.installed_app_id() == installed_app_id,
+            AppRequest::ZomeCall(call) | AppRequest::ZomeCallInvocation(call) => {
+                // Cells may have been cloned since the cache was filled.
+                self.has_cell(&call.cell_id)
+                    || (self.refresh_cells(api, &installed_app_id).await?.is_ok()
+                        && self.has_cell(&call.cell_id))
+            }
+            AppRequest::Authenticate { .. } => true,
+        };
+        if allowed {
+            Ok(None)
+        } else {
+            Ok(Some(AppResponse::Error(ExternalApiWireError::Unauthorized(
+                format!(
+                    "The connection is authenticated for app {} and can't make requests for other apps",
+                    installed_app_id
+                ),
+            ))))
+        }
+    }
+
+    /// Check whether a signal should be sent over this connection.
+    fn allows(&self, signal: &Signal) -> bool {
+        match (self.app.read().as_ref(), signal) {
+            (None, _) => !self.required,
+            (Some((_, cells)), Signal::App { cell_id, .. }) => cells.contains(cell_id),
+            (Some(_), Signal::System(_)) => true,
+        }
+    }
+}
+
+/// The signal subscriptions made over a single App interface connection.
+///
+/// Each subscription only applies to the cells its app had when the
+/// subscription was made. Signals from cells which aren't covered by any
+/// subscription are always sent, as are system signals.
+#[derive(Clone, Default)]
+struct SignalSubscriptions(
+    Arc<RwLock<HashMap<InstalledAppId, (HashSet<CellId>, SignalFilterSet)>>>,
+);
+
+impl SignalSubscriptions {
+    /// Replace the subscription for an app, looking up its cells through the api.
+    async fn subscribe<A>(
+        &self,
+        api: &A,
+        subscription: SignalSubscription,
+    ) -> InterfaceResult<AppResponse>
+    where
+        A: InterfaceApi<ApiRequest = AppRequest, ApiResponse = AppResponse>,
+    {
+        let (installed_app_id, filters) = subscription.into_inner();
+        match lookup_app_cells(api, &installed_app_id).await? {
+            Ok(cells) => {
+                self.0.write().insert(installed_app_id, (cells, filters));
+                Ok(AppResponse::SignalSubscriptionUpdated)
+            }
+            Err(error) => Ok(error),
+        }
+    }
+
+    /// Check whether a signal should be sent over this connection.
+    fn allows(&self, signal: &Signal) -> bool {
+        match signal {
+            Signal::App {
+                cell_id,
+                zome_name,
+                signal,
+            } => self
+                .0
+                .read()
+                .values()
+                .filter(|(cells, _)| cells.contains(cell_id))
+                .all(|(_, filters)| filters.allows(cell_id, zome_name, signal)),
+            Signal::System(_) => true,
+        }
+    }
+}
+
 /// Polls for messages coming in from the external client while simultaneously
 /// polling for signals being broadcast from the Cells associated with this
 /// App interface.
-fn spawn_recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
+fn spawn_recv_incoming_msgs_and_outgoing_signals<A>(
     api: A,
     rx_from_iface: WebsocketReceiver,
     rx_from_cell: broadcast::Receiver<Signal>,
     tx_to_iface: WebsocketSender,
-) {
+    auth: AppConnectionAuth,
+) where
+    A: InterfaceApi<ApiRequest = AppRequest, ApiResponse = AppResponse>,
+{
     use futures::stream::StreamExt;
 
     trace!("CONNECTION: {}", rx_from_iface.remote_addr());
 
+    let subscriptions = SignalSubscriptions::default();
+
     let rx_from_cell = futures::stream::unfold(rx_from_cell, |mut rx_from_cell| async move {
         if let Ok(item) = rx_from_cell.recv().await {
             Some((item, rx_from_cell))
@@ -179,9 +491,16 @@ fn spawn_recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
         }
     });
 
+    let signal_subscriptions = subscriptions.clone();
+    let signal_auth = auth.clone();
     tokio::task::spawn(rx_from_cell.for_each_concurrent(4096, move |signal| {
         let mut tx_to_iface = tx_to_iface.clone();
+        let allowed = signal_auth.allows(&signal) && signal_subscriptions.allows(&signal);
         async move {
+            if !allowed {
+                trace!(msg = "Signal filtered by subscription", ?signal);
+                return;
+            }
             trace!(msg = "Sending signal!", ?signal);
             if let Err(err) = async move {
                 let bytes = SerializedBytes::try_from(signal)?;
@@ -197,14 +516,44 @@ fn spawn_recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
 
     tokio::task::spawn(rx_from_iface.for_each_concurrent(4096, move |msg| {
         let api = api.clone();
+        let subscriptions = subscriptions.clone();
+        let auth = auth.clone();
         async move {
-            if let Err(err) = handle_incoming_message(msg, api).await {
+            if let Err(err) = handle_incoming_app_message(msg, api, subscriptions, auth).await {
                 error!(?err, "error handling websocket message");
             }
         }
     }));
 }
 
+/// Handles messages on app interfaces, managing authentication and signal
+/// subscriptions for the connection and passing everything else on to the api
+async fn handle_incoming_app_message<A>(
+    ws_msg: WebsocketMessage,
+    api: A,
+    subscriptions: SignalSubscriptions,
+    auth: AppConnectionAuth,
+) -> InterfaceResult<()>
+where
+    A: InterfaceApi<ApiRequest = AppRequest, ApiResponse = AppResponse>,
+{
+    let (bytes, respond) = ws_msg;
+    let response = match bytes.try_into() {
+        Ok(AppRequest::Authenticate { token }) => auth.authenticate(&api, token).await?,
+        Ok(request) => match auth.check(&api, &request).await? {
+            Some(rejection) => rejection,
+            None => match request {
+                AppRequest::SignalSubscription(subscription) => {
+                    subscriptions.subscribe(&api, subscription).await?
+                }
+                request => api.handle_request(Ok(request)).await?,
+            },
+        },
+        Err(e) => api.handle_request(Err(e)).await?,
+    };
+    Ok(respond.respond(response.try_into()?).await?)
+}
+
 /// Handles messages on all interfaces
 async fn handle_incoming_message<A>(ws_msg: WebsocketMessage, api: A) -> InterfaceResult<()>
 where
@@ -546,7 +895,11 @@ pub mod test {
         let (_tmpdir, conductor_handle) = setup_admin().await;
         let shutdown = conductor_handle.take_shutdown_handle().unwrap();
         let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
-        let msg = AdminRequest::AttachAppInterface { port: None };
+        let msg = AdminRequest::AttachAppInterface {
+            port: None,
+            allowed_origins: AllowedOrigins::Any,
+            require_authentication: false,
+        };
         let msg = msg.try_into().unwrap();
         let respond = |bytes: SerializedBytes| {
             let response: AdminResponse = bytes.try_into().unwrap();
@@ -750,6 +1103,96 @@ pub mod test {
         rx
     }
 
+    async fn make_authenticated_req(
+        admin_api: RealAdminInterfaceApi,
+        session: AdminSession,
+        req: AdminRequest,
+    ) -> AdminResponse {
+        let msg = req.try_into().unwrap();
+        let (tx, rx) = tokio::sync::oneshot::channel();
+
+        let respond = move |bytes: SerializedBytes| {
+            let response: AdminResponse = bytes.try_into().unwrap();
+            tx.send(response).unwrap();
+            async { Ok(()) }.boxed().into()
+        };
+        let respond = Respond::Request(Box::new(respond));
+        let msg = (msg, respond);
+
+        handle_incoming_admin_message(msg, admin_api, session)
+            .await
+            .unwrap();
+        rx.await.unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn admin_requests_require_authentication() {
+        observability::test_run().ok();
+        let (_tmpdir, conductor_handle) = setup_admin().await;
+        let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
+        let session = AdminSession::new(Arc::new(AdminAuthConfig {
+            token: "secret".to_string(),
+            session_duration_seconds: None,
+        }));
+
+        let response =
+            make_authenticated_req(admin_api.clone(), session.clone(), AdminRequest::ListDnas)
+                .await;
+        assert_matches!(
+            response,
+            AdminResponse::Error(ExternalApiWireError::Unauthenticated(_))
+        );
+
+        let response = make_authenticated_req(
+            admin_api.clone(),
+            session.clone(),
+            AdminRequest::Authenticate {
+                token: "wrong".to_string(),
+            },
+        )
+        .await;
+        assert_matches!(
+            response,
+            AdminResponse::Error(ExternalApiWireError::Unauthenticated(_))
+        );
+
+        let response = make_authenticated_req(
+            admin_api.clone(),
+            session.clone(),
+            AdminRequest::Authenticate {
+                token: "secret".to_string(),
+            },
+        )
+        .await;
+        assert_matches!(response, AdminResponse::Authenticated);
+
+        let response =
+            make_authenticated_req(admin_api.clone(), session, AdminRequest::ListDnas).await;
+        assert_matches!(response, AdminResponse::DnasListed(_));
+
+        // A session which expires immediately never allows requests.
+        let session = AdminSession::new(Arc::new(AdminAuthConfig {
+            token: "secret".to_string(),
+            session_duration_seconds: Some(0),
+        }));
+        let response = make_authenticated_req(
+            admin_api.clone(),
+            session.clone(),
+            AdminRequest::Authenticate {
+                token: "secret".to_string(),
+            },
+        )
+        .await;
+        assert_matches!(response, AdminResponse::Authenticated);
+        let response = make_authenticated_req(admin_api, session, AdminRequest::ListDnas).await;
+        assert_matches!(
+            response,
+            AdminResponse::Error(ExternalApiWireError::Unauthenticated(_))
+        );
+
+        conductor_handle.shutdown();
+    }
+
     fn to_key(r: Vec<AgentInfoSigned>) -> Vec<(Arc<KitsuneSpace>, Arc<KitsuneAgent>)> {
         let mut results = r
             .into_iter()
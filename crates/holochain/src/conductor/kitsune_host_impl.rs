@@ -1,6 +1,6 @@
 //! Implementation of the Kitsune Host API
 
-mod query_region_set;
+pub(crate) mod query_region_set;
 mod query_size_limited_regions;
 
 use std::sync::Arc;
@@ -106,8 +106,14 @@ impl KitsuneHost for KitsuneHostImpl {
         let dna_hash = DnaHash::from_kitsune(&space);
         async move {
             let topology = self.get_topology(space.clone()).await?;
-            let db = self.spaces.dht_db(&dna_hash)?;
-            Ok(query_region_set::query_region_set(db, topology, &self.strat, dht_arc_set).await?)
+            Ok(query_region_set::query_region_set(
+                &self.spaces,
+                &dna_hash,
+                topology,
+                &self.strat,
+                dht_arc_set,
+            )
+            .await?)
         }
         .boxed()
         .into()
@@ -1,14 +1,16 @@
 use std::sync::Arc;
 
+use holo_hash::DnaHash;
 use holochain_p2p::{dht::prelude::*, dht_arc::DhtArcSet};
 use holochain_sqlite::prelude::*;
 use rusqlite::named_params;
 
-use crate::conductor::error::ConductorResult;
+use crate::conductor::{error::ConductorResult, space::Spaces};
 
 /// The network module needs info about various groupings ("regions") of ops
 pub async fn query_region_set(
-    db: DbWrite<DbKindDht>,
+    spaces: &Spaces,
+    dna_hash: &DnaHash,
     topology: Topology,
     strat: &ArqStrat,
     dht_arc_set: Arc<DhtArcSet>,
@@ -17,23 +19,10 @@ pub async fn query_region_set(
         .expect("arc is not quantizable (FIXME: only use quantized arcs)");
     let times = TelescopingTimes::historical(&topology);
     let coords = RegionCoordSetLtcs::new(times, arq_set);
-
-    let region_set = db
-        .async_reader(move |txn| {
-            let sql = holochain_sqlite::sql::sql_cell::FETCH_OP_REGION;
-            let mut stmt = txn.prepare_cached(sql).map_err(DatabaseError::from)?;
-            DatabaseResult::Ok(
-                coords.into_region_set(|(_, coords)| {
-                    query_region_data(&mut stmt, &topology, coords)
-                })?,
-            )
-        })
-        .await?;
-
-    Ok(region_set)
+    spaces.region_set(dna_hash, topology, coords).await
 }
 
-pub(super) fn query_region_data(
+pub(crate) fn query_region_data(
     stmt: &mut rusqlite::CachedStatement,
     topology: &Topology,
     coords: RegionCoords,
@@ -0,0 +1,440 @@
+//! Metrics about the conductor, served over HTTP in the
+//! [Prometheus text format](https://prometheus.io/docs/instrumenting/exposition_formats/).
+//!
+//! Durations are recorded into each conductor's histograms as they happen,
+//! while gossip totals, workflow queue depths and database sizes are read
+//! from the spaces each time the metrics are scraped.
+
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use holochain_sqlite::rusqlite::Transaction;
+use holochain_sqlite::sql::sql_cell;
+use holochain_state::prelude::*;
+use holochain_types::prelude::*;
+use hyper::service::make_service_fn;
+use hyper::service::service_fn;
+use hyper::Body;
+use hyper::Method;
+use hyper::Request;
+use hyper::Response;
+use hyper::StatusCode;
+use kitsune_p2p::metrics::GossipTotals;
+use parking_lot::Mutex;
+
+use super::error::ConductorError;
+use super::error::ConductorResult;
+use super::manager::ManagedTaskResult;
+use super::space::Space;
+use super::ConductorHandle;
+use crate::core::workflow::publish_dht_ops_workflow::num_still_needing_publish;
+
+/// The upper bounds of the histogram buckets, in seconds.
+const BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+const ZOME_CALL_DURATION: &str = "holochain_zome_call_duration_seconds";
+const WASM_INSTANTIATION_DURATION: &str = "holochain_wasm_instantiation_duration_seconds";
+
+type Labels = Vec<(&'static str, String)>;
+
+#[derive(Debug, Default)]
+struct Histogram {
+    buckets: [u64; BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(BUCKETS) {
+            if value <= bound {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += value;
+    }
+}
+
+/// The duration histograms of a single conductor,
+/// keyed by metric name and then by label set.
+#[derive(Debug, Default)]
+pub struct Histograms(Mutex<BTreeMap<&'static str, BTreeMap<Labels, Histogram>>>);
+
+impl Histograms {
+    fn observe(&self, name: &'static str, labels: Labels, duration: Duration) {
+        self.0
+            .lock()
+            .entry(name)
+            .or_default()
+            .entry(labels)
+            .or_default()
+            .observe(duration.as_secs_f64());
+    }
+
+    /// Record how long a zome call took to run.
+    pub fn record_zome_call_duration(
+        &self,
+        dna_hash: &DnaHash,
+        zome_name: &ZomeName,
+        fn_name: &FunctionName,
+        duration: Duration,
+    ) {
+        self.observe(
+            ZOME_CALL_DURATION,
+            vec![
+                ("dna", dna_hash.to_string()),
+                ("zome", zome_name.to_string()),
+                ("fn", fn_name.to_string()),
+            ],
+            duration,
+        );
+    }
+
+    /// Record how long it took to create a wasm instance
+    /// which wasn't available in the instance cache.
+    pub fn record_wasm_instantiation_duration(&self, dna_hash: &DnaHash, duration: Duration) {
+        self.observe(
+            WASM_INSTANTIATION_DURATION,
+            vec![("dna", dna_hash.to_string())],
+            duration,
+        );
+    }
+}
+
+/// The metrics of a single space which are read at scrape time.
+#[derive(Debug)]
+pub struct SpaceMetrics {
+    /// The space these metrics are for.
+    pub dna_hash: DnaHash,
+    /// The gossip totals of the space, if the network has any.
+    pub gossip: Option<GossipTotals>,
+    /// Ops waiting for sys validation.
+    pub sys_validation_queue: u64,
+    /// Ops waiting for app validation.
+    pub app_validation_queue: u64,
+    /// Ops waiting to be integrated.
+    pub integration_queue: u64,
+    /// Authored ops still needing to be published.
+    pub publish_queue: u64,
+    /// The size of the authored database, in bytes.
+    pub authored_db_size: u64,
+    /// The size of the DHT database, in bytes.
+    pub dht_db_size: u64,
+    /// The size of the cache database, in bytes.
+    pub cache_db_size: u64,
+}
+
+impl SpaceMetrics {
+    /// Read the queue depths and database sizes of a space.
+    pub async fn read(space: &Space) -> ConductorResult<Self> {
+        let (sys_validation_queue, app_validation_queue, integration_queue, dht_db_size) = space
+            .dht_db
+            .async_reader(|txn| {
+                let depths = txn.query_row(sql_cell::QUEUE_DEPTHS, [], |row| {
+                    Ok((
+                        row.get("sys_validation")?,
+                        row.get("app_validation")?,
+                        row.get("integration")?,
+                    ))
+                })?;
+                ConductorResult::Ok((depths.0, depths.1, depths.2, db_size(&txn)?))
+            })
+            .await?;
+        let (publish_queue, authored_db_size) = space
+            .authored_db
+            .async_reader(|txn| {
+                ConductorResult::Ok((num_still_needing_publish(&txn)? as u64, db_size(&txn)?))
+            })
+            .await?;
+        let cache_db_size = space.cache_db.async_reader(|txn| db_size(&txn)).await?;
+        Ok(Self {
+            dna_hash: (*space.dna_hash).clone(),
+            gossip: None,
+            sys_validation_queue,
+            app_validation_queue,
+            integration_queue,
+            publish_queue,
+            authored_db_size,
+            dht_db_size,
+            cache_db_size,
+        })
+    }
+}
+
+/// The size of the database file, in bytes.
+fn db_size(txn: &Transaction) -> ConductorResult<u64> {
+    Ok(txn.query_row(
+        "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+        [],
+        |row| row.get(0),
+    )?)
+}
+
+/// Render the metrics of every space, along with
+/// every recorded histogram, in the Prometheus text format.
+pub fn render(spaces: &[SpaceMetrics], histograms: &Histograms) -> String {
+    let mut out = String::new();
+    let mut family = |name: &str, kind: &str, help: &str, samples: Vec<(Labels, u64)>| {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} {}", name, kind);
+        for (labels, value) in samples {
+            let _ = writeln!(out, "{}{} {}", name, format_labels(&labels), value);
+        }
+    };
+
+    let per_space = |f: &dyn Fn(&SpaceMetrics) -> Vec<(Labels, u64)>| -> Vec<(Labels, u64)> {
+        spaces
+            .iter()
+            .flat_map(|space| {
+                f(space).into_iter().map(move |(mut labels, value)| {
+                    labels.insert(0, ("dna", space.dna_hash.to_string()));
+                    (labels, value)
+                })
+            })
+            .collect()
+    };
+    let gossip = |f: &dyn Fn(&GossipTotals) -> Vec<(Labels, u64)>| {
+        per_space(&|space| space.gossip.as_ref().map(f).unwrap_or_default())
+    };
+
+    family(
+        "holochain_gossip_rounds_started_total",
+        "counter",
+        "Gossip rounds started, by which side initiated them.",
+        gossip(&|g| {
+            vec![
+                (vec![("initiator", "local".into())], g.rounds_initiated),
+                (vec![("initiator", "remote".into())], g.rounds_accepted),
+            ]
+        }),
+    );
+    family(
+        "holochain_gossip_rounds_finished_total",
+        "counter",
+        "Gossip rounds finished, by outcome.",
+        gossip(&|g| {
+            vec![
+                (vec![("outcome", "success".into())], g.rounds_succeeded),
+                (vec![("outcome", "error".into())], g.rounds_failed),
+            ]
+        }),
+    );
+    family(
+        "holochain_gossip_bytes_total",
+        "counter",
+        "Bytes of gossip sent and received.",
+        gossip(&|g| {
+            vec![
+                (vec![("direction", "in".into())], g.bytes_in),
+                (vec![("direction", "out".into())], g.bytes_out),
+            ]
+        }),
+    );
+    family(
+        "holochain_workflow_queue_depth",
+        "gauge",
+        "Ops waiting to be processed by each workflow.",
+        per_space(&|s| {
+            vec![
+                (
+                    vec![("queue", "sys_validation".into())],
+                    s.sys_validation_queue,
+                ),
+                (
+                    vec![("queue", "app_validation".into())],
+                    s.app_validation_queue,
+                ),
+                (vec![("queue", "integration".into())], s.integration_queue),
+                (vec![("queue", "publish".into())], s.publish_queue),
+            ]
+        }),
+    );
+    family(
+        "holochain_database_size_bytes",
+        "gauge",
+        "Size of each database of a space.",
+        per_space(&|s| {
+            vec![
+                (vec![("kind", "authored".into())], s.authored_db_size),
+                (vec![("kind", "dht".into())], s.dht_db_size),
+                (vec![("kind", "cache".into())], s.cache_db_size),
+            ]
+        }),
+    );
+
+    let histograms = histograms.0.lock();
+    for (name, help) in [
+        (ZOME_CALL_DURATION, "Time taken to run zome calls."),
+        (
+            WASM_INSTANTIATION_DURATION,
+            "Time taken to create wasm instances which weren't cached.",
+        ),
+    ] {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+        for (labels, histogram) in histograms.get(name).into_iter().flatten() {
+            for (bound, count) in BUCKETS.iter().zip(histogram.buckets) {
+                let mut labels = labels.clone();
+                labels.push(("le", bound.to_string()));
+                let _ = writeln!(out, "{}_bucket{} {}", name, format_labels(&labels), count);
+            }
+            let mut inf = labels.clone();
+            inf.push(("le", "+Inf".into()));
+            let labels = format_labels(&labels);
+            let _ = writeln!(
+                out,
+                "{}_bucket{} {}",
+                name,
+                format_labels(&inf),
+                histogram.count
+            );
+            let _ = writeln!(out, "{}_sum{} {}", name, labels, histogram.sum);
+            let _ = writeln!(out, "{}_count{} {}", name, labels, histogram.count);
+        }
+    }
+    out
+}
+
+fn format_labels(labels: &[(&'static str, String)]) -> String {
+    if labels.is_empty() {
+        return String::new();
+    }
+    let labels: Vec<_> = labels
+        .iter()
+        .map(|(name, value)| {
+            let value = value
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n");
+            format!("{}=\"{}\"", name, value)
+        })
+        .collect();
+    format!("{{{}}}", labels.join(","))
+}
+
+/// Serve the metrics at `GET /metrics` on `addr` until `stop_rx` fires.
+///
+/// The address is bound before returning, so a port which is already
+/// in use is reported as an error instead of from within the task.
+pub(crate) fn spawn_metrics_server(
+    addr: SocketAddr,
+    handle: ConductorHandle,
+    mut stop_rx: tokio::sync::broadcast::Receiver<()>,
+) -> ConductorResult<tokio::task::JoinHandle<ManagedTaskResult>> {
+    let make_service = make_service_fn(move |_| {
+        let handle = handle.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req| {
+                let handle = handle.clone();
+                async move { Ok::<_, Infallible>(respond(handle, req).await) }
+            }))
+        }
+    });
+    let server = hyper::Server::try_bind(&addr)
+        .map_err(ConductorError::other)?
+        .serve(make_service)
+        .with_graceful_shutdown(async move {
+            stop_rx.recv().await.ok();
+        });
+    tracing::info!("Serving metrics at http://{}/metrics", addr);
+    Ok(tokio::task::spawn(async move {
+        if let Err(e) = server.await {
+            tracing::error!("Metrics server failed: {:?}", e);
+        }
+        Ok(())
+    }))
+}
+
+async fn respond(handle: ConductorHandle, req: Request<Body>) -> Response<Body> {
+    if req.method() != Method::GET || req.uri().path() != "/metrics" {
+        return status(StatusCode::NOT_FOUND);
+    }
+    match handle.render_metrics().await {
+        Ok(body) => Response::builder()
+            .header("Content-Type", "text/plain; version=0.0.4")
+            .body(Body::from(body))
+            .unwrap_or_else(|_| status(StatusCode::INTERNAL_SERVER_ERROR)),
+        Err(e) => {
+            tracing::error!("Failed to render metrics: {:?}", e);
+            status(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+fn status(code: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = code;
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_histograms_and_space_metrics() {
+        let dna_hash = DnaHash::from_raw_32(vec![0; 32]);
+        let histograms = Histograms::default();
+        histograms.record_zome_call_duration(
+            &dna_hash,
+            &"zome".into(),
+            &"fn".into(),
+            Duration::from_millis(20),
+        );
+        let out = render(
+            &[SpaceMetrics {
+                dna_hash: dna_hash.clone(),
+                gossip: Some(GossipTotals {
+                    bytes_out: 42,
+                    ..Default::default()
+                }),
+                sys_validation_queue: 0,
+                app_validation_queue: 0,
+                integration_queue: 0,
+                publish_queue: 3,
+                authored_db_size: 0,
+                dht_db_size: 0,
+                cache_db_size: 0,
+            }],
+            &histograms,
+        );
+
+        let dna = dna_hash.to_string();
+        let has = |line: String| assert!(out.lines().any(|l| l == line), "missing {}", line);
+        has(format!(
+            "holochain_gossip_bytes_total{{dna=\"{}\",direction=\"out\"}} 42",
+            dna
+        ));
+        has(format!(
+            "holochain_workflow_queue_depth{{dna=\"{}\",queue=\"publish\"}} 3",
+            dna
+        ));
+        let labels = format!("dna=\"{}\",zome=\"zome\",fn=\"fn\"", dna);
+        has(format!(
+            "holochain_zome_call_duration_seconds_bucket{{{},le=\"0.01\"}} 0",
+            labels
+        ));
+        has(format!(
+            "holochain_zome_call_duration_seconds_bucket{{{},le=\"0.025\"}} 1",
+            labels
+        ));
+        has(format!(
+            "holochain_zome_call_duration_seconds_count{{{}}} 1",
+            labels
+        ));
+    }
+
+    #[test]
+    fn escapes_label_values() {
+        assert_eq!(
+            format_labels(&[("fn", "a\"b\\c\nd".into())]),
+            "{fn=\"a\\\"b\\\\c\\nd\"}"
+        );
+    }
+}
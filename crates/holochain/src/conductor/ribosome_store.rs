@@ -1,33 +1,34 @@
 use holochain_types::{prelude::*, share::RwShare};
 use holochain_zome_types::entry_def::EntryDef;
 use std::collections::HashMap;
+use std::sync::Arc;
 use tracing::*;
 
+use super::metrics::Histograms;
 use crate::core::ribosome::{error::RibosomeResult, real_ribosome::RealRibosome, RibosomeT};
 
 #[derive(Default, Debug)]
 pub struct RibosomeStore {
     ribosomes: HashMap<DnaHash, RealRibosome>,
     entry_defs: HashMap<EntryDefBufferKey, EntryDef>,
+    /// The conductor's histograms, which every stored ribosome records into.
+    histograms: Arc<Histograms>,
 }
 
 impl RibosomeStore {
     pub fn new() -> RwShare<Self> {
-        RwShare::new(RibosomeStore {
-            ribosomes: HashMap::new(),
-            entry_defs: HashMap::new(),
-        })
+        RwShare::new(RibosomeStore::default())
     }
 
     #[instrument]
     pub fn add_dna(&mut self, dna: DnaFile) -> RibosomeResult<()> {
-        let hash = dna.dna_hash().clone();
         let ribosome = RealRibosome::new(dna)?;
-        self.ribosomes.insert(hash, ribosome);
+        self.add_ribosome(ribosome);
         Ok(())
     }
 
     pub fn add_ribosome(&mut self, ribosome: RealRibosome) {
+        let ribosome = ribosome.with_histograms(self.histograms.clone());
         self.ribosomes.insert(ribosome.dna_hash().clone(), ribosome);
     }
 
@@ -35,7 +36,16 @@ impl RibosomeStore {
         &mut self,
         ribosomes: T,
     ) {
-        self.ribosomes.extend(ribosomes);
+        let histograms = self.histograms.clone();
+        self.ribosomes.extend(
+            ribosomes
+                .into_iter()
+                .map(|(hash, ribosome)| (hash, ribosome.with_histograms(histograms.clone()))),
+        );
+    }
+
+    pub fn histograms(&self) -> Arc<Histograms> {
+        self.histograms.clone()
     }
 
     #[instrument]
@@ -3,14 +3,14 @@
 //! Multiple [`Cell`](crate::conductor::Cell)'s could share the same space.
 use std::{collections::HashMap, sync::Arc, time::Duration};
 
-use holo_hash::{DhtOpHash, DnaHash};
-use holochain_conductor_api::conductor::{ConductorConfig, DatabaseRootPath};
+use holo_hash::{AgentPubKey, DhtOpHash, DnaHash};
+use holochain_conductor_api::conductor::{ArcGcConfig, ConductorConfig, DatabaseRootPath};
 use holochain_p2p::{
     dht::{
         arq::{power_and_count_from_length, ArqBoundsSet},
         hash::RegionHash,
         prelude::Topology,
-        region::{RegionBounds, RegionData},
+        region::{RegionBounds, RegionData, RegionTree},
         region_set::{RegionCoordSetLtcs, RegionSetLtcs},
         spacetime::TelescopingTimes,
         ArqBounds, ArqStrat,
@@ -24,17 +24,23 @@ use holochain_sqlite::{
         DbKindAuthored, DbKindCache, DbKindConductor, DbKindDht, DbKindP2pAgents, DbKindP2pMetrics,
         DbKindWasm, DbWrite, ReadAccess,
     },
-    prelude::{DatabaseError, DatabaseResult},
+    key::DbKey,
+    prelude::DatabaseResult,
 };
 use holochain_state::{
+    mutations::{insert_warrant, StateMutationResult},
     prelude::{from_blob, StateQueryResult},
     query::{map_sql_dht_op_common, StateQueryError},
+    warrants::{count_warrants, verify_warrant, warrant_is_proven, MAX_WARRANTS_PER_WARRANTEE},
+    workflow_queues,
 };
 use holochain_types::{
     db_cache::DhtDbQueryCache,
     dht_op::{DhtOp, DhtOpType},
 };
-use holochain_zome_types::{Entry, EntryVisibility, SignedAction, Timestamp};
+use holochain_zome_types::{
+    warrant::SignedWarrant, Entry, EntryVisibility, SignedAction, Timestamp,
+};
 use kitsune_p2p::{
     event::{TimeWindow, TimeWindowInclusive},
     KitsuneP2pConfig,
@@ -45,6 +51,7 @@ use tracing::instrument;
 use crate::core::{
     queue_consumer::QueueConsumerMap,
     workflow::{
+        arc_gc_workflow::{arc_gc_workflow, ArcGcHistory},
         countersigning_workflow::{incoming_countersigning, CountersigningWorkspace},
         incoming_dht_ops_workflow::{
             incoming_dht_ops_workflow, IncomingOpHashes, IncomingOpsBatch,
@@ -54,6 +61,7 @@ use crate::core::{
 
 use super::{
     conductor::RwShare,
+    db_key::DbKeyManager,
     error::ConductorResult,
     p2p_agent_store::{self, P2pBatch},
 };
@@ -70,6 +78,7 @@ pub struct Spaces {
     map: RwShare<HashMap<DnaHash, Space>>,
     pub(crate) db_dir: Arc<DatabaseRootPath>,
     pub(crate) db_sync_strategy: DbSyncStrategy,
+    pub(crate) db_key: Option<Arc<DbKeyManager>>,
     /// The map of running queue consumer workflows.
     pub(crate) queue_consumer_map: QueueConsumerMap,
     pub(crate) conductor_db: DbWrite<DbKindConductor>,
@@ -118,6 +127,15 @@ pub struct Space {
 
     /// Incoming ops batch for this space.
     pub incoming_ops_batch: IncomingOpsBatch,
+
+    /// The region data of every op in the dht database, along with the rowid
+    /// of the last op added to it. This is built from the database on first
+    /// use, and new ops are added to it before each query.
+    region_tree: Arc<parking_lot::Mutex<Option<(RegionTree, i64)>>>,
+
+    /// The storage arcs recently held by the local agents of this space,
+    /// which decide what DHT data may be deleted.
+    arc_gc_history: Arc<parking_lot::Mutex<ArcGcHistory>>,
 }
 
 #[cfg(test)]
@@ -135,20 +153,37 @@ pub struct TestSpace {
 impl Spaces {
     /// Create a new empty set of [`DnaHash`] spaces.
     pub fn new(config: &ConductorConfig) -> ConductorResult<Self> {
+        Self::new_with_db_key(config, None)
+    }
+
+    /// Create a new empty set of [`DnaHash`] spaces whose databases
+    /// are encrypted with the key held by the [`DbKeyManager`].
+    pub fn new_with_db_key(
+        config: &ConductorConfig,
+        db_key: Option<Arc<DbKeyManager>>,
+    ) -> ConductorResult<Self> {
+        let key = db_key
+            .as_ref()
+            .map(|k| k.current_key().map(|key| key.clone()))
+            .transpose()?;
         let root_db_dir = config.environment_path.clone();
         let db_sync_strategy = config.db_sync_strategy;
         let db_sync_level = match db_sync_strategy {
             DbSyncStrategy::Fast => DbSyncLevel::Off,
             DbSyncStrategy::Resilient => DbSyncLevel::Normal,
         };
-        let conductor_db =
-            DbWrite::open_with_sync_level(root_db_dir.as_ref(), DbKindConductor, db_sync_level)?;
-        let wasm_db =
-            DbWrite::open_with_sync_level(root_db_dir.as_ref(), DbKindWasm, db_sync_level)?;
+        let conductor_db = DbWrite::open_with_key(
+            root_db_dir.as_ref(),
+            DbKindConductor,
+            db_sync_level,
+            key.clone(),
+        )?;
+        let wasm_db = DbWrite::open_with_key(root_db_dir.as_ref(), DbKindWasm, db_sync_level, key)?;
         Ok(Spaces {
             map: RwShare::new(HashMap::new()),
             db_dir: Arc::new(root_db_dir),
             db_sync_strategy,
+            db_key,
             queue_consumer_map: QueueConsumerMap::new(),
             conductor_db,
             wasm_db,
@@ -173,25 +208,47 @@ impl Spaces {
     {
         match self.map.share_ref(|spaces| spaces.get(dna_hash).map(&f)) {
             Some(r) => Ok(r),
-            None => self
-                .map
-                .share_mut(|spaces| match spaces.entry(dna_hash.clone()) {
-                    std::collections::hash_map::Entry::Occupied(entry) => Ok(f(entry.get())),
-                    std::collections::hash_map::Entry::Vacant(entry) => {
-                        let space = Space::new(
-                            Arc::new(dna_hash.clone()),
-                            &self.db_dir,
-                            self.db_sync_strategy,
-                        )?;
-
-                        let r = f(&space);
-                        entry.insert(space);
-                        Ok(r)
-                    }
-                }),
+            None => {
+                // The key is held until the databases are opened
+                // so they can't miss a key rotation.
+                let key = self.db_key.as_ref().map(|k| k.current_key()).transpose()?;
+                self.map
+                    .share_mut(|spaces| match spaces.entry(dna_hash.clone()) {
+                        std::collections::hash_map::Entry::Occupied(entry) => Ok(f(entry.get())),
+                        std::collections::hash_map::Entry::Vacant(entry) => {
+                            let space = Space::new(
+                                Arc::new(dna_hash.clone()),
+                                &self.db_dir,
+                                self.db_sync_strategy,
+                                key.as_deref().cloned(),
+                            )?;
+
+                            let r = f(&space);
+                            entry.insert(space);
+                            Ok(r)
+                        }
+                    })
+            }
         }
     }
 
+    /// Remove a space, stop its queue consumers and delete all of its
+    /// databases from disk.
+    /// Only call this once no cell of the dna is in the conductor anymore.
+    pub async fn delete_space(&self, dna_hash: &DnaHash) -> ConductorResult<()> {
+        // The space is opened if it isn't yet so its databases are
+        // released by this process before their files are removed.
+        let space = self.get_or_create_space(dna_hash)?;
+        self.map.share_mut(|spaces| spaces.remove(dna_hash));
+        self.queue_consumer_map.stop_space(dna_hash);
+        space.cache_db.delete().await?;
+        space.authored_db.delete().await?;
+        space.dht_db.delete().await?;
+        space.p2p_agents_db.delete().await?;
+        space.p2p_metrics_db.delete().await?;
+        Ok(())
+    }
+
     /// Get the cache database (this will create the space if it doesn't already exist).
     pub fn cache(&self, dna_hash: &DnaHash) -> ConductorResult<DbWrite<DbKindCache>> {
         self.get_or_create_space_ref(dna_hash, |space| space.cache_db.clone())
@@ -333,7 +390,6 @@ impl Spaces {
         topology: Topology,
         dht_arc_set: DhtArcSet,
     ) -> ConductorResult<RegionSetLtcs> {
-        let sql = holochain_sqlite::sql::sql_cell::FETCH_OP_REGION;
         let max_chunks = ArqStrat::default().max_chunks();
         let arq_set = ArqBoundsSet::new(
             dht_arc_set
@@ -348,34 +404,56 @@ impl Spaces {
         );
         let times = TelescopingTimes::historical(&topology);
         let coords = RegionCoordSetLtcs::new(times, arq_set);
-        let coords_clone = coords.clone();
-        let db = self.dht_db(dna_hash)?;
+        self.region_set(dna_hash, topology, coords).await
+    }
+
+    /// Compute the data of a set of regions from the in-memory region tree
+    /// of a space, rather than querying the database for each region.
+    ///
+    /// Any ops written to the dht database since the last call are added to
+    /// the tree first, and the tree is rebuilt if the topology changes.
+    /// Like [`Self::handle_fetch_op_regions`], this includes all ops
+    /// regardless of integration status.
+    pub async fn region_set(
+        &self,
+        dna_hash: &DnaHash,
+        topology: Topology,
+        coords: RegionCoordSetLtcs,
+    ) -> ConductorResult<RegionSetLtcs> {
+        let (db, region_tree) = self.get_or_create_space_ref(dna_hash, |space| {
+            (space.dht_db.clone(), space.region_tree.clone())
+        })?;
         db.async_reader(move |txn| {
-            let mut stmt = txn.prepare_cached(sql).map_err(DatabaseError::from)?;
-            Ok(coords_clone.into_region_set(|(_, coords)| {
-                let bounds = coords.to_bounds(&topology);
-                let (x0, x1) = bounds.x;
-                let (t0, t1) = bounds.t;
-                stmt.query_row(
-                    named_params! {
-                        ":storage_start_loc": x0,
-                        ":storage_end_loc": x1,
-                        ":timestamp_min": t0,
-                        ":timestamp_max": t1,
-                    },
-                    |row| {
-                        let size: f64 = row.get("total_size")?;
-                        Ok(RegionData {
-                            hash: RegionHash::from_vec(row.get("xor_hash")?)
-                                .expect("region hash must be 32 bytes"),
-                            size: size.min(u32::MAX as f64) as u32,
-                            count: row.get("count")?,
-                        })
-                    },
-                )
-            })?)
+            let mut region_tree = region_tree.lock();
+            if region_tree
+                .as_ref()
+                .map_or(true, |(tree, _)| *tree.topo() != topology)
+            {
+                *region_tree = Some((RegionTree::new(topology), 0));
+            }
+            let (tree, last_rowid) = region_tree.as_mut().expect("the tree was just set");
+
+            let sql = holochain_sqlite::sql::sql_cell::FETCH_OP_REGION_DATA_SINCE;
+            let mut stmt = txn.prepare_cached(sql)?;
+            let mut rows = stmt.query(named_params! { ":last_rowid": *last_rowid })?;
+            while let Some(row) = rows.next()? {
+                let loc: u32 = row.get("storage_center_loc")?;
+                let timestamp: Timestamp = row.get("authored_timestamp")?;
+                let size: i64 = row.get("size")?;
+                let data = RegionData {
+                    hash: RegionHash::from_vec(row.get("hash")?)
+                        .expect("region hash must be 32 bytes"),
+                    size: size.clamp(0, u32::MAX as i64) as u32,
+                    count: 1,
+                };
+                tree.insert(loc.into(), timestamp, data);
+                *last_rowid = row.get("rowid")?;
+            }
+
+            DatabaseResult::Ok(tree.region_set(coords))
         })
         .await
+        .map_err(Into::into)
     }
 
     #[instrument(skip(self, query))]
@@ -550,7 +628,7 @@ impl Spaces {
                 // If the workflow has not been spawned yet we can't handle incoming messages.
                 None => return Ok(()),
             };
-            incoming_countersigning(ops, &workspace, trigger)?;
+            incoming_countersigning(ops, &workspace, trigger).await?;
         } else {
             let space = self.get_or_create_space(dna_hash)?;
             let trigger = match self
@@ -567,6 +645,124 @@ impl Spaces {
         Ok(())
     }
 
+    #[instrument(skip(self, warrants))]
+    /// we are receiving warrants from the network about an agent whose
+    /// activity we are an authority for.
+    ///
+    /// Warrants with a valid signature which can be proven from the data we
+    /// hold are held and returned with the agent's activity, up to
+    /// [`MAX_WARRANTS_PER_WARRANTEE`] per agent. Warrants we can't prove are
+    /// dropped, as anyone can sign a warrant with a throwaway key.
+    /// The agents whose warrants were proven are returned so they can be blocked.
+    ///
+    /// Warrants are only published once and aren't gossiped, so authorities
+    /// which join later only hold warrants that they can issue themselves.
+    pub async fn handle_publish_warrants(
+        &self,
+        dna_hash: &DnaHash,
+        warrants: Vec<SignedWarrant>,
+    ) -> ConductorResult<Vec<AgentPubKey>> {
+        let mut verified = Vec::with_capacity(warrants.len());
+        for warrant in warrants {
+            if verify_warrant(&warrant).await {
+                verified.push(warrant);
+            } else {
+                tracing::warn!(?warrant, "Dropping warrant with an invalid signature");
+            }
+        }
+        if verified.is_empty() {
+            return Ok(Vec::new());
+        }
+        let db = self.dht_db(dna_hash)?;
+        let proven = db
+            .async_commit(move |txn| {
+                let mut proven = Vec::new();
+                for warrant in verified {
+                    if !warrant_is_proven(txn, &warrant.warrant().proof)? {
+                        tracing::debug!(?warrant, "Dropping warrant we can't prove");
+                        continue;
+                    }
+                    let warrantee = warrant.warrant().warrantee().clone();
+                    if count_warrants(txn, &warrantee)? < MAX_WARRANTS_PER_WARRANTEE {
+                        insert_warrant(txn, warrant)?;
+                    }
+                    if !proven.contains(&warrantee) {
+                        proven.push(warrantee);
+                    }
+                }
+                StateMutationResult::Ok(proven)
+            })
+            .await?;
+        Ok(proven)
+    }
+
+    /// Delete the DHT data of a space which has been outside the storage arcs
+    /// of its local agents for the grace period of the config.
+    ///
+    /// `local_arcs` are the current storage arcs of `local_agents`.
+    /// Returns the number of ops deleted.
+    pub async fn collect_arc_garbage(
+        &self,
+        dna_hash: &DnaHash,
+        local_arcs: DhtArcSet,
+        local_agents: Vec<AgentPubKey>,
+        config: &ArcGcConfig,
+    ) -> ConductorResult<usize> {
+        let space = self.get_or_create_space(dna_hash)?;
+        let now = Timestamp::now();
+        let grace_period = config.grace_period();
+        let held_arcs = match space
+            .arc_gc_history
+            .lock()
+            .observe(now, local_arcs, grace_period)
+        {
+            Some(held_arcs) => held_arcs,
+            None => return Ok(0),
+        };
+        let integrated_before = (now - grace_period).unwrap_or(Timestamp::MIN);
+        let collected = arc_gc_workflow(
+            space.dht_db.clone(),
+            space.dht_query_cache.clone(),
+            space.cache_db.clone(),
+            space.p2p_agents_db.clone(),
+            held_arcs,
+            local_agents,
+            integrated_before,
+            config.min_redundancy,
+        )
+        .await?;
+        if collected > 0 {
+            // The tree only tracks ops added since it was built,
+            // so it must be rebuilt without the deleted ones.
+            *space.region_tree.lock() = None;
+        }
+        Ok(collected)
+    }
+
+    /// Put ops of a space back into the sys validation queue.
+    ///
+    /// If `op_hashes` is `None` every abandoned op is re-queued.
+    /// Returns the hashes of the ops which were re-queued.
+    pub async fn requeue_ops_for_validation(
+        &self,
+        dna_hash: &DnaHash,
+        op_hashes: Option<Vec<DhtOpHash>>,
+    ) -> ConductorResult<Vec<DhtOpHash>> {
+        let space = self.get_or_create_space(dna_hash)?;
+        // The region tree holds ops whatever their validation status,
+        // so it doesn't change when ops are re-queued.
+        Ok(space
+            .dht_db
+            .async_commit(move |txn| {
+                let op_hashes = match op_hashes {
+                    Some(op_hashes) => op_hashes,
+                    None => workflow_queues::abandoned_ops(txn)?,
+                };
+                workflow_queues::requeue_ops(txn, &op_hashes)
+            })
+            .await?)
+    }
+
     /// Get the recent_threshold based on the kitsune network config
     pub fn recent_threshold(&self) -> Duration {
         self.network_config
@@ -580,6 +776,7 @@ impl Space {
         dna_hash: Arc<DnaHash>,
         root_db_dir: &DatabaseRootPath,
         db_sync_strategy: DbSyncStrategy,
+        key: Option<DbKey>,
     ) -> ConductorResult<Self> {
         use holochain_p2p::DnaHashExt;
         let space = dna_hash.to_kitsune();
@@ -587,30 +784,35 @@ impl Space {
             DbSyncStrategy::Fast => DbSyncLevel::Off,
             DbSyncStrategy::Resilient => DbSyncLevel::Normal,
         };
-        let cache = DbWrite::open_with_sync_level(
+        let cache = DbWrite::open_with_key(
             root_db_dir.as_ref(),
             DbKindCache(dna_hash.clone()),
             db_sync_level,
+            key.clone(),
         )?;
-        let authored_db = DbWrite::open_with_sync_level(
+        let authored_db = DbWrite::open_with_key(
             root_db_dir.as_ref(),
             DbKindAuthored(dna_hash.clone()),
             DbSyncLevel::Normal,
+            key.clone(),
         )?;
-        let dht_db = DbWrite::open_with_sync_level(
+        let dht_db = DbWrite::open_with_key(
             root_db_dir.as_ref(),
             DbKindDht(dna_hash.clone()),
             db_sync_level,
+            key.clone(),
         )?;
-        let p2p_agents_db = DbWrite::open_with_sync_level(
+        let p2p_agents_db = DbWrite::open_with_key(
             root_db_dir.as_ref(),
             DbKindP2pAgents(space.clone()),
             db_sync_level,
+            key.clone(),
         )?;
-        let p2p_metrics_db = DbWrite::open_with_sync_level(
+        let p2p_metrics_db = DbWrite::open_with_key(
             root_db_dir.as_ref(),
             DbKindP2pMetrics(space),
             db_sync_level,
+            key,
         )?;
 
         let (tx, rx) = tokio::sync::mpsc::channel(100);
@@ -620,7 +822,7 @@ impl Space {
         ));
         let p2p_batch_sender = tx;
 
-        let countersigning_workspace = CountersigningWorkspace::new();
+        let countersigning_workspace = CountersigningWorkspace::new(dht_db.clone());
         let incoming_op_hashes = IncomingOpHashes::default();
         let incoming_ops_batch = IncomingOpsBatch::default();
         let dht_query_cache = DhtDbQueryCache::new(dht_db.clone().into());
@@ -636,6 +838,8 @@ impl Space {
             incoming_op_hashes,
             incoming_ops_batch,
             dht_query_cache,
+            region_tree: Default::default(),
+            arc_gc_history: Default::default(),
         };
         Ok(r)
     }
@@ -693,6 +897,7 @@ impl TestSpace {
                 Arc::new(dna_hash),
                 &temp_dir.path().to_path_buf().into(),
                 Default::default(),
+                None,
             )
             .unwrap(),
             _temp_dir: temp_dir,
@@ -16,6 +16,9 @@ use holochain_zome_types::{DnaDef, DnaDefHashed, NOISE};
 use rand::Rng;
 
 use super::Spaces;
+use crate::conductor::kitsune_host_impl::query_region_set::query_region_data;
+use holochain_sqlite::prelude::DatabaseResult;
+use holochain_sqlite::sql::sql_cell::FETCH_OP_REGION;
 
 /// Test that `fetch_op_regions` returns regions which correctly describe
 /// the set of ops in the database, and that `fetch_ops_by_region` returns the
@@ -36,7 +39,7 @@ async fn test_region_queries() {
     let path = temp_dir.path().to_path_buf();
 
     let spaces = Spaces::new(&ConductorConfig {
-        environment_path: path.into(),
+        environment_path: path.clone().into(),
         ..Default::default()
     })
     .unwrap();
@@ -117,6 +120,35 @@ async fn test_region_queries() {
     assert_eq!(region_sum.count as usize, NUM_OPS);
     assert_eq!(region_sum.hash, hash_sum);
 
+    // - Check that the region tree agrees with querying each region from the database
+    let db_region_set = db
+        .async_reader({
+            let coords = region_set.coords.clone();
+            let topo = topo.clone();
+            move |txn| {
+                let mut stmt = txn.prepare_cached(FETCH_OP_REGION)?;
+                DatabaseResult::Ok(
+                    coords.into_region_set(|(_, coords)| {
+                        query_region_data(&mut stmt, &topo, coords)
+                    })?,
+                )
+            }
+        })
+        .await
+        .unwrap();
+    assert_eq!(region_set, db_region_set);
+
+    // - Check that a tree rebuilt from the database on startup matches too
+    let restarted = Spaces::new(&ConductorConfig {
+        environment_path: path.clone().into(),
+        ..Default::default()
+    })
+    .unwrap()
+    .handle_fetch_op_regions(dna_def.as_hash(), topo.clone(), DhtArcSet::Full)
+    .await
+    .unwrap();
+    assert_eq!(region_set, restarted);
+
     let mut fetched_ops: Vec<_> = spaces
         .handle_fetch_op_data_by_regions(
             dna_def.as_hash(),
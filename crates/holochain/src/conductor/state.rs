@@ -2,11 +2,11 @@
 //! startups and shutdowns
 
 use holochain_conductor_api::signal_subscription::SignalSubscription;
-use holochain_conductor_api::{config::InterfaceDriver, InstalledAppInfo};
+use holochain_conductor_api::{config::AllowedOrigins, config::InterfaceDriver, InstalledAppInfo};
 use holochain_types::prelude::*;
 use serde::Deserialize;
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use super::error::{ConductorError, ConductorResult};
 
@@ -122,13 +122,37 @@ impl ConductorState {
             .ok_or_else(|| ConductorError::AppNotInstalled(id.clone()))
     }
 
-    /// Getter for a single app. Returns error if app missing.
+    /// Remove a single app. Returns error if app missing,
+    /// or if other apps reuse any of its cells.
     pub fn remove_app(&mut self, id: &InstalledAppId) -> ConductorResult<InstalledApp> {
+        let dependents = self.dependents_of(id)?;
+        if !dependents.is_empty() {
+            return Err(ConductorError::AppHasDependents(id.clone(), dependents));
+        }
         self.installed_apps
             .remove(id)
             .ok_or_else(|| ConductorError::AppNotInstalled(id.clone()))
     }
 
+    /// The ids of all other apps which reuse a cell of this app.
+    pub fn dependents_of(&self, id: &InstalledAppId) -> ConductorResult<Vec<InstalledAppId>> {
+        let app = self.get_app(id)?;
+        let existing: HashSet<&CellId> = app.existing_cells().collect();
+        let own_cells: HashSet<&CellId> = app
+            .all_cells()
+            .filter(|cell_id| !existing.contains(cell_id))
+            .collect();
+        let mut dependents: Vec<_> = self
+            .installed_apps
+            .iter()
+            .filter(|(other_id, _)| *other_id != id)
+            .filter(|(_, other)| other.existing_cells().any(|c| own_cells.contains(c)))
+            .map(|(other_id, _)| other_id.clone())
+            .collect();
+        dependents.sort();
+        Ok(dependents)
+    }
+
     /// Add an app in the Deactivated state. Returns an error if an app is already
     /// present at the given ID.
     pub fn add_app(&mut self, app: InstalledAppCommon) -> ConductorResult<StoppedApp> {
@@ -187,6 +211,14 @@ pub struct AppInterfaceConfig {
 
     /// The driver for the interface, e.g. Websocket
     pub driver: InterfaceDriver,
+
+    /// The origins which are allowed to connect to the interface
+    #[serde(default)]
+    pub allowed_origins: AllowedOrigins,
+
+    /// Whether connections must authenticate with an app authentication token
+    #[serde(default)]
+    pub require_authentication: bool,
 }
 
 impl AppInterfaceConfig {
@@ -195,6 +227,8 @@ impl AppInterfaceConfig {
         Self {
             signal_subscriptions: HashMap::new(),
             driver: InterfaceDriver::Websocket { port },
+            allowed_origins: AllowedOrigins::Any,
+            require_authentication: false,
         }
     }
 }
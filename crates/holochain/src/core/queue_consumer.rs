@@ -92,6 +92,8 @@ pub async fn spawn_queue_consumer_tasks(
     let keystore = conductor_handle.keystore().clone();
     let dna_hash = Arc::new(cell_id.dna_hash().clone());
     let queue_consumer_map = conductor_handle.get_queue_consumer_workflows();
+    // The consumers stop with the conductor or when their space is deleted.
+    let stop = queue_consumer_map.space_stop(dna_hash.clone(), &stop);
 
     // Publish
     let (tx_publish, handle) = spawn_publish_dht_ops_consumer(
@@ -251,10 +253,17 @@ pub async fn spawn_queue_consumer_tasks(
         QueueTriggers {
             sys_validation: tx_sys.clone(),
             publish_dht_ops: tx_publish.clone(),
-            countersigning: tx_cs,
+            countersigning: tx_cs.clone(),
             integrate_dht_ops: tx_integration.clone(),
         },
-        InitialQueueTriggers::new(tx_sys, tx_publish, tx_app, tx_integration, tx_receipt),
+        InitialQueueTriggers::new(
+            tx_sys,
+            tx_publish,
+            tx_app,
+            tx_integration,
+            tx_receipt,
+            tx_cs,
+        ),
     )
 }
 
@@ -262,6 +271,7 @@ pub async fn spawn_queue_consumer_tasks(
 /// Map of running queue consumers workflows per dna space.
 pub struct QueueConsumerMap {
     map: RwShare<HashMap<QueueEntry, TriggerSender>>,
+    stops: RwShare<HashMap<Arc<DnaHash>, broadcast::Sender<()>>>,
 }
 
 impl Default for QueueConsumerMap {
@@ -275,6 +285,42 @@ impl QueueConsumerMap {
     pub fn new() -> Self {
         Self {
             map: RwShare::new(HashMap::new()),
+            stops: RwShare::new(HashMap::new()),
+        }
+    }
+
+    /// Get the stop signal of the consumers for this dna hash,
+    /// which also fires when the conductor stops.
+    fn space_stop(
+        &self,
+        dna_hash: Arc<DnaHash>,
+        conductor_stop: &broadcast::Sender<()>,
+    ) -> broadcast::Sender<()> {
+        self.stops.share_mut(|stops| {
+            stops
+                .entry(dna_hash)
+                .or_insert_with(|| {
+                    let (tx, _) = broadcast::channel(1);
+                    let mut conductor_stop = conductor_stop.subscribe();
+                    let space_stop = tx.clone();
+                    tokio::spawn(async move {
+                        if conductor_stop.recv().await.is_ok() {
+                            space_stop.send(()).ok();
+                        }
+                    });
+                    tx
+                })
+                .clone()
+        })
+    }
+
+    /// Stop all consumers for this dna hash so they release its databases.
+    /// Cells of this dna spawned later start new consumers.
+    pub fn stop_space(&self, dna_hash: &DnaHash) {
+        self.map
+            .share_mut(|map| map.retain(|QueueEntry(hash, _), _| **hash != *dna_hash));
+        if let Some(stop) = self.stops.share_mut(|stops| stops.remove(dna_hash)) {
+            stop.send(()).ok();
         }
     }
 
@@ -416,6 +462,7 @@ pub struct InitialQueueTriggers {
     app_validation: TriggerSender,
     integrate_dht_ops: TriggerSender,
     validation_receipt: TriggerSender,
+    countersigning: TriggerSender,
 }
 
 impl InitialQueueTriggers {
@@ -425,6 +472,7 @@ impl InitialQueueTriggers {
         app_validation: TriggerSender,
         integrate_dht_ops: TriggerSender,
         validation_receipt: TriggerSender,
+        countersigning: TriggerSender,
     ) -> Self {
         Self {
             sys_validation,
@@ -432,6 +480,7 @@ impl InitialQueueTriggers {
             app_validation,
             integrate_dht_ops,
             validation_receipt,
+            countersigning,
         }
     }
 
@@ -442,6 +491,8 @@ impl InitialQueueTriggers {
         self.integrate_dht_ops.trigger(&"init");
         self.publish_dht_ops.trigger(&"init");
         self.validation_receipt.trigger(&"init");
+        // Resume any countersigning sessions persisted before a restart.
+        self.countersigning.trigger(&"init");
     }
 }
 /// The means of nudging a queue consumer to tell it to look for more work
@@ -16,6 +16,7 @@ pub mod real_ribosome;
 use crate::conductor::api::CellConductorApi;
 use crate::conductor::api::CellConductorReadHandle;
 use crate::conductor::api::ZomeCall;
+use crate::conductor::config::ZomeCallConfig;
 use crate::conductor::interface::SignalBroadcaster;
 use crate::core::ribosome::guest_callback::entry_defs::EntryDefsResult;
 use crate::core::ribosome::guest_callback::init::InitInvocation;
@@ -23,10 +24,13 @@ use crate::core::ribosome::guest_callback::init::InitResult;
 use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentInvocation;
 use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentResult;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitInvocation;
+use crate::core::ribosome::guest_callback::rate_limits::RateLimitsInvocation;
+use crate::core::ribosome::guest_callback::rate_limits::RateLimitsResult;
 use crate::core::ribosome::guest_callback::validate::ValidateInvocation;
 use crate::core::ribosome::guest_callback::validate::ValidateResult;
 use crate::core::ribosome::guest_callback::validation_package::ValidationPackageInvocation;
 use crate::core::ribosome::guest_callback::validation_package::ValidationPackageResult;
+use crate::core::ribosome::guest_callback::weigh::WeighInvocation;
 use crate::core::ribosome::guest_callback::CallIterator;
 use derive_more::Constructor;
 use error::RibosomeResult;
@@ -34,8 +38,10 @@ use guest_callback::entry_defs::EntryDefsHostAccess;
 use guest_callback::init::InitHostAccess;
 use guest_callback::migrate_agent::MigrateAgentHostAccess;
 use guest_callback::post_commit::PostCommitHostAccess;
+use guest_callback::rate_limits::RateLimitsHostAccess;
 use guest_callback::validate::ValidateHostAccess;
 use guest_callback::validation_package::ValidationPackageHostAccess;
+use guest_callback::weigh::WeighHostAccess;
 use holo_hash::AgentPubKey;
 use holochain_keystore::MetaLairClient;
 use holochain_p2p::HolochainP2pDna;
@@ -443,6 +449,35 @@ pub struct ZomeCallHostAccess {
     pub network: HolochainP2pDna,
     pub signal_tx: SignalBroadcaster,
     pub call_zome_handle: CellConductorReadHandle,
+    pub limits: ZomeCallLimits,
+}
+
+/// The limits that apply to a single zome call,
+/// derived from the conductor's [`ZomeCallConfig`].
+#[derive(Clone, Copy, Debug)]
+pub struct ZomeCallLimits {
+    /// The time at which the call times out, if it can time out.
+    pub deadline: Option<tokio::time::Instant>,
+    /// The longest a single call to `sleep` may last.
+    pub max_sleep: std::time::Duration,
+}
+
+impl ZomeCallLimits {
+    /// The limits for a zome call starting now.
+    pub fn new(config: &ZomeCallConfig) -> Self {
+        Self {
+            deadline: config
+                .timeout()
+                .map(|timeout| tokio::time::Instant::now() + timeout),
+            max_sleep: config.max_sleep(),
+        }
+    }
+}
+
+impl Default for ZomeCallLimits {
+    fn default() -> Self {
+        Self::new(&ZomeCallConfig::default())
+    }
 }
 
 impl From<ZomeCallHostAccess> for HostContext {
@@ -575,6 +610,20 @@ pub trait RibosomeT: Sized + std::fmt::Debug + Send + Sync {
         invocation: PostCommitInvocation,
     ) -> RibosomeResult<()>;
 
+    fn run_rate_limits(
+        &self,
+        access: RateLimitsHostAccess,
+        invocation: RateLimitsInvocation,
+    ) -> RibosomeResult<RateLimitsResult>;
+
+    /// Weigh an action with the `weigh` callback of a single integrity zome.
+    /// If the zome doesn't implement `weigh` the action is weightless.
+    fn run_weigh(
+        &self,
+        access: WeighHostAccess,
+        invocation: WeighInvocation,
+    ) -> RibosomeResult<EntryRateWeight>;
+
     /// Helper function for running a validation callback. Calls
     /// private fn `do_callback!` under the hood.
     fn run_validate(
@@ -594,7 +643,23 @@ pub trait RibosomeT: Sized + std::fmt::Debug + Send + Sync {
     fn zome_types(&self) -> &Arc<GlobalZomeTypes>;
 }
 
-/// Placeholder for weighing. Currently produces zero weight.
+/// Weigh an action for rate limiting with the integrity zome that defines
+/// its type. Actions with types that aren't defined by an integrity zome,
+/// such as cap grants, are weightless.
+pub fn weigh<R: RibosomeT>(
+    ribosome: &R,
+    zome_id: Option<ZomeId>,
+    input: WeighInput,
+) -> RibosomeResult<EntryRateWeight> {
+    match zome_id.and_then(|zome_id| ribosome.get_integrity_zome(&zome_id)) {
+        Some(zome) => ribosome.run_weigh(WeighHostAccess, WeighInvocation::new(zome, input)),
+        None => Ok(EntryRateWeight::default()),
+    }
+}
+
+/// Weight for countersigned actions.
+/// Every party to a session builds all the actions from the session data,
+/// so the actions can't be weighed by any one party and are weightless.
 pub fn weigh_placeholder() -> EntryRateWeight {
     EntryRateWeight::default()
 }
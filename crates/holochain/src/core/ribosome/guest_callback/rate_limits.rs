@@ -0,0 +1,97 @@
+use crate::core::ribosome::guest_callback::entry_defs::EntryDefsHostAccess;
+use crate::core::ribosome::FnComponents;
+use crate::core::ribosome::HostContext;
+use crate::core::ribosome::Invocation;
+use crate::core::ribosome::InvocationAuth;
+use crate::core::ribosome::ZomesToInvoke;
+use derive_more::Constructor;
+use holochain_serialized_bytes::prelude::*;
+use holochain_types::prelude::*;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Constructor)]
+pub struct RateLimitsInvocation;
+
+/// Rate limits are declared without any access to the host,
+/// the same as entry defs.
+#[derive(Clone, Constructor)]
+pub struct RateLimitsHostAccess;
+
+impl From<RateLimitsHostAccess> for HostContext {
+    fn from(_: RateLimitsHostAccess) -> Self {
+        Self::EntryDefs(EntryDefsHostAccess)
+    }
+}
+
+impl Invocation for RateLimitsInvocation {
+    fn zomes(&self) -> ZomesToInvoke {
+        ZomesToInvoke::AllIntegrity
+    }
+    fn fn_components(&self) -> FnComponents {
+        vec!["rate_limits".into()].into()
+    }
+    fn host_input(self) -> Result<ExternIO, SerializedBytesError> {
+        ExternIO::encode(())
+    }
+    fn auth(&self) -> InvocationAuth {
+        InvocationAuth::LocalCallback
+    }
+}
+
+/// The rate limiting buckets declared by all the integrity zomes of a dna.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct RateLimitsResult(pub BTreeMap<RateBucketId, RateLimit>);
+
+impl RateLimitsResult {
+    /// Get the limit for a bucket, if it was declared.
+    pub fn get(&self, bucket_id: &RateBucketId) -> Option<&RateLimit> {
+        self.0.get(bucket_id)
+    }
+}
+
+impl From<Vec<(ZomeName, RateLimitsCallbackResult)>> for RateLimitsResult {
+    fn from(callback_results: Vec<(ZomeName, RateLimitsCallbackResult)>) -> Self {
+        let mut limits = BTreeMap::new();
+        for (_, RateLimitsCallbackResult::Limits(zome_limits)) in callback_results {
+            for limit in zome_limits {
+                // Buckets are shared by the whole dna, so the first integrity
+                // zome to declare a bucket sets its limit.
+                limits.entry(limit.bucket_id).or_insert(limit);
+            }
+        }
+        Self(limits)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn limit(bucket_id: RateBucketId, capacity: RateBucketCapacity) -> RateLimit {
+        RateLimit {
+            bucket_id,
+            capacity,
+            drain_amount: 1,
+            drain_interval_ms: 1000,
+        }
+    }
+
+    #[test]
+    fn first_declaration_of_a_bucket_wins() {
+        let result: RateLimitsResult = vec![
+            (
+                ZomeName::from("a"),
+                RateLimitsCallbackResult::Limits(vec![limit(0, 10), limit(1, 20)]),
+            ),
+            (
+                ZomeName::from("b"),
+                RateLimitsCallbackResult::Limits(vec![limit(1, 30), limit(2, 40)]),
+            ),
+        ]
+        .into();
+        assert_eq!(result.get(&0), Some(&limit(0, 10)));
+        assert_eq!(result.get(&1), Some(&limit(1, 20)));
+        assert_eq!(result.get(&2), Some(&limit(2, 40)));
+        assert_eq!(result.get(&3), None);
+    }
+}
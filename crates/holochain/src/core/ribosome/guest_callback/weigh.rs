@@ -0,0 +1,42 @@
+use crate::core::ribosome::guest_callback::entry_defs::EntryDefsHostAccess;
+use crate::core::ribosome::FnComponents;
+use crate::core::ribosome::HostContext;
+use crate::core::ribosome::Invocation;
+use crate::core::ribosome::InvocationAuth;
+use crate::core::ribosome::ZomesToInvoke;
+use derive_more::Constructor;
+use holochain_serialized_bytes::prelude::*;
+use holochain_types::prelude::*;
+
+/// Weigh an action with the integrity zome that defines its type.
+#[derive(Clone, Constructor)]
+pub struct WeighInvocation {
+    pub zome: IntegrityZome,
+    pub input: WeighInput,
+}
+
+/// Weighing is a pure function of the action,
+/// so it has no more access to the host than entry defs.
+#[derive(Clone, Constructor)]
+pub struct WeighHostAccess;
+
+impl From<WeighHostAccess> for HostContext {
+    fn from(_: WeighHostAccess) -> Self {
+        Self::EntryDefs(EntryDefsHostAccess)
+    }
+}
+
+impl Invocation for WeighInvocation {
+    fn zomes(&self) -> ZomesToInvoke {
+        ZomesToInvoke::OneIntegrity(self.zome.clone())
+    }
+    fn fn_components(&self) -> FnComponents {
+        vec!["weigh".into()].into()
+    }
+    fn host_input(self) -> Result<ExternIO, SerializedBytesError> {
+        ExternIO::encode(self.input)
+    }
+    fn auth(&self) -> InvocationAuth {
+        InvocationAuth::LocalCallback
+    }
+}
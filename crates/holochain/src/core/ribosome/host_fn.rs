@@ -74,14 +74,14 @@ host_fn_api_impls! {
 
     fn call (Vec<zt::call::Call>) -> Vec<zt::ZomeCallResponse>;
 
-    // @todo List all the local capability claims.
-    fn capability_claims (()) -> ();
+    // List the live capability claims on the local source chain.
+    fn capability_claims (zt::capability::CapClaimQuery) -> Vec<zt::capability::CapClaimInfo>;
 
-    // @todo List all the local capability grants.
-    fn capability_grants (()) -> ();
+    // List the live capability grants on the local source chain.
+    fn capability_grants (zt::capability::CapGrantQuery) -> Vec<zt::capability::CapGrantInfo>;
 
-    // @todo Get the capability for the current zome call.
-    fn capability_info (()) -> ();
+    // Get the capability for the current zome call.
+    fn capability_info (()) -> zt::capability::CapInfo;
 
     // The EntryDefId determines how a create is handled on the host side.
     // CapGrant and CapClaim are handled natively.
@@ -146,6 +146,12 @@ host_fn_api_impls! {
 
     fn get_link_details (Vec<zt::link::GetLinksInput>) -> Vec<zt::link::LinkDetails>;
 
+    // Count the links on a base without getting them.
+    fn count_links (zt::link::LinkQuery) -> usize;
+
+    // Get the validation receipts received for the ops of an action.
+    fn get_validation_receipts (zt::validate::GetValidationReceiptsInput) -> Vec<zt::validate::ValidationReceiptSet>;
+
     // Hash data on the host.
     fn hash (zt::hash::HashInput) -> zt::hash::HashOutput;
 
@@ -158,6 +164,9 @@ host_fn_api_impls! {
     // Retrieve an action from the DHT or short circuit.
     fn must_get_action (zt::entry::MustGetActionInput) -> SignedActionHashed;
 
+    // Retrieve a hash bounded range of an agent's chain or short circuit.
+    fn must_get_agent_activity (zt::chain::MustGetAgentActivityInput) -> Vec<SignedActionHashed>;
+
     // Attempt to accept a preflight request.
     fn accept_countersigning_preflight_request(zt::countersigning::PreflightRequest) -> zt::countersigning::PreflightRequestAcceptance;
 
@@ -1,13 +1,119 @@
 use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
 use crate::core::ribosome::RibosomeT;
-use std::sync::Arc;
+use holochain_types::prelude::*;
 use holochain_wasmer_host::prelude::*;
+use std::sync::Arc;
 
-/// lists all the local claims filtered by tag
+/// lists all the local claims filtered by the query
+/// this is only the current claims as per local CRUD
 pub fn capability_claims(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
-    _input: (),
-) -> Result<(), RuntimeError> {
-    unimplemented!();
+    call_context: Arc<CallContext>,
+    input: CapClaimQuery,
+) -> Result<Vec<CapClaimInfo>, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            read_workspace: Permission::Allow,
+            ..
+        } => tokio_helper::block_forever_on(async move {
+            call_context
+                .host_context
+                .workspace()
+                .source_chain()
+                .as_ref()
+                .expect("Must have source chain if read_workspace access is given")
+                .live_cap_claims(input)
+                .await
+                .map_err(|source_chain_error| -> RuntimeError {
+                    wasm_error!(WasmErrorInner::Host(source_chain_error.to_string())).into()
+                })
+        }),
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "capability_claims".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod wasm_test {
+    use crate::core::ribosome::wasm_test::RibosomeTestFixture;
+    use hdk::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ribosome_list_cap_claims() {
+        observability::test_run().ok();
+        let RibosomeTestFixture {
+            conductor,
+            alice,
+            bob,
+            alice_pubkey,
+            bob_pubkey,
+            ..
+        } = RibosomeTestFixture::new(TestWasm::Capability).await;
+
+        let claims: Vec<CapClaimInfo> = conductor
+            .call(&alice, "list_cap_claims", CapClaimQuery::default())
+            .await;
+        assert!(claims.is_empty());
+
+        // Bob grants alice access and sends her the claim.
+        let _: () = conductor
+            .call(&bob, "send_assigned_cap_claim", alice_pubkey.clone())
+            .await;
+
+        let claims: Vec<CapClaimInfo> = conductor
+            .call(&alice, "list_cap_claims", CapClaimQuery::default())
+            .await;
+        assert_eq!(claims.len(), 1);
+        assert_eq!(claims[0].cap_claim.tag, "has_cap_claim");
+        assert_eq!(claims[0].cap_claim.grantor, bob_pubkey);
+
+        let claims: Vec<CapClaimInfo> = conductor
+            .call(
+                &alice,
+                "list_cap_claims",
+                CapClaimQuery {
+                    tag: Some("has_cap_claim".into()),
+                    grantor: Some(bob_pubkey),
+                },
+            )
+            .await;
+        assert_eq!(claims.len(), 1);
+
+        let claims: Vec<CapClaimInfo> = conductor
+            .call(
+                &alice,
+                "list_cap_claims",
+                CapClaimQuery {
+                    grantor: Some(alice_pubkey.clone()),
+                    ..Default::default()
+                },
+            )
+            .await;
+        assert!(claims.is_empty());
+
+        // Bob's grant shows up as assigned to alice.
+        let grants: Vec<CapGrantInfo> = conductor
+            .call(
+                &bob,
+                "list_cap_grants",
+                CapGrantQuery {
+                    tag: Some("has_cap_claim".into()),
+                    assignee: Some(alice_pubkey),
+                    ..Default::default()
+                },
+            )
+            .await;
+        assert_eq!(grants.len(), 1);
+    }
 }
@@ -1,16 +1,45 @@
 use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
 use crate::core::ribosome::RibosomeT;
+use holochain_types::prelude::*;
 use holochain_wasmer_host::prelude::*;
 use std::sync::Arc;
 
-/// list all the grants stored locally in the chain filtered by tag
+/// list all the grants stored locally in the chain filtered by the query
 /// this is only the current grants as per local CRUD
 pub fn capability_grants(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
-    _input: (),
-) -> Result<(), RuntimeError> {
-    unimplemented!();
+    call_context: Arc<CallContext>,
+    input: CapGrantQuery,
+) -> Result<Vec<CapGrantInfo>, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            read_workspace: Permission::Allow,
+            ..
+        } => tokio_helper::block_forever_on(async move {
+            call_context
+                .host_context
+                .workspace()
+                .source_chain()
+                .as_ref()
+                .expect("Must have source chain if read_workspace access is given")
+                .live_cap_grants(input)
+                .await
+                .map_err(|source_chain_error| -> RuntimeError {
+                    wasm_error!(WasmErrorInner::Host(source_chain_error.to_string())).into()
+                })
+        }),
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "capability_grants".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
 }
 
 #[cfg(test)]
@@ -180,4 +209,81 @@ pub mod wasm_test {
 
         Ok(())
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ribosome_list_cap_grants() {
+        observability::test_run().ok();
+        let RibosomeTestFixture {
+            conductor,
+            alice,
+            bob_pubkey,
+            ..
+        } = RibosomeTestFixture::new(TestWasm::Capability).await;
+
+        let needs_cap_claim = CapGrantQuery {
+            function: Some((TestWasm::Capability.into(), "needs_cap_claim".into())),
+            ..Default::default()
+        };
+
+        // Only the unrestricted grant from init exists.
+        let grants: Vec<CapGrantInfo> = conductor
+            .call(&alice, "list_cap_grants", CapGrantQuery::default())
+            .await;
+        assert_eq!(grants.len(), 1);
+        assert_eq!(grants[0].cap_grant.access, CapAccess::Unrestricted);
+        let grants: Vec<CapGrantInfo> = conductor
+            .call(&alice, "list_cap_grants", needs_cap_claim.clone())
+            .await;
+        assert!(grants.is_empty());
+
+        let secret: CapSecret = conductor.call(&alice, "cap_secret", ()).await;
+        let grant_hash: ActionHash = conductor
+            .call(&alice, "transferable_cap_grant", secret)
+            .await;
+        let grants: Vec<CapGrantInfo> = conductor
+            .call(&alice, "list_cap_grants", needs_cap_claim.clone())
+            .await;
+        assert_eq!(grants.len(), 1);
+        assert_eq!(grants[0].action_hash, grant_hash);
+        assert_eq!(
+            grants[0].cap_grant.access,
+            CapAccess::Transferable { secret }
+        );
+
+        // A transferable grant has no assignees.
+        let grants: Vec<CapGrantInfo> = conductor
+            .call(
+                &alice,
+                "list_cap_grants",
+                CapGrantQuery {
+                    assignee: Some(bob_pubkey),
+                    ..Default::default()
+                },
+            )
+            .await;
+        assert!(grants.is_empty());
+
+        // Rolling the grant replaces it.
+        let rolled_hash: ActionHash = conductor
+            .call(&alice, "roll_cap_grant", grant_hash.clone())
+            .await;
+        let grants: Vec<CapGrantInfo> = conductor
+            .call(&alice, "list_cap_grants", needs_cap_claim.clone())
+            .await;
+        assert_eq!(grants.len(), 1);
+        assert_eq!(grants[0].action_hash, rolled_hash);
+        assert_ne!(
+            grants[0].cap_grant.access,
+            CapAccess::Transferable { secret }
+        );
+
+        // Deleting the grant removes it.
+        let _: ActionHash = conductor
+            .call(&alice, "delete_cap_grant", rolled_hash)
+            .await;
+        let grants: Vec<CapGrantInfo> = conductor
+            .call(&alice, "list_cap_grants", needs_cap_claim)
+            .await;
+        assert!(grants.is_empty());
+    }
 }
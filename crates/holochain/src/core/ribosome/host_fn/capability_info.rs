@@ -1,14 +1,95 @@
 use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
 use crate::core::ribosome::RibosomeT;
-use std::sync::Arc;
+use holochain_types::prelude::*;
 use holochain_wasmer_host::prelude::*;
+use std::sync::Arc;
 
 /// return the access info used for this call
 /// also return who is originated the call (pubkey)
 pub fn capability_info(
-    _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
+    ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
     _input: (),
-) -> Result<(), RuntimeError> {
-    unimplemented!();
+) -> Result<CapInfo, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            bindings: Permission::Allow,
+            read_workspace: Permission::Allow,
+            ..
+        } => {
+            let CallInfo {
+                provenance,
+                cap_grant,
+                ..
+            } = super::call_info::call_info(ribosome, call_context.clone(), ())?;
+            let action_hash = match &cap_grant {
+                CapGrant::ChainAuthor(_) => None,
+                CapGrant::RemoteAgent(zome_call_cap_grant) => {
+                    let query = CapGrantQuery {
+                        tag: Some(zome_call_cap_grant.tag.clone()),
+                        ..Default::default()
+                    };
+                    let grants = tokio_helper::block_forever_on(async move {
+                        call_context
+                            .host_context
+                            .workspace()
+                            .source_chain()
+                            .as_ref()
+                            .expect("Must have source chain if read_workspace access is given")
+                            .live_cap_grants(query)
+                            .await
+                            .map_err(|source_chain_error| -> RuntimeError {
+                                wasm_error!(WasmErrorInner::Host(source_chain_error.to_string()))
+                                    .into()
+                            })
+                    })?;
+                    grants
+                        .into_iter()
+                        .find(|info| info.cap_grant == *zome_call_cap_grant)
+                        .map(|info| info.action_hash)
+                }
+            };
+            Ok(CapInfo {
+                provenance,
+                cap_grant,
+                action_hash,
+            })
+        }
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "capability_info".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod wasm_test {
+    use crate::core::ribosome::wasm_test::RibosomeTestFixture;
+    use hdk::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ribosome_capability_info_test() {
+        observability::test_run().ok();
+        let RibosomeTestFixture {
+            conductor,
+            alice,
+            alice_pubkey,
+            ..
+        } = RibosomeTestFixture::new(TestWasm::Capability).await;
+
+        // The author of a chain calls with the implicit author grant.
+        let info: CapInfo = conductor.call(&alice, "cap_info", ()).await;
+        assert_eq!(info.provenance, alice_pubkey);
+        assert_eq!(info.cap_grant, CapGrant::ChainAuthor(alice_pubkey));
+        assert_eq!(info.action_hash, None);
+    }
 }
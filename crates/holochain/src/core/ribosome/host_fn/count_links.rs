@@ -0,0 +1,96 @@
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
+use crate::core::ribosome::RibosomeT;
+use holochain_cascade::Cascade;
+use holochain_p2p::actor::GetLinksOptions;
+use holochain_types::prelude::*;
+use holochain_wasmer_host::prelude::*;
+use std::sync::Arc;
+
+#[allow(clippy::extra_unused_lifetimes)]
+#[tracing::instrument(skip(_ribosome, call_context), fields(?call_context.zome, function = ?call_context.function_name))]
+pub fn count_links<'a>(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    query: LinkQuery,
+) -> Result<usize, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            read_workspace: Permission::Allow,
+            ..
+        } => {
+            let count = tokio_helper::block_forever_on(async move {
+                Cascade::from_workspace_network(
+                    &call_context.host_context.workspace(),
+                    call_context.host_context.network().to_owned(),
+                )
+                .dht_count_links(query.into(), GetLinksOptions::default())
+                .await
+            })
+            .map_err(|cascade_error| -> RuntimeError {
+                wasm_error!(WasmErrorInner::Host(cascade_error.to_string())).into()
+            })?;
+            Ok(count)
+        }
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "count_links".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod slow_tests {
+    use crate::core::ribosome::wasm_test::RibosomeTestFixture;
+    use hdk::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn count_and_page_links() {
+        observability::test_run().ok();
+        let RibosomeTestFixture {
+            conductor, alice, ..
+        } = RibosomeTestFixture::new(TestWasm::Link).await;
+
+        let mut created: Vec<ActionHash> = Vec::new();
+        for _ in 0..3 {
+            created.push(conductor.call(&alice, "create_link", ()).await);
+        }
+
+        let count: usize = conductor.call(&alice, "count_links", ()).await;
+        assert_eq!(count, 3);
+
+        // Page through the links two at a time.
+        let first: Vec<Link> = conductor
+            .call(&alice, "get_links_page", None::<LinkCursor>)
+            .await;
+        assert_eq!(first.len(), 2);
+        let second: Vec<Link> = conductor
+            .call(
+                &alice,
+                "get_links_page",
+                first.last().map(LinkCursor::from),
+            )
+            .await;
+        assert_eq!(second.len(), 1);
+        let mut paged = first
+            .into_iter()
+            .chain(second)
+            .map(|l| l.create_link_hash)
+            .collect::<Vec<_>>();
+        paged.sort();
+        created.sort();
+        assert_eq!(paged, created);
+
+        let _: () = conductor.call(&alice, "delete_all_links", ()).await;
+        let count: usize = conductor.call(&alice, "count_links", ()).await;
+        assert_eq!(count, 0);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::core::ribosome::weigh;
 use crate::core::ribosome::weigh_placeholder;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::HostFnAccess;
@@ -11,7 +12,7 @@ use std::sync::Arc;
 /// create record
 #[allow(clippy::extra_unused_lifetimes)]
 pub fn create<'a>(
-    _ribosome: Arc<impl RibosomeT>,
+    ribosome: Arc<impl RibosomeT>,
     call_context: Arc<CallContext>,
     input: CreateInput,
 ) -> Result<ActionHash, RuntimeError> {
@@ -27,11 +28,10 @@ pub fn create<'a>(
                 chain_top_ordering,
             } = input;
 
-            let weight = weigh_placeholder();
-
             // Countersigned entries have different action handling.
             match entry {
                 Entry::CounterSign(_, _) => tokio_helper::block_forever_on(async move {
+                    let weight = weigh_placeholder();
                     call_context
                         .host_context
                         .workspace_write()
@@ -49,17 +49,17 @@ pub fn create<'a>(
                     let entry_hash = EntryHash::with_data_sync(&entry);
 
                     // extract the entry defs for a zome
-                    let entry_type = match entry_location {
+                    let (entry_type, zome_id) = match entry_location {
                         EntryDefLocation::App(AppEntryDefLocation {
                             zome_id,
                             entry_def_index,
                         }) => {
                             let app_entry_type =
                                 AppEntryType::new(entry_def_index, zome_id, entry_visibility);
-                            EntryType::App(app_entry_type)
+                            (EntryType::App(app_entry_type), Some(zome_id))
                         }
-                        EntryDefLocation::CapGrant => EntryType::CapGrant,
-                        EntryDefLocation::CapClaim => EntryType::CapClaim,
+                        EntryDefLocation::CapGrant => (EntryType::CapGrant, None),
+                        EntryDefLocation::CapClaim => (EntryType::CapClaim, None),
                     };
 
                     // build an action for the entry being committed
@@ -72,15 +72,36 @@ pub fn create<'a>(
                     // note that validation is handled by the workflow
                     // if the validation fails this commit will be rolled back by virtue of the DB transaction
                     // being atomic
+                    let workspace = call_context.host_context.workspace_write();
+                    let source_chain = workspace
+                        .source_chain()
+                        .as_ref()
+                        .expect("Must have source chain if write_workspace access is given");
+                    let action = source_chain.next_action(action_builder).map_err(
+                        |source_chain_error| -> RuntimeError {
+                            wasm_error!(WasmErrorInner::Host(source_chain_error.to_string()))
+                                .into()
+                        },
+                    )?;
+
+                    // weigh the action with the integrity zome that defines the entry type
+                    let weight = weigh(
+                        ribosome.as_ref(),
+                        zome_id,
+                        WeighInput::Create(action.clone(), entry.clone()),
+                    )
+                    .map_err(|ribosome_error| -> RuntimeError {
+                        wasm_error!(WasmErrorInner::Host(ribosome_error.to_string())).into()
+                    })?;
+
                     tokio_helper::block_forever_on(async move {
                         // push the action and the entry into the source chain
-                        call_context
-                            .host_context
-                            .workspace_write()
-                            .source_chain()
-                            .as_ref()
-                            .expect("Must have source chain if write_workspace access is given")
-                            .put_weightless(action_builder, Some(entry), chain_top_ordering)
+                        source_chain
+                            .put_with_action(
+                                action.weighed(weight).into(),
+                                Some(entry),
+                                chain_top_ordering,
+                            )
                             .await
                             .map_err(|source_chain_error| -> RuntimeError {
                                 wasm_error!(WasmErrorInner::Host(source_chain_error.to_string()))
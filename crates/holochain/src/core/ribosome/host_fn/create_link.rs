@@ -1,3 +1,4 @@
+use crate::core::ribosome::weigh;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::HostFnAccess;
 use crate::core::ribosome::RibosomeError;
@@ -9,7 +10,7 @@ use std::sync::Arc;
 
 #[allow(clippy::extra_unused_lifetimes)]
 pub fn create_link<'a>(
-    _ribosome: Arc<impl RibosomeT>,
+    ribosome: Arc<impl RibosomeT>,
     call_context: Arc<CallContext>,
     input: CreateLinkInput,
 ) -> Result<ActionHash, RuntimeError> {
@@ -30,6 +31,26 @@ pub fn create_link<'a>(
             // Construct the link add
             let action_builder =
                 builder::CreateLink::new(base_address, target_address, zome_id, link_type, tag);
+            let action = call_context
+                .host_context
+                .workspace_write()
+                .source_chain()
+                .as_ref()
+                .expect("Must have source chain if write_workspace access is given")
+                .next_action(action_builder)
+                .map_err(|source_chain_error| -> RuntimeError {
+                    wasm_error!(WasmErrorInner::Host(source_chain_error.to_string())).into()
+                })?;
+
+            // weigh the link with the integrity zome that defines the link type
+            let weight = weigh(
+                ribosome.as_ref(),
+                Some(zome_id),
+                WeighInput::Link(action.clone()),
+            )
+            .map_err(|ribosome_error| -> RuntimeError {
+                wasm_error!(WasmErrorInner::Host(ribosome_error.to_string())).into()
+            })?;
 
             let action_hash = tokio_helper::block_forever_on(tokio::task::spawn(async move {
                 // push the action into the source chain
@@ -39,7 +60,7 @@ pub fn create_link<'a>(
                     .source_chain()
                     .as_ref()
                     .expect("Must have source chain if write_workspace access is given")
-                    .put_weightless(action_builder, None, chain_top_ordering)
+                    .put_with_action(action.weighed(weight.into()).into(), None, chain_top_ordering)
                     .await?;
                 Ok::<ActionHash, RibosomeError>(action_hash)
             }))
@@ -1,4 +1,5 @@
 use crate::core::ribosome::error::RibosomeError;
+use crate::core::ribosome::weigh;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::RibosomeT;
 use holochain_cascade::error::CascadeError;
@@ -13,7 +14,7 @@ use std::sync::Arc;
 
 #[allow(clippy::extra_unused_lifetimes)]
 pub fn delete<'a>(
-    _ribosome: Arc<impl RibosomeT>,
+    ribosome: Arc<impl RibosomeT>,
     call_context: Arc<CallContext>,
     input: DeleteInput,
 ) -> Result<ActionHash, RuntimeError> {
@@ -26,24 +27,43 @@ pub fn delete<'a>(
                 deletes_action_hash,
                 chain_top_ordering,
             } = input;
-            let (deletes_entry_address, _) =
+            let (deletes_entry_address, entry_type) =
                 get_original_entry_data(call_context.clone(), deletes_action_hash.clone())?;
+            let zome_id = match &entry_type {
+                EntryType::App(app_entry_type) => Some(app_entry_type.zome_id()),
+                _ => None,
+            };
 
             let host_access = call_context.host_context();
+            let source_chain = host_access
+                .workspace_write()
+                .source_chain()
+                .as_ref()
+                .expect("Must have source chain if write_workspace access is given");
+            let action_builder = builder::Delete {
+                deletes_address: deletes_action_hash,
+                deletes_entry_address,
+            };
+            let action = source_chain
+                .next_action(action_builder)
+                .map_err(|source_chain_error| {
+                    wasm_error!(WasmErrorInner::Host(source_chain_error.to_string()))
+                })?;
+
+            // weigh the action with the integrity zome that defines the deleted entry type
+            let weight = weigh(ribosome.as_ref(), zome_id, WeighInput::Delete(action.clone()))
+                .map_err(|ribosome_error| {
+                    wasm_error!(WasmErrorInner::Host(ribosome_error.to_string()))
+                })?;
 
             // handle timeouts at the source chain layer
             tokio_helper::block_forever_on(async move {
-                let source_chain = host_access
-                    .workspace_write()
-                    .source_chain()
-                    .as_ref()
-                    .expect("Must have source chain if write_workspace access is given");
-                let action_builder = builder::Delete {
-                    deletes_address: deletes_action_hash,
-                    deletes_entry_address,
-                };
                 let action_hash = source_chain
-                    .put_weightless(action_builder, None, chain_top_ordering)
+                    .put_with_action(
+                        action.weighed(weight.into()).into(),
+                        None,
+                        chain_top_ordering,
+                    )
                     .await
                     .map_err(|source_chain_error| {
                         wasm_error!(WasmErrorInner::Host(source_chain_error.to_string()))
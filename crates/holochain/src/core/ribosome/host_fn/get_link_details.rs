@@ -23,17 +23,7 @@ pub fn get_link_details<'a>(
             let results: Vec<Result<Vec<_>, RibosomeError>> =
                 tokio_helper::block_forever_on(async move {
                     join_all(inputs.into_iter().map(|input| async {
-                        let GetLinksInput {
-                            base_address,
-                            link_type,
-                            tag_prefix,
-                        } = input;
-
-                        let key = WireLinkKey {
-                            base: base_address,
-                            type_query: link_type,
-                            tag: tag_prefix,
-                        };
+                        let key = WireLinkKey::from(input);
                         Ok(Cascade::from_workspace_network(
                             &call_context.host_context.workspace(),
                             call_context.host_context.network().to_owned(),
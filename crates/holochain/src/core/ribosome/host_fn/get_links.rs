@@ -26,17 +26,7 @@ pub fn get_links<'a>(
             let results: Vec<Result<Vec<Link>, RibosomeError>> =
                 tokio_helper::block_forever_on(async move {
                     futures::stream::iter(inputs.into_iter().map(|input| async {
-                        let GetLinksInput {
-                            base_address,
-                            link_type,
-                            tag_prefix,
-                        } = input;
-
-                        let key = WireLinkKey {
-                            base: base_address,
-                            type_query: link_type,
-                            tag: tag_prefix,
-                        };
+                        let key = WireLinkKey::from(input);
                         Ok(Cascade::from_workspace_network(
                             &call_context.host_context.workspace(),
                             call_context.host_context.network().to_owned(),
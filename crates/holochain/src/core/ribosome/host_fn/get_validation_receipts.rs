@@ -0,0 +1,90 @@
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
+use crate::core::ribosome::RibosomeT;
+use holochain_state::prelude::*;
+use holochain_types::prelude::*;
+use holochain_wasmer_host::prelude::*;
+use std::sync::Arc;
+
+#[allow(clippy::extra_unused_lifetimes)]
+#[tracing::instrument(skip(_ribosome, call_context), fields(?call_context.zome, function = ?call_context.function_name))]
+pub fn get_validation_receipts<'a>(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: GetValidationReceiptsInput,
+) -> Result<Vec<ValidationReceiptSet>, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            read_workspace: Permission::Allow,
+            ..
+        } => {
+            let (_, dht_db, _) = call_context.host_context.workspace().databases();
+            let GetValidationReceiptsInput { action_hash } = input;
+            tokio_helper::block_forever_on(async move {
+                dht_db
+                    .async_reader(move |txn| list_receipt_sets(&txn, Some(&action_hash), None))
+                    .await
+            })
+            .map_err(|state_error| -> RuntimeError {
+                wasm_error!(WasmErrorInner::Host(state_error.to_string())).into()
+            })
+        }
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "get_validation_receipts".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod slow_tests {
+    use crate::conductor::handle::ConductorHandleT;
+    use crate::core::ribosome::wasm_test::RibosomeTestFixture;
+    use hdk::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn get_validation_receipts_for_own_action() {
+        observability::test_run().ok();
+        let RibosomeTestFixture {
+            conductor, alice, ..
+        } = RibosomeTestFixture::new(TestWasm::Create).await;
+
+        let action_hash: ActionHash = conductor.call(&alice, "create_entry", ()).await;
+
+        // Alice and Bob share a conductor, which never sends receipts to itself,
+        // but the ops it holds for the action are still listed.
+        crate::assert_eq_retry_10s!(
+            {
+                let sets: Vec<ValidationReceiptSet> = conductor
+                    .call(&alice, "get_validation_receipts", action_hash.clone())
+                    .await;
+                let mut op_types: Vec<_> = sets
+                    .iter()
+                    .inspect(|set| assert!(set.receipts.is_empty()))
+                    .map(|set| set.op_type.clone())
+                    .collect();
+                op_types.sort();
+                op_types
+            },
+            vec![
+                "RegisterAgentActivity".to_string(),
+                "StoreEntry".to_string(),
+                "StoreRecord".to_string(),
+            ]
+        );
+
+        let sets = conductor
+            .dump_validation_receipts(alice.cell_id(), Some(action_hash))
+            .await
+            .unwrap();
+        assert_eq!(sets.len(), 3);
+    }
+}
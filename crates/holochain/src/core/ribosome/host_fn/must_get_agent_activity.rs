@@ -0,0 +1,228 @@
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostContext;
+use crate::core::ribosome::RibosomeError;
+use crate::core::ribosome::RibosomeT;
+use holochain_cascade::Cascade;
+use holochain_types::prelude::*;
+use holochain_wasmer_host::prelude::*;
+use std::sync::Arc;
+
+#[allow(clippy::extra_unused_lifetimes)]
+pub fn must_get_agent_activity<'a>(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: MustGetAgentActivityInput,
+) -> Result<Vec<SignedActionHashed>, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            read_workspace_deterministic: Permission::Allow,
+            ..
+        } => {
+            let MustGetAgentActivityInput {
+                author,
+                chain_filter,
+            } = input;
+            let chain_top = chain_filter.chain_top.clone();
+
+            // timeouts must be handled by the network
+            tokio_helper::block_forever_on(async move {
+                let workspace = call_context.host_context.workspace();
+                let mut cascade = match call_context.host_context {
+                    HostContext::Validate(_) => Cascade::from_workspace(workspace.stores(), None),
+                    _ => Cascade::from_workspace_network(
+                        &workspace,
+                        call_context.host_context.network().clone(),
+                    ),
+                };
+                let result = cascade
+                    .must_get_agent_activity(author.clone(), chain_filter)
+                    .await
+                    .map_err(|cascade_error| -> RuntimeError {
+                        wasm_error!(WasmErrorInner::Host(cascade_error.to_string())).into()
+                    })?;
+                match result {
+                    MustGetAgentActivityResponse::Activity(activity) => Ok(activity),
+                    MustGetAgentActivityResponse::ChainTopNotFound(ref missing)
+                    | MustGetAgentActivityResponse::IncompleteChain(ref missing) => match call_context.host_context {
+                        HostContext::EntryDefs(_)
+                        | HostContext::GenesisSelfCheck(_)
+                        | HostContext::MigrateAgent(_)
+                        | HostContext::PostCommit(_)
+                        | HostContext::ZomeCall(_) => Err(wasm_error!(WasmErrorInner::Host(
+                            format!(
+                                "Failed to get agent activity for {} from {}: {:?}",
+                                author, chain_top, result
+                            )
+                        ))
+                        .into()),
+                        HostContext::Init(_) => Err(wasm_error!(WasmErrorInner::HostShortCircuit(
+                            holochain_serialized_bytes::encode(
+                                &ExternIO::encode(InitCallbackResult::UnresolvedDependencies(
+                                    vec![missing.clone().into()],
+                                ))
+                                .map_err(|e| -> RuntimeError { wasm_error!(e.into()).into() })?,
+                            )
+                            .map_err(|e| -> RuntimeError { wasm_error!(e.into()).into() })?
+                        ))
+                        .into()),
+                        HostContext::Validate(_) => Err(wasm_error!(WasmErrorInner::HostShortCircuit(
+                            holochain_serialized_bytes::encode(
+                                &ExternIO::encode(ValidateCallbackResult::UnresolvedDependencies(
+                                    vec![missing.clone().into()],
+                                ))
+                                .map_err(|e| -> RuntimeError { wasm_error!(e.into()).into() })?,
+                            )
+                            .map_err(|e| -> RuntimeError { wasm_error!(e.into()).into() })?
+                        ))
+                        .into()),
+                        HostContext::ValidationPackage(_) =>
+                           Err(wasm_error!(WasmErrorInner::HostShortCircuit(
+                                holochain_serialized_bytes::encode(
+                                    &ExternIO::encode(
+                                        ValidationPackageCallbackResult::UnresolvedDependencies(
+                                            vec![missing.clone().into(),]
+                                        ),
+                                    )
+                                    .map_err(|e| -> RuntimeError { wasm_error!(e.into()).into() })?
+                                )
+                                .map_err(|e| -> RuntimeError { wasm_error!(e.into()).into() })?,
+                            ))
+                            .into())
+                    },
+                }
+            })
+        }
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "must_get_agent_activity".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use crate::core::ribosome::wasm_test::RibosomeTestFixture;
+    use crate::test_entry_impl;
+    use ::fixt::prelude::*;
+    use hdk::prelude::*;
+    use holochain_state::prelude::insert_action;
+    use holochain_zome_types::fixt::*;
+    use holochain_wasm_test_utils::TestWasm;
+
+    /// Mimics inside the must_get wasm.
+    #[derive(serde::Serialize, serde::Deserialize, SerializedBytes, Debug, PartialEq)]
+    struct Something(#[serde(with = "serde_bytes")] Vec<u8>);
+
+    test_entry_impl!(Something);
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ribosome_must_get_agent_activity_test<'a>() {
+        observability::test_run().ok();
+        let RibosomeTestFixture {
+            conductor,
+            alice,
+            alice_pubkey,
+            alice_host_fn_caller,
+            ..
+        } = RibosomeTestFixture::new(TestWasm::MustGet).await;
+
+        let mut commits = Vec::new();
+        for i in 0..3 {
+            let entry = Entry::try_from(Something(vec![i])).unwrap();
+            commits.push(
+                alice_host_fn_caller
+                    .commit_entry(
+                        entry,
+                        EntryDefLocation::app(0, EntryDefIndex(0)),
+                        EntryVisibility::Public,
+                    )
+                    .await,
+            );
+        }
+
+        // The whole chain is walked back from the top to genesis.
+        let activity: Vec<SignedActionHashed> = conductor
+            .call(
+                &alice,
+                "must_get_agent_activity",
+                MustGetAgentActivityInput::new(
+                    alice_pubkey.clone(),
+                    ChainFilter::new(commits[2].clone()),
+                ),
+            )
+            .await;
+        assert_eq!(activity[0].as_hash(), &commits[2]);
+        let activity_seq = activity[0].action().action_seq();
+        assert_eq!(activity.last().unwrap().action().action_seq(), 0);
+        assert!(activity
+            .windows(2)
+            .all(|w| w[0].action().prev_action() == Some(w[1].as_hash())));
+
+        // The range stops at the until hash.
+        let activity: Vec<ActionHash> = conductor
+            .call::<_, Vec<SignedActionHashed>, _>(
+                &alice,
+                "must_get_agent_activity",
+                MustGetAgentActivityInput::new(
+                    alice_pubkey.clone(),
+                    ChainFilter::new(commits[2].clone()).until(commits[0].clone()),
+                ),
+            )
+            .await
+            .into_iter()
+            .map(|a| a.as_hash().clone())
+            .collect();
+        assert_eq!(activity, commits.iter().rev().cloned().collect::<Vec<_>>());
+
+        // A chain top that doesn't exist is an error.
+        let bad_chain_top = ActionHash::from_raw_32(vec![2; 32]);
+        let result: Result<Vec<SignedActionHashed>, _> = conductor
+            .call_fallible(
+                &alice,
+                "must_get_agent_activity",
+                MustGetAgentActivityInput::new(
+                    alice_pubkey.clone(),
+                    ChainFilter::new(bad_chain_top),
+                ),
+            )
+            .await;
+        assert!(result.is_err());
+
+        // A hole in the middle of the chain reports the missing action.
+        let seq = activity_seq + 1;
+        let mut gap = fixt!(Create);
+        gap.author = alice_pubkey.clone();
+        gap.prev_action = commits[2].clone();
+        gap.action_seq = seq;
+        let gap = Action::Create(gap);
+        let gap_hash = ActionHash::with_data_sync(&gap);
+        let mut top = fixt!(Create);
+        top.author = alice_pubkey.clone();
+        top.prev_action = gap_hash.clone();
+        top.action_seq = seq + 1;
+        let top = SignedActionHashed::with_presigned(
+            ActionHashed::from_content_sync(Action::Create(top)),
+            fixt!(Signature),
+        );
+        alice_host_fn_caller
+            .cache
+            .test_commit(|txn| insert_action(txn, &top).unwrap());
+        let result: Result<Vec<SignedActionHashed>, _> = conductor
+            .call_fallible(
+                &alice,
+                "must_get_agent_activity",
+                MustGetAgentActivityInput::new(
+                    alice_pubkey,
+                    ChainFilter::new(top.as_hash().clone()).until(commits[0].clone()),
+                ),
+            )
+            .await;
+        let error = format!("{:?}", result.unwrap_err());
+        assert!(error.contains(&format!("IncompleteChain({:?})", gap_hash)));
+    }
+}
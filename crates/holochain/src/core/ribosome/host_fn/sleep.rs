@@ -1,12 +1,118 @@
 use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
 use crate::core::ribosome::RibosomeT;
-use std::sync::Arc;
+use crate::core::ribosome::ZomeCallHostAccess;
+use holochain_types::access::Permission;
+use holochain_util::tokio_helper;
 use holochain_wasmer_host::prelude::*;
+use std::sync::Arc;
 
+/// Suspend the zome call for the given duration.
+///
+/// Only zome calls may sleep, as every other callback is either
+/// expected to be deterministic or holds up the conductor while it runs.
+/// The wasm call runs on a blocking thread so waiting on the tokio timer
+/// here doesn't hold up any other task.
 pub fn sleep(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
-    _input: core::time::Duration,
+    call_context: Arc<CallContext>,
+    input: core::time::Duration,
 ) -> Result<(), RuntimeError> {
-    unimplemented!()
+    match (
+        HostFnAccess::from(&call_context.host_context()),
+        &call_context.host_context,
+    ) {
+        (
+            HostFnAccess {
+                non_determinism: Permission::Allow,
+                ..
+            },
+            HostContext::ZomeCall(ZomeCallHostAccess { limits, .. }),
+        ) => {
+            if input > limits.max_sleep {
+                return Err(wasm_error!(WasmErrorInner::Host(format!(
+                    "Cannot sleep for {:?} as the maximum is {:?}",
+                    input, limits.max_sleep
+                )))
+                .into());
+            }
+            let wake = tokio::time::Instant::now() + input;
+            match limits.deadline {
+                // Fail early rather than sleep through the timeout.
+                Some(deadline) if wake > deadline => Err(wasm_error!(WasmErrorInner::Host(
+                    format!("Cannot sleep for {:?} as the zome call would time out", input)
+                ))
+                .into()),
+                _ => {
+                    tokio_helper::block_forever_on(tokio::time::sleep_until(wake));
+                    Ok(())
+                }
+            }
+        }
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "sleep".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod wasm_test {
+    use crate::conductor::config::ConductorConfig;
+    use crate::conductor::config::ZomeCallConfig;
+    use crate::sweettest::SweetConductor;
+    use crate::sweettest::SweetDnaFile;
+    use holochain_wasm_test_utils::TestWasm;
+    use std::time::Duration;
+    use std::time::Instant;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn invoke_import_sleep_test() {
+        observability::test_run().ok();
+        let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Sleep])
+            .await
+            .unwrap();
+        let mut conductor = SweetConductor::from_config(ConductorConfig {
+            zome_call: ZomeCallConfig {
+                timeout_ms: Some(1_000),
+                max_sleep_ms: 2_000,
+            },
+            ..Default::default()
+        })
+        .await;
+        let app = conductor.setup_app("app", &[dna]).await.unwrap();
+        let (cell,) = app.into_tuple();
+        let zome = cell.zome(TestWasm::Sleep);
+
+        // A short sleep suspends the call.
+        let start = Instant::now();
+        let _: () = conductor.call(&zome, "sleep", 100u64).await;
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        // Sleeping longer than the maximum fails without sleeping.
+        let start = Instant::now();
+        let result: Result<(), _> = conductor.call_fallible(&zome, "sleep", 5_000u64).await;
+        assert!(result.is_err());
+        assert!(start.elapsed() < Duration::from_millis(1_000));
+
+        // Sleeping past the zome call timeout fails without sleeping.
+        let start = Instant::now();
+        let result: Result<(), _> = conductor.call_fallible(&zome, "sleep", 1_500u64).await;
+        assert!(result.is_err());
+        assert!(start.elapsed() < Duration::from_millis(1_000));
+
+        // Sleeping in a loop still hits the timeout.
+        let start = Instant::now();
+        let result: Result<(), _> = conductor.call_fallible(&zome, "sleep_loop", 200u64).await;
+        assert!(result.is_err());
+        assert!(start.elapsed() < Duration::from_millis(1_500));
+    }
 }
@@ -1,4 +1,5 @@
 use super::delete::get_original_entry_data;
+use crate::core::ribosome::weigh;
 use crate::core::ribosome::weigh_placeholder;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::HostFnAccess;
@@ -11,7 +12,7 @@ use std::sync::Arc;
 
 #[allow(clippy::extra_unused_lifetimes)]
 pub fn update<'a>(
-    _ribosome: Arc<impl RibosomeT>,
+    ribosome: Arc<impl RibosomeT>,
     call_context: Arc<CallContext>,
     input: UpdateInput,
 ) -> Result<ActionHash, RuntimeError> {
@@ -30,11 +31,10 @@ pub fn update<'a>(
             let (original_entry_address, entry_type) =
                 get_original_entry_data(call_context.clone(), original_action_address.clone())?;
 
-            let weight = weigh_placeholder();
-
             // Countersigned entries have different action handling.
             match entry {
                 Entry::CounterSign(_, _) => tokio_helper::block_forever_on(async move {
+                    let weight = weigh_placeholder();
                     call_context
                         .host_context
                         .workspace_write()
@@ -52,6 +52,10 @@ pub fn update<'a>(
                     let entry_hash = EntryHash::with_data_sync(&entry);
 
                     // build an action for the entry being updated
+                    let zome_id = match &entry_type {
+                        EntryType::App(app_entry_type) => Some(app_entry_type.zome_id()),
+                        _ => None,
+                    };
                     let action_builder = builder::Update {
                         original_entry_address,
                         original_action_address,
@@ -59,19 +63,39 @@ pub fn update<'a>(
                         entry_hash,
                     };
                     let workspace = call_context.host_context.workspace_write();
+                    let source_chain = workspace
+                        .source_chain()
+                        .as_ref()
+                        .expect("Must have source chain if write_workspace access is given");
+                    let action = source_chain.next_action(action_builder).map_err(
+                        |source_chain_error| -> RuntimeError {
+                            wasm_error!(WasmErrorInner::Host(source_chain_error.to_string()))
+                                .into()
+                        },
+                    )?;
+
+                    // weigh the action with the integrity zome that defines the entry type
+                    let weight = weigh(
+                        ribosome.as_ref(),
+                        zome_id,
+                        WeighInput::Update(action.clone(), entry.clone()),
+                    )
+                    .map_err(|ribosome_error| -> RuntimeError {
+                        wasm_error!(WasmErrorInner::Host(ribosome_error.to_string())).into()
+                    })?;
 
                     // return the hash of the updated entry
                     // note that validation is handled by the workflow
                     // if the validation fails this update will be rolled back by virtue of the DB transaction
                     // being atomic
                     tokio_helper::block_forever_on(async move {
-                        let source_chain = workspace
-                            .source_chain()
-                            .as_ref()
-                            .expect("Must have source chain if write_workspace access is given");
                         // push the action and the entry into the source chain
                         let action_hash = source_chain
-                            .put_weightless(action_builder, Some(entry), chain_top_ordering)
+                            .put_with_action(
+                                action.weighed(weight).into(),
+                                Some(entry),
+                                chain_top_ordering,
+                            )
                             .await
                             .map_err(|source_chain_error| -> RuntimeError {
                                 wasm_error!(WasmErrorInner::Host(source_chain_error.to_string()))
@@ -2,12 +2,15 @@ use super::guest_callback::entry_defs::EntryDefsHostAccess;
 use super::guest_callback::init::InitHostAccess;
 use super::guest_callback::migrate_agent::MigrateAgentHostAccess;
 use super::guest_callback::post_commit::PostCommitHostAccess;
+use super::guest_callback::rate_limits::RateLimitsHostAccess;
 use super::guest_callback::validate::ValidateHostAccess;
 use super::guest_callback::validation_package::ValidationPackageHostAccess;
+use super::guest_callback::weigh::WeighHostAccess;
 use super::host_fn::get_agent_activity::get_agent_activity;
 use super::host_fn::HostFnApi;
 use super::HostContext;
 use super::ZomeCallHostAccess;
+use crate::conductor::metrics::Histograms;
 use crate::core::ribosome::error::RibosomeError;
 use crate::core::ribosome::error::RibosomeResult;
 use crate::core::ribosome::guest_callback::entry_defs::EntryDefsInvocation;
@@ -20,10 +23,13 @@ use crate::core::ribosome::guest_callback::init::InitResult;
 use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentInvocation;
 use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentResult;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitInvocation;
+use crate::core::ribosome::guest_callback::rate_limits::RateLimitsInvocation;
+use crate::core::ribosome::guest_callback::rate_limits::RateLimitsResult;
 use crate::core::ribosome::guest_callback::validate::ValidateInvocation;
 use crate::core::ribosome::guest_callback::validate::ValidateResult;
 use crate::core::ribosome::guest_callback::validation_package::ValidationPackageInvocation;
 use crate::core::ribosome::guest_callback::validation_package::ValidationPackageResult;
+use crate::core::ribosome::guest_callback::weigh::WeighInvocation;
 use crate::core::ribosome::guest_callback::CallIterator;
 use crate::core::ribosome::host_fn::accept_countersigning_preflight_request::accept_countersigning_preflight_request;
 use crate::core::ribosome::host_fn::agent_info::agent_info;
@@ -32,6 +38,7 @@ use crate::core::ribosome::host_fn::call_info::call_info;
 use crate::core::ribosome::host_fn::capability_claims::capability_claims;
 use crate::core::ribosome::host_fn::capability_grants::capability_grants;
 use crate::core::ribosome::host_fn::capability_info::capability_info;
+use crate::core::ribosome::host_fn::count_links::count_links;
 use crate::core::ribosome::host_fn::create::create;
 use crate::core::ribosome::host_fn::create_link::create_link;
 use crate::core::ribosome::host_fn::create_x25519_keypair::create_x25519_keypair;
@@ -43,8 +50,10 @@ use crate::core::ribosome::host_fn::get::get;
 use crate::core::ribosome::host_fn::get_details::get_details;
 use crate::core::ribosome::host_fn::get_link_details::get_link_details;
 use crate::core::ribosome::host_fn::get_links::get_links;
+use crate::core::ribosome::host_fn::get_validation_receipts::get_validation_receipts;
 use crate::core::ribosome::host_fn::hash::hash;
 use crate::core::ribosome::host_fn::must_get_action::must_get_action;
+use crate::core::ribosome::host_fn::must_get_agent_activity::must_get_agent_activity;
 use crate::core::ribosome::host_fn::must_get_entry::must_get_entry;
 use crate::core::ribosome::host_fn::must_get_valid_record::must_get_valid_record;
 use crate::core::ribosome::host_fn::query::query;
@@ -84,6 +93,7 @@ use holochain_types::zome_types::GlobalZomeTypes;
 use holochain_types::zome_types::ZomeTypesError;
 use holochain_wasmer_host::prelude::*;
 use once_cell::sync::Lazy;
+use once_cell::sync::OnceCell;
 use std::collections::HashMap;
 use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
@@ -103,6 +113,12 @@ pub struct RealRibosome {
 
     /// Dependencies for every zome.
     pub zome_dependencies: Arc<HashMap<ZomeName, Vec<ZomeId>>>,
+
+    /// Rate limits declared by the integrity zomes, cached on first use.
+    rate_limits: Arc<OnceCell<Arc<RateLimitsResult>>>,
+
+    /// The histograms of the conductor this ribosome is registered with.
+    histograms: Option<Arc<Histograms>>,
 }
 
 struct HostFnBuilder {
@@ -238,6 +254,8 @@ impl RealRibosome {
             dna_file,
             zome_types: Default::default(),
             zome_dependencies: Default::default(),
+            rate_limits: Default::default(),
+            histograms: None,
         };
 
         // Collect the number of entry and link types
@@ -323,6 +341,8 @@ impl RealRibosome {
             dna_file: ribosome.dna_file,
             zome_types,
             zome_dependencies: Arc::new(zome_dependencies),
+            rate_limits: Default::default(),
+            histograms: None,
         })
     }
 
@@ -332,9 +352,35 @@ impl RealRibosome {
             dna_file,
             zome_types: Default::default(),
             zome_dependencies: Default::default(),
+            rate_limits: Default::default(),
+            histograms: None,
         }
     }
 
+    /// Record the durations of wasm instantiation into these histograms.
+    pub fn with_histograms(mut self, histograms: Arc<Histograms>) -> Self {
+        self.histograms = Some(histograms);
+        self
+    }
+
+    /// The histograms of the conductor this ribosome is registered with, if any.
+    pub fn histograms(&self) -> Option<Arc<Histograms>> {
+        self.histograms.clone()
+    }
+
+    /// The rate limits declared by the integrity zomes of this dna.
+    /// The `rate_limits` callbacks are only run the first time.
+    pub fn rate_limits(&self) -> RibosomeResult<Arc<RateLimitsResult>> {
+        self.rate_limits
+            .get_or_try_init(|| {
+                Ok(Arc::new(self.run_rate_limits(
+                    RateLimitsHostAccess,
+                    RateLimitsInvocation,
+                )?))
+            })
+            .cloned()
+    }
+
     pub fn module(&self, zome_name: &ZomeName) -> RibosomeResult<Arc<Module>> {
         if holochain_wasmer_host::module::SERIALIZED_MODULE_CACHE
             .get()
@@ -473,7 +519,12 @@ impl RealRibosome {
                 .insert(context_key, Arc::new(call_context));
         }
         // Fallback to creating the instance.
+        let start = std::time::Instant::now();
         let instance = fallback(context_key)?;
+        if let Some(histograms) = &self.histograms {
+            histograms
+                .record_wasm_instantiation_duration(self.dna_file.dna_hash(), start.elapsed());
+        }
         Ok((instance, context_key))
     }
 
@@ -559,9 +610,20 @@ impl RealRibosome {
             .with_host_function(&mut ns, "__get_details", get_details)
             .with_host_function(&mut ns, "__get_links", get_links)
             .with_host_function(&mut ns, "__get_link_details", get_link_details)
+            .with_host_function(&mut ns, "__count_links", count_links)
             .with_host_function(&mut ns, "__get_agent_activity", get_agent_activity)
+            .with_host_function(
+                &mut ns,
+                "__get_validation_receipts",
+                get_validation_receipts,
+            )
             .with_host_function(&mut ns, "__must_get_entry", must_get_entry)
             .with_host_function(&mut ns, "__must_get_action", must_get_action)
+            .with_host_function(
+                &mut ns,
+                "__must_get_agent_activity",
+                must_get_agent_activity,
+            )
             .with_host_function(&mut ns, "__must_get_valid_record", must_get_valid_record)
             .with_host_function(
                 &mut ns,
@@ -880,6 +942,28 @@ impl RibosomeT for RealRibosome {
         do_callback!(self, host_access, invocation, EntryDefsCallbackResult)
     }
 
+    fn run_rate_limits(
+        &self,
+        host_access: RateLimitsHostAccess,
+        invocation: RateLimitsInvocation,
+    ) -> RibosomeResult<RateLimitsResult> {
+        do_callback!(self, host_access, invocation, RateLimitsCallbackResult)
+    }
+
+    fn run_weigh(
+        &self,
+        host_access: WeighHostAccess,
+        invocation: WeighInvocation,
+    ) -> RibosomeResult<EntryRateWeight> {
+        match self.call_iterator(host_access.into(), invocation).next() {
+            Ok(Some((_zome, extern_io))) => Ok(extern_io
+                .decode()
+                .map_err(|e| -> RuntimeError { wasm_error!(e.into()).into() })?),
+            Ok(None) => Ok(EntryRateWeight::default()),
+            Err((_zome, ribosome_error)) => Err(ribosome_error),
+        }
+    }
+
     fn run_migrate_agent(
         &self,
         host_access: MigrateAgentHostAccess,
@@ -2,6 +2,9 @@
 //! This module contains all the checks we run for sys validation
 
 use super::queue_consumer::TriggerSender;
+use super::ribosome::real_ribosome::RealRibosome;
+use super::ribosome::weigh;
+use super::ribosome::weigh_placeholder;
 use super::ribosome::RibosomeT;
 use super::workflow::incoming_dht_ops_workflow::incoming_dht_ops_workflow;
 use super::workflow::sys_validation_workflow::SysValidationWorkspace;
@@ -222,10 +225,187 @@ pub async fn check_chain_rollback(
     }
 }
 
-/// Placeholder for future spam check.
-/// Check action timestamps don't exceed MAX_PUBLISH_FREQUENCY
-pub async fn check_spam(_action: &Action) -> SysValidationResult<()> {
-    Ok(())
+/// The number of actions read from an author's chain at a time when
+/// replaying a rate limiting bucket.
+const RATE_LIMIT_PAGE_SIZE: u32 = 100;
+
+/// Check the action is weighed as its integrity zome weighs it and the
+/// author hasn't exceeded the capacity of the rate limiting bucket it is
+/// weighed in.
+///
+/// The level of the bucket is replayed along the author's chain, draining
+/// between actions by the time elapsed. The chain is only walked back until
+/// the bucket would have fully drained, as nothing before that affects it.
+/// The weight of private entries can't be checked by other agents, so it is
+/// taken as declared.
+pub async fn check_rate_limit(
+    action: &Action,
+    maybe_entry: Option<&Entry>,
+    dna_hash: &DnaHash,
+    workspace: &SysValidationWorkspace,
+    network: HolochainP2pDna,
+    conductor: &dyn ConductorHandleT,
+) -> SysValidationResult<()> {
+    let ribosome = conductor
+        .get_ribosome(dna_hash)
+        .map_err(|_| SysValidationError::DnaMissing(dna_hash.clone()))?;
+    check_rate_weight(action, maybe_entry, ribosome.clone(), workspace, network).await?;
+
+    let RateWeight { bucket_id, units } = action.rate_data();
+    if units == 0 {
+        return Ok(());
+    }
+    let rate_limits = tokio::task::spawn_blocking(move || ribosome.rate_limits()).await??;
+
+    // Buckets that no integrity zome declares are unlimited.
+    let rate_limit = match rate_limits.get(&bucket_id) {
+        Some(rate_limit) => rate_limit.clone(),
+        None => return Ok(()),
+    };
+
+    // Walk back from this action, keeping the earlier actions weighed in
+    // the same bucket, until the bucket has had time to fully drain.
+    // The previous actions have passed this check, so the bucket never
+    // held more than its capacity after any of them.
+    let mut weighed = Vec::new();
+    let mut newest_timestamp = action.timestamp();
+    let mut next_chain_top = action.prev_action().cloned();
+    'walk: while let Some(chain_top) = next_chain_top.take() {
+        let page = match workspace
+            .local_cascade()
+            .must_get_agent_activity(
+                action.author().clone(),
+                ChainFilter::new(chain_top.clone()).take(RATE_LIMIT_PAGE_SIZE),
+            )
+            .await?
+        {
+            MustGetAgentActivityResponse::Activity(page) => page,
+            MustGetAgentActivityResponse::ChainTopNotFound(missing)
+            | MustGetAgentActivityResponse::IncompleteChain(missing) => {
+                return Err(ValidationOutcome::not_found(&missing).into())
+            }
+        };
+        for activity in page {
+            let prev = activity.action();
+            let elapsed = newest_timestamp.as_micros() - prev.timestamp().as_micros();
+            if rate_limit.drain(rate_limit.capacity, elapsed) == 0 {
+                break 'walk;
+            }
+            let weight = prev.rate_data();
+            if weight.bucket_id == bucket_id && weight.units > 0 {
+                newest_timestamp = prev.timestamp();
+                weighed.push(prev.clone());
+            }
+            next_chain_top = prev.prev_action().cloned();
+        }
+    }
+
+    // Replay the bucket from the oldest of those actions to this action.
+    let mut level: RateBucketCapacity = 0;
+    let mut last_timestamp: Option<Timestamp> = None;
+    for action in weighed.iter().rev().chain(std::iter::once(action)) {
+        if let Some(last_timestamp) = last_timestamp {
+            let elapsed = action.timestamp().as_micros() - last_timestamp.as_micros();
+            level = rate_limit.drain(level, elapsed);
+        }
+        level = level.saturating_add(action.rate_data().units as RateBucketCapacity);
+        last_timestamp = Some(action.timestamp());
+    }
+
+    if level > rate_limit.capacity {
+        Err(ValidationOutcome::RateLimitExceeded(bucket_id).into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Check the weight declared in an action is the weight the `weigh`
+/// callback of its integrity zome gives it, so authors can't avoid
+/// rate limits by declaring their actions weightless.
+async fn check_rate_weight(
+    action: &Action,
+    maybe_entry: Option<&Entry>,
+    ribosome: RealRibosome,
+    workspace: &SysValidationWorkspace,
+    network: HolochainP2pDna,
+) -> SysValidationResult<()> {
+    fn zome_id(entry_type: &EntryType) -> Option<ZomeId> {
+        match entry_type {
+            EntryType::App(app_entry_type) => Some(app_entry_type.zome_id()),
+            _ => None,
+        }
+    }
+
+    let input = match action {
+        Action::Create(Create { entry_type, .. }) | Action::Update(Update { entry_type, .. }) => {
+            let zome_id = zome_id(entry_type);
+            if zome_id.is_none() {
+                None
+            } else {
+                let entry = match maybe_entry {
+                    Some(entry) => entry.clone(),
+                    None if *entry_type.visibility() == EntryVisibility::Private => return Ok(()),
+                    None => {
+                        let entry_hash =
+                            action.entry_hash().expect("New entry actions have entries");
+                        workspace
+                            .full_cascade(network)
+                            .retrieve_entry(entry_hash.clone(), Default::default())
+                            .await?
+                            .ok_or_else(|| ValidationOutcome::not_found(entry_hash))?
+                            .into_content()
+                    }
+                };
+                match (action, entry) {
+                    // Countersigned actions are built by every party to the
+                    // session, so they can't be weighed by any one party.
+                    (_, Entry::CounterSign(_, _)) => None,
+                    (Action::Create(create), entry) => Some((
+                        zome_id,
+                        WeighInput::Create(create.clone().unweighed(), entry),
+                    )),
+                    (Action::Update(update), entry) => Some((
+                        zome_id,
+                        WeighInput::Update(update.clone().unweighed(), entry),
+                    )),
+                    _ => unreachable!("Only new entry actions are matched"),
+                }
+            }
+        }
+        Action::Delete(delete) => {
+            let deleted = workspace
+                .full_cascade(network)
+                .retrieve_action(delete.deletes_address.clone(), Default::default())
+                .await?
+                .ok_or_else(|| ValidationOutcome::not_found(&delete.deletes_address))?;
+            Some((
+                deleted.action().entry_type().and_then(zome_id),
+                WeighInput::Delete(delete.clone().unweighed()),
+            ))
+        }
+        Action::CreateLink(create_link) => Some((
+            Some(create_link.zome_id),
+            WeighInput::Link(create_link.clone().unweighed()),
+        )),
+        // All other actions are weightless.
+        _ => return Ok(()),
+    };
+
+    let expected = match input {
+        Some((zome_id, input)) => {
+            tokio::task::spawn_blocking(move || weigh(&ribosome, zome_id, input)).await??
+        }
+        None => weigh_placeholder(),
+    };
+    let matches = match action.entry_rate_data() {
+        Some(declared) => declared == expected,
+        None => action.rate_data() == expected.into(),
+    };
+    if matches {
+        Ok(())
+    } else {
+        Err(ValidationOutcome::RateWeightMismatch(ActionHash::with_data_sync(action)).into())
+    }
 }
 
 /// Check previous action timestamp is before this action
@@ -313,6 +493,17 @@ pub async fn check_entry_hash(hash: &EntryHash, entry: &Entry) -> SysValidationR
     }
 }
 
+/// Check the entry an op carries is the one its action points to.
+/// The author's signature only covers the action so an op with
+/// any other entry was forged by whoever sent it.
+pub fn check_op_entry_hash(op: &DhtOp) -> SysValidationResult<()> {
+    match (op.entry(), op.action().entry_hash()) {
+        (None, _) => Ok(()),
+        (Some(entry), Some(hash)) if *hash == EntryHash::with_data_sync(entry) => Ok(()),
+        (Some(_), _) => Err(ValidationOutcome::EntryHash.into()),
+    }
+}
+
 /// Check the action should have an entry.
 /// Is either a Create or Update
 pub fn check_new_entry_action(action: &Action) -> SysValidationResult<()> {
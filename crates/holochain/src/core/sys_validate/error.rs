@@ -3,6 +3,7 @@ use std::convert::TryFrom;
 use super::SourceChainError;
 use crate::conductor::api::error::ConductorApiError;
 use crate::conductor::entry_def_store::error::EntryDefStoreError;
+use crate::core::ribosome::error::RibosomeError;
 use crate::core::validation::OutcomeOrError;
 use crate::core::workflow::error::WorkflowError;
 use crate::from_sub_error;
@@ -33,8 +34,12 @@ pub enum SysValidationError {
     #[error(transparent)]
     EntryDefStoreError(#[from] EntryDefStoreError),
     #[error(transparent)]
+    JoinError(#[from] tokio::task::JoinError),
+    #[error(transparent)]
     KeystoreError(#[from] KeystoreError),
     #[error(transparent)]
+    RibosomeError(#[from] RibosomeError),
+    #[error(transparent)]
     SourceChainError(#[from] SourceChainError),
     #[error("Dna is missing for this hash {0:?}. Cannot validate without dna.")]
     DnaMissing(DnaHash),
@@ -129,6 +134,10 @@ pub enum ValidationOutcome {
     PrevActionError(#[from] PrevActionError),
     #[error("StoreEntry should not be gossiped for private entries")]
     PrivateEntry,
+    #[error("The author exceeded the capacity of rate limiting bucket {0}")]
+    RateLimitExceeded(RateBucketId),
+    #[error("The rate weight declared by action {0:?} is not the weight its zome gives it")]
+    RateWeightMismatch(ActionHash),
     #[error("Update original EntryType: {0:?} doesn't match new EntryType {1:?}")]
     UpdateTypeMismatch(EntryType, EntryType),
     #[error("Signature {0:?} failed to verify for Action {1:?}")]
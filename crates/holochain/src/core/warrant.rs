@@ -0,0 +1,186 @@
+//! Warrants issued when validation proves that an agent has broken the
+//! rules of a DNA.
+
+use super::workflow::error::WorkflowResult;
+use crate::conductor::conductor::CellStatus;
+use crate::conductor::ConductorHandle;
+use crate::core::sys_validate::check_op_entry_hash;
+use holochain_keystore::MetaLairClient;
+use holochain_p2p::HolochainP2pDna;
+use holochain_p2p::HolochainP2pDnaT;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_sqlite::rusqlite::OptionalExtension;
+use holochain_sqlite::rusqlite::Transaction;
+use holochain_state::prelude::*;
+use holochain_state::query::row_blob_and_hash_to_action;
+use holochain_types::prelude::*;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use tracing::*;
+
+#[cfg(test)]
+mod tests;
+
+/// Sign and store a warrant for each proof then publish them to the
+/// warrantee's agent activity authorities.
+///
+/// Warrants are authored by the first local agent that has joined the
+/// network for this DNA. If there is no such agent nothing is issued.
+/// Local agents are never warranted.
+pub async fn issue_warrants(
+    dna_hash: &DnaHash,
+    proofs: Vec<WarrantProof>,
+    dht_db: &DbWrite<DbKindDht>,
+    keystore: &MetaLairClient,
+    conductor: &ConductorHandle,
+    network: &HolochainP2pDna,
+) -> WorkflowResult<()> {
+    if proofs.is_empty() {
+        return Ok(());
+    }
+    let local_agents = conductor
+        .list_cell_ids(Some(CellStatus::Joined))
+        .into_iter()
+        .filter_map(|id| {
+            let (d, a) = id.into_dna_and_agent();
+            if d == *dna_hash {
+                Some(a)
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    let author = match local_agents.first() {
+        Some(author) => author.clone(),
+        None => return Ok(()),
+    };
+
+    let mut warrants: HashMap<AgentPubKey, Vec<SignedWarrant>> = HashMap::new();
+    let mut issued = HashSet::new();
+    for proof in proofs {
+        // Several ops can prove the same thing about an action.
+        if local_agents.contains(proof.warrantee()) || !issued.insert(proof.clone()) {
+            continue;
+        }
+        let warrant = Warrant::new(proof, author.clone(), Timestamp::now());
+        match sign_warrant(keystore, warrant).await {
+            Ok(warrant) => warrants
+                .entry(warrant.warrant().warrantee().clone())
+                .or_default()
+                .push(warrant),
+            Err(e) => info!(failed_to_sign_warrant = ?e),
+        }
+    }
+    if warrants.is_empty() {
+        return Ok(());
+    }
+
+    dht_db
+        .async_commit({
+            let warrants = warrants.clone();
+            move |txn| {
+                for warrant in warrants.into_values().flatten() {
+                    insert_warrant(txn, warrant)?;
+                }
+                WorkflowResult::Ok(())
+            }
+        })
+        .await?;
+
+    network
+        .block_agents(warrants.keys().cloned().collect())
+        .await?;
+
+    for (warrantee, warrants) in warrants {
+        warn!(agent = %warrantee, "Issuing {} warrants for invalid behaviour", warrants.len());
+        // The authorities may not be reachable right now but the warrants
+        // are still held by this node and returned with the agent's activity.
+        if let Err(e) = network.publish_warrants(warrantee, warrants, None).await {
+            info!(failed_to_publish_warrants = ?e);
+        }
+    }
+    Ok(())
+}
+
+/// Block every agent that this node holds a proven warrant for.
+pub async fn block_warranted_agents(
+    dht_db: &DbWrite<DbKindDht>,
+    network: &HolochainP2pDna,
+) -> WorkflowResult<()> {
+    let agents = dht_db
+        .async_reader(|txn| {
+            let mut blocked = Vec::new();
+            for agent in list_warranted_agents(&txn)? {
+                for warrant in list_warrants(&txn, &agent)? {
+                    if warrant_is_proven(&txn, &warrant.warrant().proof)? {
+                        blocked.push(agent);
+                        break;
+                    }
+                }
+            }
+            StateQueryResult::Ok(blocked)
+        })
+        .await?;
+    if !agents.is_empty() {
+        network.block_agents(agents).await?;
+    }
+    Ok(())
+}
+
+/// The proof that the author of a rejected op broke the rules.
+///
+/// If the op carries an entry its action doesn't point to, it was forged
+/// by whoever sent it and says nothing about the author.
+pub fn invalid_op_proof(op: &DhtOp, validation_type: ValidationType) -> Option<WarrantProof> {
+    check_op_entry_hash(op).ok()?;
+    let action = op.action();
+    Some(WarrantProof::InvalidChainOp {
+        action_author: action.author().clone(),
+        action: (ActionHash::with_data_sync(&action), op.signature().clone()),
+        validation_type,
+    })
+}
+
+/// Check if the author of this action has already had another action
+/// at the same sequence number validated, which forks their chain.
+pub fn find_chain_fork(
+    txn: &Transaction,
+    action: &SignedActionHashed,
+) -> StateQueryResult<Option<WarrantProof>> {
+    let other = txn
+        .query_row(
+            "
+            SELECT Action.blob, Action.hash FROM Action
+            JOIN DhtOp ON DhtOp.action_hash = Action.hash
+            WHERE Action.author = :author
+            AND Action.seq = :seq
+            AND Action.hash != :hash
+            AND DhtOp.type = :op_type
+            AND DhtOp.validation_status = :status
+            LIMIT 1
+            ",
+            named_params! {
+                ":author": action.action().author(),
+                ":seq": action.action().action_seq(),
+                ":hash": action.as_hash(),
+                ":op_type": DhtOpType::RegisterAgentActivity,
+                ":status": ValidationStatus::Valid,
+            },
+            |row| Ok(row_blob_and_hash_to_action("blob", "hash")(row)),
+        )
+        .optional()?
+        .transpose()?;
+    Ok(other.map(|other| {
+        let mut pair = [
+            (action.as_hash().clone(), action.signature().clone()),
+            (other.as_hash().clone(), other.signature().clone()),
+        ];
+        // Sort so every authority produces the same proof.
+        pair.sort_by(|a, b| a.0.cmp(&b.0));
+        let [a, b] = pair;
+        WarrantProof::ChainFork {
+            chain_author: action.action().author().clone(),
+            action_pair: (a, b),
+        }
+    }))
+}
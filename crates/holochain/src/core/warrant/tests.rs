@@ -0,0 +1,101 @@
+use super::*;
+use ::fixt::prelude::*;
+use holo_hash::HasHash;
+use holochain_state::prelude::insert_op;
+use holochain_state::prelude::set_validation_status;
+use holochain_state::prelude::test_dht_db;
+use holochain_types::dht_op::DhtOpHashed;
+use holochain_zome_types::fixt::*;
+
+fn activity_op(action: Create) -> (DhtOpHashed, SignedActionHashed) {
+    let signature = fixt!(Signature);
+    let action = Action::Create(action);
+    let op = DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(
+        signature.clone(),
+        action.clone(),
+    ));
+    let action =
+        SignedActionHashed::with_presigned(ActionHashed::from_content_sync(action), signature);
+    (op, action)
+}
+
+#[test]
+fn chain_fork_is_found_against_valid_activity() {
+    let db = test_dht_db();
+    let db = db.to_db();
+
+    let mut a = fixt!(Create);
+    a.action_seq = 5;
+    let mut b = a.clone();
+    b.entry_hash = fixt!(EntryHash);
+    let mut c = a.clone();
+    c.action_seq = 6;
+
+    let (a_op, a_action) = activity_op(a);
+    let (b_op, b_action) = activity_op(b);
+    let (c_op, c_action) = activity_op(c);
+
+    db.test_commit(|txn| {
+        insert_op(txn, &a_op).unwrap();
+        set_validation_status(txn, a_op.as_hash(), ValidationStatus::Valid).unwrap();
+        insert_op(txn, &b_op).unwrap();
+        insert_op(txn, &c_op).unwrap();
+    });
+
+    db.test_commit(|txn| {
+        // A different action at the same sequence number is a fork.
+        let mut expected = [
+            (a_action.as_hash().clone(), a_action.signature().clone()),
+            (b_action.as_hash().clone(), b_action.signature().clone()),
+        ];
+        expected.sort_by(|a, b| a.0.cmp(&b.0));
+        let [first, second] = expected;
+        assert_eq!(
+            find_chain_fork(txn, &b_action).unwrap(),
+            Some(WarrantProof::ChainFork {
+                chain_author: b_action.action().author().clone(),
+                action_pair: (first, second),
+            })
+        );
+
+        // The valid action doesn't fork with itself and the forking
+        // action hasn't been validated yet.
+        assert_eq!(find_chain_fork(txn, &a_action).unwrap(), None);
+
+        // The next action in the chain isn't a fork.
+        assert_eq!(find_chain_fork(txn, &c_action).unwrap(), None);
+    });
+}
+
+#[test]
+fn forged_entry_is_not_warranted() {
+    let entry = fixt!(Entry);
+    let mut create = fixt!(Create);
+    create.entry_hash = EntryHash::with_data_sync(&entry);
+    let signature = fixt!(Signature);
+    let store_entry = |entry: Entry| {
+        DhtOp::StoreEntry(
+            signature.clone(),
+            NewEntryAction::Create(create.clone()),
+            Box::new(entry),
+        )
+    };
+
+    // The author signed for this entry so they can be warranted for it.
+    let honest = store_entry(entry);
+    assert_eq!(
+        invalid_op_proof(&honest, ValidationType::Sys),
+        Some(WarrantProof::InvalidChainOp {
+            action_author: create.author.clone(),
+            action: (
+                ActionHash::with_data_sync(&Action::Create(create.clone())),
+                signature.clone(),
+            ),
+            validation_type: ValidationType::Sys,
+        })
+    );
+
+    // Anyone can swap in another entry under the author's action.
+    let forged = store_entry(fixt!(Entry));
+    assert_eq!(invalid_op_proof(&forged, ValidationType::Sys), None);
+}
@@ -24,6 +24,7 @@
 pub mod error;
 
 pub mod app_validation_workflow;
+pub mod arc_gc_workflow;
 pub mod call_zome_workflow;
 pub mod countersigning_workflow;
 pub mod genesis_workflow;
@@ -13,6 +13,7 @@ use crate::core::ribosome::guest_callback::validate::ValidateInvocation;
 use crate::core::ribosome::guest_callback::validate::ValidateResult;
 use crate::core::ribosome::RibosomeT;
 use crate::core::ribosome::ZomesToInvoke;
+use crate::core::warrant::issue_warrants;
 use error::AppValidationResult;
 pub use error::*;
 use futures::stream::StreamExt;
@@ -92,6 +93,9 @@ async fn app_validation_workflow_inner(
     tracing::debug!("validating {} ops", start_len);
     let start = (start_len >= NUM_CONCURRENT_OPS).then(std::time::Instant::now);
     let saturated = start.is_some();
+    let keystore = conductor_handle.keystore().clone();
+    let warrant_conductor = conductor_handle.clone();
+    let warrant_dna_hash = dna_hash.clone();
 
     // Validate all the ops
     let iter = sorted_ops.into_iter().map({
@@ -108,6 +112,8 @@ async fn app_validation_workflow_inner(
                 let action = op.action();
                 let dependency = get_dependency(op_type, &action);
                 let op_light = op.to_light();
+                // Kept in case the op is rejected and a warrant is needed.
+                let signature = op.signature().clone();
 
                 // If this is agent activity, track it for the cache.
                 let activity = matches!(op_type, DhtOpType::RegisterAgentActivity).then(|| {
@@ -127,7 +133,14 @@ async fn app_validation_workflow_inner(
                     }
                     Err(e) => Err(e),
                 };
-                (op_hash, dependency, op_light, r, activity)
+                (
+                    op_hash,
+                    dependency,
+                    op_light,
+                    r,
+                    activity,
+                    (action, signature),
+                )
             }
         }
     });
@@ -165,15 +178,17 @@ async fn app_validation_workflow_inner(
             "Committing {} ops",
             chunk.iter().map(|c| c.len()).sum::<usize>()
         );
-        let (t, a, r, activity) = workspace
+        let (t, a, r, activity, proofs) = workspace
             .dht_db
             .async_commit(move |txn| {
                 let mut total = 0;
                 let mut awaiting = 0;
                 let mut rejected = 0;
                 let mut agent_activity = Vec::new();
+                let mut proofs = Vec::new();
                 for outcome in chunk.into_iter().flatten() {
-                    let (op_hash, dependency, op_light, outcome, activity) = outcome;
+                    let (op_hash, dependency, op_light, outcome, activity, (action, signature)) =
+                        outcome;
                     // Get the outcome or return the error
                     let outcome = outcome.or_else(|outcome_or_err| outcome_or_err.try_into())?;
 
@@ -185,7 +200,6 @@ async fn app_validation_workflow_inner(
                         }
                     }
 
-
                     if let Outcome::AwaitingDeps(_) | Outcome::Rejected(_) = &outcome {
                         warn!(
                             msg = "DhtOp has failed app validation",
@@ -208,7 +222,15 @@ async fn app_validation_workflow_inner(
                         }
                         Outcome::Rejected(_) => {
                             rejected += 1;
-                            tracing::warn!("Received invalid op! Warrants aren't implemented yet, so we can't do anything about this right now, but be warned that somebody on the network has maliciously hacked their node.\nOp: {:?}", op_light);
+                            tracing::warn!(
+                                "Received invalid op! Issuing a warrant for the author.\nOp: {:?}",
+                                op_light
+                            );
+                            proofs.push(WarrantProof::InvalidChainOp {
+                                action_author: action.author().clone(),
+                                action: (ActionHash::with_data_sync(&action), signature),
+                                validation_type: ValidationType::App,
+                            });
                             if let Dependency::Null = dependency {
                                 put_integrated(txn, &op_hash, ValidationStatus::Rejected)?;
                             } else {
@@ -217,9 +239,18 @@ async fn app_validation_workflow_inner(
                         }
                     }
                 }
-                WorkflowResult::Ok((total, awaiting, rejected, agent_activity))
+                WorkflowResult::Ok((total, awaiting, rejected, agent_activity, proofs))
             })
             .await?;
+        issue_warrants(
+            &warrant_dna_hash,
+            proofs,
+            &workspace.dht_db,
+            &keystore,
+            &warrant_conductor,
+            network,
+        )
+        .await?;
         // Once the database transaction is committed, add agent activity to the cache
         // that is ready for integration.
         for (author, seq, has_no_dependency) in activity {
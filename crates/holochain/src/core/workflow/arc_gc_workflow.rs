@@ -0,0 +1,240 @@
+//! The workflow for deleting DHT data outside the storage arcs of local agents
+//!
+//! When an agent's storage arc shrinks, the ops outside of it stay in the
+//! DHT database even though the agent is no longer an authority for them.
+//! This workflow deletes those ops once they have been outside every local
+//! arc for a grace period and other peers are covering that part of the DHT.
+//! Records are moved to the cache so they can still be served locally.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use holochain_p2p::dht_arc::DhtArcSet;
+use holochain_p2p::dht_arc::DhtLocation;
+use holochain_sqlite::db::AsP2pStateTxExt;
+use holochain_sqlite::rusqlite::Transaction;
+use holochain_sqlite::sql::sql_cell;
+use holochain_state::prelude::*;
+use holochain_state::query::get_public_op_from_db;
+use holochain_types::db_cache::DhtDbQueryCache;
+use holochain_types::prelude::*;
+use tracing::*;
+
+use super::error::WorkflowResult;
+
+#[cfg(test)]
+mod tests;
+
+/// The number of ops read from the DHT database at a time.
+pub const ARC_GC_BATCH_SIZE: usize = 1000;
+
+/// The storage arcs the local agents of a space have held recently.
+///
+/// Data is only deleted once it has been outside of every local arc for the
+/// whole grace period, so an arc which shrinks and grows again doesn't cause
+/// the data in between to be deleted and fetched again.
+#[derive(Debug, Default)]
+pub struct ArcGcHistory {
+    observed_since: Option<Timestamp>,
+    arcs: VecDeque<(Timestamp, DhtArcSet)>,
+}
+
+impl ArcGcHistory {
+    /// Record the current storage arcs of the local agents and return every
+    /// part of the DHT which was covered by them during the grace period.
+    ///
+    /// Returns [`None`] until the arcs have been observed for a whole grace
+    /// period, as nothing is known about the arcs before that.
+    pub fn observe(
+        &mut self,
+        now: Timestamp,
+        arcs: DhtArcSet,
+        grace_period: Duration,
+    ) -> Option<DhtArcSet> {
+        let observed_since = *self.observed_since.get_or_insert(now);
+        self.arcs.push_back((now, arcs));
+        let cutoff = (now - grace_period).ok()?;
+        // The newest observation before the cutoff is kept
+        // because those arcs were still held at the cutoff.
+        while self.arcs.len() > 1 && self.arcs[1].0 <= cutoff {
+            self.arcs.pop_front();
+        }
+        if observed_since > cutoff {
+            return None;
+        }
+        Some(
+            self.arcs
+                .iter()
+                .fold(DhtArcSet::new_empty(), |set, (_, arcs)| set.union(arcs)),
+        )
+    }
+}
+
+#[instrument(skip(
+    dht_db,
+    dht_query_cache,
+    cache_db,
+    p2p_agents_db,
+    held_arcs,
+    local_agents
+))]
+/// Delete the integrated ops which fall outside `held_arcs` and were
+/// integrated before `integrated_before`.
+///
+/// Nothing is deleted unless the peers this conductor knows of are estimated
+/// to cover every part of the DHT outside `held_arcs` at least `min_redundancy`
+/// times. Ops authored by `local_agents` are kept. The activity of an author
+/// is only deleted once all of it can be, and is then removed from
+/// `dht_query_cache`. Store record and store entry ops are copied to the
+/// cache before they are deleted. Returns the number of ops deleted.
+#[allow(clippy::too_many_arguments)]
+pub async fn arc_gc_workflow(
+    dht_db: DbWrite<DbKindDht>,
+    dht_query_cache: DhtDbQueryCache,
+    cache_db: DbWrite<DbKindCache>,
+    p2p_agents_db: DbWrite<DbKindP2pAgents>,
+    held_arcs: DhtArcSet,
+    local_agents: Vec<AgentPubKey>,
+    integrated_before: Timestamp,
+    min_redundancy: u32,
+) -> WorkflowResult<usize> {
+    let outside = held_arcs.complement();
+    if outside.is_empty() {
+        return Ok(0);
+    }
+
+    let coverage = p2p_agents_db
+        .async_reader({
+            let outside = outside.clone();
+            move |txn| txn.p2p_extrapolated_coverage(outside)
+        })
+        .await?;
+    if coverage.iter().any(|c| *c < min_redundancy as f64) {
+        debug!(
+            ?coverage,
+            "Not deleting DHT data outside of the local arcs as it is not covered by enough peers"
+        );
+        return Ok(0);
+    }
+
+    let mut last_rowid = 0;
+    let mut collected = 0;
+    loop {
+        let (ops, activity_authors, batch_len, batch_last_rowid) = dht_db
+            .async_reader({
+                let outside = outside.clone();
+                let local_agents = local_agents.clone();
+                move |txn| {
+                    collectable_ops(&txn, last_rowid, integrated_before, &outside, &local_agents)
+                }
+            })
+            .await?;
+        last_rowid = batch_last_rowid;
+
+        if !ops.is_empty() {
+            let to_cache: Vec<_> = ops.iter().filter_map(|(_, op)| op.clone()).collect();
+            if !to_cache.is_empty() {
+                cache_db
+                    .async_commit(move |txn| {
+                        let now = Timestamp::now();
+                        for (op, status) in &to_cache {
+                            insert_op(txn, op)?;
+                            set_validation_status(txn, op.as_hash(), *status)?;
+                            set_when_integrated(txn, op.as_hash(), now)?;
+                        }
+                        StateMutationResult::Ok(())
+                    })
+                    .await?;
+            }
+
+            collected += ops.len();
+            dht_db
+                .async_commit(move |txn| {
+                    let mut stmt = txn.prepare_cached(sql_cell::arc_gc::DELETE_OP)?;
+                    for (hash, _) in &ops {
+                        stmt.execute(named_params! { ":hash": hash })?;
+                    }
+                    StateMutationResult::Ok(())
+                })
+                .await?;
+            dht_query_cache.remove_activity(&activity_authors);
+        }
+
+        if batch_len < ARC_GC_BATCH_SIZE {
+            break;
+        }
+    }
+
+    if collected > 0 {
+        // Remove the actions and entries which no remaining op refers to.
+        dht_db
+            .async_commit(|txn| {
+                txn.execute(sql_cell::arc_gc::DELETE_ORPHAN_ACTIONS, [])?;
+                txn.execute(sql_cell::cache::DELETE_ORPHAN_ENTRIES, [])?;
+                StateMutationResult::Ok(())
+            })
+            .await?;
+    }
+    Ok(collected)
+}
+
+/// The ops to delete, along with a copy of the ones to move to the cache,
+/// from the next batch of integrated ops after `last_rowid`.
+/// Also returns the authors whose activity is deleted,
+/// the size of the batch and the rowid of its last op.
+#[allow(clippy::type_complexity)]
+fn collectable_ops(
+    txn: &Transaction,
+    last_rowid: i64,
+    integrated_before: Timestamp,
+    outside: &DhtArcSet,
+    local_agents: &[AgentPubKey],
+) -> StateQueryResult<(
+    Vec<(DhtOpHash, Option<(DhtOpHashed, ValidationStatus)>)>,
+    Vec<AgentPubKey>,
+    usize,
+    i64,
+)> {
+    let mut stmt = txn.prepare_cached(sql_cell::arc_gc::COLLECTABLE_OPS)?;
+    let batch = stmt
+        .query_map(
+            named_params! {
+                ":last_rowid": last_rowid,
+                ":integrated_before": integrated_before,
+                ":activity": DhtOpType::RegisterAgentActivity,
+                ":limit": ARC_GC_BATCH_SIZE as i64,
+            },
+            |row| {
+                Ok((
+                    row.get::<_, i64>("rowid")?,
+                    row.get::<_, DhtOpHash>("hash")?,
+                    row.get::<_, DhtOpType>("type")?,
+                    row.get::<_, u32>("storage_center_loc")?,
+                    row.get::<_, AgentPubKey>("author")?,
+                    row.get::<_, Option<ValidationStatus>>("validation_status")?,
+                ))
+            },
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    let batch_len = batch.len();
+    let batch_last_rowid = batch.last().map_or(last_rowid, |(rowid, ..)| *rowid);
+
+    let mut ops = Vec::new();
+    let mut activity_authors = Vec::new();
+    for (_, hash, op_type, loc, author, status) in batch {
+        if !outside.contains(DhtLocation::new(loc)) || local_agents.contains(&author) {
+            continue;
+        }
+        if op_type == DhtOpType::RegisterAgentActivity && !activity_authors.contains(&author) {
+            activity_authors.push(author);
+        }
+        let op = match (op_type, status) {
+            (DhtOpType::StoreRecord | DhtOpType::StoreEntry, Some(status)) => {
+                get_public_op_from_db(txn, &hash)?.map(|op| (op, status))
+            }
+            _ => None,
+        };
+        ops.push((hash, op));
+    }
+    Ok((ops, activity_authors, batch_len, batch_last_rowid))
+}
@@ -0,0 +1,196 @@
+use super::*;
+use ::fixt::prelude::*;
+use holo_hash::HasHash;
+use holochain_state::test_utils::test_cache_db;
+use holochain_state::test_utils::test_dht_db;
+use holochain_state::test_utils::test_p2p_agents_db;
+use holochain_zome_types::fixt::*;
+
+fn store_record_op(author: AgentPubKey) -> DhtOpHashed {
+    let mut create = fixt!(Create);
+    create.author = author;
+    DhtOpHashed::from_content_sync(DhtOp::StoreRecord(
+        fixt!(Signature),
+        Action::Create(create),
+        None,
+    ))
+}
+
+fn activity_op(author: AgentPubKey, seq: u32) -> DhtOpHashed {
+    let mut create = fixt!(Create);
+    create.author = author;
+    create.action_seq = seq;
+    DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(
+        fixt!(Signature),
+        Action::Create(create),
+    ))
+}
+
+fn loc(op: &DhtOpHashed) -> u32 {
+    op.as_content().dht_basis().get_loc().as_u32()
+}
+
+fn op_exists(txn: &Transaction, op: &DhtOpHashed) -> bool {
+    txn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM DhtOp WHERE hash = :hash)",
+        named_params! { ":hash": op.as_hash() },
+        |row| row.get(0),
+    )
+    .unwrap()
+}
+
+#[test]
+fn arcs_are_held_for_the_grace_period() {
+    let grace_period = Duration::from_secs(60);
+    let start = Timestamp::now();
+    let at = |secs| (start + Duration::from_secs(secs)).unwrap();
+    let wide = DhtArcSet::from(vec![(0, 1000)]);
+    let narrow = DhtArcSet::from(vec![(0, 10)]);
+    let mut history = ArcGcHistory::default();
+
+    // - Nothing is known about the arcs until they have been observed for the grace period.
+    assert_eq!(history.observe(at(0), wide.clone(), grace_period), None);
+    assert_eq!(history.observe(at(30), narrow.clone(), grace_period), None);
+
+    // - The wide arc is still held as it was shrunk within the grace period.
+    assert_eq!(
+        history.observe(at(60), narrow.clone(), grace_period),
+        Some(wide.clone())
+    );
+    assert_eq!(
+        history.observe(at(89), narrow.clone(), grace_period),
+        Some(wide)
+    );
+
+    // - Once the arc has been narrow for the grace period only the narrow arc is held.
+    assert_eq!(
+        history.observe(at(90), narrow.clone(), grace_period),
+        Some(narrow)
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn deletes_ops_outside_of_the_held_arcs() {
+    let dht_db = test_dht_db();
+    let cache_db = test_cache_db();
+    let p2p_agents_db = test_p2p_agents_db();
+    let local_agent = fixt!(AgentPubKey);
+
+    let held = store_record_op(fixt!(AgentPubKey));
+    let outside = store_record_op(fixt!(AgentPubKey));
+    let activity_author = fixt!(AgentPubKey);
+    let activity = activity_op(activity_author.clone(), 0);
+    let recent_activity_author = fixt!(AgentPubKey);
+    let old_activity = activity_op(recent_activity_author.clone(), 0);
+    let recent_activity = activity_op(recent_activity_author.clone(), 1);
+    let authored = store_record_op(local_agent.clone());
+    let recent = store_record_op(fixt!(AgentPubKey));
+    let held_arcs = DhtArcSet::from(vec![(loc(&held), loc(&held))]);
+
+    let now = Timestamp::now();
+    let long_ago = (now - Duration::from_secs(60 * 60)).unwrap();
+    dht_db
+        .to_db()
+        .async_commit({
+            let ops = vec![
+                (held.clone(), long_ago),
+                (outside.clone(), long_ago),
+                (activity.clone(), long_ago),
+                (old_activity.clone(), long_ago),
+                (recent_activity.clone(), now),
+                (authored.clone(), long_ago),
+                (recent.clone(), now),
+            ];
+            move |txn| {
+                for (op, integrated) in ops {
+                    insert_op(txn, &op)?;
+                    set_validation_status(txn, op.as_hash(), ValidationStatus::Valid)?;
+                    set_when_integrated(txn, op.as_hash(), integrated)?;
+                }
+                StateMutationResult::Ok(())
+            }
+        })
+        .await
+        .unwrap();
+    let integrated_before = (now - Duration::from_secs(60)).unwrap();
+    let dht_query_cache = DhtDbQueryCache::new(dht_db.to_db().into());
+    assert!(!dht_query_cache
+        .is_chain_empty(&activity_author)
+        .await
+        .unwrap());
+
+    // - Nothing is deleted when no peers are covering the rest of the DHT.
+    let collected = arc_gc_workflow(
+        dht_db.to_db(),
+        dht_query_cache.clone(),
+        cache_db.to_db(),
+        p2p_agents_db.to_db(),
+        held_arcs.clone(),
+        vec![local_agent.clone()],
+        integrated_before,
+        1,
+    )
+    .await
+    .unwrap();
+    assert_eq!(collected, 0);
+
+    // - Only the old ops from other authors outside of the arc are deleted,
+    // and activity is only deleted when all of the author's activity is old.
+    let collected = arc_gc_workflow(
+        dht_db.to_db(),
+        dht_query_cache.clone(),
+        cache_db.to_db(),
+        p2p_agents_db.to_db(),
+        held_arcs,
+        vec![local_agent],
+        integrated_before,
+        0,
+    )
+    .await
+    .unwrap();
+    assert_eq!(collected, 2);
+    assert!(dht_query_cache
+        .is_chain_empty(&activity_author)
+        .await
+        .unwrap());
+    assert!(!dht_query_cache
+        .is_chain_empty(&recent_activity_author)
+        .await
+        .unwrap());
+    let moved = outside.as_hash().clone();
+    dht_db
+        .to_db()
+        .async_reader(move |txn| {
+            assert!(op_exists(&txn, &held));
+            assert!(!op_exists(&txn, &outside));
+            assert!(!op_exists(&txn, &activity));
+            assert!(op_exists(&txn, &old_activity));
+            assert!(op_exists(&txn, &recent_activity));
+            assert!(op_exists(&txn, &authored));
+            assert!(op_exists(&txn, &recent));
+            let actions: usize = txn
+                .query_row("SELECT COUNT(*) FROM Action", [], |row| row.get(0))
+                .unwrap();
+            assert_eq!(actions, 5);
+            DatabaseResult::Ok(())
+        })
+        .await
+        .unwrap();
+
+    // - The deleted record was moved to the cache.
+    cache_db
+        .to_db()
+        .async_reader(move |txn| {
+            let status: Option<ValidationStatus> = txn
+                .query_row(
+                    "SELECT validation_status FROM DhtOp WHERE hash = :hash",
+                    named_params! { ":hash": moved },
+                    |row| row.get(0),
+                )
+                .unwrap();
+            assert_eq!(status, Some(ValidationStatus::Valid));
+            DatabaseResult::Ok(())
+        })
+        .await
+        .unwrap();
+}
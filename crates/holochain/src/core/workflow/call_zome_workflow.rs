@@ -13,6 +13,7 @@ use crate::core::ribosome::guest_callback::post_commit::send_post_commit;
 use crate::core::ribosome::RibosomeT;
 use crate::core::ribosome::ZomeCallHostAccess;
 use crate::core::ribosome::ZomeCallInvocation;
+use crate::core::ribosome::ZomeCallLimits;
 use crate::core::workflow::error::WorkflowError;
 use holochain_keystore::MetaLairClient;
 use holochain_p2p::HolochainP2pDna;
@@ -131,6 +132,8 @@ where
 
     let call_zome_handle =
         CellConductorApi::new(conductor_handle.clone(), cell_id).into_call_zome_handle();
+    let zome_call_config = &conductor_handle.get_config().zome_call;
+    let limits = ZomeCallLimits::new(zome_call_config);
 
     tracing::trace!("Before zome call");
     let host_access = ZomeCallHostAccess::new(
@@ -139,9 +142,17 @@ where
         network.clone(),
         signal_tx,
         call_zome_handle,
+        limits,
     );
-    let (ribosome, result) =
-        call_zome_function_authorized(ribosome, host_access, invocation).await?;
+    let call = call_zome_function_authorized(ribosome, host_access, invocation);
+    let (ribosome, result) = match zome_call_config.timeout() {
+        // The zome function can't be interrupted so it is left running
+        // in the background, but nothing it wrote is flushed.
+        Some(timeout) => tokio::time::timeout(timeout, call)
+            .await
+            .map_err(|_| WorkflowError::ZomeCallTimeout(timeout))??,
+        None => call.await?,
+    };
     tracing::trace!("After zome call");
 
     let validation_result =
@@ -5,16 +5,17 @@ use holo_hash::{ActionHash, AgentPubKey, DhtOpHash};
 use holo_hash::{AnyDhtHash, EntryHash};
 use holochain_keystore::AgentPubKeyExt;
 use holochain_p2p::{HolochainP2pDna, HolochainP2pDnaT};
+use holochain_state::countersigning::{list_pending_countersigning_ops, PendingCountersigningOp};
 use holochain_state::integrate::authored_ops_to_dht_db;
 use holochain_state::mutations;
 use holochain_state::prelude::{
     current_countersigning_session, SourceChainResult, StateMutationResult, Store,
 };
+use holochain_types::db::{DbKindDht, DbWrite};
 use holochain_types::dht_op::DhtOp;
 use holochain_types::signal::{Signal, SystemSignal};
 use holochain_zome_types::Timestamp;
 use holochain_zome_types::{Entry, SignedAction, ZomeCallResponse};
-use kitsune_p2p_types::tx2::tx2_utils::Share;
 use rusqlite::{named_params, Transaction};
 
 use crate::conductor::interface::SignalBroadcaster;
@@ -27,16 +28,11 @@ use holochain_p2p::event::CountersigningSessionNegotiationMessage;
 use super::{error::WorkflowResult, incoming_dht_ops_workflow::incoming_dht_ops_workflow};
 
 #[derive(Clone)]
-/// A cheaply clonable, thread safe and in-memory store for
-/// active countersigning sessions.
+/// A cheaply clonable store for active countersigning sessions.
+/// Sessions are persisted to the DHT database so the signatures
+/// collected so far survive a restart of the conductor.
 pub struct CountersigningWorkspace {
-    inner: Share<CountersigningWorkspaceInner>,
-}
-
-#[derive(Default)]
-/// Pending countersigning sessions.
-pub struct CountersigningWorkspaceInner {
-    pending: HashMap<EntryHash, Session>,
+    db: DbWrite<DbKindDht>,
 }
 
 #[derive(Default)]
@@ -51,15 +47,12 @@ struct Session {
 }
 
 /// New incoming DhtOps for a countersigning session.
-// TODO: PERF: This takes a lock on the workspace which could
-// block other incoming DhtOps if there are many active sessions.
-// We could create an incoming buffer if this actually becomes an issue.
-pub(crate) fn incoming_countersigning(
+pub(crate) async fn incoming_countersigning(
     ops: Vec<(DhtOpHash, DhtOp)>,
     workspace: &CountersigningWorkspace,
     trigger: TriggerSender,
 ) -> WorkflowResult<()> {
-    let mut should_trigger = false;
+    let mut pending = Vec::new();
 
     // For each op check it's the right type and extract the
     // entry hash, required actions and expires time.
@@ -87,18 +80,22 @@ pub(crate) fn incoming_countersigning(
 
                     // Check if already timed out.
                     if holochain_zome_types::Timestamp::now() < expires {
-                        // Put this op in the pending map.
-                        workspace.put(entry_hash, hash, op, required_actions, expires);
-                        // We have new ops so we should trigger the workflow.
-                        should_trigger = true;
+                        pending.push(PendingCountersigningOp {
+                            entry_hash,
+                            op_hash: hash,
+                            op,
+                            required_actions,
+                            expires,
+                        });
                     }
                 }
             }
         }
     }
 
-    // Trigger the workflow if we have new ops.
-    if should_trigger {
+    // Put the ops in the workspace and trigger the workflow if we have new ops.
+    if !pending.is_empty() {
+        workspace.put(pending).await?;
         trigger.trigger(&"incoming_countersigning");
     }
     Ok(())
@@ -112,7 +109,10 @@ pub(crate) async fn countersigning_workflow(
     sys_validation_trigger: &TriggerSender,
 ) -> WorkflowResult<WorkComplete> {
     // Get any complete sessions.
-    let complete_sessions = space.countersigning_workspace.get_complete_sessions();
+    let complete_sessions = space
+        .countersigning_workspace
+        .get_complete_sessions()
+        .await?;
     let mut notify_agents = Vec::with_capacity(complete_sessions.len());
 
     // For each complete session send the ops to validation.
@@ -344,54 +344,57 @@ type Ops = Vec<(DhtOpHash, DhtOp)>;
 type SignedActions = Vec<SignedAction>;
 
 impl CountersigningWorkspace {
-    /// Create a new empty countersigning workspace.
-    pub fn new() -> CountersigningWorkspace {
-        Self {
-            inner: Share::new(Default::default()),
-        }
+    /// Create a countersigning workspace which persists sessions to
+    /// the given DHT database.
+    /// Any sessions already in the database are resumed.
+    pub fn new(db: DbWrite<DbKindDht>) -> CountersigningWorkspace {
+        Self { db }
     }
 
-    /// Put a single signers store entry op in the workspace.
-    fn put(
-        &self,
-        entry_hash: EntryHash,
-        op_hash: DhtOpHash,
-        op: DhtOp,
-        required_actions: Vec<ActionHash>,
-        expires: Timestamp,
-    ) {
-        // hash the action of this ops.
-        let action_hash = ActionHash::with_data_sync(&op.action());
-        self.inner
-            .share_mut(|i, _| {
-                // Get the session at this entry or create an empty one.
-                let session = i.pending.entry(entry_hash).or_default();
-
-                // Insert the op into the session.
-                session
-                    .map
-                    .insert(action_hash, (op_hash, op, required_actions));
-
-                // Set the expires time.
-                session.expires = Some(expires);
-                Ok(())
+    /// Put signers store entry ops in the workspace.
+    async fn put(&self, ops: Vec<PendingCountersigningOp>) -> WorkflowResult<()> {
+        self.db
+            .async_commit(move |txn| {
+                for op in &ops {
+                    mutations::insert_countersigning_session_op(txn, op)?;
+                }
+                StateMutationResult::Ok(())
             })
-            // We don't close this share so we can ignore this error.
-            .ok();
+            .await?;
+        Ok(())
     }
 
-    fn get_complete_sessions(&self) -> Vec<(AgentsToNotify, Ops, SignedActions)> {
+    async fn get_complete_sessions(
+        &self,
+    ) -> WorkflowResult<Vec<(AgentsToNotify, Ops, SignedActions)>> {
         let now = holochain_zome_types::Timestamp::now();
-        self.inner
-            .share_mut(|i, _| {
+        let complete_sessions = self
+            .db
+            .async_commit(move |txn| {
                 // Remove any expired sessions.
-                i.pending.retain(|_, session| {
-                    session.expires.as_ref().map(|e| now < *e).unwrap_or(false)
-                });
+                mutations::delete_expired_countersigning_sessions(txn, now)?;
+
+                // Gather the ops of each pending session.
+                let mut pending: HashMap<EntryHash, Session> = HashMap::new();
+                for op in list_pending_countersigning_ops(txn, now)? {
+                    let PendingCountersigningOp {
+                        entry_hash,
+                        op_hash,
+                        op,
+                        required_actions,
+                        expires,
+                    } = op;
+                    // hash the action of this ops.
+                    let action_hash = ActionHash::with_data_sync(&op.action());
+                    let session = pending.entry(entry_hash).or_default();
+                    session
+                        .map
+                        .insert(action_hash, (op_hash, op, required_actions));
+                    session.expires = Some(expires);
+                }
 
                 // Get all complete session's entry hashes.
-                let complete: Vec<_> = i
-                    .pending
+                let complete: Vec<_> = pending
                     .iter()
                     .filter_map(|(entry_hash, session)| {
                         // If all session required actions are contained in the map
@@ -410,11 +413,12 @@ impl CountersigningWorkspace {
 
                 let mut ret = Vec::with_capacity(complete.len());
 
-                // For each complete session remove from the pending map
+                // For each complete session remove it from the database
                 // and fold into the signed actions to send to the agents
                 // and the ops to validate.
                 for hash in complete {
-                    if let Some(session) = i.pending.remove(&hash) {
+                    mutations::delete_countersigning_session(txn, &hash)?;
+                    if let Some(session) = pending.remove(&hash) {
                         let map = session.map;
                         let r = map.into_iter().fold(
                             (Vec::new(), Vec::new(), Vec::new()),
@@ -433,105 +437,131 @@ impl CountersigningWorkspace {
                         ret.push(r);
                     }
                 }
-                Ok(ret)
+                StateMutationResult::Ok(ret)
             })
-            .unwrap_or_default()
-    }
-}
-
-impl Default for CountersigningWorkspace {
-    fn default() -> Self {
-        Self::new()
+            .await?;
+        Ok(complete_sessions)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use arbitrary::Arbitrary;
+    use holochain_state::prelude::test_dht_db;
 
     use super::*;
 
-    #[test]
+    /// Create the ops of a session where each op requires the actions of all the ops.
+    fn session_ops(
+        u: &mut arbitrary::Unstructured,
+        entry_hash: &EntryHash,
+        num_signers: usize,
+        expires: Timestamp,
+    ) -> Vec<PendingCountersigningOp> {
+        let ops: Vec<_> = (0..num_signers)
+            .map(|_| {
+                let op_hash = DhtOpHash::arbitrary(u).unwrap();
+                let op = DhtOp::arbitrary(u).unwrap();
+                (op_hash, op)
+            })
+            .collect();
+        let required_actions: Vec<_> = ops
+            .iter()
+            .map(|(_, op)| ActionHash::with_data_sync(&op.action()))
+            .collect();
+        ops.into_iter()
+            .map(|(op_hash, op)| PendingCountersigningOp {
+                entry_hash: entry_hash.clone(),
+                op_hash,
+                op,
+                required_actions: required_actions.clone(),
+                expires,
+            })
+            .collect()
+    }
+
+    /// All the ops in the database, including those of expired sessions.
+    async fn stored_ops(db: &DbWrite<DbKindDht>) -> Vec<PendingCountersigningOp> {
+        db.async_reader(|txn| list_pending_countersigning_ops(&txn, Timestamp::MIN))
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
     /// Test that a session of 5 actions is complete when
     /// the expiry time is in the future and all required actions
     /// are present.
-    fn gets_complete_sessions() {
+    async fn gets_complete_sessions() {
         let mut u = arbitrary::Unstructured::new(&holochain_zome_types::NOISE);
-        let workspace = CountersigningWorkspace::new();
-
-        // - Create the ops.
-        let data = |u: &mut arbitrary::Unstructured| {
-            let op_hash = DhtOpHash::arbitrary(u).unwrap();
-            let op = DhtOp::arbitrary(u).unwrap();
-            let action = op.action();
-            (op_hash, op, action)
-        };
-        let entry_hash = EntryHash::arbitrary(&mut u).unwrap();
-        let mut op_hashes = Vec::new();
-        let mut ops = Vec::new();
-        let mut required_actions = Vec::new();
-        for _ in 0..5 {
-            let (op_hash, op, action) = data(&mut u);
-            let action_hash = ActionHash::with_data_sync(&action);
-            op_hashes.push(op_hash);
-            ops.push(op);
-            required_actions.push(action_hash);
-        }
+        let test_db = test_dht_db();
+        let workspace = CountersigningWorkspace::new(test_db.to_db());
 
         // - Put the ops in the workspace with expiry set to one hour from now.
-        for (op_h, op) in op_hashes.into_iter().zip(ops.into_iter()) {
-            let expires = (Timestamp::now() + std::time::Duration::from_secs(60 * 60)).unwrap();
-            workspace.put(
-                entry_hash.clone(),
-                op_h,
-                op,
-                required_actions.clone(),
-                expires,
-            );
-        }
+        let entry_hash = EntryHash::arbitrary(&mut u).unwrap();
+        let expires = (Timestamp::now() + std::time::Duration::from_secs(60 * 60)).unwrap();
+        let ops = session_ops(&mut u, &entry_hash, 5, expires);
+        workspace.put(ops).await.unwrap();
 
         // - Get all complete sessions.
-        let r = workspace.get_complete_sessions();
+        let r = workspace.get_complete_sessions().await.unwrap();
         // - Expect we have one.
         assert_eq!(r.len(), 1);
 
-        workspace
-            .inner
-            .share_mut(|i, _| {
-                // - Check we have none pending.
-                assert_eq!(i.pending.len(), 0);
-                Ok(())
-            })
-            .unwrap();
+        // - Check we have none pending.
+        assert!(stored_ops(&test_db.to_db()).await.is_empty());
     }
 
-    #[test]
+    #[tokio::test(flavor = "multi_thread")]
     /// Test that expired sessions are removed.
-    fn expired_sessions_removed() {
+    async fn expired_sessions_removed() {
         let mut u = arbitrary::Unstructured::new(&holochain_zome_types::NOISE);
-        let workspace = CountersigningWorkspace::new();
+        let test_db = test_dht_db();
+        let workspace = CountersigningWorkspace::new(test_db.to_db());
 
         // - Create an op for a session that has expired in the past.
-        let op_hash = DhtOpHash::arbitrary(&mut u).unwrap();
-        let op = DhtOp::arbitrary(&mut u).unwrap();
-        let action = op.action();
         let entry_hash = EntryHash::arbitrary(&mut u).unwrap();
-        let action_hash = ActionHash::with_data_sync(&action);
         let expires = (Timestamp::now() - std::time::Duration::from_secs(60 * 60)).unwrap();
+        let ops = session_ops(&mut u, &entry_hash, 1, expires);
 
         // - Add it to the workspace.
-        workspace.put(entry_hash, op_hash, op, vec![action_hash], expires);
-        let r = workspace.get_complete_sessions();
+        workspace.put(ops).await.unwrap();
+        let r = workspace.get_complete_sessions().await.unwrap();
 
         // - Expect we have no complete sessions.
         assert_eq!(r.len(), 0);
-        workspace
-            .inner
-            .share_mut(|i, _| {
-                // - Check we have none pending.
-                assert_eq!(i.pending.len(), 0);
-                Ok(())
-            })
-            .unwrap();
+        // - Check we have none pending.
+        assert!(stored_ops(&test_db.to_db()).await.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    /// Test that the ops collected for a session are not lost
+    /// when the workspace is recreated, i.e. after a restart.
+    async fn sessions_survive_restart() {
+        let mut u = arbitrary::Unstructured::new(&holochain_zome_types::NOISE);
+        let test_db = test_dht_db();
+
+        let entry_hash = EntryHash::arbitrary(&mut u).unwrap();
+        let expires = (Timestamp::now() + std::time::Duration::from_secs(60 * 60)).unwrap();
+        let mut ops = session_ops(&mut u, &entry_hash, 3, expires);
+        let last = ops.pop().unwrap();
+
+        // - Collect all but the last op then "restart".
+        {
+            let workspace = CountersigningWorkspace::new(test_db.to_db());
+            workspace.put(ops).await.unwrap();
+            assert!(workspace.get_complete_sessions().await.unwrap().is_empty());
+        }
+        assert_eq!(stored_ops(&test_db.to_db()).await.len(), 2);
+
+        // - The session completes once the last op arrives.
+        let workspace = CountersigningWorkspace::new(test_db.to_db());
+        workspace.put(vec![last]).await.unwrap();
+        let r = workspace.get_complete_sessions().await.unwrap();
+        assert_eq!(r.len(), 1);
+        let (agents, ops, actions) = &r[0];
+        assert_eq!(agents.len(), 3);
+        assert_eq!(ops.len(), 3);
+        assert_eq!(actions.len(), 3);
+        assert!(stored_ops(&test_db.to_db()).await.is_empty());
     }
 }
@@ -27,6 +27,9 @@ pub enum WorkflowError {
     #[error("Agent is invalid: {0:?}")]
     AgentInvalid(AgentPubKey),
 
+    #[error("The zome call did not complete within {0:?}")]
+    ZomeCallTimeout(std::time::Duration),
+
     #[error("Conductor API error: {0}")]
     ConductorApi(#[from] Box<ConductorApiError>),
 
@@ -2,6 +2,7 @@
 
 use super::error::WorkflowResult;
 use super::sys_validation_workflow::counterfeit_check;
+use crate::core::sys_validate::check_op_entry_hash;
 use crate::{
     conductor::{conductor::RwShare, space::Space},
     core::queue_consumer::TriggerSender,
@@ -162,7 +163,7 @@ pub async fn incoming_dht_ops_workflow(
             Ok(()) => filter_ops.push((hash, op)),
             Err(e) => {
                 tracing::warn!(
-                    msg = "Dropping op because it failed counterfeit checks",
+                    msg = "Dropping op because it failed counterfeit or entry hash checks",
                     ?op
                 );
                 return Err(e);
@@ -231,11 +232,13 @@ pub async fn incoming_dht_ops_workflow(
 }
 
 #[instrument(skip(op))]
-/// If this op fails the counterfeit check it should be dropped
+/// If this op fails the counterfeit check or carries an
+/// entry its action doesn't point to it should be dropped
 async fn should_keep(op: &DhtOp) -> WorkflowResult<()> {
     let action = op.action();
     let signature = op.signature();
-    Ok(counterfeit_check(signature, &action).await?)
+    counterfeit_check(signature, &action).await?;
+    Ok(check_op_entry_hash(op)?)
 }
 
 fn add_to_pending(
@@ -22,6 +22,7 @@ use std::time;
 use tracing::*;
 
 mod publish_query;
+pub(crate) use publish_query::num_still_needing_publish;
 
 /// Default redundancy factor for validation receipts
 pub const DEFAULT_RECEIPT_BUNDLE_SIZE: u8 = 5;
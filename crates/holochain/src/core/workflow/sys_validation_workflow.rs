@@ -10,7 +10,10 @@ use crate::core::queue_consumer::WorkComplete;
 use crate::core::sys_validate::check_and_hold_store_record;
 use crate::core::sys_validate::*;
 use crate::core::validation::*;
-use error::WorkflowResult;
+use crate::core::warrant::find_chain_fork;
+use crate::core::warrant::invalid_op_proof;
+use crate::core::warrant::issue_warrants;
+use super::error::WorkflowResult;
 use holo_hash::DhtOpHash;
 use holochain_cascade::Cascade;
 use holochain_p2p::HolochainP2pDna;
@@ -89,6 +92,10 @@ async fn sys_validation_workflow_inner(
     tracing::debug!("Validating {} ops", start_len);
     let start = (start_len >= NUM_CONCURRENT_OPS).then(std::time::Instant::now);
     let saturated = start.is_some();
+    let dna_hash = workspace.dna_hash().clone();
+    let keystore = conductor_handle.keystore().clone();
+    let warrant_conductor = conductor_handle.clone();
+    let warrant_network = network.clone();
 
     // Process each op
     let iter = sorted_ops.into_iter().map({
@@ -108,6 +115,8 @@ async fn sys_validation_workflow_inner(
                 let action = op.action();
 
                 let dependency = get_dependency(op_type, &action);
+                // Kept in case the op proves the author is breaking the rules.
+                let signature = op.signature().clone();
 
                 let r = validate_op(
                     &op,
@@ -117,7 +126,13 @@ async fn sys_validation_workflow_inner(
                     Some(incoming_dht_ops_sender),
                 )
                 .await;
-                r.map(|o| (op_hash, o, dependency))
+                r.map(|o| {
+                    let proof = match o {
+                        Outcome::Rejected => invalid_op_proof(&op, ValidationType::Sys),
+                        _ => None,
+                    };
+                    (op_hash, o, dependency, op_type, (action, signature), proof)
+                })
             }
         }
     });
@@ -154,18 +169,27 @@ async fn sys_validation_workflow_inner(
     while let Some(chunk) = iter.next().await {
         let num_ops: usize = chunk.iter().map(|c| c.len()).sum();
         tracing::debug!("Committing {} ops", num_ops);
-        let (t, a, m, r) = space
+        let (t, a, m, r, proofs) = space
             .dht_db
             .async_commit(move |txn| {
                 let mut total = 0;
                 let mut awaiting = 0;
                 let mut missing = 0;
                 let mut rejected = 0;
+                let mut proofs = Vec::new();
                 for outcome in chunk.into_iter().flatten() {
-                    let (op_hash, outcome, dependency) = outcome?;
+                    let (op_hash, outcome, dependency, op_type, (action, signature), proof) =
+                        outcome?;
                     match outcome {
                         Outcome::Accepted => {
                             total += 1;
+                            if let DhtOpType::RegisterAgentActivity = op_type {
+                                let action = SignedActionHashed::with_presigned(
+                                    ActionHashed::from_content_sync(action),
+                                    signature,
+                                );
+                                proofs.extend(find_chain_fork(txn, &action)?);
+                            }
                             put_validation_limbo(
                                 txn,
                                 &op_hash,
@@ -193,6 +217,7 @@ async fn sys_validation_workflow_inner(
                         }
                         Outcome::Rejected => {
                             rejected += 1;
+                            proofs.extend(proof);
                             if let Dependency::Null = dependency {
                                 put_integrated(txn, &op_hash, ValidationStatus::Rejected)?;
                             } else {
@@ -201,9 +226,18 @@ async fn sys_validation_workflow_inner(
                         }
                     }
                 }
-                WorkflowResult::Ok((total, awaiting, missing, rejected))
+                WorkflowResult::Ok((total, awaiting, missing, rejected, proofs))
             })
             .await?;
+        issue_warrants(
+            &dna_hash,
+            proofs,
+            &space.dht_db,
+            &keystore,
+            &warrant_conductor,
+            &warrant_network,
+        )
+        .await?;
 
         total += t;
         if let (Some(start), Some(round_time)) = (start, &mut round_time) {
@@ -295,6 +329,8 @@ fn handle_failed(error: ValidationOutcome) -> Outcome {
         }
         ValidationOutcome::PrevActionError(_) => Rejected,
         ValidationOutcome::PrivateEntry => Rejected,
+        ValidationOutcome::RateLimitExceeded(_) => Rejected,
+        ValidationOutcome::RateWeightMismatch(_) => Rejected,
         ValidationOutcome::PreflightResponseSignature(_) => Rejected,
         ValidationOutcome::UpdateTypeMismatch(_, _) => Rejected,
         ValidationOutcome::VerifySignature(_, _) => Rejected,
@@ -382,8 +418,14 @@ async fn validate_op_inner(
             Ok(())
         }
         DhtOp::RegisterAgentActivity(_, action) => {
-            register_agent_activity(action, workspace, network.clone(), incoming_dht_ops_sender)
-                .await?;
+            register_agent_activity(
+                action,
+                workspace,
+                network.clone(),
+                incoming_dht_ops_sender,
+                conductor_handle,
+            )
+            .await?;
             store_record(action, workspace, network).await?;
             Ok(())
         }
@@ -490,6 +532,15 @@ async fn sys_validate_record_inner(
     ) -> SysValidationResult<()> {
         let incoming_dht_ops_sender = None;
         store_record(action, workspace, network.clone()).await?;
+        check_rate_limit(
+            action,
+            maybe_entry,
+            workspace.dna_hash(),
+            workspace,
+            network.clone(),
+            conductor_handle,
+        )
+        .await?;
         if let Some((maybe_entry, EntryVisibility::Public)) =
             &maybe_entry.and_then(|e| action.entry_type().map(|et| (e, et.visibility())))
         {
@@ -561,6 +612,7 @@ async fn register_agent_activity(
     workspace: &SysValidationWorkspace,
     network: HolochainP2pDna,
     incoming_dht_ops_sender: Option<IncomingDhtOpSender>,
+    conductor_handle: &dyn ConductorHandleT,
 ) -> SysValidationResult<()> {
     // Get data ready to validate
     let prev_action_hash = action.prev_action();
@@ -572,13 +624,22 @@ async fn register_agent_activity(
         check_and_hold_register_agent_activity(
             prev_action_hash,
             workspace,
-            network,
+            network.clone(),
             incoming_dht_ops_sender,
             |_| Ok(()),
         )
         .await?;
     }
     check_chain_rollback(action, workspace).await?;
+    check_rate_limit(
+        action,
+        None,
+        workspace.dna_hash(),
+        workspace,
+        network,
+        conductor_handle,
+    )
+    .await?;
     Ok(())
 }
 
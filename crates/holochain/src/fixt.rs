@@ -22,6 +22,7 @@ use crate::core::ribosome::HostContext;
 use crate::core::ribosome::InvocationAuth;
 use crate::core::ribosome::ZomeCallHostAccess;
 use crate::core::ribosome::ZomeCallInvocation;
+use crate::core::ribosome::ZomeCallLimits;
 use crate::core::ribosome::ZomesToInvoke;
 use crate::sweettest::SweetDnaFile;
 use crate::test_utils::fake_genesis;
@@ -356,9 +357,14 @@ fixturator!(
     vanilla fn make_call_zome_handle(CellId);
 );
 
+fixturator!(
+    ZomeCallLimits;
+    constructor fn default();
+);
+
 fixturator!(
     ZomeCallHostAccess;
-    constructor fn new(HostFnWorkspace, MetaLairClient, HolochainP2pDna, SignalBroadcaster, CellConductorReadHandle);
+    constructor fn new(HostFnWorkspace, MetaLairClient, HolochainP2pDna, SignalBroadcaster, CellConductorReadHandle, ZomeCallLimits);
 );
 
 fixturator!(
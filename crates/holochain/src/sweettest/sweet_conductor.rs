@@ -8,7 +8,7 @@ use crate::conductor::{
 };
 use hdk::prelude::*;
 use holo_hash::DnaHash;
-use holochain_conductor_api::{AdminInterfaceConfig, InterfaceDriver};
+use holochain_conductor_api::AdminInterfaceConfig;
 use holochain_keystore::MetaLairClient;
 use holochain_state::prelude::test_db_dir;
 use holochain_types::prelude::*;
@@ -52,9 +52,7 @@ pub fn standard_config() -> ConductorConfig {
         override_host: None,
         override_port: None,
     }];
-    let admin_interface = AdminInterfaceConfig {
-        driver: InterfaceDriver::Websocket { port: 0 },
-    };
+    let admin_interface = AdminInterfaceConfig::websocket(0);
     ConductorConfig {
         network: Some(network),
         admin_interfaces: Some(vec![admin_interface]),
@@ -5,7 +5,6 @@ use crate::conductor::api::ZomeCall;
 use crate::conductor::conductor::CellStatus;
 use crate::conductor::config::AdminInterfaceConfig;
 use crate::conductor::config::ConductorConfig;
-use crate::conductor::config::InterfaceDriver;
 use crate::conductor::p2p_agent_store;
 use crate::conductor::ConductorBuilder;
 use crate::conductor::ConductorHandle;
@@ -371,9 +370,7 @@ pub async fn setup_app_inner(
 ) -> (RealAppInterfaceApi, ConductorHandle) {
     let conductor_handle = ConductorBuilder::new()
         .config(ConductorConfig {
-            admin_interfaces: Some(vec![AdminInterfaceConfig {
-                driver: InterfaceDriver::Websocket { port: 0 },
-            }]),
+            admin_interfaces: Some(vec![AdminInterfaceConfig::websocket(0)]),
             network,
             ..Default::default()
         })
@@ -452,6 +452,71 @@ async fn simple_validation() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Simple zome which weighs every entry into a small rate limiting bucket
+/// that doesn't drain during the test
+fn rate_limited_zome() -> InlineZomeSet {
+    let entry_def = EntryDef::default_with_id("string");
+
+    SweetEasyInline::new(vec![entry_def.clone()], 0)
+        .callback("create", move |api, s: AppString| {
+            let entry = Entry::app(s.try_into().unwrap()).unwrap();
+            let hash = api.create(CreateInput::new(
+                InlineZomeSet::get_entry_location(&api, EntryDefIndex(0)),
+                EntryVisibility::Public,
+                entry,
+                ChainTopOrdering::default(),
+            ))?;
+            Ok(hash)
+        })
+        .integrity_callback("rate_limits", |_api, ()| {
+            Ok(RateLimitsCallbackResult::Limits(vec![RateLimit {
+                bucket_id: 0,
+                capacity: 3,
+                drain_amount: 1,
+                drain_interval_ms: u32::MAX,
+            }]))
+        })
+        .integrity_callback("weigh", |_api, _input: WeighInput| {
+            Ok(EntryRateWeight {
+                bucket_id: 0,
+                units: 1,
+                rate_bytes: 0,
+            })
+        })
+        .0
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rate_limited_creates() -> anyhow::Result<()> {
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(rate_limited_zome()).await?;
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let alice = SweetAgents::one(conductor.keystore()).await;
+    let app = conductor
+        .setup_app_for_agent("app", alice, &[dna_file])
+        .await
+        .unwrap();
+    let (alice,) = app.into_tuple();
+    let alice = alice.zome(SweetEasyInline::COORDINATOR);
+
+    // The bucket has room for three entries
+    for i in 0..3 {
+        let _: ActionHash = conductor
+            .call(&alice, "create", AppString::new(i.to_string()))
+            .await;
+    }
+
+    // The fourth entry overflows the bucket and fails sys validation
+    let err: ConductorApiResult<ActionHash> = conductor
+        .call_fallible(&alice, "create", AppString::new("overflow"))
+        .await;
+    assert!(err
+        .unwrap_err()
+        .to_string()
+        .contains("exceeded the capacity of rate limiting bucket 0"));
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn can_call_real_zomes_too() {
     observability::test_run().ok();
@@ -712,6 +777,85 @@ async fn insert_source_chain() {
     assert_eq!(chain.last().unwrap().1, 3);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+/// Test that a source chain can be exported and imported into another conductor.
+async fn export_and_import_source_chain() {
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome())
+        .await
+        .unwrap();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let apps = conductor
+        .setup_app("app", &[dna_file.clone()])
+        .await
+        .unwrap();
+    let (alice,) = apps.into_tuple();
+
+    let hash: ActionHash = conductor
+        .call(&alice.zome(SweetEasyInline::COORDINATOR), "create_unit", ())
+        .await;
+
+    let archive = conductor
+        .export_source_chain(alice.cell_id())
+        .await
+        .unwrap();
+    assert_eq!(archive.archive.cell_id(), *alice.cell_id());
+    assert!(archive
+        .archive
+        .records
+        .iter()
+        .any(|r| *r.action_address() == hash));
+
+    // The chain already exists on this conductor so it can't be imported.
+    let result = conductor
+        .clone()
+        .import_source_chain(archive.clone(), false)
+        .await;
+    assert!(result.is_err());
+
+    // A tampered archive is rejected.
+    let mut tampered = archive.clone();
+    tampered.archive.records.pop();
+    let mut other_conductor = SweetConductor::from_standard_config().await;
+    let result = other_conductor
+        .clone()
+        .import_source_chain(tampered, false)
+        .await;
+    assert!(matches!(
+        result,
+        Err(ConductorApiError::ChainArchiveError(
+            ChainArchiveError::InvalidSignature(_)
+        ))
+    ));
+
+    // The dna needs to be installed first.
+    let result = other_conductor
+        .clone()
+        .import_source_chain(archive.clone(), true)
+        .await;
+    assert!(matches!(result, Err(ConductorApiError::DnaMissing(_))));
+    other_conductor
+        .register_dna(dna_file.clone())
+        .await
+        .unwrap();
+
+    other_conductor
+        .clone()
+        .import_source_chain(archive.clone(), true)
+        .await
+        .expect("Should import a valid archive");
+
+    let apps = other_conductor
+        .setup_app_for_agent("restored", alice.agent_pubkey().clone(), &[dna_file])
+        .await
+        .unwrap();
+    let (alice_restored,) = apps.into_tuple();
+    let restored = other_conductor
+        .export_source_chain(alice_restored.cell_id())
+        .await
+        .unwrap();
+    assert_eq!(restored.archive.records, archive.archive.records);
+}
+
 async fn make_record(keystore: &MetaLairClient, action: Action) -> Record {
     let shh = SignedActionHashed::sign(
         keystore,
@@ -516,6 +516,9 @@ async fn run_fixt_network(
                             updates: btreeset! {},
                             invalid_actions: btreeset! {},
                             entry_dht_status: None,
+                            links: btreeset! {},
+                            deleted_links: btreeset! {},
+                            validation_status: None,
                         };
                         respond.respond(Ok(async move { Ok(metadata.try_into().unwrap()) }
                             .boxed()
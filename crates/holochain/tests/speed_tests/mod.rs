@@ -23,6 +23,7 @@ use ::fixt::prelude::*;
 use hdk::prelude::*;
 use holochain::conductor::api::AdminRequest;
 use holochain::conductor::api::AdminResponse;
+use holochain::conductor::api::AllowedOrigins;
 use holochain::conductor::api::AppRequest;
 use holochain::conductor::api::AppResponse;
 use holochain::conductor::api::ZomeCall;
@@ -176,7 +177,11 @@ async fn speed_test(n: Option<usize>) -> Arc<TempDir> {
 
     // Setup websocket handle and app interface
     let (mut client, _) = websocket_client(&handle).await.unwrap();
-    let request = AdminRequest::AttachAppInterface { port: None };
+    let request = AdminRequest::AttachAppInterface {
+        port: None,
+        allowed_origins: AllowedOrigins::Any,
+        require_authentication: false,
+    };
     let response = client.request(request);
     let response = response.await.unwrap();
     let app_port = match response {
@@ -26,7 +26,7 @@ use futures::Future;
 use holochain_conductor_api::conductor::ConductorConfig;
 use holochain_conductor_api::conductor::KeystoreConfig;
 use holochain_conductor_api::AdminInterfaceConfig;
-use holochain_conductor_api::InterfaceDriver;
+use holochain_conductor_api::AllowedOrigins;
 use matches::assert_matches;
 use serde::Serialize;
 use std::time::Duration;
@@ -116,7 +116,11 @@ pub async fn call_zome_fn<S>(
 }
 
 pub async fn attach_app_interface(client: &mut WebsocketSender, port: Option<u16>) -> u16 {
-    let request = AdminRequest::AttachAppInterface { port };
+    let request = AdminRequest::AttachAppInterface {
+        port,
+        allowed_origins: AllowedOrigins::Any,
+        require_authentication: false,
+    };
     let response = client.request(request);
     let response = check_timeout(response, 3000).await;
     match response {
@@ -270,14 +274,14 @@ pub async fn check_started(holochain: &mut Child) {
 
 pub fn create_config(port: u16, environment_path: PathBuf) -> ConductorConfig {
     ConductorConfig {
-        admin_interfaces: Some(vec![AdminInterfaceConfig {
-            driver: InterfaceDriver::Websocket { port },
-        }]),
+        admin_interfaces: Some(vec![AdminInterfaceConfig::websocket(port)]),
         environment_path: environment_path.into(),
         network: None,
         dpki: None,
         keystore: KeystoreConfig::DangerTestKeystoreLegacyDeprecated,
         db_sync_strategy: DbSyncStrategy::default(),
+        db_encryption: None,
+        zome_call: Default::default(),
     }
 }
 
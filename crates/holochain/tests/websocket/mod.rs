@@ -256,7 +256,7 @@ async fn remote_signals() -> anyhow::Result<()> {
     for mut rx in rxs {
         let r = rx.try_recv();
         // Each handle should recv a signal
-        assert_matches!(r, Ok(Signal::App(_, a)) if a == signal);
+        assert_matches!(r, Ok(Signal::App { signal: a, .. }) if a == signal);
     }
 
     Ok(())
@@ -345,7 +345,11 @@ async fn emit_signals() {
     assert!(!msg2.is_request());
 
     assert_eq!(
-        Signal::App(cell_id, AppSignal::new(ExternIO::encode(()).unwrap())),
+        Signal::App {
+            cell_id,
+            zome_name: TestWasm::EmitSignal.into(),
+            signal: AppSignal::new(ExternIO::encode(()).unwrap()),
+        },
         Signal::try_from(sig1.clone()).unwrap(),
     );
     assert_eq!(sig1, sig2);
@@ -353,6 +357,126 @@ async fn emit_signals() {
     ///////////////////////////////////////////////////////
 }
 
+#[tokio::test(flavor = "multi_thread")]
+#[cfg(feature = "slow_tests")]
+async fn signal_subscriptions_filter_signals() {
+    use holochain::conductor::api::signal_subscription::*;
+    use holochain::conductor::api::{AppRequest, AppResponse};
+
+    observability::test_run().ok();
+    // NOTE: This is a full integration test that
+    // actually runs the holochain binary
+
+    let admin_port = 0;
+
+    let tmp_dir = TempDir::new("conductor_cfg_signal_subscriptions").unwrap();
+    let path = tmp_dir.path().to_path_buf();
+    let environment_path = path.clone();
+    let config = create_config(admin_port, environment_path);
+    let config_path = write_config(path, &config);
+
+    let (_holochain, admin_port) = start_holochain(config_path.clone()).await;
+    let admin_port = admin_port.await.unwrap();
+
+    let (mut admin_tx, _) = websocket_client_by_port(admin_port).await.unwrap();
+
+    let uuid = uuid::Uuid::new_v4();
+    let dna = fake_dna_zomes(
+        &uuid.to_string(),
+        vec![(TestWasm::EmitSignal.into(), TestWasm::EmitSignal.into())],
+    );
+    let orig_dna_hash = dna.dna_hash().clone();
+    let (fake_dna_path, _tmpdir) = write_fake_dna_file(dna).await.unwrap();
+    let agent_key = fake_agent_pubkey_1();
+
+    let dna_hash = register_and_install_dna(
+        &mut admin_tx,
+        orig_dna_hash,
+        fake_agent_pubkey_1(),
+        fake_dna_path,
+        None,
+        "".into(),
+        6000,
+    )
+    .await;
+    let cell_id = CellId::new(dna_hash.clone(), agent_key.clone());
+
+    let request = AdminRequest::EnableApp {
+        installed_app_id: "test".to_string(),
+    };
+    let response = admin_tx.request(request);
+    let response = check_timeout(response, 3000).await;
+    assert_matches!(response, AdminResponse::AppEnabled { .. });
+
+    let app_port = attach_app_interface(&mut admin_tx, None).await;
+
+    let (mut include_tx, include_rx) = websocket_client_by_port(app_port).await.unwrap();
+    let (mut exclude_tx, exclude_rx) = websocket_client_by_port(app_port).await.unwrap();
+    let (mut tagged_tx, tagged_rx) = websocket_client_by_port(app_port).await.unwrap();
+
+    let subscribe = |filters: SignalFilterSet| {
+        AppRequest::SignalSubscription(SignalSubscription::new("test".to_string(), filters))
+    };
+    let zome_filter = || SignalFilter::empty().with_zome_name(TestWasm::EmitSignal.into());
+
+    // Only include signals from the emitting zome.
+    let response = include_tx.request(subscribe(SignalFilterSet::Include(
+        [(cell_id.clone(), zome_filter())].into_iter().collect(),
+    )));
+    let response: AppResponse = check_timeout(response, 3000).await;
+    assert_matches!(response, AppResponse::SignalSubscriptionUpdated);
+
+    // Exclude signals from the emitting zome.
+    let response = exclude_tx.request(subscribe(SignalFilterSet::Exclude(
+        [(cell_id.clone(), zome_filter())].into_iter().collect(),
+    )));
+    let response: AppResponse = check_timeout(response, 3000).await;
+    assert_matches!(response, AppResponse::SignalSubscriptionUpdated);
+
+    // Only include signals with a type tag the emitted signal doesn't have.
+    let response = tagged_tx.request(subscribe(SignalFilterSet::Include(
+        [(cell_id.clone(), zome_filter().with_signal_type("other"))]
+            .into_iter()
+            .collect(),
+    )));
+    let response: AppResponse = check_timeout(response, 3000).await;
+    assert_matches!(response, AppResponse::SignalSubscriptionUpdated);
+
+    // Subscribing to an app which isn't installed is an error.
+    let response = tagged_tx.request(AppRequest::SignalSubscription(SignalSubscription::new(
+        "not-installed".to_string(),
+        SignalFilterSet::allow_all(),
+    )));
+    let response: AppResponse = check_timeout(response, 3000).await;
+    assert_matches!(response, AppResponse::Error(_));
+
+    call_zome_fn(
+        &mut include_tx,
+        cell_id.clone(),
+        TestWasm::EmitSignal,
+        "emit".into(),
+        (),
+    )
+    .await;
+
+    let (sig, msg) = Box::pin(include_rx.timeout(Duration::from_secs(1)))
+        .next()
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!msg.is_request());
+    assert_matches!(
+        Signal::try_from(sig).unwrap(),
+        Signal::App { cell_id: c, .. } if c == cell_id
+    );
+
+    // The other connections filtered the signal out.
+    for rx in [exclude_rx, tagged_rx] {
+        let r = Box::pin(rx.timeout(Duration::from_secs(1))).next().await;
+        assert_matches!(r, Some(Err(_)));
+    }
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn conductor_admin_interface_runs_from_config() -> Result<()> {
     observability::test_run().ok();
@@ -1,3 +1,4 @@
+use self::count_links_query::CountLinksQuery;
 use self::get_agent_activity_query::hashes::GetAgentActivityQuery;
 use self::get_entry_ops_query::GetEntryOpsQuery;
 use self::get_links_ops_query::GetLinksOpsQuery;
@@ -9,6 +10,7 @@ use self::{
 use super::error::CascadeResult;
 use holo_hash::ActionHash;
 use holo_hash::AgentPubKey;
+use holochain_state::query::metadata::GetMetadataQuery;
 use holochain_state::query::Query;
 use holochain_state::query::Txn;
 use holochain_types::prelude::*;
@@ -18,6 +20,7 @@ use tracing::*;
 #[cfg(test)]
 mod test;
 
+pub(crate) mod count_links_query;
 pub(crate) mod get_agent_activity_query;
 pub(crate) mod get_entry_ops_query;
 pub(crate) mod get_links_ops_query;
@@ -56,9 +59,13 @@ pub async fn handle_get_agent_activity(
     query: ChainQueryFilter,
     options: holochain_p2p::event::GetActivityOptions,
 ) -> CascadeResult<AgentActivityResponse<ActionHash>> {
-    let query = GetAgentActivityQuery::new(agent, query, options);
+    let query = GetAgentActivityQuery::new(agent.clone(), query, options);
     let results = env
-        .async_reader(move |txn| query.run(Txn::from(&txn)))
+        .async_reader(move |txn| {
+            let mut response = query.run(Txn::from(&txn))?;
+            response.warrants = holochain_state::warrants::list_warrants(&txn, &agent)?;
+            CascadeResult::Ok(response)
+        })
         .await?;
     Ok(results)
 }
@@ -77,6 +84,19 @@ pub async fn handle_get_agent_activity_deterministic(
     Ok(results)
 }
 
+#[instrument(skip(env))]
+pub async fn handle_get_meta(
+    env: DbRead<DbKindDht>,
+    hash: AnyDhtHash,
+    options: holochain_p2p::event::GetMetaOptions,
+) -> CascadeResult<MetadataSet> {
+    let query = GetMetadataQuery::new(hash, options.metadata_request);
+    let results = env
+        .async_reader(move |txn| query.run(Txn::from(&txn)))
+        .await?;
+    Ok(results)
+}
+
 #[instrument(skip(env, _options))]
 pub async fn handle_get_links(
     env: DbRead<DbKindDht>,
@@ -89,3 +109,15 @@ pub async fn handle_get_links(
         .await?;
     Ok(results)
 }
+
+#[instrument(skip(env))]
+pub async fn handle_count_links(
+    env: DbRead<DbKindDht>,
+    query: WireLinkKey,
+) -> CascadeResult<CountLinksResponse> {
+    let query = CountLinksQuery::new(query);
+    let results = env
+        .async_reader(move |txn| query.run(Txn::from(&txn)))
+        .await?;
+    Ok(results)
+}
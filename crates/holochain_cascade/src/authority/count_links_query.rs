@@ -0,0 +1,113 @@
+use std::sync::Arc;
+
+use holo_hash::ActionHash;
+use holo_hash::AnyLinkableHash;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_sqlite::rusqlite::Row;
+use holochain_state::query::link::LinksFilter;
+use holochain_state::query::link::LinksQuery;
+use holochain_state::query::prelude::*;
+use holochain_types::dht_op::DhtOpType;
+use holochain_types::link::CountLinksResponse;
+use holochain_types::sql::ToSqlStatement;
+use holochain_zome_types::Judged;
+use holochain_zome_types::LinkTag;
+use holochain_zome_types::LinkTypeFilter;
+use holochain_zome_types::ValidationStatus;
+
+use super::WireLinkKey;
+
+/// Get the hashes of the valid create links on a base which
+/// haven't been deleted, without the links themselves.
+#[derive(Debug, Clone)]
+pub struct CountLinksQuery {
+    base: Arc<AnyLinkableHash>,
+    type_query: LinkTypeFilter,
+    tag: Option<Arc<LinkTag>>,
+    filter: LinksFilter,
+}
+
+impl CountLinksQuery {
+    pub fn new(key: WireLinkKey) -> Self {
+        let filter = LinksFilter::from_key(&key);
+        Self {
+            base: Arc::new(key.base),
+            type_query: key.type_query,
+            tag: key.tag.map(Arc::new),
+            filter,
+        }
+    }
+}
+
+impl Query for CountLinksQuery {
+    type Item = Judged<ActionHash>;
+    type State = Vec<ActionHash>;
+    type Output = CountLinksResponse;
+
+    fn query(&self) -> String {
+        let tag = match &self.tag {
+            Some(tag) => format!(
+                "
+                AND
+                HEX(Action.tag) LIKE '{}%'
+                ",
+                LinksQuery::tag_to_hex(tag.as_ref())
+            ),
+            None => String::new(),
+        };
+        format!(
+            "
+            SELECT Action.hash FROM DhtOp
+            JOIN Action On DhtOp.action_hash = Action.hash
+            WHERE DhtOp.type = :create
+            AND
+            Action.base_hash = :base_hash
+            AND
+            DhtOp.validation_status = :status
+            AND
+            DhtOp.when_integrated IS NOT NULL
+            {}
+            {}
+            {}
+            {}
+            ",
+            tag,
+            self.type_query.to_sql_statement(),
+            LinksFilter::sql(),
+            LinksFilter::not_deleted_sql(),
+        )
+    }
+
+    fn params(&self) -> Vec<Params> {
+        let mut params = named_params! {
+            ":create": DhtOpType::RegisterAddLink,
+            ":delete": DhtOpType::RegisterRemoveLink,
+            ":status": ValidationStatus::Valid,
+            ":base_hash": self.base,
+        }
+        .to_vec();
+        params.extend(self.filter.params());
+        params
+    }
+
+    fn as_map(&self) -> Arc<dyn Fn(&Row) -> StateQueryResult<Self::Item>> {
+        // Data is valid because it is filtered in the sql query.
+        Arc::new(|row: &Row| Ok(Judged::valid(row.get("hash")?)))
+    }
+
+    fn init_fold(&self) -> StateQueryResult<Self::State> {
+        Ok(Vec::new())
+    }
+
+    fn fold(&self, mut state: Self::State, hash: Self::Item) -> StateQueryResult<Self::State> {
+        state.push(hash.data);
+        Ok(state)
+    }
+
+    fn render<S>(&self, state: Self::State, _stores: S) -> StateQueryResult<Self::Output>
+    where
+        S: Store,
+    {
+        Ok(CountLinksResponse::new(state))
+    }
+}
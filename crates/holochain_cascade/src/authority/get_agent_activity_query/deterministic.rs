@@ -90,7 +90,8 @@ impl Query for DeterministicGetAgentActivityQuery {
     }
 
     fn as_filter(&self) -> Box<dyn Fn(&QueryData<Self>) -> bool> {
-        todo!()
+        let agent = self.agent.clone();
+        Box::new(move |action| action.action().author() == &agent)
     }
 
     fn fold(&self, mut state: Self::State, item: Self::Item) -> StateQueryResult<Self::State> {
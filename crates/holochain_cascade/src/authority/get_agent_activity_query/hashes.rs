@@ -168,6 +168,8 @@ impl Query for GetAgentActivityQuery {
             rejected_activity,
             status,
             highest_observed,
+            // Warrants are added by the authority.
+            warrants: Vec::new(),
         })
     }
 }
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use holo_hash::AnyLinkableHash;
 use holochain_sqlite::rusqlite::named_params;
 use holochain_sqlite::rusqlite::Row;
+use holochain_state::query::link::LinksFilter;
 use holochain_state::query::prelude::*;
 use holochain_state::query::StateQueryError;
 use holochain_types::dht_op::DhtOpType;
@@ -16,6 +17,7 @@ use holochain_zome_types::Judged;
 use holochain_zome_types::LinkTag;
 use holochain_zome_types::LinkTypeFilter;
 use holochain_zome_types::SignedAction;
+use holochain_zome_types::ValidationStatus;
 
 use super::WireLinkKey;
 
@@ -24,14 +26,17 @@ pub struct GetLinksOpsQuery {
     base: Arc<AnyLinkableHash>,
     type_query: LinkTypeFilter,
     tag: Option<Arc<LinkTag>>,
+    filter: LinksFilter,
 }
 
 impl GetLinksOpsQuery {
     pub fn new(key: WireLinkKey) -> Self {
+        let filter = LinksFilter::from_key(&key);
         Self {
             base: Arc::new(key.base),
             type_query: key.type_query,
             tag: key.tag.map(Arc::new),
+            filter,
         }
     }
     pub fn tag_to_hex(tag: &LinkTag) -> String {
@@ -88,11 +93,33 @@ impl Query for GetLinksOpsQuery {
             "
             {}
             {}
+            {}
             ",
             common_query,
             self.type_query.to_sql_statement(),
+            LinksFilter::sql(),
+        );
+        // When a page of links is requested only valid creates that
+        // haven't been deleted count towards the limit, so short pages
+        // aren't returned when some of the links have been deleted.
+        // Deletes are still returned for every matching create so that
+        // links deleted since they were cached are removed.
+        let create_query = format!(
+            "
+            SELECT * FROM (
+                {}{}
+                AND (
+                    :limit IS NULL
+                    OR (DhtOp.validation_status = :status {})
+                )
+                ORDER BY DhtOp.authored_timestamp, Action.hash
+                LIMIT COALESCE(:limit, -1)
+            )
+            ",
+            create,
+            common_query,
+            LinksFilter::not_deleted_sql(),
         );
-        let create_query = format!("{}{}", create, common_query);
         let sub_create_query = format!("{}{}", sub_create, common_query);
         let delete_query = format!(
             "
@@ -112,12 +139,16 @@ impl Query for GetLinksOpsQuery {
     }
 
     fn params(&self) -> Vec<Params> {
-        named_params! {
+        let mut params = named_params! {
             ":create": DhtOpType::RegisterAddLink,
             ":delete": DhtOpType::RegisterRemoveLink,
+            ":status": ValidationStatus::Valid,
             ":base_hash": self.base,
         }
-        .to_vec()
+        .to_vec();
+        params.extend(self.filter.params());
+        params.extend(self.filter.limit_params());
+        params
     }
 
     fn as_map(&self) -> Arc<dyn Fn(&Row) -> StateQueryResult<Self::Item>> {
@@ -1,11 +1,14 @@
 use super::*;
 use crate::authority::handle_get_agent_activity;
 use crate::test_utils::*;
+use ::fixt::prelude::*;
 use ghost_actor::dependencies::observability;
 use holochain_p2p::actor;
 use holochain_p2p::event::GetRequest;
 use holochain_state::prelude::test_dht_db;
 use holochain_types::activity::ChainItems;
+use holochain_types::metadata::TimedActionHash;
+use std::collections::BTreeSet;
 
 fn options() -> holochain_p2p::event::GetOptions {
     holochain_p2p::event::GetOptions {
@@ -15,6 +18,16 @@ fn options() -> holochain_p2p::event::GetOptions {
     }
 }
 
+fn meta_options() -> holochain_p2p::event::GetMetaOptions {
+    holochain_p2p::event::GetMetaOptions {
+        metadata_request: MetadataRequest {
+            entry_dht_status: true,
+            all_links: true,
+            ..Default::default()
+        },
+    }
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn get_entry() {
     observability::test_run().ok();
@@ -224,6 +237,7 @@ async fn get_agent_activity() {
         rejected_activity: ChainItems::NotRequested,
         status: ChainStatus::Valid(td.chain_head.clone()),
         highest_observed: Some(td.highest_observed.clone()),
+        warrants: Vec::new(),
     };
     assert_eq!(result, expected);
 
@@ -246,3 +260,95 @@ async fn get_agent_activity() {
 
     assert_eq!(result, expected);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_agent_activity_returns_warrants() {
+    observability::test_run().ok();
+    let db = test_dht_db();
+
+    let td = ActivityTestData::valid_chain_scenario();
+
+    for hash_op in td.hash_ops.iter().cloned() {
+        fill_db(&db.to_db(), hash_op);
+    }
+
+    let warrant = |warrantee: AgentPubKey| {
+        SignedWarrant::new(
+            Warrant::new(
+                WarrantProof::InvalidChainOp {
+                    action_author: warrantee,
+                    action: (fixt!(ActionHash), fixt!(Signature)),
+                    validation_type: ValidationType::App,
+                },
+                fixt!(AgentPubKey),
+                Timestamp::now(),
+            ),
+            fixt!(Signature),
+        )
+    };
+    let agent_warrant = warrant(td.agent.clone());
+    let other_warrant = warrant(fixt!(AgentPubKey));
+    db.to_db().test_commit(|txn| {
+        holochain_state::mutations::insert_warrant(txn, agent_warrant.clone()).unwrap();
+        holochain_state::mutations::insert_warrant(txn, other_warrant).unwrap();
+    });
+
+    let options = actor::GetActivityOptions {
+        include_valid_activity: false,
+        include_rejected_activity: false,
+        include_full_actions: false,
+        ..Default::default()
+    };
+
+    let result = handle_get_agent_activity(
+        db.to_db().into(),
+        td.agent.clone(),
+        td.query_filter.clone(),
+        (&options).into(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.warrants, vec![agent_warrant]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_meta() {
+    observability::test_run().ok();
+    let db = test_dht_db();
+
+    let td = EntryTestData::create();
+    let hashes = |set: &BTreeSet<TimedActionHash>| {
+        set.iter()
+            .map(|timed| timed.action_hash.clone())
+            .collect::<Vec<_>>()
+    };
+
+    fill_db(&db.to_db(), td.store_entry_op.clone());
+    fill_db(&db.to_db(), td.create_link_op.clone());
+
+    let result = handle_get_meta(db.to_db().into(), td.hash.clone().into(), meta_options())
+        .await
+        .unwrap();
+    assert_eq!(hashes(&result.actions), vec![td.create_hash.clone()]);
+    assert_eq!(
+        hashes(&result.links),
+        vec![td.create_link_action.as_hash().clone()]
+    );
+    assert!(!result.is_deleted());
+    assert!(result.updates.is_empty());
+    assert_eq!(result.entry_dht_status, Some(EntryDhtStatus::Live));
+
+    fill_db(&db.to_db(), td.delete_entry_action_op.clone());
+    fill_db(&db.to_db(), td.update_content_op.clone());
+    fill_db(&db.to_db(), td.delete_link_op.clone());
+
+    let result = handle_get_meta(db.to_db().into(), td.hash.clone().into(), meta_options())
+        .await
+        .unwrap();
+    assert_eq!(hashes(&result.actions), vec![td.create_hash.clone()]);
+    assert_eq!(hashes(&result.deletes), vec![td.delete_hash.clone()]);
+    assert_eq!(hashes(&result.updates), vec![td.update_hash.clone()]);
+    assert!(result.links.is_empty());
+    assert_eq!(result.entry_dht_status, Some(EntryDhtStatus::Dead));
+    assert_eq!(result.validation_status, None);
+}
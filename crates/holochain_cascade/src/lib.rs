@@ -4,6 +4,7 @@
 //! where as retrieve only checks that where the data was found
 //! the appropriate validation has been run.
 
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use error::CascadeResult;
@@ -14,6 +15,7 @@ use holo_hash::AnyDhtHash;
 use holo_hash::EntryHash;
 use holochain_p2p::actor::GetActivityOptions;
 use holochain_p2p::actor::GetLinksOptions;
+use holochain_p2p::actor::GetMetaOptions;
 use holochain_p2p::actor::GetOptions as NetworkGetOptions;
 use holochain_p2p::HolochainP2pDna;
 use holochain_p2p::HolochainP2pDnaT;
@@ -27,6 +29,8 @@ use holochain_state::query::link::GetLinksQuery;
 use holochain_state::query::link_details::GetLinkDetailsQuery;
 use holochain_state::query::live_entry::GetLiveEntryQuery;
 use holochain_state::query::live_record::GetLiveRecordQuery;
+use holochain_state::query::metadata::GetMetadataQuery;
+use holochain_state::query::must_get_agent_activity::MustGetAgentActivityQuery;
 use holochain_state::query::record_details::GetRecordDetailsQuery;
 use holochain_state::query::DbScratch;
 use holochain_state::query::PrivateDataQuery;
@@ -705,6 +709,33 @@ where
         }
     }
 
+    #[instrument(skip(self, options))]
+    /// Get the metadata for a hash without getting the data itself.
+    ///
+    /// This is a cheaper alternative to [`Cascade::get_details`] when only
+    /// the deletes, updates, links or status of some data are needed.
+    /// If this agent isn't an authority for the hash the metadata is
+    /// requested from authorities and merged with the metadata held locally.
+    pub async fn dht_get_meta(
+        &mut self,
+        hash: AnyDhtHash,
+        options: GetMetaOptions,
+    ) -> CascadeResult<MetadataSet> {
+        let mut metadata = MetadataSet::default();
+        let authority = self.am_i_an_authority(hash.clone()).await?;
+        if !authority {
+            if let Some(network) = self.network.as_mut() {
+                let results = network.get_meta(hash.clone(), options.clone()).await?;
+                for result in results {
+                    metadata.merge(result);
+                }
+            }
+        }
+        let query = GetMetadataQuery::new(hash, options.metadata_request);
+        metadata.merge(self.cascading(query).await?);
+        Ok(metadata)
+    }
+
     #[instrument(skip(self, options))]
     /// Gets an links from the cas or cache depending on it's metadata
     // The default behavior is to skip deleted or replaced entries.
@@ -717,11 +748,42 @@ where
         if !authority {
             self.fetch_links(key.clone(), options).await?;
         }
-        let query = GetLinksQuery::new(key.base, key.type_query, key.tag);
+        let query = GetLinksQuery::from_key(key);
         let results = self.cascading(query).await?;
         Ok(results)
     }
 
+    #[instrument(skip(self, options))]
+    /// Count the links on a base.
+    ///
+    /// If this agent isn't an authority for the base the hashes of the
+    /// links are fetched from an authority without the links themselves.
+    /// They are merged with the links held locally and any link that has
+    /// been deleted locally isn't counted.
+    pub async fn dht_count_links(
+        &mut self,
+        query: WireLinkKey,
+        options: GetLinksOptions,
+    ) -> CascadeResult<usize> {
+        let mut create_link_actions = HashSet::new();
+        let authority = self.am_i_an_authority(query.base.clone().into()).await?;
+        if !authority {
+            if let Some(network) = self.network.as_mut() {
+                let response = network.count_links(query.clone(), options).await?;
+                create_link_actions.extend(response.into_create_link_actions());
+            }
+        }
+        let details = self.cascading(GetLinkDetailsQuery::from_key(query)).await?;
+        for (create, deletes) in details {
+            if deletes.is_empty() {
+                create_link_actions.insert(create.as_hash().clone());
+            } else {
+                create_link_actions.remove(create.as_hash());
+            }
+        }
+        Ok(create_link_actions.len())
+    }
+
     #[instrument(skip(self, key, options))]
     /// Return all CreateLink actions
     /// and DeleteLink actions ordered by time.
@@ -734,7 +796,7 @@ where
         if !authority {
             self.fetch_links(key.clone(), options).await?;
         }
-        let query = GetLinkDetailsQuery::new(key.base, key.type_query, key.tag);
+        let query = GetLinkDetailsQuery::from_key(key);
         let results = self.cascading(query).await?;
         Ok(results)
     }
@@ -807,6 +869,7 @@ where
             rejected_activity,
             status,
             highest_observed,
+            warrants,
         } = merged_response;
         let valid_activity = match valid_activity {
             ChainItems::Hashes(hashes) => {
@@ -861,10 +924,100 @@ where
             rejected_activity,
             status,
             highest_observed,
+            warrants,
         };
         Ok(r)
     }
 
+    #[instrument(skip(self, author, filter))]
+    /// Get a range of an agent's source chain that is linked by hash from
+    /// the chain top, so the result can't include a fork.
+    /// If the range can't be found in the local stores the agent's activity
+    /// is fetched from the authorities into the cache and the range is
+    /// tried again.
+    pub async fn must_get_agent_activity(
+        &mut self,
+        author: AgentPubKey,
+        filter: ChainFilter,
+    ) -> CascadeResult<MustGetAgentActivityResponse> {
+        let response = self.must_get_agent_activity_local(&author, &filter).await?;
+        if matches!(response, MustGetAgentActivityResponse::Activity(_)) || self.network.is_none() {
+            return Ok(response);
+        }
+
+        // Fetching the full actions puts them in the cache.
+        // Only the range walked back from the chain top is fetched.
+        let options = GetActivityOptions {
+            include_valid_activity: true,
+            include_rejected_activity: true,
+            include_full_actions: true,
+            ..Default::default()
+        };
+        let query = ChainQueryFilter::new().sequence_range(chain_filter_range(&filter));
+        self.get_agent_activity(author.clone(), query, options)
+            .await?;
+        self.must_get_agent_activity_local(&author, &filter).await
+    }
+
+    /// Get a range of an agent's source chain from the local stores.
+    ///
+    /// Only the actions between the chain top and the point the filter
+    /// stops at are read, which is found from the sequence numbers of the
+    /// chain top and the actions to stop at.
+    async fn must_get_agent_activity_local(
+        &mut self,
+        author: &AgentPubKey,
+        filter: &ChainFilter,
+    ) -> CascadeResult<MustGetAgentActivityResponse> {
+        let chain_top = filter.chain_top.clone();
+        let max_seq = match self
+            .find_map(move |store| Ok(store.get_action(&chain_top)?))
+            .await?
+        {
+            Some(top) if top.action().author() == author => top.action().action_seq(),
+            _ => {
+                return Ok(MustGetAgentActivityResponse::ChainTopNotFound(
+                    filter.chain_top.clone(),
+                ))
+            }
+        };
+        let min_seq = filter
+            .get_take()
+            .map_or(0, |n| max_seq.saturating_add(1).saturating_sub(n));
+
+        // The walk stops at the first action to stop at that is on the
+        // chain, which is at or above the lowest of them.
+        let mut until_seq: Option<u32> = None;
+        for hash in filter.get_until().into_iter().flatten() {
+            let hash = hash.clone();
+            if let Some(action) = self
+                .find_map(move |store| Ok(store.get_action(&hash)?))
+                .await?
+            {
+                let seq = action.action().action_seq();
+                if action.action().author() == author && seq <= max_seq {
+                    until_seq = Some(until_seq.map_or(seq, |s| s.min(seq)));
+                }
+            }
+        }
+
+        let query = MustGetAgentActivityQuery::new(author.clone(), filter.clone());
+        match until_seq {
+            Some(until_seq) if until_seq > min_seq => {
+                let response = self
+                    .cascading(query.clone().with_seq_range(until_seq, max_seq))
+                    .await?;
+                if !matches!(response, MustGetAgentActivityResponse::IncompleteChain(_)) {
+                    return Ok(response);
+                }
+                // The actions to stop at were all on forks,
+                // so the walk went past them.
+                self.cascading(query.with_seq_range(min_seq, max_seq)).await
+            }
+            _ => self.cascading(query.with_seq_range(min_seq, max_seq)).await,
+        }
+    }
+
     /// Get the validation package if it is cached without going to the network
     pub fn get_validation_package_local(
         &self,
@@ -901,3 +1054,20 @@ where
         }
     }
 }
+
+/// The part of an agent's chain that walking a [`ChainFilter`] can reach,
+/// as a range authorities can filter the agent's activity by.
+fn chain_filter_range(filter: &ChainFilter) -> ChainQueryFilterRange {
+    let chain_top = filter.chain_top.clone();
+    // Several actions to stop at can't be a single range,
+    // so everything below the chain top is fetched.
+    let until = filter
+        .get_until()
+        .filter(|until| until.len() == 1)
+        .and_then(|until| until.iter().next());
+    match (filter.get_take(), until) {
+        (Some(n), _) => ChainQueryFilterRange::ActionHashTerminated(chain_top, n.saturating_sub(1)),
+        (None, Some(until)) => ChainQueryFilterRange::ActionHashRange(until.clone(), chain_top),
+        (None, None) => ChainQueryFilterRange::ActionHashTerminated(chain_top, u32::MAX),
+    }
+}
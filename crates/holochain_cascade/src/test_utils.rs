@@ -30,12 +30,14 @@ use holochain_types::db::DbRead;
 use holochain_types::db::DbWrite;
 use holochain_types::dht_op::DhtOpHashed;
 use holochain_types::dht_op::WireOps;
+use holochain_types::link::CountLinksResponse;
 use holochain_types::link::WireLinkKey;
 use holochain_types::link::WireLinkOps;
 use holochain_types::metadata::MetadataSet;
 use holochain_types::prelude::ValidationPackageResponse;
 use holochain_types::prelude::WireEntryOps;
 use holochain_types::record::WireRecordOps;
+use holochain_zome_types::warrant::SignedWarrant;
 use holochain_zome_types::ActionHashed;
 use holochain_zome_types::QueryFilter;
 use holochain_zome_types::SignedAction;
@@ -129,10 +131,17 @@ impl HolochainP2pDnaT for PassThroughNetwork {
     }
     async fn get_meta(
         &self,
-        _dht_hash: holo_hash::AnyDhtHash,
-        _options: actor::GetMetaOptions,
+        dht_hash: holo_hash::AnyDhtHash,
+        options: actor::GetMetaOptions,
     ) -> actor::HolochainP2pResult<Vec<MetadataSet>> {
-        todo!()
+        let mut out = Vec::new();
+        for env in &self.envs {
+            let r = authority::handle_get_meta(env.clone(), dht_hash.clone(), (&options).into())
+                .await
+                .map_err(|e| HolochainP2pError::Other(e.into()))?;
+            out.push(r);
+        }
+        Ok(out)
     }
     async fn get_links(
         &self,
@@ -148,6 +157,22 @@ impl HolochainP2pDnaT for PassThroughNetwork {
         }
         Ok(out)
     }
+    async fn count_links(
+        &self,
+        query: WireLinkKey,
+        _options: actor::GetLinksOptions,
+    ) -> actor::HolochainP2pResult<CountLinksResponse> {
+        let mut out = Vec::new();
+        for env in &self.envs {
+            let r = authority::handle_count_links(env.clone(), query.clone())
+                .await
+                .map_err(|e| HolochainP2pError::Other(e.into()))?;
+            out.extend(r.into_create_link_actions());
+        }
+        out.sort();
+        out.dedup();
+        Ok(CountLinksResponse::new(out))
+    }
     async fn get_agent_activity(
         &self,
         agent: AgentPubKey,
@@ -223,6 +248,19 @@ impl HolochainP2pDnaT for PassThroughNetwork {
         todo!()
     }
 
+    async fn publish_warrants(
+        &self,
+        _warrantee: AgentPubKey,
+        _warrants: Vec<SignedWarrant>,
+        _timeout_ms: Option<u64>,
+    ) -> actor::HolochainP2pResult<()> {
+        todo!()
+    }
+
+    async fn block_agents(&self, _agents: Vec<AgentPubKey>) -> actor::HolochainP2pResult<()> {
+        todo!()
+    }
+
     async fn join(
         &self,
         _agent: AgentPubKey,
@@ -334,6 +372,14 @@ impl HolochainP2pDnaT for MockNetwork {
         self.0.lock().await.get_links(link_key, options).await
     }
 
+    async fn count_links(
+        &self,
+        query: WireLinkKey,
+        options: actor::GetLinksOptions,
+    ) -> actor::HolochainP2pResult<CountLinksResponse> {
+        self.0.lock().await.count_links(query, options).await
+    }
+
     async fn get_agent_activity(
         &self,
         agent: AgentPubKey,
@@ -401,6 +447,19 @@ impl HolochainP2pDnaT for MockNetwork {
         todo!()
     }
 
+    async fn publish_warrants(
+        &self,
+        _warrantee: AgentPubKey,
+        _warrants: Vec<SignedWarrant>,
+        _timeout_ms: Option<u64>,
+    ) -> actor::HolochainP2pResult<()> {
+        todo!()
+    }
+
+    async fn block_agents(&self, _agents: Vec<AgentPubKey>) -> actor::HolochainP2pResult<()> {
+        todo!()
+    }
+
     async fn join(
         &self,
         _agent: AgentPubKey,
@@ -2,6 +2,7 @@ use ghost_actor::dependencies::observability;
 use holo_hash::HasHash;
 use holochain_cascade::test_utils::*;
 use holochain_cascade::Cascade;
+use holochain_p2p::actor::GetMetaOptions;
 use holochain_p2p::HolochainP2pDnaT;
 use holochain_p2p::MockHolochainP2pDnaT;
 use holochain_state::mutations::insert_op_scratch;
@@ -14,6 +15,7 @@ use holochain_zome_types::Details;
 use holochain_zome_types::EntryDetails;
 use holochain_zome_types::EntryDhtStatus;
 use holochain_zome_types::GetOptions;
+use holochain_zome_types::MetadataRequest;
 use holochain_zome_types::RecordDetails;
 use holochain_zome_types::ValidationStatus;
 
@@ -246,6 +248,88 @@ async fn entry_not_authority_or_authoring() {
     assert_can_get(&td_entry, &td_record, &mut cascade, GetOptions::latest()).await;
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn meta_not_authority_or_authoring() {
+    observability::test_run().ok();
+
+    // Environments
+    let cache = test_cache_db();
+    let authority = test_dht_db();
+
+    // Data
+    let td_entry = EntryTestData::create();
+    let td_record = RecordTestData::create();
+    fill_db(&authority.to_db(), td_entry.store_entry_op.clone());
+    fill_db(&authority.to_db(), td_entry.delete_entry_action_op.clone());
+    fill_db(&authority.to_db(), td_record.any_store_record_op.clone());
+
+    // Network
+    let network = PassThroughNetwork::authority_for_nothing(vec![authority.to_db().clone().into()]);
+
+    // Cascade
+    let mut cascade = Cascade::empty().with_network(network, cache.to_db());
+
+    let options = GetMetaOptions {
+        metadata_request: MetadataRequest {
+            entry_dht_status: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let meta = cascade
+        .dht_get_meta(td_entry.hash.clone().into(), options.clone())
+        .await
+        .unwrap();
+    assert!(meta.is_deleted());
+    assert_eq!(meta.deletes.len(), 1);
+    assert_eq!(
+        meta.deletes.iter().next().unwrap().action_hash,
+        td_entry.delete_hash
+    );
+    assert_eq!(meta.entry_dht_status, Some(EntryDhtStatus::Dead));
+
+    let meta = cascade
+        .dht_get_meta(td_record.any_action_hash.clone().into(), options)
+        .await
+        .unwrap();
+    assert!(!meta.is_deleted());
+    assert_eq!(meta.validation_status, Some(ValidationStatus::Valid));
+    assert_eq!(meta.entry_dht_status, None);
+
+    // - A link deleted on one authority stays deleted
+    // when another authority hasn't seen the delete yet.
+    let stale_authority = test_dht_db();
+    fill_db(&stale_authority.to_db(), td_entry.create_link_op.clone());
+    fill_db(&authority.to_db(), td_entry.create_link_op.clone());
+    let network = PassThroughNetwork::authority_for_nothing(vec![
+        stale_authority.to_db().clone().into(),
+        authority.to_db().clone().into(),
+    ]);
+    let mut cascade = Cascade::empty().with_network(network, test_cache_db().to_db());
+    let options = GetMetaOptions {
+        metadata_request: MetadataRequest {
+            all_links: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let meta = cascade
+        .dht_get_meta(td_entry.hash.clone().into(), options.clone())
+        .await
+        .unwrap();
+    assert_eq!(meta.links.len(), 1);
+
+    fill_db(&authority.to_db(), td_entry.delete_link_op.clone());
+    let meta = cascade
+        .dht_get_meta(td_entry.hash.clone().into(), options)
+        .await
+        .unwrap();
+    assert!(meta.links.is_empty());
+    assert!(meta
+        .deleted_links
+        .contains(&td_entry.links[0].create_link_hash));
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn entry_authoring() {
     observability::test_run().ok();
@@ -178,3 +178,103 @@ async fn links_authoring() {
 async fn test_links_can_match_a_partial_tag() {
     todo!()
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn links_paged_and_counted_not_authority() {
+    use ::fixt::prelude::*;
+    use holochain_types::prelude::*;
+
+    observability::test_run().ok();
+
+    // Environments
+    let cache = test_cache_db();
+    let authority = test_dht_db();
+
+    // Data
+    let td = EntryTestData::create();
+    let create_link = match td.create_link_action.action() {
+        Action::CreateLink(create_link) => create_link.clone(),
+        _ => unreachable!(),
+    };
+    let delete_link = match td.delete_link_action.action() {
+        Action::DeleteLink(delete_link) => delete_link.clone(),
+        _ => unreachable!(),
+    };
+    let mut links = Vec::new();
+    for i in 0..5 {
+        let mut create_link = create_link.clone();
+        create_link.timestamp = Timestamp::from_micros(i);
+        let hash = ActionHash::with_data_sync(&Action::CreateLink(create_link.clone()));
+        fill_db(
+            &authority.to_db(),
+            DhtOpHashed::from_content_sync(DhtOp::RegisterAddLink(
+                fixt!(Signature),
+                create_link.clone(),
+            )),
+        );
+        links.push(Link {
+            target: create_link.target_address.clone(),
+            timestamp: create_link.timestamp,
+            tag: create_link.tag.clone(),
+            create_link_hash: hash,
+        });
+    }
+    // Delete the second link.
+    let mut delete_link = delete_link;
+    delete_link.link_add_address = links[1].create_link_hash.clone();
+    fill_db(
+        &authority.to_db(),
+        DhtOpHashed::from_content_sync(DhtOp::RegisterRemoveLink(fixt!(Signature), delete_link)),
+    );
+    links.remove(1);
+
+    // Network
+    let network = PassThroughNetwork::authority_for_nothing(vec![authority.to_db().clone().into()]);
+
+    // Cascade
+    let mut cascade = Cascade::empty().with_network(network, cache.to_db());
+
+    let key = td.link_key_tag.clone();
+    let page = cascade
+        .dht_get_links(
+            WireLinkKey {
+                limit: Some(2),
+                ..key.clone()
+            },
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(page, links[..2]);
+
+    let page = cascade
+        .dht_get_links(
+            WireLinkKey {
+                limit: Some(2),
+                cursor: Some(LinkCursor::from(&links[1])),
+                ..key.clone()
+            },
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(page, links[2..]);
+
+    let count = cascade
+        .dht_count_links(key.clone(), Default::default())
+        .await
+        .unwrap();
+    assert_eq!(count, 4);
+
+    let count = cascade
+        .dht_count_links(
+            WireLinkKey {
+                after: Some(Timestamp::from_micros(3)),
+                ..key
+            },
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(count, 2);
+}
@@ -1,9 +1,11 @@
 use holo_hash::*;
+use holochain_state::workflow_queues::WorkflowQueuesDump;
 use holochain_types::prelude::*;
 use holochain_zome_types::cell::CellId;
 use kitsune_p2p::agent_store::AgentInfoSigned;
 
-use crate::{FullStateDump, InstalledAppInfo};
+use crate::config::AllowedOrigins;
+use crate::{AppAuthenticationToken, FullStateDump, InstalledAppInfo};
 
 /// Represents the available conductor functions to call over an admin interface.
 ///
@@ -47,6 +49,40 @@ pub enum AdminRequest {
     /// [`AdminResponse::CloneCellCreated`]
     CreateCloneCell(Box<CreateCloneCellPayload>),
 
+    /// Archive a clone cell of an app.
+    ///
+    /// The cell is stopped and removed from the app, but its data is kept
+    /// so it can be restored with [`RestoreCloneCell`]. An archived clone
+    /// still counts towards the clone limit of its role until it is deleted
+    /// with [`DeleteArchivedCloneCells`].
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::CloneCellArchived`]
+    ///
+    /// [`RestoreCloneCell`]: AdminRequest::RestoreCloneCell
+    /// [`DeleteArchivedCloneCells`]: AdminRequest::DeleteArchivedCloneCells
+    ArchiveCloneCell(Box<ArchiveCloneCellPayload>),
+
+    /// Restore an archived clone cell to its app.
+    ///
+    /// The cell is started again if the app is running.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::CloneCellRestored`]
+    RestoreCloneCell(Box<ArchiveCloneCellPayload>),
+
+    /// Permanently delete all archived clone cells of an app role.
+    ///
+    /// The data of the deleted cells is removed and they no longer
+    /// count towards the clone limit of the role.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::ArchivedCloneCellsDeleted`]
+    DeleteArchivedCloneCells(Box<DeleteArchivedCloneCellsPayload>),
+
     /// Install an app from a list of DNA paths.
     ///
     /// Triggers genesis to be run on all cells and to be stored.
@@ -193,10 +229,22 @@ pub enum AdminRequest {
     /// a free port is chosen by the conductor.
     /// The response will contain the port chosen by the conductor if `None` was passed.
     ///
+    /// If `require_authentication` is set, every connection to the interface must
+    /// first send an [`AppRequest::Authenticate`] with a token issued by
+    /// [`AdminRequest::IssueAppAuthenticationToken`], and can then only access
+    /// the app the token was issued for.
+    ///
     /// [`AppRequest`]: super::AppRequest
+    /// [`AppRequest::Authenticate`]: super::AppRequest::Authenticate
     AttachAppInterface {
         /// Optional port number
         port: Option<u16>,
+        /// The origins which are allowed to connect to the interface
+        #[serde(default)]
+        allowed_origins: AllowedOrigins,
+        /// Whether connections must authenticate with an app authentication token
+        #[serde(default)]
+        require_authentication: bool,
     },
 
     /// List all the app interfaces currently attached with [`AttachAppInterface`].
@@ -254,6 +302,95 @@ pub enum AdminRequest {
         dna_hash: Option<DnaHash>,
     },
 
+    /// Dump the validation receipts that the cell specified by argument `cell_id`
+    /// has received for its ops, grouped by op.
+    ///
+    /// This shows how many validators are holding each op, which helps to
+    /// diagnose data that is not being replicated to enough peers.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::ValidationReceiptsDumped`]
+    DumpValidationReceipts {
+        /// The cell ID for which to dump the validation receipts
+        cell_id: Box<CellId>,
+        /// If set, only the ops produced by this action are dumped.
+        /// Otherwise the ops of every action authored by the cell's agent are dumped.
+        action_hash: Option<ActionHash>,
+    },
+
+    /// Dump the workflow queues of the cell specified by argument `cell_id`.
+    ///
+    /// This shows how many ops are waiting for sys validation, app validation,
+    /// integration and publishing, how long the oldest of them has waited, and
+    /// which ops are stuck waiting on dependencies the cell doesn't hold.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::WorkflowQueuesDumped`]
+    DumpWorkflowQueues {
+        /// The cell ID for which to dump the workflow queues
+        cell_id: Box<CellId>,
+    },
+
+    /// Put the ops specified by argument `op_hashes` back into the sys
+    /// validation queue of the cell specified by argument `cell_id`.
+    ///
+    /// This is for ops which are stuck waiting on dependencies, or which
+    /// were abandoned, after the cause has been fixed. Ops which are already
+    /// integrated are only re-queued if they were abandoned, and integrated
+    /// agent activity is never re-queued.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::OpsRequeued`]
+    RequeueOpsForValidation {
+        /// The cell ID whose ops should be re-queued
+        cell_id: Box<CellId>,
+        /// The hashes of the ops to re-queue
+        op_hashes: Vec<DhtOpHash>,
+    },
+
+    /// Put every abandoned op of the cell specified by argument `cell_id`
+    /// back into the sys validation queue.
+    ///
+    /// Abandoned agent activity is not re-queued.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::OpsRequeued`]
+    RequeueAbandonedOps {
+        /// The cell ID whose abandoned ops should be re-queued
+        cell_id: Box<CellId>,
+    },
+
+    /// List the countersigning sessions that the chains of the cells
+    /// on this conductor are locked for.
+    ///
+    /// Sessions whose lock has expired are listed until the chain is
+    /// unlocked, so sessions interrupted by a restart can be found.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::CountersigningSessionsListed`]
+    ListCountersigningSessions,
+
+    /// Abandon the countersigning session that the chain of the cell
+    /// specified by argument `cell_id` is locked for, and unlock the chain.
+    ///
+    /// The countersigned entry, if it was committed, is removed from the
+    /// chain along with its ops, which were never published. If the session
+    /// has not expired the other signers may still complete it, which would
+    /// fork the chain, so this should only be used for stuck sessions.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::CountersigningSessionAbandoned`]
+    AbandonCountersigningSession {
+        /// The cell ID whose session to abandon
+        cell_id: Box<CellId>,
+    },
+
     /// Add a list of agents to this conductor's peer store.
     ///
     /// This is a way of shortcutting peer discovery and is useful for testing.
@@ -333,6 +470,120 @@ pub enum AdminRequest {
         /// The records to be inserted into the source chain.
         records: Vec<Record>,
     },
+
+    /// Export the full source chain of a cell as an archive signed by the
+    /// cell's agent, for backups or to move the chain to another conductor.
+    ///
+    /// The archive includes private entries and capability grants,
+    /// so it should be stored securely.
+    /// It does not include the agent's private key.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::SourceChainExported`]
+    ExportSourceChain {
+        /// The cell whose chain is exported.
+        cell_id: Box<CellId>,
+    },
+
+    /// Import a source chain exported with [`AdminRequest::ExportSourceChain`].
+    ///
+    /// The archive's signature, version and chain integrity are checked
+    /// before anything is written. The [`DnaFile`] must already be installed
+    /// on this conductor and the cell must not have a source chain yet,
+    /// so this should be done before the app is installed or enabled.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::SourceChainImported`]
+    ImportSourceChain {
+        /// The archive to import.
+        archive: Box<SignedSourceChainArchive>,
+        /// If this is `true`, then the records will also be validated
+        /// by the DNA before insertion.
+        validate: bool,
+    },
+
+    /// Replace the key used to encrypt this conductor's databases
+    /// and re-encrypt every database with the new key.
+    ///
+    /// Only available when the conductor was configured with
+    /// `db_encryption`. Databases stay usable while they are rekeyed,
+    /// but writes to them wait until the rotation is complete.
+    /// Databases for a DNA that isn't in use yet can't be created
+    /// until the rotation is complete.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::DatabaseKeyRotated`]
+    RotateDatabaseKey,
+
+    /// Issue a token which an app interface connection can authenticate with,
+    /// giving it access to a single app.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::AppAuthenticationTokenIssued`]
+    IssueAppAuthenticationToken(IssueAppAuthenticationTokenPayload),
+
+    /// Authenticate this connection with the token of the admin interface.
+    ///
+    /// Only needed for admin interfaces which are configured with `auth`,
+    /// in which case every other request is rejected until the connection
+    /// has authenticated, and again once its session has expired.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::Authenticated`]
+    Authenticate {
+        /// The token from the admin interface config
+        token: String,
+    },
+}
+
+/// The arguments to [`AdminRequest::IssueAppAuthenticationToken`].
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct IssueAppAuthenticationTokenPayload {
+    /// The app which connections authenticated with the token may access
+    pub installed_app_id: InstalledAppId,
+    /// How many seconds the token can be used for.
+    /// If set to 0, the token never expires. [default = 30]
+    #[serde(default = "default_app_token_expiry_seconds")]
+    pub expiry_seconds: u64,
+    /// Whether the token is revoked after it has been used once. [default = true]
+    #[serde(default = "default_app_token_single_use")]
+    pub single_use: bool,
+}
+
+fn default_app_token_expiry_seconds() -> u64 {
+    30
+}
+
+fn default_app_token_single_use() -> bool {
+    true
+}
+
+impl IssueAppAuthenticationTokenPayload {
+    /// A single use token which expires after the default 30 seconds
+    pub fn for_app(installed_app_id: InstalledAppId) -> Self {
+        Self {
+            installed_app_id,
+            expiry_seconds: default_app_token_expiry_seconds(),
+            single_use: default_app_token_single_use(),
+        }
+    }
+}
+
+/// A newly issued app authentication token,
+/// returned in [`AdminResponse::AppAuthenticationTokenIssued`].
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct AppAuthenticationTokenIssued {
+    /// The token to send with [`AppRequest::Authenticate`]
+    ///
+    /// [`AppRequest::Authenticate`]: super::AppRequest::Authenticate
+    pub token: AppAuthenticationToken,
+    /// When the token expires, if ever
+    pub expires_at: Option<Timestamp>,
 }
 
 /// Represents the possible responses to an [`AdminRequest`]
@@ -378,6 +629,21 @@ pub enum AdminResponse {
     /// The response contains the [`CellId`] of the newly created clone.
     CloneCellCreated(CellId),
 
+    /// The successful response to an [`AdminRequest::ArchiveCloneCell`].
+    ///
+    /// It means the clone cell was archived successfully.
+    CloneCellArchived,
+
+    /// The successful response to an [`AdminRequest::RestoreCloneCell`].
+    ///
+    /// The response contains the [`CellId`] of the restored clone.
+    CloneCellRestored(CellId),
+
+    /// The successful response to an [`AdminRequest::DeleteArchivedCloneCells`].
+    ///
+    /// It means the archived clone cells of the role were deleted successfully.
+    ArchivedCloneCellsDeleted,
+
     /// The successful response to an [`AdminRequest::AddAdminInterfaces`].
     ///
     /// It means the `AdminInterface`s have successfully been added.
@@ -474,6 +740,32 @@ pub enum AdminResponse {
     /// The string is a JSON blob of the metrics results.
     NetworkMetricsDumped(String),
 
+    /// The successful result of a call to [`AdminRequest::DumpValidationReceipts`].
+    ///
+    /// There is one [`ValidationReceiptSet`] per op, listing the receipts
+    /// received for it so far.
+    ValidationReceiptsDumped(Vec<ValidationReceiptSet>),
+
+    /// The successful result of a call to [`AdminRequest::DumpWorkflowQueues`].
+    WorkflowQueuesDumped(WorkflowQueuesDump),
+
+    /// The successful result of a call to [`AdminRequest::RequeueOpsForValidation`]
+    /// or [`AdminRequest::RequeueAbandonedOps`].
+    ///
+    /// Contains the hashes of the ops which were re-queued.
+    OpsRequeued(Vec<DhtOpHash>),
+
+    /// The successful result of a call to [`AdminRequest::ListCountersigningSessions`].
+    ///
+    /// There is one [`CountersigningSessionInfo`] per cell with a locked chain.
+    CountersigningSessionsListed(Vec<CountersigningSessionInfo>),
+
+    /// The successful result of a call to [`AdminRequest::AbandonCountersigningSession`].
+    ///
+    /// Contains the session that was abandoned, or `None` if the
+    /// cell's chain was not locked.
+    CountersigningSessionAbandoned(Option<CountersigningSessionInfo>),
+
     /// The successful response to an [`AdminRequest::AddAgentInfo`].
     ///
     /// This means the agent info was successfully added to the peer store.
@@ -486,6 +778,23 @@ pub enum AdminResponse {
 
     /// The successful response to an [`AdminRequest::AddRecords`].
     RecordsAdded,
+
+    /// The successful response to an [`AdminRequest::ExportSourceChain`].
+    SourceChainExported(Box<SignedSourceChainArchive>),
+
+    /// The successful response to an [`AdminRequest::ImportSourceChain`].
+    SourceChainImported,
+
+    /// The successful response to an [`AdminRequest::RotateDatabaseKey`].
+    ///
+    /// Every database is now encrypted with the new key.
+    DatabaseKeyRotated,
+
+    /// The successful response to an [`AdminRequest::IssueAppAuthenticationToken`].
+    AppAuthenticationTokenIssued(AppAuthenticationTokenIssued),
+
+    /// The successful response to an [`AdminRequest::Authenticate`].
+    Authenticated,
 }
 
 /// Error type that goes over the websocket wire.
@@ -511,6 +820,10 @@ pub enum ExternalApiWireError {
     ZomeCallUnauthorized(String),
     /// A countersigning session has failed.
     CountersigningSessionError(String),
+    /// The connection has not authenticated, or failed to authenticate.
+    Unauthenticated(String),
+    /// The authenticated connection is not allowed to make this request.
+    Unauthorized(String),
 }
 
 impl ExternalApiWireError {
@@ -25,8 +25,16 @@ pub enum AppRequest {
         /// The app ID for which to get information
         installed_app_id: InstalledAppId,
     },
-    /// Is currently unimplemented and will return
-    /// an [`AppResponse::Unimplemented`].
+    /// Sign, encrypt or decrypt data with the agent keys of an app.
+    /// See [`CryptoRequest`].
+    ///
+    /// The connection must have authenticated as the app with
+    /// [`AppRequest::Authenticate`], even if the interface doesn't
+    /// require authentication.
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::Crypto`]
     Crypto(Box<CryptoRequest>),
     /// Call a zome function. See [`ZomeCall`]
     /// to understand the data that must be provided.
@@ -39,8 +47,32 @@ pub enum AppRequest {
     #[deprecated = "use ZomeCall"]
     ZomeCallInvocation(Box<ZomeCall>),
 
-    /// Is currently unimplemented and will return
-    /// an [`AppResponse::Unimplemented`].
+    /// Authenticate this connection with a token issued by
+    /// [`AdminRequest::IssueAppAuthenticationToken`].
+    ///
+    /// On app interfaces which require authentication, this must be the first
+    /// request on a connection, and the connection can then only access
+    /// the app the token was issued for.
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::Authenticated`]
+    ///
+    /// [`AdminRequest::IssueAppAuthenticationToken`]: crate::AdminRequest::IssueAppAuthenticationToken
+    Authenticate {
+        /// The token to authenticate with
+        token: AppAuthenticationToken,
+    },
+
+    /// Update the signal subscription of this connection for an app.
+    ///
+    /// Subscriptions are scoped to a single websocket connection and apply
+    /// to the cells the app has at the time of subscribing.
+    /// Signals from cells of apps without a subscription are always sent.
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::SignalSubscriptionUpdated`]
     SignalSubscription(SignalSubscription),
 }
 
@@ -72,8 +104,24 @@ pub enum AppResponse {
 
     #[deprecated = "use ZomeCall"]
     ZomeCallInvocation(Box<ExternIO>),
+
+    /// The successful response to an [`AppRequest::SignalSubscription`].
+    SignalSubscriptionUpdated,
+
+    /// The successful response to an [`AppRequest::Crypto`].
+    Crypto(Box<CryptoResponse>),
+
+    /// The successful response to an [`AppRequest::Authenticate`].
+    Authenticated {
+        /// The app which this connection may now access
+        installed_app_id: InstalledAppId,
+    },
 }
 
+/// A token which an app interface connection can authenticate with.
+/// See [`AppRequest::Authenticate`].
+pub type AppAuthenticationToken = Vec<u8>;
+
 /// The data provided over an app interface in order to make a zome call
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct ZomeCall {
@@ -99,13 +147,85 @@ pub struct ZomeCall {
     pub provenance: AgentPubKey,
 }
 
-#[allow(missing_docs)]
+/// Cryptographic operations which an app can perform with its agent keys
+/// over an app interface, without going through a zome.
+///
+/// Every request is bound to an installed app and will only use the agent key
+/// of one of that app's cells. Encryption uses the x25519 key which the
+/// keystore derived from the same seed as the agent key.
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case", tag = "type", content = "data")]
 pub enum CryptoRequest {
-    Sign(String),
-    Decrypt(String),
-    Encrypt(String),
+    /// Sign data with the agent key of one of the app's cells.
+    ///
+    /// # Returns
+    ///
+    /// [`CryptoResponse::Signature`]
+    Sign {
+        /// The app whose agent key signs the data
+        installed_app_id: InstalledAppId,
+        /// The agent key to sign with and the data to sign
+        payload: Sign,
+    },
+    /// Encrypt data from an agent of the app to an x25519 recipient.
+    ///
+    /// # Returns
+    ///
+    /// [`CryptoResponse::Encrypted`]
+    Encrypt {
+        /// The app whose agent encrypts the data
+        installed_app_id: InstalledAppId,
+        /// The agent of the app which is the sender
+        agent_key: AgentPubKey,
+        /// The x25519 key of the recipient
+        recipient: X25519PubKey,
+        /// The data to encrypt
+        data: XSalsa20Poly1305Data,
+    },
+    /// Decrypt data sent by an x25519 sender to an agent of the app.
+    ///
+    /// # Returns
+    ///
+    /// [`CryptoResponse::Decrypted`]
+    Decrypt {
+        /// The app whose agent decrypts the data
+        installed_app_id: InstalledAppId,
+        /// The agent of the app which is the recipient
+        agent_key: AgentPubKey,
+        /// The x25519 key of the sender
+        sender: X25519PubKey,
+        /// The data to decrypt
+        encrypted_data: XSalsa20Poly1305EncryptedData,
+    },
+}
+
+impl CryptoRequest {
+    /// The app this request is bound to
+    pub fn installed_app_id(&self) -> &InstalledAppId {
+        match self {
+            CryptoRequest::Sign {
+                installed_app_id, ..
+            }
+            | CryptoRequest::Encrypt {
+                installed_app_id, ..
+            }
+            | CryptoRequest::Decrypt {
+                installed_app_id, ..
+            } => installed_app_id,
+        }
+    }
+}
+
+/// The results of a [`CryptoRequest`], returned in [`AppResponse::Crypto`].
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type", content = "data")]
+pub enum CryptoResponse {
+    /// The signature of the data
+    Signature(Signature),
+    /// The encrypted data along with its nonce
+    Encrypted(XSalsa20Poly1305EncryptedData),
+    /// The decrypted data
+    Decrypted(XSalsa20Poly1305Data),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
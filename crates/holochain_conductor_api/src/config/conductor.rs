@@ -7,20 +7,30 @@ use serde::Deserialize;
 use serde::Serialize;
 
 mod admin_interface_config;
+mod arc_gc_config;
+mod cache_config;
+mod db_encryption_config;
 mod dpki_config;
 #[allow(missing_docs)]
 mod error;
 mod keystore_config;
+mod metrics_config;
 pub mod paths;
+mod zome_call_config;
 //mod logger_config;
 //mod signal_config;
 pub use paths::DatabaseRootPath;
 
 pub use super::*;
+pub use arc_gc_config::ArcGcConfig;
+pub use cache_config::CacheConfig;
+pub use db_encryption_config::DbEncryptionConfig;
 pub use dpki_config::DpkiConfig;
 //pub use logger_config::LoggerConfig;
 pub use error::*;
 pub use keystore_config::KeystoreConfig;
+pub use metrics_config::MetricsConfig;
+pub use zome_call_config::ZomeCallConfig;
 //pub use signal_config::SignalConfig;
 use std::path::Path;
 
@@ -56,6 +66,29 @@ pub struct ConductorConfig {
     ///
     /// [sqlite documentation]: https://www.sqlite.org/pragma.html#pragma_synchronous
     pub db_sync_strategy: DbSyncStrategy,
+
+    /// Optional at-rest encryption of all databases.
+    ///
+    /// Requires holochain to be built with the `db-encryption` feature.
+    /// Unencrypted databases from a previous run are encrypted on startup.
+    #[serde(default)]
+    pub db_encryption: Option<DbEncryptionConfig>,
+
+    /// Limits on how long zome calls may run.
+    #[serde(default)]
+    pub zome_call: ZomeCallConfig,
+
+    /// Limits on the cache databases, which hold data fetched from the network.
+    #[serde(default)]
+    pub cache: CacheConfig,
+
+    /// Deletion of DHT data outside the storage arcs of local agents.
+    #[serde(default)]
+    pub arc_gc: ArcGcConfig,
+
+    /// Optional HTTP endpoint serving metrics in the Prometheus text format.
+    #[serde(default)]
+    pub metrics: Option<MetricsConfig>,
     //
     //
     // Which signals to emit
@@ -127,6 +160,11 @@ pub mod tests {
                 keystore: KeystoreConfig::DangerTestKeystoreLegacyDeprecated,
                 admin_interfaces: None,
                 db_sync_strategy: DbSyncStrategy::default(),
+                db_encryption: None,
+                zome_call: ZomeCallConfig::default(),
+                cache: CacheConfig::default(),
+                arc_gc: ArcGcConfig::default(),
+                metrics: None,
             }
         );
     }
@@ -177,6 +215,17 @@ pub mod tests {
       network_type: quic_bootstrap
 
     db_sync_strategy: Fast
+
+    cache:
+      max_bytes: 1000000
+      max_age_secs: 3600
+
+    arc_gc:
+      enabled: true
+      grace_period_secs: 7200
+
+    metrics:
+      port: 9100
     "#;
         let result: ConductorConfigResult<ConductorConfig> = config_from_yaml(yaml);
         use holochain_p2p::kitsune_p2p::*;
@@ -216,11 +265,26 @@ pub mod tests {
                     keystore_path: None,
                     danger_passphrase_insecure_from_config: "test-passphrase".to_string(),
                 },
-                admin_interfaces: Some(vec![AdminInterfaceConfig {
-                    driver: InterfaceDriver::Websocket { port: 1234 }
-                }]),
+                admin_interfaces: Some(vec![AdminInterfaceConfig::websocket(1234)]),
                 network: Some(network_config),
                 db_sync_strategy: DbSyncStrategy::Fast,
+                db_encryption: None,
+                zome_call: ZomeCallConfig::default(),
+                cache: CacheConfig {
+                    max_bytes: Some(1_000_000),
+                    max_age_secs: Some(3600),
+                    eviction_interval_secs: 60,
+                },
+                arc_gc: ArcGcConfig {
+                    enabled: true,
+                    interval_secs: 600,
+                    grace_period_secs: 7200,
+                    min_redundancy: 5,
+                },
+                metrics: Some(MetricsConfig {
+                    bind_address: std::net::Ipv4Addr::LOCALHOST.into(),
+                    port: 9100,
+                }),
             }
         );
     }
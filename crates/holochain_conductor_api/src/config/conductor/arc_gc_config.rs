@@ -0,0 +1,79 @@
+use serde::Deserialize;
+use serde::Serialize;
+use std::time::Duration;
+
+/// The default for [`ArcGcConfig::interval_secs`].
+pub const DEFAULT_ARC_GC_INTERVAL_SECS: u64 = 10 * 60;
+
+/// The default for [`ArcGcConfig::grace_period_secs`].
+pub const DEFAULT_ARC_GC_GRACE_PERIOD_SECS: u64 = 60 * 60;
+
+/// The default for [`ArcGcConfig::min_redundancy`].
+pub const DEFAULT_ARC_GC_MIN_REDUNDANCY: u32 = 5;
+
+/// Garbage collection of the DHT data which falls outside the storage arcs
+/// of the agents on this conductor.
+///
+/// When an agent's arc shrinks the ops outside of it are still held in the
+/// DHT database. With this enabled, integrated ops which no local agent is
+/// an authority for are deleted once they have been outside every local arc
+/// for the grace period, and only if enough other peers are holding that part
+/// of the DHT. Records are moved to the cache rather than being dropped.
+/// The activity of an author is only deleted once all of it can be.
+/// Ops authored on this conductor are always kept.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct ArcGcConfig {
+    /// Whether DHT data outside the storage arcs is deleted at all.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// How often the DHT databases are checked for data to delete, in seconds.
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+
+    /// How long data must have been outside of every local storage arc
+    /// before it is deleted, in seconds.
+    #[serde(default = "default_grace_period_secs")]
+    pub grace_period_secs: u64,
+
+    /// The number of peers which must be covering the part of the DHT
+    /// being deleted, as estimated from the peers this conductor knows of.
+    #[serde(default = "default_min_redundancy")]
+    pub min_redundancy: u32,
+}
+
+fn default_interval_secs() -> u64 {
+    DEFAULT_ARC_GC_INTERVAL_SECS
+}
+
+fn default_grace_period_secs() -> u64 {
+    DEFAULT_ARC_GC_GRACE_PERIOD_SECS
+}
+
+fn default_min_redundancy() -> u32 {
+    DEFAULT_ARC_GC_MIN_REDUNDANCY
+}
+
+impl Default for ArcGcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: DEFAULT_ARC_GC_INTERVAL_SECS,
+            grace_period_secs: DEFAULT_ARC_GC_GRACE_PERIOD_SECS,
+            min_redundancy: DEFAULT_ARC_GC_MIN_REDUNDANCY,
+        }
+    }
+}
+
+impl ArcGcConfig {
+    /// How often the DHT databases are checked for data to delete.
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs)
+    }
+
+    /// How long data must have been outside of every local storage arc
+    /// before it is deleted.
+    pub fn grace_period(&self) -> Duration {
+        Duration::from_secs(self.grace_period_secs)
+    }
+}
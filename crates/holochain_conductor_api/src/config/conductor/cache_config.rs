@@ -0,0 +1,88 @@
+use serde::Deserialize;
+use serde::Serialize;
+use std::time::Duration;
+
+/// The default for [`CacheConfig::eviction_interval_secs`].
+pub const DEFAULT_CACHE_EVICTION_INTERVAL_SECS: u64 = 60;
+
+/// The shortest interval between checks of the caches, in seconds.
+/// Shorter intervals in the config are raised to this.
+pub const MIN_CACHE_EVICTION_INTERVAL_SECS: u64 = 1;
+
+/// Limits on the cache databases, which hold data fetched from the network.
+///
+/// There is one cache database per DNA and each is held within these limits
+/// by evicting the data which was least recently fetched.
+/// Evicted data is fetched from the network again if it is needed.
+/// If no limit is set the caches are never evicted from.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct CacheConfig {
+    /// The most data each cache database may hold, in bytes.
+    ///
+    /// This counts the actions and entries in the cache,
+    /// which are most of the size of the database file.
+    /// The file itself doesn't shrink but the space of
+    /// evicted data is reused.
+    #[serde(default)]
+    pub max_bytes: Option<u64>,
+
+    /// How long data is kept after it was last fetched, in seconds.
+    #[serde(default)]
+    pub max_age_secs: Option<u64>,
+
+    /// How often the cache databases are checked against the limits, in seconds.
+    /// This is at least [`MIN_CACHE_EVICTION_INTERVAL_SECS`].
+    #[serde(default = "default_eviction_interval_secs")]
+    pub eviction_interval_secs: u64,
+}
+
+fn default_eviction_interval_secs() -> u64 {
+    DEFAULT_CACHE_EVICTION_INTERVAL_SECS
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            max_bytes: None,
+            max_age_secs: None,
+            eviction_interval_secs: DEFAULT_CACHE_EVICTION_INTERVAL_SECS,
+        }
+    }
+}
+
+impl CacheConfig {
+    /// True if there are any limits on the caches.
+    pub fn is_bounded(&self) -> bool {
+        self.max_bytes.is_some() || self.max_age_secs.is_some()
+    }
+
+    /// How long data is kept after it was last fetched, if there is a limit.
+    pub fn max_age(&self) -> Option<Duration> {
+        self.max_age_secs.map(Duration::from_secs)
+    }
+
+    /// How often the cache databases are checked against the limits.
+    pub fn eviction_interval(&self) -> Duration {
+        Duration::from_secs(
+            self.eviction_interval_secs
+                .max(MIN_CACHE_EVICTION_INTERVAL_SECS),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eviction_interval_has_a_minimum() {
+        let config = CacheConfig {
+            eviction_interval_secs: 0,
+            ..Default::default()
+        };
+        assert_eq!(
+            config.eviction_interval(),
+            Duration::from_secs(MIN_CACHE_EVICTION_INTERVAL_SECS)
+        );
+    }
+}
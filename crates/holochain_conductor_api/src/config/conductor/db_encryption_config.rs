@@ -0,0 +1,19 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Define where the key used for at-rest encryption of the
+/// conductor's databases comes from.
+///
+/// Either way the derived key also depends on a random salt stored
+/// alongside the databases, so the key can be rotated without changing
+/// the keystore or the passphrase.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DbEncryptionConfig {
+    /// Derive the key from a secret held by the keystore.
+    /// Only supported by the new lair keystore api.
+    Keystore,
+
+    /// Derive the key from the passphrase the conductor was started with.
+    Passphrase,
+}
@@ -0,0 +1,29 @@
+use serde::Deserialize;
+use serde::Serialize;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::SocketAddr;
+
+/// The HTTP endpoint which serves conductor metrics
+/// in the Prometheus text format at `/metrics`.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct MetricsConfig {
+    /// The address to listen on. Defaults to the loopback address,
+    /// so the metrics are only reachable from this machine.
+    #[serde(default = "default_bind_address")]
+    pub bind_address: IpAddr,
+
+    /// The port to listen on.
+    pub port: u16,
+}
+
+fn default_bind_address() -> IpAddr {
+    IpAddr::V4(Ipv4Addr::LOCALHOST)
+}
+
+impl MetricsConfig {
+    /// The socket address to listen on.
+    pub fn socket_addr(&self) -> SocketAddr {
+        SocketAddr::new(self.bind_address, self.port)
+    }
+}
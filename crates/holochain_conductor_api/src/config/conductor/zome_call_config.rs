@@ -0,0 +1,50 @@
+use serde::Deserialize;
+use serde::Serialize;
+use std::time::Duration;
+
+/// The default for [`ZomeCallConfig::max_sleep_ms`].
+pub const DEFAULT_MAX_SLEEP_MS: u64 = 10_000;
+
+/// Limits applied to every zome call made on this conductor.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct ZomeCallConfig {
+    /// How long a zome call may run before the caller gets an error, in milliseconds.
+    /// If omitted zome calls never time out.
+    ///
+    /// Note that a timed out zome function can't be interrupted,
+    /// so it keeps running in the background until it finishes.
+    /// Nothing it writes to the source chain is committed.
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+
+    /// The longest a zome call may suspend itself with a single call to the
+    /// `sleep` host function, in milliseconds. Longer sleeps fail
+    /// without sleeping at all.
+    #[serde(default = "default_max_sleep_ms")]
+    pub max_sleep_ms: u64,
+}
+
+fn default_max_sleep_ms() -> u64 {
+    DEFAULT_MAX_SLEEP_MS
+}
+
+impl Default for ZomeCallConfig {
+    fn default() -> Self {
+        Self {
+            timeout_ms: None,
+            max_sleep_ms: DEFAULT_MAX_SLEEP_MS,
+        }
+    }
+}
+
+impl ZomeCallConfig {
+    /// The zome call timeout, if any.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout_ms.map(Duration::from_millis)
+    }
+
+    /// The longest a single `sleep` may last.
+    pub fn max_sleep(&self) -> Duration {
+        Duration::from_millis(self.max_sleep_ms)
+    }
+}
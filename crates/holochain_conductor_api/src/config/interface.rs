@@ -1,5 +1,6 @@
 use serde::Deserialize;
 use serde::Serialize;
+use std::collections::HashSet;
 
 /// Information neeeded to spawn an admin interface
 #[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
@@ -7,9 +8,65 @@ pub struct AdminInterfaceConfig {
     /// By what means the interface will be exposed.
     /// Currently the only option is a local websocket running on a configurable port.
     pub driver: InterfaceDriver,
-    // How long will this interface be accessible between authentications?
-    // TODO: implement once we have authentication
-    // _session_duration_seconds: Option<u32>,
+    /// The origins which are allowed to connect to this interface.
+    #[serde(default)]
+    pub allowed_origins: AllowedOrigins,
+    /// Token-based authentication for this interface.
+    /// If not set, any client which can connect may use the interface.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<AdminAuthConfig>,
+}
+
+impl AdminInterfaceConfig {
+    /// Config for a websocket admin interface on the given port
+    /// which accepts any origin and doesn't require authentication.
+    pub fn websocket(port: u16) -> Self {
+        Self {
+            driver: InterfaceDriver::Websocket { port },
+            allowed_origins: AllowedOrigins::Any,
+            auth: None,
+        }
+    }
+}
+
+/// Authentication settings for an admin interface.
+///
+/// Clients must send an `AdminRequest::Authenticate` with the token
+/// before any other request is accepted on a connection.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub struct AdminAuthConfig {
+    /// The token which clients must authenticate with.
+    pub token: String,
+    /// How long will this interface be accessible between authentications?
+    /// If not set, a connection stays authenticated until it is closed.
+    #[serde(default)]
+    pub session_duration_seconds: Option<u32>,
+}
+
+/// The origins which may connect to an interface, checked against the
+/// `Origin` header of the websocket handshake.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AllowedOrigins {
+    /// Connections from any origin, or with no origin, are accepted.
+    Any,
+    /// Only connections from one of these origins are accepted.
+    Origins(HashSet<String>),
+}
+
+impl Default for AllowedOrigins {
+    fn default() -> Self {
+        Self::Any
+    }
+}
+
+impl From<AllowedOrigins> for Option<HashSet<String>> {
+    fn from(allowed_origins: AllowedOrigins) -> Self {
+        match allowed_origins {
+            AllowedOrigins::Any => None,
+            AllowedOrigins::Origins(origins) => Some(origins),
+        }
+    }
 }
 
 /// Configuration for interfaces, specifying the means by which an interface
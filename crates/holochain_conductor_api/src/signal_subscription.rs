@@ -1,6 +1,8 @@
 use holochain_serialized_bytes::prelude::*;
 use holochain_types::app::InstalledAppId;
 use holochain_zome_types::cell::CellId;
+use holochain_zome_types::signal::AppSignal;
+use holochain_zome_types::zome::ZomeName;
 use std::collections::HashMap;
 
 /// Declares updated Signal subscription settings for an App.
@@ -13,6 +15,31 @@ pub struct SignalSubscription {
     filters: SignalFilterSet,
 }
 
+impl SignalSubscription {
+    /// Constructor
+    pub fn new(installed_app_id: InstalledAppId, filters: SignalFilterSet) -> Self {
+        Self {
+            installed_app_id,
+            filters,
+        }
+    }
+
+    /// The app for which to manage subscription
+    pub fn installed_app_id(&self) -> &InstalledAppId {
+        &self.installed_app_id
+    }
+
+    /// Fine-grained per-cell filters
+    pub fn filters(&self) -> &SignalFilterSet {
+        &self.filters
+    }
+
+    /// Break the subscription into its parts
+    pub fn into_inner(self) -> (InstalledAppId, SignalFilterSet) {
+        (self.installed_app_id, self.filters)
+    }
+}
+
 /// Associate a SignalFilter with each Cell in an App.
 /// The filtering can be interpreted as inclusive or exclusive,
 /// depending on the use case.
@@ -46,22 +73,172 @@ impl SignalFilterSet {
     pub fn block_all() -> Self {
         SignalFilterSet::Include(HashMap::new())
     }
+
+    /// Check whether an app signal emitted by the given zome
+    /// in the given Cell passes this filter set.
+    pub fn allows(&self, cell_id: &CellId, zome_name: &ZomeName, signal: &AppSignal) -> bool {
+        match self {
+            SignalFilterSet::Include(filters) => filters
+                .get(cell_id)
+                .map_or(false, |filter| filter.matches(zome_name, signal)),
+            SignalFilterSet::Exclude(filters) => !filters
+                .get(cell_id)
+                .map_or(false, |filter| filter.matches(zome_name, signal)),
+        }
+    }
 }
 
 /// Specifies fine-grained filter controls for the signals
-#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
-pub struct SignalFilter;
+///
+/// Each field which is set narrows down the signals matched by this filter.
+/// A filter with no fields set matches every signal from its Cell.
+#[derive(
+    Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes,
+)]
+pub struct SignalFilter {
+    /// Only match signals emitted by this zome
+    pub zome_name: Option<ZomeName>,
+    /// Only match signals whose payload is a map with a `type` field
+    /// set to this value, e.g. an enum serialized with `#[serde(tag = "type")]`
+    pub signal_type: Option<String>,
+}
 
-impl Default for SignalFilter {
-    fn default() -> Self {
-        // The default is no filter
-        Self::empty()
-    }
+/// Used to read the `type` field of a signal payload
+#[derive(Debug, serde::Deserialize)]
+struct SignalTypeTag {
+    #[serde(rename = "type")]
+    signal_type: String,
 }
 
 impl SignalFilter {
     /// A passthrough filter which filters nothing
     pub fn empty() -> Self {
-        SignalFilter
+        Self::default()
+    }
+
+    /// Only match signals emitted by this zome
+    pub fn with_zome_name(mut self, zome_name: ZomeName) -> Self {
+        self.zome_name = Some(zome_name);
+        self
+    }
+
+    /// Only match signals with this `type` tag
+    pub fn with_signal_type(mut self, signal_type: impl Into<String>) -> Self {
+        self.signal_type = Some(signal_type.into());
+        self
+    }
+
+    /// Check whether an app signal emitted by the given zome matches this filter.
+    pub fn matches(&self, zome_name: &ZomeName, signal: &AppSignal) -> bool {
+        if let Some(z) = &self.zome_name {
+            if z != zome_name {
+                return false;
+            }
+        }
+        if let Some(t) = &self.signal_type {
+            return match signal.clone().into_inner().decode::<SignalTypeTag>() {
+                Ok(tag) => &tag.signal_type == t,
+                Err(_) => false,
+            };
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holo_hash::AgentPubKey;
+    use holo_hash::DnaHash;
+    use holochain_zome_types::ExternIO;
+
+    #[derive(serde::Serialize, Debug)]
+    #[serde(tag = "type")]
+    enum TestSignal {
+        Ping,
+        Pong { n: u32 },
+    }
+
+    fn signal(s: TestSignal) -> AppSignal {
+        AppSignal::new(ExternIO::encode(s).unwrap())
+    }
+
+    fn cell_id(n: u8) -> CellId {
+        CellId::new(
+            DnaHash::from_raw_32(vec![n; 32]),
+            AgentPubKey::from_raw_32(vec![n; 32]),
+        )
+    }
+
+    #[test]
+    fn filter_matches_zome_name_and_type() {
+        let foo: ZomeName = "foo".into();
+        let bar: ZomeName = "bar".into();
+        let ping = signal(TestSignal::Ping);
+        let pong = signal(TestSignal::Pong { n: 1 });
+        let untagged = AppSignal::new(ExternIO::encode(42u32).unwrap());
+
+        assert!(SignalFilter::empty().matches(&foo, &ping));
+        assert!(SignalFilter::empty().matches(&foo, &untagged));
+
+        let by_zome = SignalFilter::empty().with_zome_name(foo.clone());
+        assert!(by_zome.matches(&foo, &ping));
+        assert!(!by_zome.matches(&bar, &ping));
+
+        let by_type = SignalFilter::empty().with_signal_type("Pong");
+        assert!(by_type.matches(&foo, &pong));
+        assert!(!by_type.matches(&foo, &ping));
+        assert!(!by_type.matches(&foo, &untagged));
+
+        let both = by_zome.with_signal_type("Ping");
+        assert!(both.matches(&foo, &ping));
+        assert!(!both.matches(&bar, &ping));
+        assert!(!both.matches(&foo, &pong));
+    }
+
+    #[test]
+    fn include_filter_set() {
+        let cell_a = cell_id(1);
+        let cell_b = cell_id(2);
+        let foo: ZomeName = "foo".into();
+        let ping = signal(TestSignal::Ping);
+        let pong = signal(TestSignal::Pong { n: 1 });
+
+        assert!(!SignalFilterSet::block_all().allows(&cell_a, &foo, &ping));
+
+        let set = SignalFilterSet::Include(
+            [(
+                cell_a.clone(),
+                SignalFilter::empty().with_signal_type("Ping"),
+            )]
+            .into_iter()
+            .collect(),
+        );
+        assert!(set.allows(&cell_a, &foo, &ping));
+        assert!(!set.allows(&cell_a, &foo, &pong));
+        assert!(!set.allows(&cell_b, &foo, &ping));
+    }
+
+    #[test]
+    fn exclude_filter_set() {
+        let cell_a = cell_id(1);
+        let cell_b = cell_id(2);
+        let foo: ZomeName = "foo".into();
+        let bar: ZomeName = "bar".into();
+        let ping = signal(TestSignal::Ping);
+
+        assert!(SignalFilterSet::allow_all().allows(&cell_a, &foo, &ping));
+
+        let set = SignalFilterSet::Exclude(
+            [(
+                cell_a.clone(),
+                SignalFilter::empty().with_zome_name(foo.clone()),
+            )]
+            .into_iter()
+            .collect(),
+        );
+        assert!(!set.allows(&cell_a, &foo, &ping));
+        assert!(set.allows(&cell_a, &bar, &ping));
+        assert!(set.allows(&cell_b, &foo, &ping));
     }
 }
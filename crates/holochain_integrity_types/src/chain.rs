@@ -0,0 +1,108 @@
+//! Types for reading a deterministic range of an agent's source chain.
+
+use std::collections::HashSet;
+
+use holo_hash::ActionHash;
+use holo_hash::AgentPubKey;
+use holochain_serialized_bytes::prelude::*;
+
+/// Filter for a range of a source chain.
+///
+/// The chain is walked backwards from the `chain_top` by following each
+/// action's previous action hash, so the result is the same no matter who
+/// returns it and can't include actions from a fork of the chain.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct ChainFilter {
+    /// The hash of the highest action in the range.
+    pub chain_top: ActionHash,
+    /// Where to stop walking the chain.
+    pub filters: ChainFilters,
+}
+
+/// Where to stop walking the chain for a [`ChainFilter`].
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub enum ChainFilters {
+    /// Walk all the way to the genesis action.
+    ToGenesis,
+    /// Take this many actions, including the chain top.
+    Take(u32),
+    /// Walk until one of these actions is reached, including it.
+    Until(HashSet<ActionHash>),
+    /// Stop at whichever of [`ChainFilters::Take`] and [`ChainFilters::Until`]
+    /// is reached first.
+    Both(u32, HashSet<ActionHash>),
+}
+
+impl ChainFilter {
+    /// Walk the chain from this action to genesis.
+    pub fn new(chain_top: ActionHash) -> Self {
+        Self {
+            chain_top,
+            filters: ChainFilters::ToGenesis,
+        }
+    }
+
+    /// Take at most this many actions.
+    pub fn take(mut self, n: u32) -> Self {
+        self.filters = match self.filters {
+            ChainFilters::ToGenesis | ChainFilters::Take(_) => ChainFilters::Take(n),
+            ChainFilters::Until(until) | ChainFilters::Both(_, until) => {
+                ChainFilters::Both(n, until)
+            }
+        };
+        self
+    }
+
+    /// Stop at this action, including it.
+    /// Can be called more than once to stop at the first of several actions.
+    pub fn until(mut self, action_hash: ActionHash) -> Self {
+        self.filters = match self.filters {
+            ChainFilters::ToGenesis => ChainFilters::Until(HashSet::from([action_hash])),
+            ChainFilters::Take(n) => ChainFilters::Both(n, HashSet::from([action_hash])),
+            ChainFilters::Until(mut until) => {
+                until.insert(action_hash);
+                ChainFilters::Until(until)
+            }
+            ChainFilters::Both(n, mut until) => {
+                until.insert(action_hash);
+                ChainFilters::Both(n, until)
+            }
+        };
+        self
+    }
+
+    /// The maximum number of actions to take, if any.
+    pub fn get_take(&self) -> Option<u32> {
+        match &self.filters {
+            ChainFilters::Take(n) | ChainFilters::Both(n, _) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// The actions to stop at, if any.
+    pub fn get_until(&self) -> Option<&HashSet<ActionHash>> {
+        match &self.filters {
+            ChainFilters::Until(until) | ChainFilters::Both(_, until) => Some(until),
+            _ => None,
+        }
+    }
+}
+
+/// Zome input for must_get_agent_activity.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct MustGetAgentActivityInput {
+    /// The author of the chain.
+    pub author: AgentPubKey,
+    /// The range of the chain to get.
+    pub chain_filter: ChainFilter,
+}
+
+impl MustGetAgentActivityInput {
+    /// Constructor.
+    pub fn new(author: AgentPubKey, chain_filter: ChainFilter) -> Self {
+        Self {
+            author,
+            chain_filter,
+        }
+    }
+}
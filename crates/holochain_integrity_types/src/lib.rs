@@ -13,6 +13,7 @@
 #[allow(missing_docs)]
 pub mod action;
 pub mod capability;
+pub mod chain;
 pub mod countersigning;
 pub mod entry;
 #[allow(missing_docs)]
@@ -3,6 +3,7 @@
 pub use crate::action::conversions::*;
 pub use crate::action::*;
 pub use crate::capability::*;
+pub use crate::chain::*;
 pub use crate::countersigning::*;
 pub use crate::entry::*;
 pub use crate::entry_def::*;
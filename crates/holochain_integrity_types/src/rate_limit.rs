@@ -99,3 +99,60 @@ impl From<EntryRateWeight> for RateWeight {
         }
     }
 }
+
+/// A rate limiting bucket declared by an integrity zome.
+///
+/// Every weighed action of an author adds its units to the bucket it is
+/// weighed in, and the bucket drains `drain_amount` units every
+/// `drain_interval_ms`. An action that would fill the bucket beyond its
+/// `capacity` is rejected by system validation.
+#[derive(
+    Debug,
+    Clone,
+    serde::Serialize,
+    serde::Deserialize,
+    PartialEq,
+    Eq,
+    SerializedBytes,
+    Hash,
+    PartialOrd,
+    Ord,
+)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct RateLimit {
+    /// The bucket this limit applies to.
+    pub bucket_id: RateBucketId,
+    /// The most units the bucket can hold.
+    pub capacity: RateBucketCapacity,
+    /// How many units drain from the bucket every interval.
+    pub drain_amount: RateBucketCapacity,
+    /// The length of the drain interval in milliseconds.
+    pub drain_interval_ms: u32,
+}
+
+impl RateLimit {
+    /// The level of a bucket after it has drained for `elapsed_micros`.
+    /// Partial intervals drain proportionally, rounded down.
+    pub fn drain(&self, level: RateBucketCapacity, elapsed_micros: i64) -> RateBucketCapacity {
+        if elapsed_micros <= 0 {
+            return level;
+        }
+        let interval_micros = (self.drain_interval_ms as u128 * 1000).max(1);
+        let drained = elapsed_micros as u128 * self.drain_amount as u128 / interval_micros;
+        level.saturating_sub(drained.min(RateBucketCapacity::MAX as u128) as RateBucketCapacity)
+    }
+}
+
+/// The result of the `rate_limits` callback, declaring the rate limiting
+/// buckets of an integrity zome.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub enum RateLimitsCallbackResult {
+    /// The buckets declared by the zome.
+    Limits(Vec<RateLimit>),
+}
+
+impl From<Vec<RateLimit>> for RateLimitsCallbackResult {
+    fn from(v: Vec<RateLimit>) -> Self {
+        Self::Limits(v)
+    }
+}
@@ -229,6 +229,46 @@ impl MetaLairClient {
         }
     }
 
+    /// Get the x25519 encryption pub key which lair derived from the same
+    /// seed as the given agent (ed25519 signing) pub key.
+    /// The legacy keystore doesn't derive encryption keys from agent keys,
+    /// so this is not supported there.
+    pub fn agent_x25519_pub_key(
+        &self,
+        agent_pub_key: holo_hash::AgentPubKey,
+    ) -> impl Future<Output = LairResult<X25519PubKey>> + 'static + Send {
+        let this = self.clone();
+        async move {
+            match this {
+                Self::Legacy(_) => Err(one_err::OneErr::new(
+                    "The legacy keystore has no encryption keys for agent keys",
+                )),
+                Self::NewLair(client) => {
+                    let agent = agent_pub_key.get_raw_32();
+                    client
+                        .list_entries()
+                        .await?
+                        .into_iter()
+                        .find_map(|entry| match entry {
+                            LairEntryInfo::Seed { seed_info, .. }
+                            | LairEntryInfo::DeepLockedSeed { seed_info, .. }
+                                if &seed_info.ed25519_pub_key.0[..] == agent =>
+                            {
+                                Some(seed_info.x25519_pub_key)
+                            }
+                            _ => None,
+                        })
+                        .ok_or_else(|| {
+                            one_err::OneErr::new(format!(
+                                "No seed found for agent key {}",
+                                agent_pub_key
+                            ))
+                        })
+                }
+            }
+        }
+    }
+
     /// Encrypt an authenticated "box"ed message to a specific recipient.
     pub fn crypto_box_xsalsa(
         &self,
@@ -308,6 +348,57 @@ impl MetaLairClient {
         }
     }
 
+    /// Get a secret which can only be reproduced with access to this keystore,
+    /// for use as the master key of at-rest database encryption.
+    /// It is the hash of a signature over a fixed message, made with a
+    /// seed that is generated the first time this is called.
+    pub fn get_or_create_db_master_key(
+        &self,
+    ) -> impl Future<Output = LairResult<[u8; 32]>> + 'static + Send {
+        let this = self.clone();
+        async move {
+            match this {
+                Self::Legacy(_) => Err("LegacyLairDoesNotSupportDbEncryption".into()),
+                Self::NewLair(client) => {
+                    const DB_SEED: &str = "HolochainDbEncryptionSeed";
+                    const DB_KEY_CONTEXT: &[u8] = b"holochain-db-encryption-master-key";
+                    // The seed is only created if there is no entry with its tag,
+                    // so a failure to read it can't replace the master key.
+                    let exists = client.list_entries().await?.into_iter().any(|entry| {
+                        matches!(
+                            entry,
+                            LairEntryInfo::Seed { tag, .. }
+                            | LairEntryInfo::DeepLockedSeed { tag, .. }
+                            | LairEntryInfo::WkaTlsCert { tag, .. } if &*tag == DB_SEED
+                        )
+                    });
+                    let info = if exists {
+                        match client.get_entry(DB_SEED.into()).await? {
+                            LairEntryInfo::Seed { seed_info, .. } => seed_info,
+                            oth => {
+                                return Err(format!(
+                                    "invalid entry type, expecting seed: {:?}",
+                                    oth
+                                )
+                                .into())
+                            }
+                        }
+                    } else {
+                        client.new_seed(DB_SEED.into(), None, false).await?
+                    };
+                    let sig = client
+                        .sign_by_pub_key(info.ed25519_pub_key, None, DB_KEY_CONTEXT.into())
+                        .await?;
+                    let hash = sodoken::BufWriteSized::<32>::new_no_lock();
+                    sodoken::hash::blake2b::hash(hash.clone(), sig.0.to_vec()).await?;
+                    let mut key = [0; 32];
+                    key.copy_from_slice(&*hash.read_lock());
+                    Ok(key)
+                }
+            }
+        }
+    }
+
     /// Get a single tls cert from lair for use in conductor
     /// NOTE: once we delete the deprecated legacy lair api
     /// we can support multiple conductors using the same lair
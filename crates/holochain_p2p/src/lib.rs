@@ -103,6 +103,13 @@ pub trait HolochainP2pDnaT {
         options: actor::GetLinksOptions,
     ) -> actor::HolochainP2pResult<Vec<WireLinkOps>>;
 
+    /// Get the hashes of the links on a base from the DHT.
+    async fn count_links(
+        &self,
+        query: WireLinkKey,
+        options: actor::GetLinksOptions,
+    ) -> actor::HolochainP2pResult<CountLinksResponse>;
+
     /// Get agent activity from the DHT.
     async fn get_agent_activity(
         &self,
@@ -133,6 +140,17 @@ pub trait HolochainP2pDnaT {
 
     /// New data has been integrated and is ready for gossiping.
     async fn new_integrated_data(&self) -> actor::HolochainP2pResult<()>;
+
+    /// Publish warrants about an agent to that agent's activity authorities.
+    async fn publish_warrants(
+        &self,
+        warrantee: AgentPubKey,
+        warrants: Vec<SignedWarrant>,
+        timeout_ms: Option<u64>,
+    ) -> actor::HolochainP2pResult<()>;
+
+    /// Stop communicating with agents that have been warranted.
+    async fn block_agents(&self, agents: Vec<AgentPubKey>) -> actor::HolochainP2pResult<()>;
 }
 
 /// A wrapper around HolochainP2pSender that partially applies the dna_hash / agent_pub_key.
@@ -285,6 +303,17 @@ impl HolochainP2pDnaT for HolochainP2pDna {
             .await
     }
 
+    /// Get the hashes of the links on a base from the DHT.
+    async fn count_links(
+        &self,
+        query: WireLinkKey,
+        options: actor::GetLinksOptions,
+    ) -> actor::HolochainP2pResult<CountLinksResponse> {
+        self.sender
+            .count_links((*self.dna_hash).clone(), query, options)
+            .await
+    }
+
     /// Get agent activity from the DHT.
     async fn get_agent_activity(
         &self,
@@ -333,6 +362,23 @@ impl HolochainP2pDnaT for HolochainP2pDna {
             .new_integrated_data((*self.dna_hash).clone())
             .await
     }
+
+    async fn publish_warrants(
+        &self,
+        warrantee: AgentPubKey,
+        warrants: Vec<SignedWarrant>,
+        timeout_ms: Option<u64>,
+    ) -> actor::HolochainP2pResult<()> {
+        self.sender
+            .publish_warrants((*self.dna_hash).clone(), warrantee, warrants, timeout_ms)
+            .await
+    }
+
+    async fn block_agents(&self, agents: Vec<AgentPubKey>) -> actor::HolochainP2pResult<()> {
+        self.sender
+            .block_agents((*self.dna_hash).clone(), agents)
+            .await
+    }
 }
 
 pub use kitsune_p2p::dht;
@@ -17,6 +17,7 @@ use ghost_actor::dependencies::tracing_futures::Instrument;
 use holochain_zome_types::zome::FunctionName;
 use kitsune_p2p::actor::KitsuneP2pSender;
 use kitsune_p2p::agent_store::AgentInfoSigned;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::future::Future;
 
@@ -207,6 +208,18 @@ impl WrapEvtSender {
         )
     }
 
+    fn count_links(
+        &self,
+        dna_hash: DnaHash,
+        to_agent: AgentPubKey,
+        query: WireLinkKey,
+    ) -> impl Future<Output = HolochainP2pResult<CountLinksResponse>> + 'static + Send {
+        timing_trace!(
+            { self.0.count_links(dna_hash, to_agent, query) },
+            "(hp2p:handle) count_links",
+        )
+    }
+
     fn get_agent_activity(
         &self,
         dna_hash: DnaHash,
@@ -304,12 +317,27 @@ impl WrapEvtSender {
             "(hp2p:handle) countersigning_session_negotiation"
         )
     }
+
+    fn publish_warrants(
+        &self,
+        dna_hash: DnaHash,
+        warrants: Vec<SignedWarrant>,
+    ) -> impl Future<Output = HolochainP2pResult<()>> + 'static + Send {
+        let warrant_count = warrants.len();
+        timing_trace!(
+            { self.0.publish_warrants(dna_hash, warrants) },
+            %warrant_count,
+            "(hp2p:handle) publish_warrants",
+        )
+    }
 }
 
 pub(crate) struct HolochainP2pActor {
     tuning_params: kitsune_p2p_types::config::KitsuneP2pTuningParams,
     evt_sender: WrapEvtSender,
     kitsune_p2p: ghost_actor::GhostSender<kitsune_p2p::actor::KitsuneP2p>,
+    /// Agents that have been warranted in each space.
+    blocked_agents: HashMap<DnaHash, HashSet<AgentPubKey>>,
 }
 
 impl ghost_actor::GhostControlHandler for HolochainP2pActor {}
@@ -333,9 +361,16 @@ impl HolochainP2pActor {
             tuning_params,
             evt_sender: WrapEvtSender(evt_sender),
             kitsune_p2p,
+            blocked_agents: HashMap::new(),
         })
     }
 
+    fn is_blocked(&self, dna_hash: &DnaHash, agent: &AgentPubKey) -> bool {
+        self.blocked_agents
+            .get(dna_hash)
+            .map_or(false, |agents| agents.contains(agent))
+    }
+
     /// receiving an incoming request from a remote node
     #[allow(clippy::too_many_arguments)]
     fn handle_incoming_call_remote(
@@ -348,6 +383,9 @@ impl HolochainP2pActor {
         cap_secret: Option<CapSecret>,
         data: Vec<u8>,
     ) -> kitsune_p2p::actor::KitsuneP2pHandlerResult<Vec<u8>> {
+        if self.is_blocked(&dna_hash, &from_agent) {
+            return Err(HolochainP2pError::BlockedAgent(from_agent).into());
+        }
         let evt_sender = self.evt_sender.clone();
         Ok(async move {
             let res = evt_sender
@@ -431,6 +469,24 @@ impl HolochainP2pActor {
         .into())
     }
 
+    /// receiving an incoming count_links request from a remote node
+    fn handle_incoming_count_links(
+        &mut self,
+        dna_hash: DnaHash,
+        to_agent: AgentPubKey,
+        query: WireLinkKey,
+    ) -> kitsune_p2p::actor::KitsuneP2pHandlerResult<Vec<u8>> {
+        let evt_sender = self.evt_sender.clone();
+        Ok(async move {
+            let res = evt_sender.count_links(dna_hash, to_agent, query).await;
+            res.and_then(|r| Ok(SerializedBytes::try_from(r)?))
+                .map_err(kitsune_p2p::KitsuneP2pError::from)
+                .map(|res| UnsafeBytes::from(res).into())
+        }
+        .boxed()
+        .into())
+    }
+
     /// receiving an incoming get_links request from a remote node
     fn handle_incoming_get_agent_activity(
         &mut self,
@@ -459,8 +515,16 @@ impl HolochainP2pActor {
         dna_hash: DnaHash,
         request_validation_receipt: bool,
         countersigning_session: bool,
-        ops: Vec<holochain_types::dht_op::DhtOp>,
+        mut ops: Vec<holochain_types::dht_op::DhtOp>,
     ) -> kitsune_p2p::actor::KitsuneP2pHandlerResult<()> {
+        if let Some(blocked) = self.blocked_agents.get(&dna_hash) {
+            let op_count = ops.len();
+            ops.retain(|op| !blocked.contains(op.action().author()));
+            // Everything was authored by blocked agents.
+            if op_count > 0 && ops.is_empty() {
+                return Ok(async move { Ok(()) }.boxed().into());
+            }
+        }
         let evt_sender = self.evt_sender.clone();
         Ok(async move {
             evt_sender
@@ -520,6 +584,21 @@ impl HolochainP2pActor {
         .into())
     }
 
+    /// receiving incoming warrants from a remote node
+    fn handle_incoming_publish_warrants(
+        &mut self,
+        dna_hash: DnaHash,
+        warrants: Vec<SignedWarrant>,
+    ) -> kitsune_p2p::actor::KitsuneP2pHandlerResult<()> {
+        let evt_sender = self.evt_sender.clone();
+        Ok(async move {
+            evt_sender.publish_warrants(dna_hash, warrants).await?;
+            Ok(())
+        }
+        .boxed()
+        .into())
+    }
+
     fn handle_incoming_countersigning_session_negotiation(
         &mut self,
         dna_hash: DnaHash,
@@ -669,6 +748,9 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
             crate::wire::WireMessage::GetLinks { link_key, options } => {
                 self.handle_incoming_get_links(space, to_agent, link_key, options)
             }
+            crate::wire::WireMessage::CountLinks { query } => {
+                self.handle_incoming_count_links(space, to_agent, query)
+            }
             crate::wire::WireMessage::GetAgentActivity {
                 agent,
                 query,
@@ -696,6 +778,13 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
                 )
                 .into())
             }
+            // holochain_p2p only broadcasts this message.
+            crate::wire::WireMessage::PublishWarrants { .. } => {
+                Err(HolochainP2pError::invalid_p2p_message(
+                    "invalid: publish warrants is a broadcast type, not a request".to_string(),
+                )
+                .into())
+            }
         }
     }
 
@@ -717,6 +806,7 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
             crate::wire::WireMessage::Get { .. }
             | crate::wire::WireMessage::GetMeta { .. }
             | crate::wire::WireMessage::GetLinks { .. }
+            | crate::wire::WireMessage::CountLinks { .. }
             | crate::wire::WireMessage::GetAgentActivity { .. }
             | crate::wire::WireMessage::GetValidationPackage { .. }
             | crate::wire::WireMessage::ValidationReceipt { .. } => {
@@ -756,6 +846,9 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
             crate::wire::WireMessage::CountersigningSessionNegotiation { message } => {
                 self.handle_incoming_countersigning_session_negotiation(space, to_agent, message)
             }
+            crate::wire::WireMessage::PublishWarrants { warrants } => {
+                self.handle_incoming_publish_warrants(space, warrants)
+            }
         }
     }
 
@@ -901,6 +994,9 @@ impl HolochainP2pHandler for HolochainP2pActor {
         cap_secret: Option<CapSecret>,
         payload: ExternIO,
     ) -> HolochainP2pHandlerResult<SerializedBytes> {
+        if self.is_blocked(&dna_hash, &to_agent) {
+            return Err(HolochainP2pError::BlockedAgent(to_agent));
+        }
         let space = dna_hash.into_kitsune();
         let to_agent = to_agent.into_kitsune();
 
@@ -929,11 +1025,12 @@ impl HolochainP2pHandler for HolochainP2pActor {
         cap: Option<CapSecret>,
         payload: ExternIO,
     ) -> HolochainP2pHandlerResult<()> {
-        let space = dna_hash.into_kitsune();
         let to_agent_list = to_agent_list
             .into_iter()
+            .filter(|a| !self.is_blocked(&dna_hash, a))
             .map(|a| a.into_kitsune())
             .collect();
+        let space = dna_hash.into_kitsune();
 
         let req =
             crate::wire::WireMessage::call_remote(zome_name, fn_name, from_agent, cap, payload)
@@ -1112,6 +1209,45 @@ impl HolochainP2pHandler for HolochainP2pActor {
         .into())
     }
 
+    #[tracing::instrument(skip(self), level = "trace")]
+    fn handle_count_links(
+        &mut self,
+        dna_hash: DnaHash,
+        query: WireLinkKey,
+        options: actor::GetLinksOptions,
+    ) -> HolochainP2pHandlerResult<CountLinksResponse> {
+        let space = dna_hash.into_kitsune();
+        let basis = AnyDhtHash::from(query.base.clone()).to_kitsune();
+
+        let payload = crate::wire::WireMessage::count_links(query).encode()?;
+
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        let tuning_params = self.tuning_params.clone();
+        Ok(async move {
+            let mut input =
+                kitsune_p2p::actor::RpcMulti::new(&tuning_params, space, basis, payload);
+            // Like get_links only a single authority is asked.
+            input.max_remote_agent_count = 1;
+            if let Some(timeout_ms) = options.timeout_ms {
+                input.max_timeout = kitsune_p2p_types::KitsuneTimeout::from_millis(timeout_ms);
+            }
+            let result = kitsune_p2p.rpc_multi(input).await?;
+
+            // Merge the responses so a link is only counted once.
+            let mut out = std::collections::BTreeSet::new();
+            for item in result {
+                let kitsune_p2p::actor::RpcMultiResponse { response, .. } = item;
+                let response: CountLinksResponse =
+                    SerializedBytes::from(UnsafeBytes::from(response)).try_into()?;
+                out.extend(response.into_create_link_actions());
+            }
+
+            Ok(CountLinksResponse::new(out.into_iter().collect()))
+        }
+        .boxed()
+        .into())
+    }
+
     #[tracing::instrument(skip(self), level = "trace")]
     fn handle_get_agent_activity(
         &mut self,
@@ -1229,6 +1365,50 @@ impl HolochainP2pHandler for HolochainP2pActor {
         .into())
     }
 
+    #[tracing::instrument(skip(self, warrants), level = "trace")]
+    fn handle_publish_warrants(
+        &mut self,
+        dna_hash: DnaHash,
+        warrantee: AgentPubKey,
+        warrants: Vec<SignedWarrant>,
+        timeout_ms: Option<u64>,
+    ) -> HolochainP2pHandlerResult<()> {
+        use kitsune_p2p_types::KitsuneTimeout;
+
+        let space = dna_hash.into_kitsune();
+        // Warrants are held by the warrantee's agent activity authorities.
+        let basis = AnyDhtHash::from(warrantee).to_kitsune();
+        let timeout = match timeout_ms {
+            Some(ms) => KitsuneTimeout::from_millis(ms),
+            None => self.tuning_params.implicit_timeout(),
+        };
+
+        let payload = crate::wire::WireMessage::publish_warrants(warrants).encode()?;
+
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(async move {
+            kitsune_p2p
+                .broadcast(space, basis, timeout, BroadcastTo::Notify, payload)
+                .await?;
+            Ok(())
+        }
+        .boxed()
+        .into())
+    }
+
+    #[tracing::instrument(skip(self), level = "trace")]
+    fn handle_block_agents(
+        &mut self,
+        dna_hash: DnaHash,
+        agents: Vec<AgentPubKey>,
+    ) -> HolochainP2pHandlerResult<()> {
+        self.blocked_agents
+            .entry(dna_hash)
+            .or_default()
+            .extend(agents);
+        Ok(async move { Ok(()) }.boxed().into())
+    }
+
     fn handle_dump_network_metrics(
         &mut self,
         dna_hash: Option<DnaHash>,
@@ -1242,4 +1422,15 @@ impl HolochainP2pHandler for HolochainP2pActor {
         .boxed()
         .into())
     }
+
+    fn handle_gossip_totals(
+        &mut self,
+        dna_hash: DnaHash,
+    ) -> HolochainP2pHandlerResult<Option<kitsune_p2p::metrics::GossipTotals>> {
+        let space = dna_hash.into_kitsune();
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(async move { Ok(kitsune_p2p.gossip_totals(space).await?) }
+            .boxed()
+            .into())
+    }
 }
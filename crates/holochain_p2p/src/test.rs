@@ -93,6 +93,14 @@ impl HolochainP2pHandler for StubNetwork {
     ) -> HolochainP2pHandlerResult<Vec<WireLinkOps>> {
         Err("stub".into())
     }
+    fn handle_count_links(
+        &mut self,
+        dna_hash: DnaHash,
+        query: WireLinkKey,
+        options: actor::GetLinksOptions,
+    ) -> HolochainP2pHandlerResult<CountLinksResponse> {
+        Err("stub".into())
+    }
     fn handle_get_agent_activity(
         &mut self,
         dna_hash: DnaHash,
@@ -128,12 +136,34 @@ impl HolochainP2pHandler for StubNetwork {
     ) -> HolochainP2pHandlerResult<()> {
         Err("stub".into())
     }
+    fn handle_publish_warrants(
+        &mut self,
+        dna_hash: DnaHash,
+        warrantee: AgentPubKey,
+        warrants: Vec<SignedWarrant>,
+        timeout_ms: Option<u64>,
+    ) -> HolochainP2pHandlerResult<()> {
+        Err("stub".into())
+    }
+    fn handle_block_agents(
+        &mut self,
+        dna_hash: DnaHash,
+        agents: Vec<AgentPubKey>,
+    ) -> HolochainP2pHandlerResult<()> {
+        Err("stub".into())
+    }
     fn handle_dump_network_metrics(
         &mut self,
         dna_hash: Option<DnaHash>,
     ) -> HolochainP2pHandlerResult<String> {
         Err("stub".into())
     }
+    fn handle_gossip_totals(
+        &mut self,
+        dna_hash: DnaHash,
+    ) -> HolochainP2pHandlerResult<Option<kitsune_p2p::metrics::GossipTotals>> {
+        Err("stub".into())
+    }
 }
 
 /// Spawn a stub network that doesn't respond to any messages.
@@ -268,6 +298,38 @@ mod tests {
         r_task.await.unwrap();
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_call_remote_to_blocked_agent_fails() {
+        let (dna, a1, a2, _) = test_setup();
+
+        let (p2p, _evt) = spawn_holochain_p2p(
+            KitsuneP2pConfig::default(),
+            TlsConfig::new_ephemeral().await.unwrap(),
+            kitsune_p2p::HostStub::new(),
+        )
+        .await
+        .unwrap();
+
+        p2p.block_agents(dna.clone(), vec![a2.clone()])
+            .await
+            .unwrap();
+
+        let res = p2p
+            .call_remote(
+                dna,
+                a1,
+                a2.clone(),
+                "".into(),
+                "".into(),
+                None,
+                ExternIO::encode(b"yippo").unwrap(),
+            )
+            .await;
+        assert!(matches!(res, Err(HolochainP2pError::BlockedAgent(a)) if a == a2));
+
+        p2p.ghost_actor_shutdown().await.unwrap();
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_send_validation_receipt_workflow() {
         let (dna, a1, a2, _) = test_setup();
@@ -542,11 +604,7 @@ mod tests {
             b"eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee".to_vec(),
             holo_hash::hash_type::Entry,
         );
-        let link_key = WireLinkKey {
-            base: hash.into(),
-            type_query: LinkTypeFilter::single_dep(0.into()),
-            tag: None,
-        };
+        let link_key = WireLinkKey::new(hash.into(), LinkTypeFilter::single_dep(0.into()), None);
 
         let res = p2p
             .get_links(dna, link_key, actor::GetLinksOptions::default())
@@ -563,6 +621,74 @@ mod tests {
         r_task.await.unwrap();
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_count_links_workflow() {
+        let (dna, a1, a2, _) = test_setup();
+
+        let mut params =
+            kitsune_p2p_types::config::tuning_params_struct::KitsuneP2pTuningParams::default();
+        params.default_rpc_multi_remote_agent_count = 1;
+        params.default_rpc_multi_remote_request_grace_ms = 100;
+        let mut config = KitsuneP2pConfig::default();
+        config.tuning_params = Arc::new(params);
+
+        let (p2p, mut evt) = spawn_holochain_p2p(
+            config,
+            TlsConfig::new_ephemeral().await.unwrap(),
+            kitsune_p2p::HostStub::new(),
+        )
+        .await
+        .unwrap();
+
+        let mut hashes = vec![fixt!(ActionHash), fixt!(ActionHash)];
+        hashes.sort();
+        let test_1 = CountLinksResponse::new(hashes);
+
+        let test_1_clone = test_1.clone();
+        let r_task = tokio::task::spawn(async move {
+            use tokio_stream::StreamExt;
+            while let Some(evt) = evt.next().await {
+                let test_1_clone = test_1_clone.clone();
+                use crate::types::event::HolochainP2pEvent::*;
+                match evt {
+                    CountLinks { respond, .. } => {
+                        respond.r(Ok(async move { Ok(test_1_clone) }.boxed().into()));
+                    }
+                    SignNetworkData { respond, .. } => {
+                        respond.r(Ok(async move { Ok([0; 64].into()) }.boxed().into()));
+                    }
+                    PutAgentInfoSigned { respond, .. } => {
+                        respond.r(Ok(async move { Ok(()) }.boxed().into()));
+                    }
+                    QueryPeerDensity { respond, .. } => {
+                        let view = test_peer_view();
+                        respond.r(Ok(async move { Ok(view) }.boxed().into()));
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        p2p.join(dna.clone(), a1.clone(), None).await.unwrap();
+        p2p.join(dna.clone(), a2.clone(), None).await.unwrap();
+
+        let hash = holo_hash::EntryHash::from_raw_36_and_type(
+            b"eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee".to_vec(),
+            holo_hash::hash_type::Entry,
+        );
+        let query = WireLinkKey::new(hash.into(), LinkTypeFilter::single_dep(0.into()), None);
+
+        let res = p2p
+            .count_links(dna, query, actor::GetLinksOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(res, test_1);
+
+        p2p.ghost_actor_shutdown().await.unwrap();
+        r_task.await.unwrap();
+    }
+
     fn test_peer_view() -> PeerView {
         PeerViewQ::new(Topology::standard_epoch_full(), ArqStrat::default(), vec![]).into()
     }
@@ -26,6 +26,10 @@ pub enum HolochainP2pError {
     #[error("InvalidP2pMessage: {0}")]
     InvalidP2pMessage(String),
 
+    /// The agent has been warranted and is blocked
+    #[error("Agent {0} is blocked")]
+    BlockedAgent(holo_hash::AgentPubKey),
+
     /// Other
     #[error("Other: {0}")]
     Other(Box<dyn std::error::Error + Send + Sync>),
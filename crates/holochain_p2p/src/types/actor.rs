@@ -272,6 +272,13 @@ ghost_actor::ghost_chan! {
             options: GetLinksOptions,
         ) -> Vec<WireLinkOps>;
 
+        /// Get the hashes of the links on a base from the DHT.
+        fn count_links(
+            dna_hash: DnaHash,
+            query: WireLinkKey,
+            options: GetLinksOptions,
+        ) -> CountLinksResponse;
+
         /// Get agent activity from the DHT.
         fn get_agent_activity(
             dna_hash: DnaHash,
@@ -296,10 +303,29 @@ ghost_actor::ghost_chan! {
             message: event::CountersigningSessionNegotiationMessage,
         ) -> ();
 
+        /// Publish warrants about an agent to that agent's activity authorities.
+        /// Warrants are only sent to the authorities reachable now, as they
+        /// aren't gossiped.
+        fn publish_warrants(
+            dna_hash: DnaHash,
+            warrantee: AgentPubKey,
+            warrants: Vec<SignedWarrant>,
+            timeout_ms: Option<u64>,
+        ) -> ();
+
+        /// Stop communicating with agents that have been warranted.
+        /// Calls and signals to or from these agents are refused and
+        /// any ops they author are dropped when they arrive.
+        fn block_agents(dna_hash: DnaHash, agents: Vec<AgentPubKey>) -> ();
+
         /// Dump network metrics.
         fn dump_network_metrics(
             dna_hash: Option<DnaHash>,
         ) -> String;
+
+        /// The totals of the gossip in a space since it was joined,
+        /// or `None` if the space hasn't been joined.
+        fn gossip_totals(dna_hash: DnaHash) -> Option<kitsune_p2p::metrics::GossipTotals>;
     }
 }
 
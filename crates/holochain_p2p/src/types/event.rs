@@ -50,11 +50,17 @@ impl Default for GetRequest {
 
 /// GetMeta options help control how the get is processed at various levels.
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
-pub struct GetMetaOptions {}
+pub struct GetMetaOptions {
+    /// Which metadata the authority should return.
+    #[serde(default)]
+    pub metadata_request: MetadataRequest,
+}
 
 impl From<&actor::GetMetaOptions> for GetMetaOptions {
-    fn from(_a: &actor::GetMetaOptions) -> Self {
-        Self {}
+    fn from(a: &actor::GetMetaOptions) -> Self {
+        Self {
+            metadata_request: a.metadata_request.clone(),
+        }
     }
 }
 
@@ -214,6 +220,13 @@ ghost_actor::ghost_chan! {
             options: GetLinksOptions,
         ) -> WireLinkOps;
 
+        /// A remote node is requesting the number of links on a base from us.
+        fn count_links(
+            dna_hash: DnaHash,
+            to_agent: AgentPubKey,
+            query: WireLinkKey,
+        ) -> CountLinksResponse;
+
         /// A remote node is requesting agent activity from us.
         fn get_agent_activity(
             dna_hash: DnaHash,
@@ -264,6 +277,13 @@ ghost_actor::ghost_chan! {
             to_agent: AgentPubKey,
             message: CountersigningSessionNegotiationMessage,
         ) -> ();
+
+        /// A remote node is publishing warrants about an agent
+        /// whose activity we are an authority for.
+        fn publish_warrants(
+            dna_hash: DnaHash,
+            warrants: Vec<SignedWarrant>,
+        ) -> ();
     }
 }
 
@@ -276,6 +296,7 @@ macro_rules! match_p2p_evt {
             HolochainP2pEvent::Get { $i, .. } => { $($t)* }
             HolochainP2pEvent::GetMeta { $i, .. } => { $($t)* }
             HolochainP2pEvent::GetLinks { $i, .. } => { $($t)* }
+            HolochainP2pEvent::CountLinks { $i, .. } => { $($t)* }
             HolochainP2pEvent::GetAgentActivity { $i, .. } => { $($t)* }
             HolochainP2pEvent::ValidationReceiptReceived { $i, .. } => { $($t)* }
             HolochainP2pEvent::SignNetworkData { $i, .. } => { $($t)* }
@@ -297,6 +318,7 @@ impl HolochainP2pEvent {
             HolochainP2pEvent::QueryGossipAgents { dna_hash, .. } => { dna_hash }
             HolochainP2pEvent::PutAgentInfoSigned { dna_hash, .. } => { dna_hash }
             HolochainP2pEvent::QueryPeerDensity { dna_hash, .. } => { dna_hash }
+            HolochainP2pEvent::PublishWarrants { dna_hash, .. } => { dna_hash }
         })
     }
 
@@ -311,6 +333,7 @@ impl HolochainP2pEvent {
             HolochainP2pEvent::QueryGossipAgents { .. } => { unimplemented!("There is no single agent target for QueryGossipAgents") },
             HolochainP2pEvent::PutAgentInfoSigned { .. } => { unimplemented!("There is no single agent target for PutAgentInfoSigned") },
             HolochainP2pEvent::QueryPeerDensity { .. } => { unimplemented!() },
+            HolochainP2pEvent::PublishWarrants { .. } => { unimplemented!("There is no single agent target for PublishWarrants") },
         })
     }
 }
@@ -55,6 +55,9 @@ pub enum WireMessage {
         link_key: WireLinkKey,
         options: event::GetLinksOptions,
     },
+    CountLinks {
+        query: WireLinkKey,
+    },
     GetAgentActivity {
         agent: AgentPubKey,
         query: ChainQueryFilter,
@@ -66,6 +69,9 @@ pub enum WireMessage {
     CountersigningSessionNegotiation {
         message: event::CountersigningSessionNegotiationMessage,
     },
+    PublishWarrants {
+        warrants: Vec<SignedWarrant>,
+    },
 }
 
 #[allow(missing_docs)]
@@ -129,6 +135,10 @@ impl WireMessage {
         Self::GetLinks { link_key, options }
     }
 
+    pub fn count_links(query: WireLinkKey) -> WireMessage {
+        Self::CountLinks { query }
+    }
+
     pub fn get_agent_activity(
         agent: AgentPubKey,
         query: ChainQueryFilter,
@@ -149,4 +159,8 @@ impl WireMessage {
     ) -> WireMessage {
         Self::CountersigningSessionNegotiation { message }
     }
+
+    pub fn publish_warrants(warrants: Vec<SignedWarrant>) -> WireMessage {
+        Self::PublishWarrants { warrants }
+    }
 }
@@ -1,4 +1,4 @@
-use crate::{functions::add_custom_functions, prelude::*};
+use crate::{db::PoolHandle, functions::add_custom_functions, key::DbKey, prelude::*};
 use holochain_serialized_bytes::prelude::*;
 use once_cell::sync::Lazy;
 use rusqlite::*;
@@ -16,6 +16,7 @@ const SQLITE_BUSY_TIMEOUT: Duration = Duration::from_secs(30);
 /// A map over any database type key'd by the full path to the database.
 pub(crate) struct Databases {
     dbs: parking_lot::RwLock<HashMap<PathBuf, Box<dyn Any + Send + Sync>>>,
+    pools: parking_lot::RwLock<HashMap<PathBuf, PoolHandle>>,
 }
 
 pub(crate) static DATABASE_HANDLES: Lazy<Databases> = Lazy::new(|| {
@@ -56,9 +57,21 @@ impl Databases {
     pub fn new() -> Self {
         Databases {
             dbs: parking_lot::RwLock::new(HashMap::new()),
+            pools: parking_lot::RwLock::new(HashMap::new()),
         }
     }
 
+    /// Get the connection pool of an open database by its full path.
+    pub(crate) fn pool_handle(&self, path: &Path) -> Option<PoolHandle> {
+        self.pools.read().get(path).cloned()
+    }
+
+    /// Forget an open database so the next `get_or_insert` creates it again.
+    pub(crate) fn remove(&self, path: &Path) {
+        self.dbs.write().remove(path);
+        self.pools.write().remove(path);
+    }
+
     /// Get a database if it exists or
     /// create it.
     pub fn get_or_insert<Kind, F>(
@@ -95,6 +108,7 @@ impl Databases {
                     // If the db is missing we run the closure to create it.
                     // Note the `Kind` is enforced by the closure return type.
                     let db = insert(kind.clone())?;
+                    self.pools.write().insert(v.key().clone(), db.pool_handle());
                     v.insert(Box::new(db.clone()));
                     Ok(db)
                 }
@@ -106,13 +120,17 @@ impl Databases {
 pub(crate) fn new_connection_pool(
     path: Option<&Path>,
     synchronous_level: DbSyncLevel,
+    key: Option<DbKey>,
 ) -> ConnectionPool {
     use r2d2_sqlite::SqliteConnectionManager;
     let manager = match path {
         Some(path) => SqliteConnectionManager::file(path),
         None => SqliteConnectionManager::memory(),
     };
-    let customizer = Box::new(ConnCustomizer { synchronous_level });
+    let customizer = Box::new(ConnCustomizer {
+        synchronous_level,
+        key,
+    });
     // We need the same amount of connections as reader threads plus one for the writer thread.
     let max_cons = num_read_threads() + 1;
     r2d2::Pool::builder()
@@ -131,6 +149,7 @@ pub(crate) fn new_connection_pool(
 #[derive(Debug)]
 struct ConnCustomizer {
     synchronous_level: DbSyncLevel,
+    key: Option<DbKey>,
 }
 
 /// The sqlite synchronous level.
@@ -176,7 +195,7 @@ impl Default for DbSyncStrategy {
 
 impl r2d2::CustomizeConnection<Connection, rusqlite::Error> for ConnCustomizer {
     fn on_acquire(&self, conn: &mut Connection) -> Result<(), rusqlite::Error> {
-        initialize_connection(conn, self.synchronous_level)?;
+        initialize_connection(conn, self.synchronous_level, self.key.as_ref())?;
         Ok(())
     }
 }
@@ -184,25 +203,12 @@ impl r2d2::CustomizeConnection<Connection, rusqlite::Error> for ConnCustomizer {
 pub(crate) fn initialize_connection(
     conn: &mut Connection,
     synchronous_level: DbSyncLevel,
+    key: Option<&DbKey>,
 ) -> rusqlite::Result<()> {
     // Tell SQLite to wait this long during write contention.
     conn.busy_timeout(SQLITE_BUSY_TIMEOUT)?;
 
-    #[cfg(feature = "db-encryption")]
-    {
-        use std::io::Write;
-        let key = get_encryption_key_shim();
-        let mut hex = *br#"0000000000000000000000000000000000000000000000000000000000000000"#;
-        let mut c = std::io::Cursor::new(&mut hex[..]);
-        for b in &key {
-            write!(c, "{:02X}", b)
-                .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
-        }
-        let _keyval = std::str::from_utf8(&hex).unwrap();
-        const FAKE_KEY: &str = "x'98483C6EB40B6C31A448C22A66DED3B5E5E8D5119CAC8327B655C8B5C483648101010101010101010101010101010101'";
-        // conn.pragma_update(None, "key", &keyval)?;
-        conn.pragma_update(None, "key", &FAKE_KEY)?;
-    }
+    apply_key(conn, key)?;
 
     // this is recommended to always be off:
     // https://sqlite.org/pragma.html#pragma_trusted_schema
@@ -222,13 +228,29 @@ pub(crate) fn initialize_connection(
     Ok(())
 }
 
+/// Set the SQLCipher key for a freshly opened connection.
+/// This must happen before anything else touches the database.
+pub(crate) fn apply_key(conn: &mut Connection, key: Option<&DbKey>) -> rusqlite::Result<()> {
+    #[cfg(feature = "db-encryption")]
+    if let Some(key) = key {
+        conn.pragma_update(None, "key", &key.pragma_value())?;
+    }
+    #[cfg(not(feature = "db-encryption"))]
+    {
+        // Checked when the database is opened.
+        let _ = (conn, key);
+    }
+    Ok(())
+}
+
+/// Re-encrypt the database behind this connection with a new key.
+/// No other connection to the database may be in use while this runs.
 #[cfg(feature = "db-encryption")]
-/// Simulate getting an encryption key from Lair.
-fn get_encryption_key_shim() -> [u8; 32] {
-    [
-        26, 111, 7, 31, 52, 204, 156, 103, 203, 171, 156, 89, 98, 51, 158, 143, 57, 134, 93, 56,
-        199, 225, 53, 141, 39, 77, 145, 130, 136, 108, 96, 201,
-    ]
+pub(crate) fn rekey_connection(conn: &Connection, key: &DbKey) -> rusqlite::Result<()> {
+    // Flush the write ahead log so every page is rewritten with the new key.
+    conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+    conn.pragma_update(None, "rekey", &key.pragma_value())?;
+    Ok(())
 }
 
 /// Singleton Connection
@@ -2,6 +2,7 @@
 
 use crate::{
     conn::{new_connection_pool, ConnectionPool, DbSyncLevel, PConn, DATABASE_HANDLES},
+    key::DbKey,
     prelude::*,
 };
 use derive_more::Into;
@@ -101,7 +102,8 @@ impl<Kind: DbKindT> ReadAccess<Kind> for DbRead<Kind> {
 pub struct DbRead<Kind: DbKindT> {
     kind: Kind,
     path: PathBuf,
-    connection_pool: ConnectionPool,
+    sync_level: DbSyncLevel,
+    connection_pool: Arc<parking_lot::RwLock<ConnectionPool>>,
     write_semaphore: Arc<Semaphore>,
     read_semaphore: Arc<Semaphore>,
     max_readers: usize,
@@ -154,7 +156,9 @@ impl<Kind: DbKindT> DbRead<Kind> {
     /// TODO: We should eventually swap this for an async solution.
     fn connection_pooled(&self) -> DatabaseResult<PConn> {
         let now = std::time::Instant::now();
-        let r = Ok(PConn::new(self.connection_pool.get()?));
+        // Clone the pool out so the lock isn't held while waiting for a connection.
+        let pool = self.connection_pool.read().clone();
+        let r = Ok(PConn::new(pool.get()?));
         let el = now.elapsed();
         if el.as_millis() > 20 {
             tracing::error!("Connection pool took {:?} to be free'd", el);
@@ -190,6 +194,15 @@ impl<Kind: DbKindT> DbRead<Kind> {
         r
     }
 
+    pub(crate) fn pool_handle(&self) -> PoolHandle {
+        PoolHandle {
+            kind: self.kind.kind(),
+            path: self.path.clone(),
+            sync_level: self.sync_level,
+            pool: self.connection_pool.clone(),
+        }
+    }
+
     async fn acquire_reader_permit(&self) -> OwnedSemaphorePermit {
         self.read_semaphore
             .clone()
@@ -220,9 +233,20 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
         path_prefix: &Path,
         kind: Kind,
         sync_level: DbSyncLevel,
+    ) -> DatabaseResult<Self> {
+        Self::open_with_key(path_prefix, kind, sync_level, None)
+    }
+
+    /// Create or open an existing database which is encrypted with `key`.
+    /// Requires the `db-encryption` feature if a key is given.
+    pub fn open_with_key(
+        path_prefix: &Path,
+        kind: Kind,
+        sync_level: DbSyncLevel,
+        key: Option<DbKey>,
     ) -> DatabaseResult<Self> {
         DATABASE_HANDLES.get_or_insert(&kind, path_prefix, |kind| {
-            Self::new(Some(path_prefix), kind, sync_level)
+            Self::new(Some(path_prefix), kind, sync_level, key)
         })
     }
 
@@ -230,7 +254,12 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
         path_prefix: Option<&Path>,
         kind: Kind,
         sync_level: DbSyncLevel,
+        key: Option<DbKey>,
     ) -> DatabaseResult<Self> {
+        #[cfg(not(feature = "db-encryption"))]
+        if key.is_some() {
+            return Err(DatabaseError::EncryptionUnsupported);
+        }
         let path = match path_prefix {
             Some(path_prefix) => {
                 let path = path_prefix.join(kind.filename());
@@ -246,7 +275,7 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
                 match Connection::open(&path)
                     // For some reason calling pragma_update is necessary to prove the database file is valid.
                     .and_then(|mut c| {
-                        crate::conn::initialize_connection(&mut c, sync_level)?;
+                        crate::conn::initialize_connection(&mut c, sync_level, key.as_ref())?;
                         c.pragma_update(None, "synchronous", &"0".to_string())
                     }) {
                     Ok(_) => (),
@@ -283,51 +312,34 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
         };
 
         // Now we know the database file is valid we can open a connection pool.
-        let pool = new_connection_pool(path.as_ref().map(|p| p.as_ref()), sync_level);
+        let pool = new_connection_pool(path.as_ref().map(|p| p.as_ref()), sync_level, key);
         let mut conn = pool.get()?;
         // set to faster write-ahead-log mode
         conn.pragma_update(None, "journal_mode", &"WAL".to_string())?;
         crate::table::initialize_database(&mut conn, kind.kind())?;
 
         Ok(DbWrite(DbRead {
-            write_semaphore: Self::get_write_semaphore(kind.kind()),
-            read_semaphore: Self::get_read_semaphore(kind.kind()),
+            write_semaphore: get_write_semaphore(kind.kind()),
+            read_semaphore: get_read_semaphore(kind.kind()),
             max_readers: num_read_threads(),
             num_readers: Arc::new(AtomicUsize::new(0)),
             kind,
             path: path.unwrap_or_default(),
-            connection_pool: pool,
+            sync_level,
+            connection_pool: Arc::new(parking_lot::RwLock::new(pool)),
         }))
     }
 
-    fn get_write_semaphore(kind: DbKind) -> Arc<Semaphore> {
-        static MAP: once_cell::sync::Lazy<Mutex<HashMap<DbKind, Arc<Semaphore>>>> =
-            once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
-        MAP.lock()
-            .entry(kind)
-            .or_insert_with(|| Arc::new(Semaphore::new(1)))
-            .clone()
-    }
-
-    fn get_read_semaphore(kind: DbKind) -> Arc<Semaphore> {
-        static MAP: once_cell::sync::Lazy<Mutex<HashMap<DbKind, Arc<Semaphore>>>> =
-            once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
-        MAP.lock()
-            .entry(kind)
-            .or_insert_with(|| Arc::new(Semaphore::new(num_read_threads())))
-            .clone()
-    }
-
     /// Create a unique db in a temp dir with no static management of the
     /// connection pool, useful for testing.
     #[cfg(any(test, feature = "test_utils"))]
     pub fn test(path: &Path, kind: Kind) -> DatabaseResult<Self> {
-        Self::new(Some(path), kind, DbSyncLevel::default())
+        Self::new(Some(path), kind, DbSyncLevel::default(), None)
     }
 
     #[cfg(any(test, feature = "test_utils"))]
     pub fn test_in_mem(kind: Kind) -> DatabaseResult<Self> {
-        Self::new(None, kind, DbSyncLevel::default())
+        Self::new(None, kind, DbSyncLevel::default(), None)
     }
 
     /// Remove the db and directory
@@ -339,6 +351,35 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
         Ok(())
     }
 
+    /// Delete the database files from disk.
+    ///
+    /// Waits until no reader or writer holds a permit for this database.
+    /// The database is closed for this process, so opening it again
+    /// creates a new, empty database. Any remaining clones of this
+    /// handle must not be used afterwards.
+    pub async fn delete(self) -> DatabaseResult<()> {
+        let _writer = self.acquire_writer_permit().await;
+        let _readers = self
+            .0
+            .read_semaphore
+            .clone()
+            .acquire_many_owned(self.0.max_readers as u32)
+            .await
+            .expect("We don't ever close these semaphores");
+        let path = self.0.path.clone();
+        DATABASE_HANDLES.remove(&path);
+        drop(self);
+        for suffix in ["", "-wal", "-shm"] {
+            let mut file = path.as_os_str().to_owned();
+            file.push(suffix);
+            match std::fs::remove_file(&file) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
     pub async fn async_commit<E, R, F>(&self, f: F) -> Result<R, E>
     where
         E: From<DatabaseError> + Send + 'static,
@@ -385,6 +426,66 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
     }
 }
 
+fn get_write_semaphore(kind: DbKind) -> Arc<Semaphore> {
+    static MAP: once_cell::sync::Lazy<Mutex<HashMap<DbKind, Arc<Semaphore>>>> =
+        once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
+    MAP.lock()
+        .entry(kind)
+        .or_insert_with(|| Arc::new(Semaphore::new(1)))
+        .clone()
+}
+
+fn get_read_semaphore(kind: DbKind) -> Arc<Semaphore> {
+    static MAP: once_cell::sync::Lazy<Mutex<HashMap<DbKind, Arc<Semaphore>>>> =
+        once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
+    MAP.lock()
+        .entry(kind)
+        .or_insert_with(|| Arc::new(Semaphore::new(num_read_threads())))
+        .clone()
+}
+
+/// The connection pool of an open database, independent of its [`DbKindT`].
+#[derive(Clone)]
+#[cfg_attr(not(feature = "db-encryption"), allow(dead_code))]
+pub(crate) struct PoolHandle {
+    kind: DbKind,
+    path: PathBuf,
+    sync_level: DbSyncLevel,
+    pool: Arc<parking_lot::RwLock<ConnectionPool>>,
+}
+
+impl PoolHandle {
+    /// Re-encrypt the database with a new key and replace its connection
+    /// pool so all future connections use the new key.
+    /// Waits until no reader or writer holds a permit for this database.
+    #[cfg(feature = "db-encryption")]
+    pub(crate) async fn rekey(&self, key: DbKey) -> DatabaseResult<()> {
+        let _writer = get_write_semaphore(self.kind.clone())
+            .acquire_owned()
+            .await
+            .expect("We don't ever close these semaphores");
+        let _readers = get_read_semaphore(self.kind.clone())
+            .acquire_many_owned(num_read_threads() as u32)
+            .await
+            .expect("We don't ever close these semaphores");
+        let pool = self.pool.read().clone();
+        let new_key = key.clone();
+        task::spawn_blocking(move || {
+            let conn = pool.get()?;
+            crate::conn::rekey_connection(&conn, &new_key)?;
+            DatabaseResult::Ok(())
+        })
+        .await??;
+        *self.pool.write() = new_connection_pool(Some(&self.path), self.sync_level, Some(key));
+        Ok(())
+    }
+
+    #[cfg(not(feature = "db-encryption"))]
+    pub(crate) async fn rekey(&self, _key: DbKey) -> DatabaseResult<()> {
+        Err(DatabaseError::EncryptionUnsupported)
+    }
+}
+
 pub fn num_read_threads() -> usize {
     let num_cpus = num_cpus::get();
     let num_threads = num_cpus.checked_div(2).unwrap_or(0);
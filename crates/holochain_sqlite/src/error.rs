@@ -50,6 +50,28 @@ pub enum DatabaseError {
     #[error("Unable to construct a value key")]
     KeyConstruction,
 
+    #[error("Database {db_kind} has schema version {db_version} but only versions up to {supported_version} are supported. Refusing to downgrade")]
+    SchemaVersionTooNew {
+        db_kind: String,
+        db_version: u16,
+        supported_version: u16,
+    },
+
+    #[error("Database has no schema version so it can't be migrated")]
+    SchemaUninitialized,
+
+    #[error("Migration {0} has no backward migration so it can't be reverted")]
+    NoBackwardMigration(usize),
+
+    #[error("Migration {0} does not exist in this schema")]
+    UnknownMigration(usize),
+
+    #[error("Database encryption requires holochain to be built with the `db-encryption` feature")]
+    EncryptionUnsupported,
+
+    #[error("Database at {0} could not be opened with the given key")]
+    WrongKey(PathBuf),
+
     #[error("transparent")]
     FailedToJoinBlocking(#[from] tokio::task::JoinError),
 }
@@ -0,0 +1,262 @@
+//! Keys for at-rest encryption of databases.
+//!
+//! Encryption is only applied when this crate is built with the
+//! `db-encryption` feature, which swaps SQLite for SQLCipher.
+//! Without the feature [`DbKey`]s can still be created but any attempt
+//! to open a database with one will fail with
+//! [`DatabaseError::EncryptionUnsupported`].
+
+use crate::prelude::*;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// The first bytes of every unencrypted SQLite database file.
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// A raw 256 bit key used to encrypt a database.
+///
+/// The key is never printed, not even in debug output.
+#[derive(Clone, PartialEq, Eq)]
+pub struct DbKey(Arc<[u8; 32]>);
+
+impl DbKey {
+    /// Create a key from raw bytes.
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(Arc::new(bytes))
+    }
+
+    /// The value of the SQLCipher `key` and `rekey` pragmas for this key.
+    /// Using the `x'...'` form means SQLCipher uses the key directly
+    /// instead of running its own key derivation.
+    #[cfg_attr(not(feature = "db-encryption"), allow(dead_code))]
+    pub(crate) fn pragma_value(&self) -> String {
+        let mut hex = String::with_capacity(67);
+        hex.push_str("x'");
+        for b in self.0.iter() {
+            hex.push_str(&format!("{:02X}", b));
+        }
+        hex.push('\'');
+        hex
+    }
+}
+
+impl std::fmt::Debug for DbKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("DbKey(<redacted>)")
+    }
+}
+
+/// Find every database file below a database root directory.
+pub fn database_files(root: &Path) -> DatabaseResult<Vec<PathBuf>> {
+    fn walk(dir: &Path, out: &mut Vec<PathBuf>) -> std::io::Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                walk(&path, out)?;
+            } else if path.extension().map_or(false, |e| e == "sqlite3") {
+                out.push(path);
+            }
+        }
+        Ok(())
+    }
+    let mut out = Vec::new();
+    if root.is_dir() {
+        walk(root, &mut out)?;
+    }
+    out.sort();
+    Ok(out)
+}
+
+/// Check if a database file is stored as plain, unencrypted SQLite.
+/// Empty files are considered plaintext because SQLite hasn't written
+/// anything to them yet.
+pub fn is_plaintext(path: &Path) -> DatabaseResult<bool> {
+    use std::io::Read;
+    let mut header = [0u8; 16];
+    let mut file = std::fs::File::open(path)?;
+    let mut read = 0;
+    while read < header.len() {
+        match file.read(&mut header[read..])? {
+            0 => break,
+            n => read += n,
+        }
+    }
+    Ok(read == 0 || (read == header.len() && &header == SQLITE_HEADER))
+}
+
+/// Encrypt every unencrypted database below `root` with `key`.
+///
+/// This is the migration path for conductors that were run without
+/// encryption. Databases which are already encrypted are left alone.
+/// Must be called before any of the databases are opened.
+pub fn encrypt_database_dir(root: &Path, key: &DbKey) -> DatabaseResult<()> {
+    for path in database_files(root)? {
+        if is_plaintext(&path)? {
+            encrypt_database_file(&path, key)?;
+            tracing::info!("encrypted database {}", path.display());
+        }
+    }
+    Ok(())
+}
+
+/// Rekey every database below `root` from `old_key` to `new_key`.
+///
+/// Databases which are currently open in this process are rekeyed
+/// through their connection pool so they stay usable. Databases which
+/// already open with `new_key` are skipped, which makes this safe to
+/// re-run after an interrupted rotation.
+pub async fn rekey_database_dir(
+    root: &Path,
+    old_key: &DbKey,
+    new_key: &DbKey,
+) -> DatabaseResult<()> {
+    for path in database_files(root)? {
+        match crate::conn::DATABASE_HANDLES.pool_handle(&path) {
+            Some(handle) => handle.rekey(new_key.clone()).await?,
+            None => {
+                let (path, old_key, new_key) = (path.clone(), old_key.clone(), new_key.clone());
+                tokio::task::spawn_blocking(move || rekey_database_file(&path, &old_key, &new_key))
+                    .await??
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Check if a database file can be read with the given key.
+pub fn key_opens(path: &Path, key: &DbKey) -> DatabaseResult<bool> {
+    let conn = open_with_key(path, Some(key))?;
+    Ok(conn
+        .query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))
+        .is_ok())
+}
+
+fn open_with_key(path: &Path, key: Option<&DbKey>) -> DatabaseResult<rusqlite::Connection> {
+    let mut conn = rusqlite::Connection::open(path)?;
+    crate::conn::apply_key(&mut conn, key)?;
+    Ok(conn)
+}
+
+#[cfg(feature = "db-encryption")]
+fn encrypt_database_file(path: &Path, key: &DbKey) -> DatabaseResult<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".encrypting");
+    let tmp = PathBuf::from(tmp);
+    if tmp.exists() {
+        // Left over from an interrupted migration.
+        std::fs::remove_file(&tmp)?;
+    }
+    {
+        let conn = open_with_key(path, None)?;
+        // Make sure nothing is left in the write ahead log.
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+        let user_version: u16 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        conn.execute(
+            "ATTACH DATABASE ?1 AS encrypted KEY ?2",
+            rusqlite::params![tmp.to_string_lossy(), key.pragma_value()],
+        )?;
+        conn.query_row("SELECT sqlcipher_export('encrypted')", [], |_| Ok(()))?;
+        // sqlcipher_export does not copy the schema version.
+        conn.pragma_update(
+            Some(rusqlite::DatabaseName::Attached("encrypted")),
+            "user_version",
+            &user_version,
+        )?;
+        conn.execute("DETACH DATABASE encrypted", [])?;
+    }
+    for suffix in ["-wal", "-shm"] {
+        let mut side = path.as_os_str().to_owned();
+        side.push(suffix);
+        let side = PathBuf::from(side);
+        if side.exists() {
+            std::fs::remove_file(side)?;
+        }
+    }
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+#[cfg(not(feature = "db-encryption"))]
+fn encrypt_database_file(_path: &Path, _key: &DbKey) -> DatabaseResult<()> {
+    Err(DatabaseError::EncryptionUnsupported)
+}
+
+#[cfg(feature = "db-encryption")]
+fn rekey_database_file(path: &Path, old_key: &DbKey, new_key: &DbKey) -> DatabaseResult<()> {
+    if !key_opens(path, old_key)? {
+        if key_opens(path, new_key)? {
+            return Ok(());
+        }
+        return Err(DatabaseError::WrongKey(path.to_owned()));
+    }
+    let conn = open_with_key(path, Some(old_key))?;
+    crate::conn::rekey_connection(&conn, new_key)?;
+    Ok(())
+}
+
+#[cfg(not(feature = "db-encryption"))]
+fn rekey_database_file(_path: &Path, _old_key: &DbKey, _new_key: &DbKey) -> DatabaseResult<()> {
+    Err(DatabaseError::EncryptionUnsupported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_is_not_printed() {
+        let key = DbKey::from_bytes([0xAB; 32]);
+        let debug = format!("{:?}", key);
+        assert!(!debug.contains("AB") && !debug.contains("ab"));
+        assert_eq!(key.pragma_value().len(), 67);
+        assert!(key.pragma_value().starts_with("x'ABAB"));
+    }
+
+    #[test]
+    fn finds_plaintext_databases() {
+        let td = tempfile::tempdir().unwrap();
+        let nested = td.path().join("nested");
+        std::fs::create_dir_all(&nested).unwrap();
+        let db = nested.join("db.sqlite3");
+        {
+            let conn = rusqlite::Connection::open(&db).unwrap();
+            conn.execute_batch("CREATE TABLE Thing (id INTEGER);")
+                .unwrap();
+        }
+        let empty = td.path().join("empty.sqlite3");
+        std::fs::File::create(&empty).unwrap();
+        let garbage = td.path().join("garbage.sqlite3");
+        std::fs::write(&garbage, [0x42; 64]).unwrap();
+        std::fs::write(td.path().join("db_key.salt"), [0; 32]).unwrap();
+
+        let files = database_files(td.path()).unwrap();
+        assert_eq!(files, vec![empty.clone(), garbage.clone(), db.clone()]);
+        assert!(is_plaintext(&db).unwrap());
+        assert!(is_plaintext(&empty).unwrap());
+        assert!(!is_plaintext(&garbage).unwrap());
+    }
+
+    #[cfg(not(feature = "db-encryption"))]
+    #[test]
+    fn opening_with_a_key_requires_the_feature() {
+        let td = tempfile::tempdir().unwrap();
+        let key = DbKey::from_bytes([1; 32]);
+        let result = DbWrite::open_with_key(
+            td.path(),
+            DbKindWasm,
+            crate::conn::DbSyncLevel::default(),
+            Some(key.clone()),
+        );
+        assert!(matches!(result, Err(DatabaseError::EncryptionUnsupported)));
+
+        let db = td.path().join("db.sqlite3");
+        rusqlite::Connection::open(&db)
+            .unwrap()
+            .execute_batch("CREATE TABLE Thing (id INTEGER);")
+            .unwrap();
+        assert_eq!(
+            encrypt_database_dir(td.path(), &key).unwrap_err(),
+            DatabaseError::EncryptionUnsupported
+        );
+    }
+}
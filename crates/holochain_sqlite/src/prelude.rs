@@ -4,6 +4,7 @@ pub use crate::db::*;
 pub use crate::error::*;
 pub use crate::exports::*;
 pub use crate::fresh_reader_test;
+pub use crate::key::DbKey;
 
 #[cfg(any(test, feature = "test_utils"))]
 pub use crate::test_utils::*;
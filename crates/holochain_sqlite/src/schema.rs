@@ -2,136 +2,250 @@ use once_cell::sync::Lazy;
 use rusqlite::Connection;
 
 use crate::db::DbKind;
+use crate::error::{DatabaseError, DatabaseResult};
 use crate::sql::*;
 
+#[cfg(test)]
+mod tests;
+
 pub static SCHEMA_CELL: Lazy<Schema> = Lazy::new(|| {
     let migration_0 = Migration::initial(sql_cell::SCHEMA);
-
-    Schema {
-        current_index: 0,
-        migrations: vec![migration_0],
-    }
+    let migration_1 = Migration::new(sql_cell::WARRANT_UP, Some(sql_cell::WARRANT_DOWN));
+    let migration_2 = Migration::new(
+        sql_cell::COUNTERSIGNING_SESSION_UP,
+        Some(sql_cell::COUNTERSIGNING_SESSION_DOWN),
+    );
+    let migration_3 = Migration::new(
+        sql_cell::WORKFLOW_QUEUES_UP,
+        Some(sql_cell::WORKFLOW_QUEUES_DOWN),
+    );
+
+    Schema::new(vec![migration_0, migration_1, migration_2, migration_3])
 });
 
 pub static SCHEMA_CONDUCTOR: Lazy<Schema> = Lazy::new(|| {
     let migration_0 = Migration::initial(sql_conductor::SCHEMA);
 
-    Schema {
-        current_index: 0,
-        migrations: vec![migration_0],
-    }
+    Schema::new(vec![migration_0])
 });
 
 pub static SCHEMA_WASM: Lazy<Schema> = Lazy::new(|| {
     let migration_0 = Migration::initial(sql_wasm::SCHEMA);
 
-    Schema {
-        current_index: 0,
-        migrations: vec![migration_0],
-    }
+    Schema::new(vec![migration_0])
 });
 
 pub static SCHEMA_P2P_STATE: Lazy<Schema> = Lazy::new(|| {
     let migration_0 = Migration::initial(sql_p2p_agent_store::SCHEMA);
 
-    Schema {
-        current_index: 0,
-        migrations: vec![migration_0],
-    }
+    Schema::new(vec![migration_0])
 });
 
 pub static SCHEMA_P2P_METRICS: Lazy<Schema> = Lazy::new(|| {
     let migration_0 = Migration::initial(sql_p2p_metrics::SCHEMA);
 
-    Schema {
-        current_index: 0,
-        migrations: vec![migration_0],
-    }
+    Schema::new(vec![migration_0])
 });
 
+/// An ordered list of migrations which together describe the
+/// current schema of a database.
+///
+/// The first migration is always the initial schema. Every following
+/// migration holds the SQL needed to move a database from the previous
+/// version to its own version, and optionally the SQL to move back.
+///
+/// The version of a database is tracked with the `user_version` pragma.
+/// A `user_version` of `0` means the database was either just created or
+/// was created before versioning existed. In both cases the initial schema
+/// is (idempotently) applied followed by every forward migration.
+/// Otherwise `user_version` is the index of the last applied migration plus one.
 pub struct Schema {
     current_index: usize,
     migrations: Vec<Migration>,
 }
 
 impl Schema {
+    /// Create a schema from a list of migrations.
+    /// The first migration must be created with [`Migration::initial`].
+    pub fn new(migrations: Vec<Migration>) -> Self {
+        assert!(
+            !migrations.is_empty(),
+            "A schema needs at least an initial migration"
+        );
+        Self {
+            current_index: migrations.len() - 1,
+            migrations,
+        }
+    }
+
+    /// The `user_version` a database will have once it is fully migrated.
+    pub fn current_version(&self) -> u16 {
+        (self.current_index + 1) as u16
+    }
+
     /// Determine if any database migrations need to run, and run them if so.
     /// The decision is based on the difference between this Schema's
     /// current_index and the user_version pragma value in the database itself.
     /// NB: The current_index is 0-based, and the user_version is 1-based.
-    pub fn initialize(
-        &self,
-        conn: &mut Connection,
-        db_kind: Option<DbKind>,
-    ) -> rusqlite::Result<()> {
-        let user_version: u16 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    ///
+    /// If the database was written by a newer version of this schema
+    /// an error is returned and the database is left untouched.
+    pub fn initialize(&self, conn: &mut Connection, db_kind: Option<DbKind>) -> DatabaseResult<()> {
+        let user_version = Self::user_version(conn)?;
         let db_kind = db_kind
             .as_ref()
             .map(ToString::to_string)
             .unwrap_or_else(|| "<no name>".to_string());
 
         if user_version == 0 {
-            // database just needs to be created / initialized
-            self.migrations[self.current_index].initialize(conn)?;
-            tracing::info!("database initialized: {}", db_kind);
+            // database either needs to be created or was created before
+            // versioning was tracked. The initial schema is idempotent
+            // so we can safely apply everything.
+            let txn = conn.transaction()?;
+            self.migrations[0].initialize(&txn)?;
+            for migration in &self.migrations[1..=self.current_index] {
+                migration.run(&txn)?;
+            }
+            txn.pragma_update(None, "user_version", &self.current_version())?;
+            txn.commit()?;
+            tracing::info!(
+                "database initialized: {} at version {}",
+                db_kind,
+                self.current_index
+            );
             return Ok(());
-        } else {
-            let current_index = user_version as usize - 1;
-            match current_index.cmp(&self.current_index) {
-                std::cmp::Ordering::Less => {
-                    // run forward migrations
-                    for v in current_index..self.current_index + 1 {
-                        self.migrations[v].run(conn)?;
-                    }
-                    // set the DB user_version so that next time we don't run
-                    // the same migration
-                    let new_user_version = (self.current_index + 1) as u16;
-                    conn.pragma_update(None, "user_version", &new_user_version)?;
-                    tracing::info!(
-                        "database forward migrated: {} from {} to {}",
-                        db_kind,
-                        current_index,
-                        self.current_index
-                    );
-                }
-                std::cmp::Ordering::Equal => {
-                    tracing::debug!(
-                        "database needed no migration or initialization, good to go: {}",
-                        db_kind
-                    );
-                }
-                std::cmp::Ordering::Greater => {
-                    unimplemented!("backward migrations unimplemented");
-                }
+        }
+
+        let current_index = user_version as usize - 1;
+        match current_index.cmp(&self.current_index) {
+            std::cmp::Ordering::Less => {
+                self.run_forward(conn, current_index, self.current_index)?;
+                tracing::info!(
+                    "database forward migrated: {} from {} to {}",
+                    db_kind,
+                    current_index,
+                    self.current_index
+                );
+            }
+            std::cmp::Ordering::Equal => {
+                tracing::debug!(
+                    "database needed no migration or initialization, good to go: {}",
+                    db_kind
+                );
+            }
+            std::cmp::Ordering::Greater => {
+                return Err(DatabaseError::SchemaVersionTooNew {
+                    db_kind,
+                    db_version: user_version,
+                    supported_version: self.current_version(),
+                });
             }
         }
 
         Ok(())
     }
+
+    /// Migrate an existing database to the migration at `target_index`,
+    /// running forward or backward migrations as needed.
+    ///
+    /// This is mostly useful for tooling that needs to roll a database back
+    /// before an older version of holochain can open it. Fails without
+    /// changing the database if any required backward migration is missing.
+    pub fn migrate_to(&self, conn: &mut Connection, target_index: usize) -> DatabaseResult<()> {
+        if target_index > self.current_index {
+            return Err(DatabaseError::UnknownMigration(target_index));
+        }
+        let user_version = Self::user_version(conn)?;
+        if user_version == 0 {
+            return Err(DatabaseError::SchemaUninitialized);
+        }
+        let current_index = user_version as usize - 1;
+        if current_index > self.current_index {
+            return Err(DatabaseError::SchemaVersionTooNew {
+                db_kind: "<no name>".to_string(),
+                db_version: user_version,
+                supported_version: self.current_version(),
+            });
+        }
+        match current_index.cmp(&target_index) {
+            std::cmp::Ordering::Less => self.run_forward(conn, current_index, target_index),
+            std::cmp::Ordering::Equal => Ok(()),
+            std::cmp::Ordering::Greater => self.run_backward(conn, current_index, target_index),
+        }
+    }
+
+    /// Run the forward migrations after `from` up to and including `to`
+    /// in a single transaction.
+    fn run_forward(&self, conn: &mut Connection, from: usize, to: usize) -> DatabaseResult<()> {
+        let txn = conn.transaction()?;
+        for migration in &self.migrations[from + 1..=to] {
+            migration.run(&txn)?;
+        }
+        // set the DB user_version so that next time we don't run
+        // the same migration
+        txn.pragma_update(None, "user_version", &((to + 1) as u16))?;
+        txn.commit()?;
+        Ok(())
+    }
+
+    /// Undo the migrations from `from` down to but not including `to`
+    /// in a single transaction.
+    fn run_backward(&self, conn: &mut Connection, from: usize, to: usize) -> DatabaseResult<()> {
+        let txn = conn.transaction()?;
+        for index in (to + 1..=from).rev() {
+            self.migrations[index].revert(&txn, index)?;
+        }
+        txn.pragma_update(None, "user_version", &((to + 1) as u16))?;
+        txn.commit()?;
+        Ok(())
+    }
+
+    fn user_version(conn: &Connection) -> DatabaseResult<u16> {
+        Ok(conn.pragma_query_value(None, "user_version", |row| row.get(0))?)
+    }
 }
 
 pub struct Migration {
     schema: Sql,
-    _forward: Sql,
-    _backward: Option<Sql>,
+    forward: Sql,
+    backward: Option<Sql>,
 }
 
 impl Migration {
+    /// The initial schema of a database.
     pub fn initial(schema: &str) -> Self {
         Self {
             schema: schema.into(),
-            _forward: "".into(),
-            _backward: None,
+            forward: "".into(),
+            backward: None,
+        }
+    }
+
+    /// A migration from the previous version of a schema.
+    /// Without `backward` SQL this migration can not be reverted.
+    pub fn new(forward: &str, backward: Option<&str>) -> Self {
+        Self {
+            schema: "".into(),
+            forward: forward.into(),
+            backward: backward.map(Into::into),
         }
     }
 
-    pub fn initialize(&self, conn: &mut Connection) -> rusqlite::Result<()> {
+    pub fn initialize(&self, conn: &Connection) -> rusqlite::Result<()> {
         conn.execute_batch(&self.schema)?;
         Ok(())
     }
 
-    pub fn run(&self, _conn: &mut Connection) -> rusqlite::Result<()> {
-        unimplemented!("actual migrations not yet implemented")
+    pub fn run(&self, conn: &Connection) -> rusqlite::Result<()> {
+        conn.execute_batch(&self.forward)?;
+        Ok(())
+    }
+
+    fn revert(&self, conn: &Connection, index: usize) -> DatabaseResult<()> {
+        match &self.backward {
+            Some(backward) => Ok(conn.execute_batch(backward)?),
+            None => Err(DatabaseError::NoBackwardMigration(index)),
+        }
     }
 }
 
@@ -0,0 +1,241 @@
+use super::*;
+use crate::prelude::*;
+use holo_hash::DnaHash;
+use kitsune_p2p::KitsuneSpace;
+use std::path::Path;
+use std::sync::Arc;
+
+fn user_version(conn: &Connection) -> u16 {
+    conn.pragma_query_value(None, "user_version", |row| row.get(0))
+        .unwrap()
+}
+
+fn table_exists(conn: &Connection, name: &str) -> bool {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?)",
+        [name],
+        |row| row.get(0),
+    )
+    .unwrap()
+}
+
+/// A schema with a few migrations which mirrors the kind of changes
+/// we expect to make to the real schemas.
+fn test_schema() -> Schema {
+    Schema::new(vec![
+        Migration::initial("CREATE TABLE IF NOT EXISTS Thing (id INTEGER PRIMARY KEY, name TEXT);"),
+        Migration::new(
+            "ALTER TABLE Thing ADD COLUMN size INTEGER NOT NULL DEFAULT 0;",
+            Some("ALTER TABLE Thing DROP COLUMN size;"),
+        ),
+        Migration::new(
+            "CREATE TABLE Other (id INTEGER PRIMARY KEY); INSERT INTO Other (id) SELECT id FROM Thing;",
+            Some("DROP TABLE Other;"),
+        ),
+    ])
+}
+
+/// Create a database at the given version of the [`test_schema`]
+/// with a single row in it.
+fn fixture_at(path: &Path, index: usize) -> Connection {
+    let schema = test_schema();
+    let mut conn = Connection::open(path.join("fixture.sqlite3")).unwrap();
+    conn.execute_batch(&schema.migrations[0].schema).unwrap();
+    conn.execute("INSERT INTO Thing (id, name) VALUES (1, 'one')", [])
+        .unwrap();
+    conn.pragma_update(None, "user_version", &1u16).unwrap();
+    if index > 0 {
+        schema.run_forward(&mut conn, 0, index).unwrap();
+    }
+    conn
+}
+
+#[test]
+fn new_database_is_fully_migrated() {
+    let mut conn = Connection::open_in_memory().unwrap();
+    let schema = test_schema();
+    schema.initialize(&mut conn, None).unwrap();
+
+    assert_eq!(user_version(&conn), 3);
+    assert!(table_exists(&conn, "Thing"));
+    assert!(table_exists(&conn, "Other"));
+
+    // Running again is a no-op.
+    schema.initialize(&mut conn, None).unwrap();
+    assert_eq!(user_version(&conn), 3);
+}
+
+#[test]
+fn fixture_database_migrates_forward() {
+    let td = tempfile::tempdir().unwrap();
+    let mut conn = fixture_at(td.path(), 0);
+    assert_eq!(user_version(&conn), 1);
+
+    test_schema().initialize(&mut conn, None).unwrap();
+
+    assert_eq!(user_version(&conn), 3);
+    let (name, size): (String, i64) = conn
+        .query_row("SELECT name, size FROM Thing WHERE id = 1", [], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .unwrap();
+    assert_eq!(name, "one");
+    assert_eq!(size, 0);
+    let copied: i64 = conn
+        .query_row("SELECT COUNT(*) FROM Other", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(copied, 1);
+}
+
+#[test]
+fn unversioned_database_is_upgraded_without_data_loss() {
+    let td = tempfile::tempdir().unwrap();
+    let schema = test_schema();
+    let mut conn = Connection::open(td.path().join("fixture.sqlite3")).unwrap();
+    conn.execute_batch(&schema.migrations[0].schema).unwrap();
+    conn.execute("INSERT INTO Thing (id, name) VALUES (1, 'one')", [])
+        .unwrap();
+    assert_eq!(user_version(&conn), 0);
+
+    schema.initialize(&mut conn, None).unwrap();
+
+    assert_eq!(user_version(&conn), 3);
+    let count: i64 = conn
+        .query_row("SELECT COUNT(*) FROM Other", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(count, 1);
+}
+
+#[test]
+fn failed_migration_is_rolled_back() {
+    let td = tempfile::tempdir().unwrap();
+    let mut conn = fixture_at(td.path(), 0);
+    let schema = Schema::new(vec![
+        Migration::initial(""),
+        Migration::new("ALTER TABLE Thing ADD COLUMN size INTEGER;", None),
+        Migration::new("THIS IS NOT SQL;", None),
+    ]);
+
+    assert!(schema.initialize(&mut conn, None).is_err());
+
+    assert_eq!(user_version(&conn), 1);
+    assert!(conn.prepare("SELECT size FROM Thing").is_err());
+}
+
+#[test]
+fn newer_database_is_refused() {
+    let td = tempfile::tempdir().unwrap();
+    let mut conn = fixture_at(td.path(), 2);
+    let older = Schema::new(vec![test_schema().migrations.remove(0)]);
+
+    let err = older.initialize(&mut conn, None).unwrap_err();
+
+    assert!(matches!(
+        err,
+        DatabaseError::SchemaVersionTooNew {
+            db_version: 3,
+            supported_version: 1,
+            ..
+        }
+    ));
+    assert_eq!(user_version(&conn), 3);
+    assert!(table_exists(&conn, "Other"));
+}
+
+#[test]
+fn migrate_backward_and_forward_again() {
+    let td = tempfile::tempdir().unwrap();
+    let mut conn = fixture_at(td.path(), 2);
+    let schema = test_schema();
+
+    schema.migrate_to(&mut conn, 0).unwrap();
+    assert_eq!(user_version(&conn), 1);
+    assert!(!table_exists(&conn, "Other"));
+    assert!(conn.prepare("SELECT size FROM Thing").is_err());
+
+    schema.migrate_to(&mut conn, 2).unwrap();
+    assert_eq!(user_version(&conn), 3);
+    assert!(table_exists(&conn, "Other"));
+
+    assert_eq!(
+        schema.migrate_to(&mut conn, 3).unwrap_err(),
+        DatabaseError::UnknownMigration(3)
+    );
+}
+
+#[test]
+fn backward_migration_requires_backward_sql() {
+    let td = tempfile::tempdir().unwrap();
+    let mut conn = fixture_at(td.path(), 0);
+    let schema = Schema::new(vec![
+        Migration::initial(""),
+        Migration::new("CREATE TABLE A (id INTEGER);", Some("DROP TABLE A;")),
+        Migration::new("CREATE TABLE B (id INTEGER);", None),
+    ]);
+    schema.initialize(&mut conn, None).unwrap();
+
+    assert_eq!(
+        schema.migrate_to(&mut conn, 0).unwrap_err(),
+        DatabaseError::NoBackwardMigration(2)
+    );
+    // Nothing was reverted.
+    assert_eq!(user_version(&conn), 3);
+    assert!(table_exists(&conn, "A"));
+    assert!(table_exists(&conn, "B"));
+}
+
+/// Every kind of database can be opened from a fixture that was created
+/// before schema versions were tracked.
+#[test]
+fn unversioned_fixtures_open_for_every_db_kind() {
+    let td = tempfile::tempdir().unwrap();
+    let dna = Arc::new(DnaHash::from_raw_36(vec![0; 36]));
+    let space = Arc::new(KitsuneSpace(vec![0; 36]));
+
+    fn check<Kind: DbKindT>(path: &Path, kind: Kind, schema: &Schema, table: &str) {
+        let file = path.join(kind.filename());
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        {
+            let conn = Connection::open(&file).unwrap();
+            conn.execute_batch(&schema.migrations[0].schema).unwrap();
+            assert_eq!(user_version(&conn), 0);
+        }
+        let db = DbWrite::test(path, kind).unwrap();
+        let conn = db.conn().unwrap();
+        assert_eq!(user_version(&conn), schema.current_version());
+        assert!(table_exists(&conn, table));
+    }
+
+    check(
+        td.path(),
+        DbKindAuthored(dna.clone()),
+        &SCHEMA_CELL,
+        "Action",
+    );
+    check(td.path(), DbKindDht(dna.clone()), &SCHEMA_CELL, "Warrant");
+    check(
+        td.path(),
+        DbKindCache(dna),
+        &SCHEMA_CELL,
+        "ValidationDependency",
+    );
+    check(
+        td.path(),
+        DbKindConductor,
+        &SCHEMA_CONDUCTOR,
+        "ConductorState",
+    );
+    check(td.path(), DbKindWasm, &SCHEMA_WASM, "Wasm");
+    check(
+        td.path(),
+        DbKindP2pAgents(space.clone()),
+        &SCHEMA_P2P_STATE,
+        "p2p_agent_store",
+    );
+    check(
+        td.path(),
+        DbKindP2pMetrics(space),
+        &SCHEMA_P2P_METRICS,
+        "p2p_metrics",
+    );
+}
@@ -1,5 +1,15 @@
 pub mod sql_cell {
     pub(crate) const SCHEMA: &str = include_str!("sql/cell/schema.sql");
+    pub(crate) const WARRANT_UP: &str = include_str!("sql/cell/migrations/warrant_up.sql");
+    pub(crate) const WARRANT_DOWN: &str = include_str!("sql/cell/migrations/warrant_down.sql");
+    pub(crate) const COUNTERSIGNING_SESSION_UP: &str =
+        include_str!("sql/cell/migrations/countersigning_session_up.sql");
+    pub(crate) const COUNTERSIGNING_SESSION_DOWN: &str =
+        include_str!("sql/cell/migrations/countersigning_session_down.sql");
+    pub(crate) const WORKFLOW_QUEUES_UP: &str =
+        include_str!("sql/cell/migrations/workflow_queues_up.sql");
+    pub(crate) const WORKFLOW_QUEUES_DOWN: &str =
+        include_str!("sql/cell/migrations/workflow_queues_down.sql");
     pub const UPDATE_INTEGRATE_DEP_ACTIVITY: &str =
         include_str!("sql/cell/update_dep_activity.sql");
     pub const ACTIVITY_INTEGRATED_UPPER_BOUND: &str =
@@ -21,10 +31,18 @@ pub mod sql_cell {
         include_str!("sql/cell/fetch_hashes/fetch_op_hashes_p2.sql");
 
     pub const FETCH_OP_REGION: &str = include_str!("sql/cell/fetch_op_region.sql");
+    pub const FETCH_OP_REGION_DATA_SINCE: &str =
+        include_str!("sql/cell/fetch_op_region_data_since.sql");
     pub const FETCH_OPS_BY_REGION: &str = include_str!("sql/cell/fetch_ops_by_region.sql");
 
     pub const FETCH_OP: &str = include_str!("sql/cell/fetch_op.sql");
 
+    pub const QUEUE_DEPTHS: &str = include_str!("sql/cell/queue_depths.sql");
+    pub const PUBLISH_QUEUE_DEPTH: &str = include_str!("sql/cell/publish_queue_depth.sql");
+    pub const BLOCKED_OPS: &str = include_str!("sql/cell/blocked_ops.sql");
+    pub const REQUEUE_OP: &str = include_str!("sql/cell/requeue_op.sql");
+    pub const ABANDONED_OPS: &str = include_str!("sql/cell/abandoned_ops.sql");
+
     pub mod schedule {
         pub const UPDATE: &str = include_str!("sql/cell/schedule/update.sql");
         pub const DELETE: &str = include_str!("sql/cell/schedule/delete.sql");
@@ -34,6 +52,21 @@ pub mod sql_cell {
         pub const DELETE_LIVE_EPHEMERAL: &str =
             include_str!("sql/cell/schedule/delete_live_ephemeral.sql");
     }
+    pub mod cache {
+        pub const OLDEST_ACTIONS: &str = include_str!("sql/cell/cache/oldest_actions.sql");
+        pub const DELETE_ACTION: &str = include_str!("sql/cell/cache/delete_action.sql");
+        pub const ORPHAN_ENTRIES_SIZE: &str =
+            include_str!("sql/cell/cache/orphan_entries_size.sql");
+        pub const DELETE_ORPHAN_ENTRIES: &str =
+            include_str!("sql/cell/cache/delete_orphan_entries.sql");
+        pub const SIZE: &str = include_str!("sql/cell/cache/size.sql");
+    }
+    pub mod arc_gc {
+        pub const COLLECTABLE_OPS: &str = include_str!("sql/cell/arc_gc/collectable_ops.sql");
+        pub const DELETE_OP: &str = include_str!("sql/cell/arc_gc/delete_op.sql");
+        pub const DELETE_ORPHAN_ACTIONS: &str =
+            include_str!("sql/cell/arc_gc/delete_orphan_actions.sql");
+    }
     pub mod state_dump {
         pub const DHT_OPS_IN_INTEGRATION_LIMBO: &str =
             include_str!("sql/cell/state_dump/dht_ops_in_integration_limbo.sql");
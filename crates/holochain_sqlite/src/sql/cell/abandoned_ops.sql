@@ -0,0 +1,9 @@
+-- no-sql-format --
+
+-- The ops whose validation was abandoned.
+SELECT
+  hash
+FROM
+  DhtOp
+WHERE
+  validation_status = :abandoned
@@ -0,0 +1,54 @@
+-- no-sql-format --
+
+SELECT
+  DhtOp.rowid,
+  DhtOp.hash,
+  DhtOp.type,
+  DhtOp.storage_center_loc,
+  DhtOp.validation_status,
+  Action.author
+FROM
+  DhtOp
+  JOIN Action ON DhtOp.action_hash = Action.hash
+WHERE
+  DhtOp.rowid > :last_rowid
+  AND DhtOp.when_integrated IS NOT NULL
+  AND DhtOp.when_integrated < :integrated_before
+  -- An author's activity is only collected once all of it can be,
+  -- so no part of a chain is left behind.
+  AND (
+    DhtOp.type != :activity
+    OR NOT EXISTS (
+      SELECT
+        1
+      FROM
+        DhtOp AS Activity
+      WHERE
+        Activity.type = :activity
+        AND Activity.basis_hash = DhtOp.basis_hash
+        AND (
+          Activity.when_integrated IS NULL
+          OR Activity.when_integrated >= :integrated_before
+          OR EXISTS (
+            SELECT
+              1
+            FROM
+              ValidationReceipt
+            WHERE
+              ValidationReceipt.op_hash = Activity.hash
+          )
+        )
+    )
+  )
+  AND NOT EXISTS (
+    SELECT
+      1
+    FROM
+      ValidationReceipt
+    WHERE
+      ValidationReceipt.op_hash = DhtOp.hash
+  )
+ORDER BY
+  DhtOp.rowid
+LIMIT
+  :limit
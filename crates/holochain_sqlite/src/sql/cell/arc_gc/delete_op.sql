@@ -0,0 +1,6 @@
+-- no-sql-format --
+
+DELETE FROM
+  DhtOp
+WHERE
+  hash = :hash
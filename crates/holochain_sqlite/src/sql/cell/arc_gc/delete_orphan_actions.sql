@@ -0,0 +1,13 @@
+-- no-sql-format --
+
+DELETE FROM
+  Action
+WHERE
+  NOT EXISTS (
+    SELECT
+      1
+    FROM
+      DhtOp
+    WHERE
+      DhtOp.action_hash = Action.hash
+  )
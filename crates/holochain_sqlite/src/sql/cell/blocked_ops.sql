@@ -0,0 +1,37 @@
+-- no-sql-format --
+
+-- Ops which can't leave validation limbo until a dependency is found,
+-- along with the dependencies they are waiting on.
+SELECT
+  DhtOp.hash,
+  DhtOp.type,
+  DhtOp.validation_stage,
+  ValidationDependency.dependency
+FROM
+  DhtOp
+  JOIN ValidationDependency ON ValidationDependency.op_hash = DhtOp.hash
+WHERE
+  DhtOp.when_integrated IS NULL
+  AND DhtOp.validation_status IS NULL
+  AND DhtOp.validation_stage IN (0, 2)
+
+UNION ALL
+
+-- Ops which can't be integrated until the action they depend on is.
+SELECT
+  DhtOp.hash,
+  DhtOp.type,
+  DhtOp.validation_stage,
+  DhtOp.dependency
+FROM
+  DhtOp
+WHERE
+  DhtOp.when_integrated IS NULL
+  AND DhtOp.validation_stage = 3
+  AND DhtOp.dependency IS NOT NULL
+  AND NOT EXISTS (
+    SELECT 1 FROM DhtOp AS Dependency
+    WHERE
+      Dependency.action_hash = DhtOp.dependency
+      AND Dependency.when_integrated IS NOT NULL
+  )
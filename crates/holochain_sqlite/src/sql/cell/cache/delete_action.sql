@@ -0,0 +1,7 @@
+-- no-sql-format --
+
+-- The ops of the action are deleted with it.
+DELETE FROM
+  Action
+WHERE
+  hash = :hash
@@ -0,0 +1,13 @@
+-- no-sql-format --
+
+DELETE FROM
+  Entry
+WHERE
+  hash NOT IN (
+    SELECT
+      entry_hash
+    FROM
+      Action
+    WHERE
+      entry_hash IS NOT NULL
+  )
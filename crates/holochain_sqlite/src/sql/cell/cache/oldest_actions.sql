@@ -0,0 +1,20 @@
+-- no-sql-format --
+
+-- The actions in the cache which were least recently fetched before
+-- :before, oldest first, with the size of each action.
+-- The cascade sets `when_integrated` every time it fetches an op
+-- so it is the last time the action was fetched.
+SELECT
+  Action.hash,
+  LENGTH(Action.blob) AS size
+FROM
+  Action
+  JOIN DhtOp ON DhtOp.action_hash = Action.hash
+GROUP BY
+  Action.hash
+HAVING
+  MAX(DhtOp.when_integrated) < :before
+ORDER BY
+  MAX(DhtOp.when_integrated) ASC
+LIMIT
+  :limit
@@ -0,0 +1,15 @@
+-- no-sql-format --
+
+SELECT
+  IFNULL(SUM(LENGTH(blob)), 0)
+FROM
+  Entry
+WHERE
+  hash NOT IN (
+    SELECT
+      entry_hash
+    FROM
+      Action
+    WHERE
+      entry_hash IS NOT NULL
+  )
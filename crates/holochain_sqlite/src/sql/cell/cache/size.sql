@@ -0,0 +1,15 @@
+-- no-sql-format --
+
+-- The size of the actions and entries in the cache.
+SELECT
+  (
+    SELECT
+      IFNULL(SUM(LENGTH(blob)), 0)
+    FROM
+      Action
+  ) + (
+    SELECT
+      IFNULL(SUM(LENGTH(blob)), 0)
+    FROM
+      Entry
+  )
@@ -0,0 +1,14 @@
+SELECT
+  DhtOp.rowid AS rowid,
+  DhtOp.hash AS hash,
+  DhtOp.storage_center_loc AS storage_center_loc,
+  DhtOp.authored_timestamp AS authored_timestamp,
+  LENGTH(Action.blob) + IFNULL(LENGTH(Entry.blob), 0) AS size
+FROM
+  DhtOp
+  JOIN Action ON DhtOp.action_hash = Action.hash
+  LEFT JOIN Entry ON Action.entry_hash = Entry.hash
+WHERE
+  DhtOp.rowid > :last_rowid
+ORDER BY
+  DhtOp.rowid
@@ -0,0 +1,3 @@
+-- no-sql-format --
+
+DROP TABLE IF EXISTS CountersigningSession;
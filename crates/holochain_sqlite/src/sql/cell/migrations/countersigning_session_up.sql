@@ -0,0 +1,16 @@
+-- no-sql-format --
+
+-- Ops collected by an authority for countersigning sessions
+-- that are not yet complete.
+CREATE TABLE IF NOT EXISTS CountersigningSession (
+    entry_hash              BLOB           NOT NULL,
+    action_hash             BLOB           NOT NULL,
+    op_hash                 BLOB           NOT NULL,
+    -- The StoreEntry op of this signer.
+    blob                    BLOB           NOT NULL,
+    -- The hashes of every action the session needs to be complete.
+    required_actions        BLOB           NOT NULL,
+    -- The end of the session as a Timestamp (microseconds).
+    expires_at_timestamp    INTEGER        NOT NULL,
+    PRIMARY KEY (entry_hash, action_hash) ON CONFLICT REPLACE
+);
@@ -0,0 +1,4 @@
+-- no-sql-format --
+
+DROP INDEX IF EXISTS Warrant_warrantee_idx;
+DROP TABLE IF EXISTS Warrant;
@@ -0,0 +1,13 @@
+-- no-sql-format --
+
+-- Warrants issued by or received from authorities.
+CREATE TABLE IF NOT EXISTS Warrant (
+    -- Hash of the author and the proof so the same warrant
+    -- from the same author is only stored once.
+    hash            BLOB           PRIMARY KEY ON CONFLICT IGNORE,
+    author          BLOB           NOT NULL,
+    warrantee       BLOB           NOT NULL,
+    timestamp       INTEGER        NOT NULL,
+    blob            BLOB           NOT NULL
+);
+CREATE INDEX IF NOT EXISTS Warrant_warrantee_idx ON Warrant ( warrantee );
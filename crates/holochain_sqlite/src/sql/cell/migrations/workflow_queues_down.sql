@@ -0,0 +1,4 @@
+-- no-sql-format --
+
+DROP TABLE IF EXISTS ValidationDependency;
+ALTER TABLE DhtOp DROP COLUMN when_stored;
@@ -0,0 +1,15 @@
+-- no-sql-format --
+
+-- When the op was first stored in this database, so the age
+-- of the ops waiting in the workflow queues can be shown.
+ALTER TABLE DhtOp ADD COLUMN when_stored INTEGER NULL;
+
+-- The dependencies an op in validation limbo is waiting on.
+-- These are replaced each time the op's validation stage is set,
+-- and are deleted along with the op.
+CREATE TABLE IF NOT EXISTS ValidationDependency (
+    op_hash     BLOB    NOT NULL,
+    dependency  BLOB    NOT NULL,
+    PRIMARY KEY (op_hash, dependency) ON CONFLICT IGNORE,
+    FOREIGN KEY(op_hash) REFERENCES DhtOp(hash) ON DELETE CASCADE
+);
@@ -0,0 +1,14 @@
+-- no-sql-format --
+
+-- The number of public ops authored by an agent which haven't
+-- got enough validation receipts yet, and when the oldest was stored.
+SELECT
+  COUNT(DhtOp.rowid) AS depth,
+  MIN(IFNULL(DhtOp.when_stored, DhtOp.authored_timestamp)) AS oldest
+FROM
+  Action
+  JOIN DhtOp ON DhtOp.action_hash = Action.hash
+WHERE
+  Action.author = :author
+  AND DhtOp.receipts_complete IS NULL
+  AND (DhtOp.type != :store_entry OR Action.private_entry = 0)
@@ -0,0 +1,33 @@
+-- no-sql-format --
+
+-- The number of ops waiting in each stage of the validation pipeline
+-- and when the longest waiting op of each stage was stored.
+-- Ops stored before `when_stored` existed fall back to when they were authored.
+SELECT
+  IFNULL(SUM(
+    validation_status IS NULL
+    AND (validation_stage IS NULL OR validation_stage = 0)
+  ), 0) AS sys_validation,
+  MIN(CASE
+    WHEN validation_status IS NULL
+    AND (validation_stage IS NULL OR validation_stage = 0)
+    THEN IFNULL(when_stored, authored_timestamp)
+  END) AS sys_validation_oldest,
+  IFNULL(SUM(
+    validation_status IS NULL
+    AND validation_stage IN (1, 2)
+  ), 0) AS app_validation,
+  MIN(CASE
+    WHEN validation_status IS NULL
+    AND validation_stage IN (1, 2)
+    THEN IFNULL(when_stored, authored_timestamp)
+  END) AS app_validation_oldest,
+  IFNULL(SUM(validation_stage = 3), 0) AS integration,
+  MIN(CASE
+    WHEN validation_stage = 3
+    THEN IFNULL(when_stored, authored_timestamp)
+  END) AS integration_oldest
+FROM
+  DhtOp
+WHERE
+  when_integrated IS NULL
@@ -0,0 +1,20 @@
+-- no-sql-format --
+
+-- Put an op back at the start of the validation pipeline.
+-- Only ops which aren't integrated, or whose validation was abandoned,
+-- can be re-queued. Integrated agent activity is never re-queued because
+-- the activity query cache assumes integrated activity stays integrated.
+UPDATE
+  DhtOp
+SET
+  validation_status = NULL,
+  validation_stage = NULL,
+  when_integrated = NULL,
+  num_validation_attempts = NULL,
+  last_validation_attempt = NULL
+WHERE
+  hash = :hash
+  AND (
+    when_integrated IS NULL
+    OR (validation_status = :abandoned AND type != :activity)
+  )
@@ -1,18 +1,22 @@
 SELECT
-  SUM(
-    -- first, sum up the 0.0 - 1.0 coverage of everyone contained in our arc
-    CASE
-      -- if start is before end
-      WHEN (storage_start_loc <= storage_end_loc) THEN IFNULL(
-        CAST(storage_end_loc AS FLOAT) - CAST(storage_start_loc AS FLOAT),
-        0.0
-      )
-      ELSE -- else if start is after end
-      IFNULL(
-        4294967295.0 - CAST(storage_start_loc AS FLOAT) + CAST(storage_end_loc AS FLOAT),
-        0.0
-      )
-    END
+  -- with no peers in the arc there is no coverage
+  IFNULL(
+    SUM(
+      -- first, sum up the 0.0 - 1.0 coverage of everyone contained in our arc
+      CASE
+        -- if start is before end
+        WHEN (storage_start_loc <= storage_end_loc) THEN IFNULL(
+          CAST(storage_end_loc AS FLOAT) - CAST(storage_start_loc AS FLOAT),
+          0.0
+        )
+        ELSE -- else if start is after end
+        IFNULL(
+          4294967295.0 - CAST(storage_start_loc AS FLOAT) + CAST(storage_end_loc AS FLOAT),
+          0.0
+        )
+      END
+    ),
+    0.0
   ) / (
     -- then extrapolate assuming similar coverage for the rest of the arc
     CASE
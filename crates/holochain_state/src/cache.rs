@@ -0,0 +1,204 @@
+//! Eviction of data from the cache databases.
+//!
+//! The cache holds data fetched from the network by the cascade,
+//! which fetches it again if it is needed after it has been evicted.
+//! Data is evicted a whole action at a time, along with all of its ops,
+//! so the cache never holds only part of what was fetched for an action.
+
+use std::time::Duration;
+
+use holo_hash::ActionHash;
+use holochain_sqlite::db::DbKindCache;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_sqlite::rusqlite::Transaction;
+use holochain_sqlite::sql::sql_cell;
+use holochain_types::db::DbWrite;
+use holochain_zome_types::Timestamp;
+
+use crate::mutations::StateMutationResult;
+use crate::query::StateQueryResult;
+
+/// The number of actions evicted in each transaction.
+pub const EVICTION_BATCH_SIZE: usize = 100;
+
+/// The size of the actions and entries in a cache database, in bytes.
+pub fn cache_size(txn: &Transaction) -> StateQueryResult<u64> {
+    let size: i64 = txn.query_row(sql_cell::cache::SIZE, [], |row| row.get(0))?;
+    Ok(size as u64)
+}
+
+/// Evict up to `limit` of the actions which were least recently fetched
+/// before `before`, along with their ops and any entries which no
+/// remaining action refers to.
+///
+/// Returns the number of actions evicted and the bytes freed.
+pub fn evict_actions(
+    txn: &mut Transaction,
+    before: Timestamp,
+    limit: usize,
+) -> StateMutationResult<(usize, u64)> {
+    let actions = txn
+        .prepare(sql_cell::cache::OLDEST_ACTIONS)?
+        .query_map(
+            named_params! {
+                ":before": before,
+                ":limit": limit as i64,
+            },
+            |row| Ok((row.get::<_, ActionHash>(0)?, row.get::<_, i64>(1)?)),
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    if actions.is_empty() {
+        return Ok((0, 0));
+    }
+
+    let mut freed = 0;
+    for (hash, size) in &actions {
+        txn.execute(
+            sql_cell::cache::DELETE_ACTION,
+            named_params! {
+                ":hash": hash,
+            },
+        )?;
+        freed += *size as u64;
+    }
+    let entries_size: i64 =
+        txn.query_row(sql_cell::cache::ORPHAN_ENTRIES_SIZE, [], |row| row.get(0))?;
+    txn.execute(sql_cell::cache::DELETE_ORPHAN_ENTRIES, [])?;
+    Ok((actions.len(), freed + entries_size as u64))
+}
+
+/// Evict data from a cache database until it is within the given limits.
+///
+/// Everything last fetched longer than `max_age` ago is evicted first,
+/// then the least recently fetched actions until the actions and entries
+/// in the cache take up no more than `max_bytes`.
+///
+/// Returns the number of actions evicted.
+pub async fn evict_cache(
+    db: &DbWrite<DbKindCache>,
+    max_age: Option<Duration>,
+    max_bytes: Option<u64>,
+) -> StateMutationResult<usize> {
+    let mut evicted = 0;
+    if let Some(max_age) = max_age {
+        // A max age reaching back past the start of time evicts nothing.
+        let before = (Timestamp::now() - max_age).unwrap_or(Timestamp::MIN);
+        loop {
+            let (n, _) = db
+                .async_commit(move |txn| evict_actions(txn, before, EVICTION_BATCH_SIZE))
+                .await?;
+            evicted += n;
+            if n < EVICTION_BATCH_SIZE {
+                break;
+            }
+        }
+    }
+    if let Some(max_bytes) = max_bytes {
+        let mut size = db.async_reader(|txn| cache_size(&txn)).await?;
+        while size > max_bytes {
+            let (n, freed) = db
+                .async_commit(|txn| evict_actions(txn, Timestamp::MAX, EVICTION_BATCH_SIZE))
+                .await?;
+            if n == 0 {
+                break;
+            }
+            evicted += n;
+            size = size.saturating_sub(freed);
+        }
+    }
+    Ok(evicted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mutations;
+    use ::fixt::prelude::*;
+    use holo_hash::EntryHash;
+    use holo_hash::HasHash;
+    use holochain_sqlite::prelude::*;
+    use holochain_types::action::NewEntryAction;
+    use holochain_types::dht_op::DhtOp;
+    use holochain_types::dht_op::DhtOpHashed;
+    use holochain_zome_types::fixt::CreateFixturator;
+    use holochain_zome_types::fixt::EntryFixturator;
+    use holochain_zome_types::fixt::SignatureFixturator;
+    use holochain_zome_types::Entry;
+
+    fn store_entry_op(entry: &Entry) -> DhtOpHashed {
+        let mut create = fixt!(Create);
+        create.entry_hash = EntryHash::with_data_sync(entry);
+        DhtOpHashed::from_content_sync(DhtOp::StoreEntry(
+            fixt!(Signature),
+            NewEntryAction::Create(create),
+            Box::new(entry.clone()),
+        ))
+    }
+
+    fn count(txn: &Transaction, table: &str) -> usize {
+        txn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+            row.get::<_, i64>(0)
+        })
+        .unwrap() as usize
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn evicts_least_recently_fetched_actions() {
+        let test_db = crate::test_utils::test_cache_db();
+        let db = test_db.to_db();
+        let now = Timestamp::now();
+        let two_hours_ago = (now - Duration::from_secs(2 * 60 * 60)).unwrap();
+
+        // - Two actions for the same entry, one fetched long ago,
+        // and an action for another entry fetched just now.
+        let shared_entry = fixt!(Entry);
+        let old = store_entry_op(&shared_entry);
+        let recent = store_entry_op(&shared_entry);
+        let other = store_entry_op(&fixt!(Entry));
+        db.async_commit(move |txn| {
+            for (op, fetched) in [(old, two_hours_ago), (recent, now), (other, now)] {
+                mutations::insert_op(txn, &op)?;
+                mutations::set_when_integrated(txn, op.as_hash(), fetched)?;
+            }
+            StateMutationResult::Ok(())
+        })
+        .await
+        .unwrap();
+        let full_size = db.async_reader(|txn| cache_size(&txn)).await.unwrap();
+
+        // - Nothing is evicted while the cache is within its limits.
+        let evicted = evict_cache(&db, Some(Duration::from_secs(3 * 60 * 60)), Some(full_size))
+            .await
+            .unwrap();
+        assert_eq!(evicted, 0);
+
+        // - The old action is evicted but its entry is kept for the recent one.
+        let evicted = evict_cache(&db, Some(Duration::from_secs(60 * 60)), None)
+            .await
+            .unwrap();
+        assert_eq!(evicted, 1);
+        db.async_reader(|txn| {
+            assert_eq!(count(&txn, "Action"), 2);
+            assert_eq!(count(&txn, "DhtOp"), 2);
+            assert_eq!(count(&txn, "Entry"), 2);
+            DatabaseResult::Ok(())
+        })
+        .await
+        .unwrap();
+        let size = db.async_reader(|txn| cache_size(&txn)).await.unwrap();
+        assert!(size < full_size);
+
+        // - Evicting down to no bytes empties the cache.
+        let evicted = evict_cache(&db, None, Some(0)).await.unwrap();
+        assert_eq!(evicted, 2);
+        db.async_reader(|txn| {
+            assert_eq!(count(&txn, "Action"), 0);
+            assert_eq!(count(&txn, "DhtOp"), 0);
+            assert_eq!(count(&txn, "Entry"), 0);
+            assert_eq!(cache_size(&txn).unwrap(), 0);
+            DatabaseResult::Ok(())
+        })
+        .await
+        .unwrap();
+    }
+}
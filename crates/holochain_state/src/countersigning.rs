@@ -0,0 +1,411 @@
+//! Module for items related to countersigning sessions
+
+use std::sync::Arc;
+
+use holo_hash::ActionHash;
+use holo_hash::DhtOpHash;
+use holo_hash::EntryHash;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_sqlite::rusqlite::Transaction;
+use holochain_types::countersigning::CountersigningSessionInfo;
+use holochain_types::dht_op::DhtOp;
+use holochain_zome_types::CellId;
+use holochain_zome_types::Entry;
+use holochain_zome_types::Timestamp;
+
+use crate::mutations::delete_withheld_record;
+use crate::mutations::unlock_chain;
+use crate::prelude::from_blob;
+use crate::query::StateQueryError;
+use crate::query::StateQueryResult;
+use crate::query::Store;
+use crate::query::Txn;
+use crate::source_chain::chain_head_db;
+use crate::source_chain::SourceChainError;
+use crate::source_chain::SourceChainResult;
+
+/// A signer's op collected by an authority for a countersigning session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingCountersigningOp {
+    /// The hash of the countersigned entry this session is for.
+    pub entry_hash: EntryHash,
+    /// The hash of the op.
+    pub op_hash: DhtOpHash,
+    /// The signer's StoreEntry op.
+    pub op: DhtOp,
+    /// The hashes of every action the session needs to be complete.
+    pub required_actions: Vec<ActionHash>,
+    /// When the session ends.
+    pub expires: Timestamp,
+}
+
+/// All the ops collected for countersigning sessions that haven't ended by `now`.
+pub fn list_pending_countersigning_ops(
+    txn: &Transaction,
+    now: Timestamp,
+) -> StateQueryResult<Vec<PendingCountersigningOp>> {
+    let mut stmt = txn.prepare(
+        "
+        SELECT entry_hash, op_hash, blob, required_actions, expires_at_timestamp
+        FROM CountersigningSession
+        WHERE expires_at_timestamp > :now
+        ",
+    )?;
+    let iter = stmt.query_and_then(
+        named_params! {
+            ":now": now,
+        },
+        |row| {
+            Ok(PendingCountersigningOp {
+                entry_hash: row.get("entry_hash")?,
+                op_hash: row.get("op_hash")?,
+                op: from_blob(row.get("blob")?)?,
+                required_actions: from_blob(row.get("required_actions")?)?,
+                expires: row.get("expires_at_timestamp")?,
+            })
+        },
+    )?;
+    iter.collect()
+}
+
+/// The countersigning session the cell's chain is locked for, if any.
+///
+/// Expired locks are included until the chain is unlocked, so sessions that
+/// were interrupted by a restart can be found and abandoned.
+pub fn countersigning_session_info(
+    txn: &Transaction,
+    cell_id: &CellId,
+) -> SourceChainResult<Option<CountersigningSessionInfo>> {
+    let author = cell_id.agent_pubkey();
+    let lock_expires: Option<Timestamp> = txn
+        .query_row(
+            "SELECT MAX(expires_at_timestamp) FROM ChainLock WHERE author = :author",
+            named_params! {
+                ":author": author,
+            },
+            |row| row.get(0),
+        )
+        .map_err(StateQueryError::from)?;
+    let lock_expires = match lock_expires {
+        Some(lock_expires) => lock_expires,
+        None => return Ok(None),
+    };
+
+    let session = match chain_head_db(txn, Arc::new(author.clone())) {
+        // We haven't done genesis so no entry can have been committed.
+        Err(SourceChainError::ChainEmpty) => None,
+        Err(e) => return Err(e),
+        Ok((hash, _, _)) => {
+            // The countersigned entry only belongs to this session while
+            // its ops are withheld from publishing.
+            let withheld: bool = txn
+                .query_row(
+                    "
+                SELECT EXISTS(
+                    SELECT 1 FROM DhtOp
+                    WHERE action_hash = :hash
+                    AND withhold_publish = 1
+                )
+                ",
+                    named_params! {
+                        ":hash": hash,
+                    },
+                    |row| row.get(0),
+                )
+                .map_err(StateQueryError::from)?;
+            if withheld {
+                let txn: Txn = txn.into();
+                txn.get_record(&hash.into())?.and_then(|record| {
+                    let (shh, ee) = record.into_inner();
+                    match (shh.action().entry_hash(), ee.into_option()) {
+                        (Some(entry_hash), Some(Entry::CounterSign(cs, _))) => {
+                            Some((entry_hash.clone(), *cs))
+                        }
+                        _ => None,
+                    }
+                })
+            } else {
+                None
+            }
+        }
+    };
+    let (entry_hash, session_data) = session.unzip();
+
+    Ok(Some(CountersigningSessionInfo {
+        cell_id: cell_id.clone(),
+        lock_expires,
+        entry_hash,
+        session_data,
+    }))
+}
+
+/// Abandon the countersigning session the cell's chain is locked for
+/// and unlock the chain.
+///
+/// If the countersigned entry was committed, its record and ops are removed
+/// as they were never published, so the next commit follows the last
+/// published action. A response from the session's authorities arriving
+/// later is ignored because the chain is no longer locked.
+/// Other signers may still complete the session if it hadn't expired,
+/// which would fork this chain, so this should only be used for sessions
+/// that are stuck.
+///
+/// Returns the session that was abandoned, if there was one.
+pub fn abandon_countersigning_session(
+    txn: &mut Transaction,
+    cell_id: &CellId,
+) -> SourceChainResult<Option<CountersigningSessionInfo>> {
+    let info = countersigning_session_info(txn, cell_id)?;
+    if let Some(info) = &info {
+        if let Some(entry_hash) = &info.entry_hash {
+            // The withheld record is always the chain head.
+            let author = Arc::new(cell_id.agent_pubkey().clone());
+            let (action_hash, _, _) = chain_head_db(txn, author)?;
+            delete_withheld_record(txn, &action_hash, entry_hash)?;
+        }
+        unlock_chain(txn, cell_id.agent_pubkey())?;
+    }
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain_lock::is_chain_locked;
+    use crate::mutations;
+    use crate::mutations::StateMutationResult;
+    use crate::source_chain::genesis;
+    use crate::source_chain::SourceChain;
+    use ::fixt::prelude::*;
+    use holo_hash::fixt::AgentPubKeyFixturator;
+    use holo_hash::fixt::DnaHashFixturator;
+    use holo_hash::fixt::EntryHashFixturator;
+    use holochain_p2p::MockHolochainP2pDnaT;
+    use holochain_sqlite::prelude::*;
+    use holochain_types::action::NewEntryAction;
+    use holochain_types::db_cache::DhtDbQueryCache;
+    use holochain_zome_types::fixt::AppEntryBytesFixturator;
+    use holochain_zome_types::fixt::CreateFixturator;
+    use holochain_zome_types::fixt::EntryFixturator;
+    use holochain_zome_types::fixt::SignatureFixturator;
+    use holochain_zome_types::prelude::*;
+
+    fn pending_op(entry_hash: &EntryHash, expires: Timestamp) -> PendingCountersigningOp {
+        let op = DhtOp::StoreEntry(
+            fixt!(Signature),
+            NewEntryAction::Create(fixt!(Create)),
+            Box::new(fixt!(Entry)),
+        );
+        let required_actions = vec![ActionHash::with_data_sync(&op.action())];
+        PendingCountersigningOp {
+            entry_hash: entry_hash.clone(),
+            op_hash: DhtOpHash::with_data_sync(&op),
+            op,
+            required_actions,
+            expires,
+        }
+    }
+
+    #[test]
+    fn pending_ops_are_persisted_until_removed() -> StateMutationResult<()> {
+        let test_db = crate::test_utils::test_dht_db();
+        let db = test_db.to_db();
+        let now = Timestamp::now();
+        let later = (now + std::time::Duration::from_secs(60 * 60)).unwrap();
+        let earlier = (now - std::time::Duration::from_secs(60 * 60)).unwrap();
+
+        let session = fixt!(EntryHash);
+        let other_session = fixt!(EntryHash);
+        let a = pending_op(&session, later);
+        let b = pending_op(&session, later);
+        let c = pending_op(&other_session, later);
+        let expired = pending_op(&fixt!(EntryHash), earlier);
+
+        let mut conn = db.conn().unwrap();
+        conn.with_commit_sync(|txn| {
+            for op in [&a, &b, &a, &c, &expired] {
+                mutations::insert_countersigning_session_op(txn, op)?;
+            }
+            StateMutationResult::Ok(())
+        })?;
+
+        // - The same op is only stored once and expired sessions are not listed.
+        let mut ops = conn.with_reader_test(|txn| list_pending_countersigning_ops(&txn, now))?;
+        ops.sort_by(|x, y| x.op_hash.cmp(&y.op_hash));
+        let mut expected = vec![a.clone(), b.clone(), c.clone()];
+        expected.sort_by(|x, y| x.op_hash.cmp(&y.op_hash));
+        assert_eq!(ops, expected);
+
+        conn.with_commit_sync(|txn| {
+            mutations::delete_countersigning_session(txn, &session)?;
+            mutations::delete_expired_countersigning_sessions(txn, now)
+        })?;
+
+        let ops = conn.with_reader_test(|txn| list_pending_countersigning_ops(&txn, earlier))?;
+        assert_eq!(ops, vec![c]);
+        Ok(())
+    }
+
+    #[test]
+    fn abandon_session_unlocks_chain() -> SourceChainResult<()> {
+        let test_db = crate::test_utils::test_authored_db();
+        let db = test_db.to_db();
+        let cell_id = CellId::new(fixt!(DnaHash), fixt!(AgentPubKey));
+        let author = cell_id.agent_pubkey().clone();
+        let expires = (Timestamp::now() + std::time::Duration::from_secs(60 * 60)).unwrap();
+
+        let mut conn = db.conn().unwrap();
+        assert_eq!(
+            conn.with_reader_test(|txn| countersigning_session_info(&txn, &cell_id))?,
+            None
+        );
+
+        // - Lock the chain as if a preflight request had been accepted.
+        conn.with_commit_sync(|txn| mutations::lock_chain(txn, b"preflight", &author, &expires))?;
+
+        let info = conn.with_reader_test(|txn| countersigning_session_info(&txn, &cell_id))?;
+        let expected = CountersigningSessionInfo {
+            cell_id: cell_id.clone(),
+            lock_expires: expires,
+            entry_hash: None,
+            session_data: None,
+        };
+        assert_eq!(info, Some(expected.clone()));
+        assert!(!expected.is_expired());
+
+        let abandoned =
+            conn.with_commit_sync(|txn| abandon_countersigning_session(txn, &cell_id))?;
+        assert_eq!(abandoned, Some(expected));
+
+        conn.with_reader_test(|txn| {
+            assert!(!is_chain_locked(&txn, &[], &author).unwrap());
+            assert_eq!(countersigning_session_info(&txn, &cell_id).unwrap(), None);
+        });
+
+        // - Abandoning again is a no-op.
+        let abandoned =
+            conn.with_commit_sync(|txn| abandon_countersigning_session(txn, &cell_id))?;
+        assert_eq!(abandoned, None);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn commit_after_abandoning_follows_published_chain() -> SourceChainResult<()> {
+        let test_db = crate::test_utils::test_authored_db();
+        let dht_db = crate::test_utils::test_dht_db();
+        let keystore = crate::test_utils::test_keystore();
+        let db = test_db.to_db();
+        let dht_db_cache = DhtDbQueryCache::new(dht_db.to_db().into());
+        let alice = fixt!(AgentPubKey, Predictable, 0);
+        let cell_id = CellId::new(fixt!(DnaHash), alice.clone());
+        let mut network = MockHolochainP2pDnaT::new();
+        network.expect_authority_for_hash().returning(|_| Ok(false));
+
+        genesis(
+            db.clone(),
+            dht_db.to_db(),
+            &dht_db_cache,
+            keystore.clone(),
+            cell_id.dna_hash().clone(),
+            alice.clone(),
+            None,
+        )
+        .await?;
+        let source_chain = || {
+            SourceChain::new(
+                db.clone().into(),
+                dht_db.to_db(),
+                dht_db_cache.clone(),
+                keystore.clone(),
+                alice.clone(),
+            )
+        };
+        let (published_head, published_seq, _) = db
+            .async_reader({
+                let alice = Arc::new(alice.clone());
+                move |txn| chain_head_db(&txn, alice)
+            })
+            .await?;
+
+        // - Commit a countersigned entry with another signer.
+        let now = Timestamp::now();
+        let session_times = CounterSigningSessionTimes::try_new(
+            now,
+            (now + std::time::Duration::from_secs(60 * 60)).unwrap(),
+        )
+        .unwrap();
+        let entry_type = EntryType::App(AppEntryType::new(
+            0.into(),
+            0.into(),
+            EntryVisibility::Public,
+        ));
+        let preflight_request = PreflightRequest::try_new(
+            fixt!(EntryHash),
+            vec![(alice.clone(), vec![]), (fixt!(AgentPubKey), vec![])],
+            vec![],
+            0,
+            false,
+            session_times,
+            ActionBase::Create(CreateBase::new(entry_type)),
+            PreflightBytes(vec![]),
+        )
+        .unwrap();
+        let chain = source_chain().await?;
+        let agent_state = chain
+            .accept_countersigning_preflight_request(preflight_request.clone(), 0)
+            .await?;
+        let session_data = CounterSigningSessionData::try_new(
+            preflight_request,
+            vec![
+                (agent_state, fixt!(Signature)),
+                (
+                    CounterSigningAgentState::new(1, fixt!(ActionHash), 3),
+                    fixt!(Signature),
+                ),
+            ],
+            vec![],
+        )
+        .unwrap();
+        let entry = Entry::CounterSign(Box::new(session_data), fixt!(AppEntryBytes));
+        chain
+            .put_countersigned(entry, ChainTopOrdering::Strict, Default::default())
+            .await?;
+        chain.flush(&network).await?;
+
+        let abandoned = db
+            .async_commit({
+                let cell_id = cell_id.clone();
+                move |txn| abandon_countersigning_session(txn, &cell_id)
+            })
+            .await?;
+        assert!(abandoned.unwrap().entry_hash.is_some());
+
+        // - The next commit follows the last published action
+        //   and the countersigned record is gone.
+        let chain = source_chain().await?;
+        chain
+            .put(
+                builder::CloseChain {
+                    new_dna_hash: fixt!(DnaHash),
+                },
+                None,
+                ChainTopOrdering::Strict,
+            )
+            .await?;
+        let committed = chain.flush(&network).await?;
+        assert_eq!(committed[0].action().prev_action(), Some(&published_head));
+        assert_eq!(committed[0].action().action_seq(), published_seq + 1);
+        let withheld: bool = db
+            .async_reader(|txn| {
+                txn.query_row(
+                    "SELECT EXISTS(SELECT 1 FROM DhtOp WHERE withhold_publish = 1)",
+                    [],
+                    |row| row.get(0),
+                )
+                .map_err(StateQueryError::from)
+            })
+            .await?;
+        assert!(!withheld);
+        Ok(())
+    }
+}
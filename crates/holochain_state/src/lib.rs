@@ -35,7 +35,9 @@
 
 #![allow(deprecated)]
 
+pub mod cache;
 pub mod chain_lock;
+pub mod countersigning;
 #[allow(missing_docs)]
 pub mod dna_def;
 pub mod entry_def;
@@ -51,8 +53,10 @@ pub mod scratch;
 pub mod source_chain;
 pub mod validation_db;
 pub mod validation_receipts;
+pub mod warrants;
 #[allow(missing_docs)]
 pub mod wasm;
+pub mod workflow_queues;
 pub mod workspace;
 
 #[allow(missing_docs)]
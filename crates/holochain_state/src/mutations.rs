@@ -1,3 +1,4 @@
+use crate::countersigning::PendingCountersigningOp;
 use crate::entry_def::EntryDefStoreKey;
 use crate::prelude::SignedValidationReceipt;
 use crate::query::from_blob;
@@ -18,6 +19,8 @@ use holochain_types::prelude::DnaDefHashed;
 use holochain_types::prelude::DnaWasmHashed;
 use holochain_types::sql::AsSql;
 use holochain_zome_types::entry::EntryHashed;
+use holochain_zome_types::warrant::SignedWarrant;
+use holochain_zome_types::warrant::Warrant;
 use holochain_zome_types::*;
 use std::str::FromStr;
 
@@ -188,6 +191,7 @@ pub fn insert_op_lite(
         "action_hash": action_hash,
         "require_receipt": 0,
         "op_order": order,
+        "when_stored": Timestamp::now(),
     })?;
     Ok(())
 }
@@ -209,6 +213,30 @@ pub fn insert_validation_receipt(
     Ok(())
 }
 
+/// Insert a [`SignedWarrant`] into the database.
+/// The same warrant from the same author is only stored once.
+pub fn insert_warrant(txn: &mut Transaction, warrant: SignedWarrant) -> StateMutationResult<()> {
+    let Warrant {
+        proof,
+        author,
+        timestamp,
+    } = warrant.warrant();
+    let hash = blake2b_256(&holochain_serialized_bytes::encode(&(author, proof))?);
+    let warrantee = proof.warrantee().clone();
+    let author = author.clone();
+    let timestamp = *timestamp;
+    let bytes: UnsafeBytes = SerializedBytes::try_from(warrant)?.into();
+    let bytes: Vec<u8> = bytes.into();
+    sql_insert!(txn, Warrant, {
+        "hash": hash,
+        "author": author,
+        "warrantee": warrantee,
+        "timestamp": timestamp,
+        "blob": bytes,
+    })?;
+    Ok(())
+}
+
 /// Insert a [`DnaWasm`](holochain_types::prelude::DnaWasm) into the database.
 pub fn insert_wasm(txn: &mut Transaction, wasm: DnaWasmHashed) -> StateMutationResult<()> {
     let (wasm, hash) = wasm.into_inner();
@@ -258,6 +286,8 @@ pub fn insert_conductor_state(
 }
 
 /// Set the validation status of a [`DhtOp`](holochain_types::dht_op::DhtOp) in the database.
+///
+/// An op with a validation status is no longer waiting on any dependencies.
 pub fn set_validation_status(
     txn: &mut Transaction,
     hash: &DhtOpHash,
@@ -266,6 +296,10 @@ pub fn set_validation_status(
     dht_op_update!(txn, hash, {
         "validation_status": status,
     })?;
+    txn.execute(
+        "DELETE FROM ValidationDependency WHERE op_hash = :hash",
+        named_params! { ":hash": hash },
+    )?;
     Ok(())
 }
 /// Set the integration dependency of a [`DhtOp`](holochain_types::dht_op::DhtOp) in the database.
@@ -302,18 +336,19 @@ pub fn set_require_receipt(
     Ok(())
 }
 
-/// Set the validation stage of a [`DhtOp`](holochain_types::dht_op::DhtOp) in the database.
+/// Set the validation stage of a [`DhtOp`](holochain_types::dht_op::DhtOp) in the database,
+/// along with the dependencies the op is waiting on in that stage.
 pub fn set_validation_stage(
     txn: &mut Transaction,
     hash: &DhtOpHash,
     status: ValidationLimboStatus,
 ) -> StateMutationResult<()> {
-    let stage = match status {
-        ValidationLimboStatus::Pending => None,
-        ValidationLimboStatus::AwaitingSysDeps(_) => Some(0),
-        ValidationLimboStatus::SysValidated => Some(1),
-        ValidationLimboStatus::AwaitingAppDeps(_) => Some(2),
-        ValidationLimboStatus::AwaitingIntegration => Some(3),
+    let (stage, dependencies) = match status {
+        ValidationLimboStatus::Pending => (None, vec![]),
+        ValidationLimboStatus::AwaitingSysDeps(dep) => (Some(0), vec![dep]),
+        ValidationLimboStatus::SysValidated => (Some(1), vec![]),
+        ValidationLimboStatus::AwaitingAppDeps(deps) => (Some(2), deps),
+        ValidationLimboStatus::AwaitingIntegration => (Some(3), vec![]),
     };
     let now = holochain_zome_types::Timestamp::now();
     txn.execute(
@@ -332,6 +367,16 @@ pub fn set_validation_stage(
             ":hash": hash,
         },
     )?;
+    txn.execute(
+        "DELETE FROM ValidationDependency WHERE op_hash = :hash",
+        named_params! { ":hash": hash },
+    )?;
+    for dependency in dependencies {
+        sql_insert!(txn, ValidationDependency, {
+            "op_hash": hash,
+            "dependency": dependency,
+        })?;
+    }
     Ok(())
 }
 
@@ -586,6 +631,68 @@ pub fn unlock_chain(txn: &mut Transaction, author: &AgentPubKey) -> StateMutatio
     Ok(())
 }
 
+/// Delete an authored record that was never published, along with its ops.
+/// The entry is only deleted if no other action has it.
+pub fn delete_withheld_record(
+    txn: &mut Transaction,
+    action_hash: &ActionHash,
+    entry_hash: &EntryHash,
+) -> StateMutationResult<()> {
+    txn.execute("DELETE FROM DhtOp WHERE action_hash = ?", [action_hash])?;
+    txn.execute("DELETE FROM Action WHERE hash = ?", [action_hash])?;
+    txn.execute(
+        "
+        DELETE FROM Entry WHERE hash = :entry_hash
+        AND NOT EXISTS (SELECT 1 FROM Action WHERE entry_hash = :entry_hash)
+        ",
+        named_params! { ":entry_hash": entry_hash },
+    )?;
+    Ok(())
+}
+
+/// Persist an op an authority has received for a countersigning session
+/// so the session survives a restart.
+/// A signer's op replaces any op previously stored for the same action.
+pub fn insert_countersigning_session_op(
+    txn: &mut Transaction,
+    op: &PendingCountersigningOp,
+) -> StateMutationResult<()> {
+    let action_hash = ActionHash::with_data_sync(&op.op.action());
+    sql_insert!(txn, CountersigningSession, {
+        "entry_hash": op.entry_hash,
+        "action_hash": action_hash,
+        "op_hash": op.op_hash,
+        "blob": to_blob(&op.op)?,
+        "required_actions": to_blob(&op.required_actions)?,
+        "expires_at_timestamp": op.expires,
+    })?;
+    Ok(())
+}
+
+/// Remove every op collected for a countersigning session.
+pub fn delete_countersigning_session(
+    txn: &mut Transaction,
+    entry_hash: &EntryHash,
+) -> StateMutationResult<()> {
+    txn.execute(
+        "DELETE FROM CountersigningSession WHERE entry_hash = ?",
+        [entry_hash],
+    )?;
+    Ok(())
+}
+
+/// Remove the ops of every countersigning session that ended before `now`.
+pub fn delete_expired_countersigning_sessions(
+    txn: &mut Transaction,
+    now: Timestamp,
+) -> StateMutationResult<()> {
+    txn.execute(
+        "DELETE FROM CountersigningSession WHERE expires_at_timestamp <= ?",
+        [now],
+    )?;
+    Ok(())
+}
+
 pub fn delete_all_ephemeral_scheduled_fns(
     txn: &mut Transaction,
     author: &AgentPubKey,
@@ -43,6 +43,8 @@ pub mod link;
 pub mod link_details;
 pub mod live_entry;
 pub mod live_record;
+pub mod metadata;
+pub mod must_get_agent_activity;
 pub mod record_details;
 
 pub mod prelude {
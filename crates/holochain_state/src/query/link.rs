@@ -1,6 +1,7 @@
 use holo_hash::*;
 use holochain_sqlite::rusqlite::named_params;
 use holochain_types::dht_op::DhtOpType;
+use holochain_types::link::WireLinkKey;
 use holochain_types::sql::ToSqlStatement;
 use holochain_zome_types::*;
 use std::fmt::Debug;
@@ -17,11 +18,131 @@ pub struct LinksQuery {
     pub base: Arc<AnyLinkableHash>,
     pub type_query: LinkTypeFilter,
     pub tag: Option<String>,
+    pub filter: LinksFilter,
     query: String,
 }
 
+/// Filters on the create links of a links query which are
+/// checked in the database rather than after the links are loaded.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LinksFilter {
+    after: Option<Timestamp>,
+    before: Option<Timestamp>,
+    author: Option<AgentPubKey>,
+    cursor_timestamp: Option<Timestamp>,
+    cursor_hash: Option<ActionHash>,
+    limit: Option<u32>,
+}
+
+impl LinksFilter {
+    /// Create the filter from a link key.
+    pub fn from_key(key: &WireLinkKey) -> Self {
+        Self {
+            after: key.after,
+            before: key.before,
+            author: key.author.clone(),
+            cursor_timestamp: key.cursor.as_ref().map(|c| c.timestamp),
+            cursor_hash: key.cursor.as_ref().map(|c| c.create_link_hash.clone()),
+            limit: key.limit,
+        }
+    }
+
+    /// The maximum number of create links to return.
+    pub fn limit(&self) -> Option<u32> {
+        self.limit
+    }
+
+    /// Conditions on the `Action` and `DhtOp` of a create link.
+    /// Unset filters are bound as null so the query is the same for every filter.
+    pub fn sql() -> &'static str {
+        "
+            AND (:after IS NULL OR DhtOp.authored_timestamp >= :after)
+            AND (:before IS NULL OR DhtOp.authored_timestamp < :before)
+            AND (:author IS NULL OR Action.author = :author)
+            AND (
+                :cursor_timestamp IS NULL
+                OR DhtOp.authored_timestamp > :cursor_timestamp
+                OR (DhtOp.authored_timestamp = :cursor_timestamp AND Action.hash > :cursor_hash)
+            )
+        "
+    }
+
+    /// Condition that the create link `Action` has not been deleted
+    /// by a valid and integrated delete link.
+    ///
+    /// Binds `:delete` to the remove link op type and `:status` to valid.
+    pub fn not_deleted_sql() -> &'static str {
+        "
+            AND NOT EXISTS (
+                SELECT 1 FROM Action AS DeleteAction
+                JOIN DhtOp AS DeleteOp ON DeleteOp.action_hash = DeleteAction.hash
+                WHERE DeleteAction.create_link_hash = Action.hash
+                AND DeleteOp.type = :delete
+                AND DeleteOp.validation_status = :status
+                AND DeleteOp.when_integrated IS NOT NULL
+            )
+        "
+    }
+
+    /// The parameters of [`LinksFilter::sql`].
+    pub fn params(&self) -> Vec<Params> {
+        named_params! {
+            ":after": self.after,
+            ":before": self.before,
+            ":author": self.author,
+            ":cursor_timestamp": self.cursor_timestamp,
+            ":cursor_hash": self.cursor_hash,
+        }
+        .to_vec()
+    }
+
+    /// Binds `:limit` for queries which limit the create links in the database.
+    pub fn limit_params(&self) -> Vec<Params> {
+        named_params! {
+            ":limit": self.limit,
+        }
+        .to_vec()
+    }
+
+    /// Check a create link against this filter.
+    pub fn contains(&self, create_link: &CreateLink, hash: &ActionHash) -> bool {
+        self.after
+            .map_or(true, |after| create_link.timestamp >= after)
+            && self
+                .before
+                .map_or(true, |before| create_link.timestamp < before)
+            && self
+                .author
+                .as_ref()
+                .map_or(true, |author| create_link.author == *author)
+            && self.cursor_timestamp.map_or(true, |timestamp| {
+                create_link.timestamp > timestamp
+                    || (create_link.timestamp == timestamp
+                        && self.cursor_hash.as_ref().map_or(true, |h| hash > h))
+            })
+    }
+
+    /// Sort links by their timestamp then by the hash of their create action
+    /// and apply the limit.
+    pub fn page<T>(&self, items: &mut Vec<T>, key: impl Fn(&T) -> (Timestamp, &ActionHash)) {
+        items.sort_by(|a, b| key(a).cmp(&key(b)));
+        if let Some(limit) = self.limit {
+            items.truncate(limit as usize);
+        }
+    }
+}
+
 impl LinksQuery {
     pub fn new(base: AnyLinkableHash, type_query: LinkTypeFilter, tag: Option<LinkTag>) -> Self {
+        Self::with_filter(base, type_query, tag, LinksFilter::default())
+    }
+
+    pub fn with_filter(
+        base: AnyLinkableHash,
+        type_query: LinkTypeFilter,
+        tag: Option<LinkTag>,
+        filter: LinksFilter,
+    ) -> Self {
         let tag = tag.map(|tag| Self::tag_to_hex(&tag));
         let create_string = Self::create_query_string(&type_query, tag.clone());
         let delete_string = Self::delete_query_string(&type_query, tag.clone());
@@ -29,10 +150,16 @@ impl LinksQuery {
             base: Arc::new(base),
             type_query,
             tag,
+            filter,
             query: Self::create_query(create_string, delete_string),
         }
     }
 
+    pub fn from_key(key: WireLinkKey) -> Self {
+        let filter = LinksFilter::from_key(&key);
+        Self::with_filter(key.base, key.type_query, key.tag, filter)
+    }
+
     pub fn tag_to_hex(tag: &LinkTag) -> String {
         use std::fmt::Write;
         let mut s = String::with_capacity(tag.0.len());
@@ -54,6 +181,18 @@ impl LinksQuery {
         self.query.clone()
     }
 
+    /// Check that a create link matches this query.
+    pub fn matches_create(&self, create_link: &CreateLink, hash: &ActionHash) -> bool {
+        create_link.base_address == *self.base
+            && self
+                .type_query
+                .contains(&create_link.zome_id, &create_link.link_type)
+            && self.tag.as_ref().map_or(true, |t| {
+                Self::tag_to_hex(&create_link.tag).starts_with(&(**t))
+            })
+            && self.filter.contains(create_link, hash)
+    }
+
     fn common_query_string() -> &'static str {
         "
             JOIN Action On DhtOp.action_hash = Action.hash
@@ -70,8 +209,10 @@ impl LinksQuery {
             "
             SELECT Action.blob AS action_blob FROM DhtOp
             {}
+            {}
             ",
-            Self::common_query_string()
+            Self::common_query_string(),
+            LinksFilter::sql(),
         );
         s = Self::add_type_query(s, type_query);
         Self::add_tag(s, tag)
@@ -97,8 +238,10 @@ impl LinksQuery {
             "
             SELECT Action.hash FROM DhtOp
             {}
+            {}
             ",
-            Self::common_query_string()
+            Self::common_query_string(),
+            LinksFilter::sql(),
         );
         sub_create_query = Self::add_type_query(sub_create_query, type_query);
         sub_create_query = Self::add_tag(sub_create_query, tag);
@@ -120,15 +263,15 @@ impl LinksQuery {
     }
 
     pub fn params(&self) -> Vec<Params> {
-        {
-            named_params! {
-                ":create": DhtOpType::RegisterAddLink,
-                ":delete": DhtOpType::RegisterRemoveLink,
-                ":status": ValidationStatus::Valid,
-                ":base_hash": self.base,
-            }
+        let mut params = named_params! {
+            ":create": DhtOpType::RegisterAddLink,
+            ":delete": DhtOpType::RegisterRemoveLink,
+            ":status": ValidationStatus::Valid,
+            ":base_hash": self.base,
         }
-        .to_vec()
+        .to_vec();
+        params.extend(self.filter.params());
+        params
     }
 }
 
@@ -144,6 +287,13 @@ impl GetLinksQuery {
             query: LinksQuery::base(base, dependencies),
         }
     }
+
+    /// Get the links matching a link key, including its filters and limit.
+    pub fn from_key(key: WireLinkKey) -> Self {
+        Self {
+            query: LinksQuery::from_key(key),
+        }
+    }
 }
 
 impl Query for GetLinksQuery {
@@ -169,25 +319,10 @@ impl Query for GetLinksQuery {
     }
 
     fn as_filter(&self) -> Box<dyn Fn(&QueryData<Self>) -> bool> {
-        let query = &self.query;
-        let base_filter = query.base.clone();
-        let type_query_filter = query.type_query.clone();
-        let tag_filter = query.tag.clone();
+        let query = self.query.clone();
         let f = move |action: &QueryData<Self>| match action.action() {
-            Action::CreateLink(CreateLink {
-                base_address,
-                tag,
-                zome_id,
-                link_type,
-                ..
-            }) => {
-                *base_address == *base_filter
-                    && type_query_filter.contains(zome_id, link_type)
-                    && tag_filter
-                        .as_ref()
-                        .map_or(true, |t| LinksQuery::tag_to_hex(tag).starts_with(&(**t)))
-            }
-            Action::DeleteLink(DeleteLink { base_address, .. }) => *base_address == *base_filter,
+            Action::CreateLink(create_link) => query.matches_create(create_link, action.as_hash()),
+            Action::DeleteLink(DeleteLink { base_address, .. }) => *base_address == *query.base,
             _ => false,
         };
         Box::new(f)
@@ -219,7 +354,9 @@ impl Query for GetLinksQuery {
         S: Store,
     {
         let mut links: Self::Output = state.creates.into_iter().map(|(_, v)| v).collect();
-        links.sort_by_key(|l| l.timestamp);
+        self.query
+            .filter
+            .page(&mut links, |l| (l.timestamp, &l.create_link_hash));
         Ok(links)
     }
 }
@@ -1,4 +1,5 @@
 use holo_hash::*;
+use holochain_types::link::WireLinkKey;
 use holochain_zome_types::*;
 use std::fmt::Debug;
 
@@ -16,6 +17,13 @@ impl GetLinkDetailsQuery {
             query: LinksQuery::new(base, type_query, tag),
         }
     }
+
+    /// Get the link details matching a link key, including its filters and limit.
+    pub fn from_key(key: WireLinkKey) -> Self {
+        Self {
+            query: LinksQuery::from_key(key),
+        }
+    }
 }
 
 impl Query for GetLinkDetailsQuery {
@@ -41,25 +49,10 @@ impl Query for GetLinkDetailsQuery {
     }
 
     fn as_filter(&self) -> Box<dyn Fn(&QueryData<Self>) -> bool> {
-        let query = &self.query;
-        let base_filter = query.base.clone();
-        let type_query_filter = query.type_query.clone();
-        let tag_filter = query.tag.clone();
+        let query = self.query.clone();
         let f = move |action: &QueryData<Self>| match action.action() {
-            Action::CreateLink(CreateLink {
-                base_address,
-                tag,
-                zome_id,
-                link_type,
-                ..
-            }) => {
-                *base_address == *base_filter
-                    && type_query_filter.contains(zome_id, link_type)
-                    && tag_filter
-                        .as_ref()
-                        .map_or(true, |t| LinksQuery::tag_to_hex(tag).starts_with(&(**t)))
-            }
-            Action::DeleteLink(DeleteLink { base_address, .. }) => *base_address == *base_filter,
+            Action::CreateLink(create_link) => query.matches_create(create_link, action.as_hash()),
+            Action::DeleteLink(DeleteLink { base_address, .. }) => *base_address == *query.base,
             _ => false,
         };
         Box::new(f)
@@ -106,7 +99,9 @@ impl Query for GetLinkDetailsQuery {
                 })
             })
             .collect::<Vec<_>>();
-        r.sort_by_key(|l| l.0.action().timestamp());
+        self.query
+            .filter
+            .page(&mut r, |l| (l.0.action().timestamp(), l.0.as_hash()));
         Ok(r)
     }
 }
@@ -0,0 +1,274 @@
+use holo_hash::*;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_types::dht_op::DhtOpType;
+use holochain_types::metadata::EntryDhtStatus;
+use holochain_types::metadata::MetadataSet;
+use holochain_types::metadata::TimedActionHash;
+use holochain_types::prelude::Judged;
+use holochain_zome_types::*;
+use std::fmt::Debug;
+
+use super::*;
+
+/// Get the metadata for an entry or action hash without
+/// returning the data itself.
+#[derive(Debug, Clone)]
+pub struct GetMetadataQuery {
+    hash: AnyDhtHash,
+    entry: Option<EntryHash>,
+    action: Option<ActionHash>,
+    base: AnyLinkableHash,
+    request: MetadataRequest,
+}
+
+impl GetMetadataQuery {
+    pub fn new(hash: AnyDhtHash, request: MetadataRequest) -> Self {
+        let (entry, action, base) = match hash.clone().into_primitive() {
+            AnyDhtHashPrimitive::Entry(entry) => (Some(entry.clone()), None, entry.into()),
+            AnyDhtHashPrimitive::Action(action) => (None, Some(action.clone()), action.into()),
+        };
+        Self {
+            hash,
+            entry,
+            action,
+            base,
+            request,
+        }
+    }
+
+    fn is_entry(&self, hash: &EntryHash) -> bool {
+        self.entry.as_ref() == Some(hash)
+    }
+
+    fn is_action(&self, hash: &ActionHash) -> bool {
+        self.action.as_ref() == Some(hash)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct State {
+    actions: HashMap<ActionHash, TimedActionHash>,
+    invalid_actions: HashSet<TimedActionHash>,
+    deletes: HashSet<TimedActionHash>,
+    deleted_actions: HashSet<ActionHash>,
+    updates: HashSet<TimedActionHash>,
+    links: HashMap<ActionHash, TimedActionHash>,
+    deleted_links: HashSet<ActionHash>,
+    validation_status: Option<ValidationStatus>,
+}
+
+impl Query for GetMetadataQuery {
+    type Item = Judged<SignedActionHashed>;
+    type State = State;
+    type Output = MetadataSet;
+
+    fn query(&self) -> String {
+        "
+        SELECT Action.blob AS action_blob, DhtOp.validation_status AS status
+        FROM DhtOp
+        JOIN Action On DhtOp.action_hash = Action.hash
+        WHERE DhtOp.type IN (
+            :store_entry, :store_record, :deleted_entry, :deleted_by,
+            :updated_content, :updated_record, :add_link, :remove_link
+        )
+        AND DhtOp.basis_hash = :hash
+        AND DhtOp.when_integrated IS NOT NULL
+        AND DhtOp.validation_status IS NOT NULL
+        "
+        .into()
+    }
+
+    fn params(&self) -> Vec<Params> {
+        let params = named_params! {
+            ":store_entry": DhtOpType::StoreEntry,
+            ":store_record": DhtOpType::StoreRecord,
+            ":deleted_entry": DhtOpType::RegisterDeletedEntryAction,
+            ":deleted_by": DhtOpType::RegisterDeletedBy,
+            ":updated_content": DhtOpType::RegisterUpdatedContent,
+            ":updated_record": DhtOpType::RegisterUpdatedRecord,
+            ":add_link": DhtOpType::RegisterAddLink,
+            ":remove_link": DhtOpType::RegisterRemoveLink,
+            ":hash": self.hash,
+        };
+        params.to_vec()
+    }
+
+    fn as_map(&self) -> Arc<dyn Fn(&Row) -> StateQueryResult<Self::Item>> {
+        let f = |row: &Row| {
+            let action =
+                from_blob::<SignedAction>(row.get(row.as_ref().column_index("action_blob")?)?)?;
+            let SignedAction(action, signature) = action;
+            let action = ActionHashed::from_content_sync(action);
+            let shh = SignedActionHashed::with_presigned(action, signature);
+            let status = row.get(row.as_ref().column_index("status")?)?;
+            let r = Judged::new(shh, status);
+            Ok(r)
+        };
+        Arc::new(f)
+    }
+
+    fn as_filter(&self) -> Box<dyn Fn(&QueryData<Self>) -> bool> {
+        let query = self.clone();
+        let f = move |action: &QueryData<Self>| {
+            if query.is_action(action.action_address()) {
+                return true;
+            }
+            match action.action() {
+                Action::Create(Create { entry_hash, .. }) => query.is_entry(entry_hash),
+                Action::Update(Update {
+                    entry_hash,
+                    original_entry_address,
+                    original_action_address,
+                    ..
+                }) => {
+                    query.is_entry(entry_hash)
+                        || query.is_entry(original_entry_address)
+                        || query.is_action(original_action_address)
+                }
+                Action::Delete(Delete {
+                    deletes_address,
+                    deletes_entry_address,
+                    ..
+                }) => query.is_action(deletes_address) || query.is_entry(deletes_entry_address),
+                Action::CreateLink(CreateLink { base_address, .. })
+                | Action::DeleteLink(DeleteLink { base_address, .. }) => {
+                    *base_address == query.base
+                }
+                _ => false,
+            }
+        };
+        Box::new(f)
+    }
+
+    fn init_fold(&self) -> StateQueryResult<Self::State> {
+        Ok(State::default())
+    }
+
+    fn fold(&self, mut state: Self::State, item: Self::Item) -> StateQueryResult<Self::State> {
+        let (shh, validation_status) = item.into();
+        let hash = shh.as_hash().clone();
+        let timed = TimedActionHash {
+            timestamp: shh.action().timestamp(),
+            action_hash: hash.clone(),
+        };
+        let valid = validation_status == Some(ValidationStatus::Valid);
+        if self.is_action(&hash) {
+            state.validation_status = state.validation_status.or(validation_status);
+        }
+        match shh.action() {
+            Action::Create(Create { entry_hash, .. }) if self.is_entry(entry_hash) => {
+                match validation_status {
+                    Some(ValidationStatus::Valid) => {
+                        state.actions.insert(hash, timed);
+                    }
+                    Some(ValidationStatus::Rejected) => {
+                        state.invalid_actions.insert(timed);
+                    }
+                    _ => (),
+                }
+            }
+            Action::Update(update) => {
+                if self.is_entry(&update.entry_hash) {
+                    match validation_status {
+                        Some(ValidationStatus::Valid) => {
+                            state.actions.insert(hash, timed.clone());
+                        }
+                        Some(ValidationStatus::Rejected) => {
+                            state.invalid_actions.insert(timed.clone());
+                        }
+                        _ => (),
+                    }
+                }
+                if valid
+                    && (self.is_entry(&update.original_entry_address)
+                        || self.is_action(&update.original_action_address))
+                {
+                    state.updates.insert(timed);
+                }
+            }
+            Action::Delete(delete) => {
+                if valid
+                    && (self.is_entry(&delete.deletes_entry_address)
+                        || self.is_action(&delete.deletes_address))
+                {
+                    state.deleted_actions.insert(delete.deletes_address.clone());
+                    state.deletes.insert(timed);
+                }
+            }
+            Action::CreateLink(create_link) if valid && create_link.base_address == self.base => {
+                state.links.insert(hash, timed);
+            }
+            Action::DeleteLink(delete_link) if valid && delete_link.base_address == self.base => {
+                state
+                    .deleted_links
+                    .insert(delete_link.link_add_address.clone());
+            }
+            _ => (),
+        }
+        Ok(state)
+    }
+
+    fn render<S>(&self, state: Self::State, _stores: S) -> StateQueryResult<Self::Output>
+    where
+        S: Store,
+    {
+        let State {
+            actions,
+            invalid_actions,
+            deletes,
+            deleted_actions,
+            updates,
+            mut links,
+            deleted_links,
+            validation_status,
+        } = state;
+        let MetadataRequest {
+            all_valid_actions,
+            all_invalid_actions,
+            all_deletes,
+            all_updates,
+            entry_dht_status,
+            all_links,
+            ..
+        } = self.request;
+
+        let entry_dht_status = match self.entry {
+            Some(_) if entry_dht_status => {
+                if actions.keys().any(|hash| !deleted_actions.contains(hash)) {
+                    Some(EntryDhtStatus::Live)
+                } else if !actions.is_empty() {
+                    Some(EntryDhtStatus::Dead)
+                } else if !invalid_actions.is_empty() {
+                    Some(EntryDhtStatus::Rejected)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        };
+        links.retain(|hash, _| !deleted_links.contains(hash));
+
+        let mut set = MetadataSet {
+            entry_dht_status,
+            validation_status,
+            ..Default::default()
+        };
+        if all_valid_actions {
+            set.actions = actions.into_values().collect();
+        }
+        if all_invalid_actions {
+            set.invalid_actions = invalid_actions.into_iter().collect();
+        }
+        if all_deletes {
+            set.deletes = deletes.into_iter().collect();
+        }
+        if all_updates {
+            set.updates = updates.into_iter().collect();
+        }
+        if all_links {
+            set.links = links.into_values().collect();
+            set.deleted_links = deleted_links.into_iter().collect();
+        }
+        Ok(set)
+    }
+}
@@ -0,0 +1,120 @@
+use holo_hash::*;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_zome_types::prelude::*;
+use std::fmt::Debug;
+
+use super::*;
+
+#[cfg(test)]
+mod test;
+
+/// Get a range of an agent's source chain by walking back from the chain top
+/// through each action's previous action hash.
+///
+/// Because the range is linked by hash it can't contain actions from a fork,
+/// so any store holding the actions will return the same result.
+#[derive(Debug, Clone)]
+pub struct MustGetAgentActivityQuery {
+    author: AgentPubKey,
+    filter: ChainFilter,
+    seq_range: (u32, u32),
+}
+
+impl MustGetAgentActivityQuery {
+    pub fn new(author: AgentPubKey, filter: ChainFilter) -> Self {
+        Self {
+            author,
+            filter,
+            seq_range: (0, u32::MAX),
+        }
+    }
+
+    /// Only read the author's actions with a sequence number in this
+    /// inclusive range, instead of the whole chain.
+    /// Any range containing the chain top and the actions the filter
+    /// stops at gives the same result.
+    pub fn with_seq_range(mut self, min_seq: u32, max_seq: u32) -> Self {
+        self.seq_range = (min_seq, max_seq);
+        self
+    }
+}
+
+impl Query for MustGetAgentActivityQuery {
+    type Item = Judged<SignedActionHashed>;
+    type State = HashMap<ActionHash, SignedActionHashed>;
+    type Output = MustGetAgentActivityResponse;
+
+    fn query(&self) -> String {
+        "
+        SELECT Action.blob, Action.hash
+        FROM Action
+        WHERE Action.author = :author
+        AND Action.seq BETWEEN :min_seq AND :max_seq
+        "
+        .into()
+    }
+
+    fn params(&self) -> Vec<Params> {
+        let params = named_params! {
+            ":author": self.author,
+            ":min_seq": self.seq_range.0,
+            ":max_seq": self.seq_range.1,
+        };
+        params.to_vec()
+    }
+
+    fn as_map(&self) -> Arc<dyn Fn(&Row) -> StateQueryResult<Self::Item>> {
+        let f = row_blob_and_hash_to_action("blob", "hash");
+        // The validity of the actions doesn't matter here because the
+        // range is determined by the hashes alone.
+        Arc::new(move |row| Ok(Judged::raw(f(row)?, None)))
+    }
+
+    fn as_filter(&self) -> Box<dyn Fn(&QueryData<Self>) -> bool> {
+        let author = self.author.clone();
+        let (min_seq, max_seq) = self.seq_range;
+        Box::new(move |action: &QueryData<Self>| {
+            let seq = action.action().action_seq();
+            action.action().author() == &author && seq >= min_seq && seq <= max_seq
+        })
+    }
+
+    fn init_fold(&self) -> StateQueryResult<Self::State> {
+        Ok(HashMap::new())
+    }
+
+    fn fold(&self, mut state: Self::State, item: Self::Item) -> StateQueryResult<Self::State> {
+        let action = item.data;
+        state.insert(action.as_hash().clone(), action);
+        Ok(state)
+    }
+
+    fn render<S>(&self, mut state: Self::State, _stores: S) -> StateQueryResult<Self::Output>
+    where
+        S: Store,
+    {
+        let take = self.filter.get_take().map(|n| n as usize);
+        let until = self.filter.get_until();
+        let mut chain = Vec::new();
+        let mut next = Some(self.filter.chain_top.clone());
+        while let Some(hash) = next {
+            if take.map_or(false, |n| chain.len() >= n) {
+                break;
+            }
+            let action = match state.remove(&hash) {
+                Some(action) => action,
+                None if chain.is_empty() => {
+                    return Ok(MustGetAgentActivityResponse::ChainTopNotFound(hash))
+                }
+                None => return Ok(MustGetAgentActivityResponse::IncompleteChain(hash)),
+            };
+            next = if until.map_or(false, |until| until.contains(&hash)) {
+                None
+            } else {
+                action.action().prev_action().cloned()
+            };
+            chain.push(action);
+        }
+        Ok(MustGetAgentActivityResponse::Activity(chain))
+    }
+}
@@ -0,0 +1,178 @@
+use ::fixt::prelude::*;
+use holochain_sqlite::rusqlite::Connection;
+use holochain_sqlite::rusqlite::TransactionBehavior;
+use holochain_sqlite::schema::SCHEMA_CELL;
+
+use crate::mutations::insert_action;
+
+use super::*;
+
+fn signed(action: Action) -> SignedActionHashed {
+    SignedActionHashed::with_presigned(ActionHashed::from_content_sync(action), fixt!(Signature))
+}
+
+/// Create a chain of `len` actions for the author, followed by a fork of
+/// the last action.
+fn make_chain(author: &AgentPubKey, len: u32) -> (Vec<SignedActionHashed>, SignedActionHashed) {
+    let mut chain: Vec<SignedActionHashed> = Vec::new();
+    for seq in 0..len {
+        let action: Action = match chain.last() {
+            Some(prev) => {
+                let mut action = fixt!(Create);
+                action.author = author.clone();
+                action.prev_action = prev.as_hash().clone();
+                action.action_seq = seq;
+                action.into()
+            }
+            None => {
+                let mut action = fixt!(Dna);
+                action.author = author.clone();
+                action.into()
+            }
+        };
+        chain.push(signed(action));
+    }
+    let mut fork = fixt!(Create);
+    fork.author = author.clone();
+    fork.prev_action = chain[len as usize - 2].as_hash().clone();
+    fork.action_seq = len - 1;
+    (chain, signed(fork.into()))
+}
+
+fn hashes(response: MustGetAgentActivityResponse) -> Vec<ActionHash> {
+    match response {
+        MustGetAgentActivityResponse::Activity(activity) => {
+            activity.into_iter().map(|a| a.as_hash().clone()).collect()
+        }
+        r => panic!("Expected activity but got {:?}", r),
+    }
+}
+
+#[test]
+fn walks_chain_from_top() {
+    let mut conn = Connection::open_in_memory().unwrap();
+    SCHEMA_CELL.initialize(&mut conn, None).unwrap();
+    let mut txn = conn
+        .transaction_with_behavior(TransactionBehavior::Exclusive)
+        .unwrap();
+
+    let author = fixt!(AgentPubKey);
+    let (chain, fork) = make_chain(&author, 10);
+    let (other_chain, _) = make_chain(&fixt!(AgentPubKey), 10);
+    for action in chain.iter().chain(other_chain.iter()).chain([&fork]) {
+        insert_action(&mut txn, action).unwrap();
+    }
+    let expected: Vec<_> = chain.iter().rev().map(|a| a.as_hash().clone()).collect();
+    let run = |filter: ChainFilter| {
+        MustGetAgentActivityQuery::new(author.clone(), filter)
+            .run(Txn::from(&txn))
+            .unwrap()
+    };
+
+    // - The whole chain is returned without the fork or the other author.
+    let top = chain[9].as_hash().clone();
+    assert_eq!(hashes(run(ChainFilter::new(top.clone()))), expected);
+
+    // - The fork is walked back to the shared part of the chain.
+    let r = hashes(run(ChainFilter::new(fork.as_hash().clone())));
+    assert_eq!(r[0], *fork.as_hash());
+    assert_eq!(r[1..], expected[1..]);
+
+    // - Take stops after n actions.
+    let r = hashes(run(ChainFilter::new(top.clone()).take(3)));
+    assert_eq!(r, expected[..3]);
+
+    // - Until stops at and includes the given action.
+    let r = hashes(run(
+        ChainFilter::new(top.clone()).until(chain[5].as_hash().clone())
+    ));
+    assert_eq!(r, expected[..5]);
+
+    // - Both stop at whichever comes first.
+    let filter = ChainFilter::new(top.clone())
+        .take(6)
+        .until(chain[7].as_hash().clone());
+    assert_eq!(hashes(run(filter)), expected[..3]);
+    let filter = ChainFilter::new(top)
+        .take(2)
+        .until(chain[5].as_hash().clone());
+    assert_eq!(hashes(run(filter)), expected[..2]);
+
+    // - A missing chain top is reported.
+    let missing = fixt!(ActionHash);
+    assert_eq!(
+        run(ChainFilter::new(missing.clone())),
+        MustGetAgentActivityResponse::ChainTopNotFound(missing)
+    );
+}
+
+#[test]
+fn missing_action_is_incomplete() {
+    let mut conn = Connection::open_in_memory().unwrap();
+    SCHEMA_CELL.initialize(&mut conn, None).unwrap();
+    let mut txn = conn
+        .transaction_with_behavior(TransactionBehavior::Exclusive)
+        .unwrap();
+
+    let author = fixt!(AgentPubKey);
+    let (chain, _) = make_chain(&author, 5);
+    for (i, action) in chain.iter().enumerate() {
+        if i != 2 {
+            insert_action(&mut txn, action).unwrap();
+        }
+    }
+    let query =
+        MustGetAgentActivityQuery::new(author, ChainFilter::new(chain[4].as_hash().clone()));
+    // - The missing action is reported so it can be fetched.
+    assert_eq!(
+        query.run(Txn::from(&txn)).unwrap(),
+        MustGetAgentActivityResponse::IncompleteChain(chain[2].as_hash().clone())
+    );
+
+    // - The range above the missing action is still available.
+    let query = MustGetAgentActivityQuery::new(
+        query.author.clone(),
+        ChainFilter::new(chain[4].as_hash().clone()).until(chain[3].as_hash().clone()),
+    );
+    assert_eq!(
+        hashes(query.run(Txn::from(&txn)).unwrap()),
+        vec![chain[4].as_hash().clone(), chain[3].as_hash().clone()]
+    );
+}
+
+#[test]
+fn seq_range_bounds_the_read() {
+    let mut conn = Connection::open_in_memory().unwrap();
+    SCHEMA_CELL.initialize(&mut conn, None).unwrap();
+    let mut txn = conn
+        .transaction_with_behavior(TransactionBehavior::Exclusive)
+        .unwrap();
+
+    let author = fixt!(AgentPubKey);
+    let (chain, _) = make_chain(&author, 10);
+    for action in &chain {
+        insert_action(&mut txn, action).unwrap();
+    }
+    let top = chain[9].as_hash().clone();
+    let run = |filter: ChainFilter, min_seq, max_seq| {
+        MustGetAgentActivityQuery::new(author.clone(), filter)
+            .with_seq_range(min_seq, max_seq)
+            .run(Txn::from(&txn))
+            .unwrap()
+    };
+
+    // - A range covering the filter gives the same result.
+    let r = hashes(run(ChainFilter::new(top.clone()).take(3), 7, 9));
+    let expected: Vec<_> = chain[7..]
+        .iter()
+        .rev()
+        .map(|a| a.as_hash().clone())
+        .collect();
+    assert_eq!(r, expected);
+
+    // - Actions outside the range aren't read.
+    assert_eq!(
+        run(ChainFilter::new(top), 7, 9),
+        MustGetAgentActivityResponse::IncompleteChain(chain[6].as_hash().clone())
+    );
+}
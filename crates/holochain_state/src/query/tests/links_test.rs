@@ -3,6 +3,7 @@ use crate::here;
 use crate::prelude::mutations_helpers::insert_valid_integrated_op;
 use crate::prelude::*;
 use holochain_types::db::DbWrite;
+use holochain_types::link::WireLinkKey;
 use holochain_types::record::SignedActionHashedExt;
 use observability;
 
@@ -917,3 +918,110 @@ async fn link_type_ranges() {
         here!("individual types"),
     );
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn links_filtered_and_paged() {
+    observability::test_run().ok();
+    let test_db = test_dht_db();
+    let arc = test_db.to_db();
+
+    let mut td = fixtures(arc.clone(), 10);
+    let base_hash = td[0].base_hash.clone();
+    let zome_id = td[0].zome_id;
+    let link_type = td[0].link_type;
+    let alice = fixt!(AgentPubKey);
+    let bob = fixt!(AgentPubKey);
+
+    for (i, d) in td.iter_mut().enumerate() {
+        d.link_add.base_address = base_hash.clone().into();
+        d.link_add.zome_id = zome_id;
+        d.link_add.link_type = link_type;
+        // Two links share each timestamp so the hash breaks the tie.
+        d.link_add.timestamp = Timestamp::from_micros((i / 2) as i64);
+        d.link_add.author = if i % 2 == 0 {
+            alice.clone()
+        } else {
+            bob.clone()
+        };
+        d.link_remove.base_address = base_hash.clone().into();
+
+        let link_add_hash = ActionHash::with_data_sync(&Action::CreateLink(d.link_add.clone()));
+        d.expected_link.create_link_hash = link_add_hash.clone();
+        d.expected_link.timestamp = d.link_add.timestamp;
+        d.link_remove.link_add_address = link_add_hash;
+        d.add_link();
+    }
+    td[3].delete_link();
+
+    let mut expected = td
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != 3)
+        .map(|(_, d)| d.expected_link.clone())
+        .collect::<Vec<_>>();
+    expected.sort_by(|a, b| {
+        (a.timestamp, &a.create_link_hash).cmp(&(b.timestamp, &b.create_link_hash))
+    });
+
+    let key = WireLinkKey::new(
+        base_hash.clone().into(),
+        LinkTypeFilter::single_dep(zome_id),
+        None,
+    );
+    let run = |key: WireLinkKey| {
+        fresh_reader_test(arc.clone(), |txn| {
+            GetLinksQuery::from_key(key)
+                .run(DbScratch::new(&[&txn], &Scratch::new()))
+                .unwrap()
+        })
+    };
+
+    // No filter returns every live link in order.
+    assert_eq!(run(key.clone()), expected);
+
+    // Filter by author.
+    let alices = run(WireLinkKey {
+        author: Some(alice.clone()),
+        ..key.clone()
+    });
+    assert_eq!(alices.len(), 5);
+    assert!(alices.iter().all(|l| td
+        .iter()
+        .any(|d| d.expected_link == *l && d.link_add.author == alice)));
+
+    // Filter by a half open time range.
+    let range = run(WireLinkKey {
+        after: Some(Timestamp::from_micros(1)),
+        before: Some(Timestamp::from_micros(3)),
+        ..key.clone()
+    });
+    assert_eq!(
+        range,
+        expected
+            .iter()
+            .filter(|l| l.timestamp >= Timestamp::from_micros(1)
+                && l.timestamp < Timestamp::from_micros(3))
+            .cloned()
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(range.len(), 3);
+
+    // Paging through the links returns each live link once in order.
+    let mut pages = Vec::new();
+    let mut cursor = None;
+    loop {
+        let page = run(WireLinkKey {
+            cursor: cursor.clone(),
+            limit: Some(4),
+            ..key.clone()
+        });
+        if page.is_empty() {
+            break;
+        }
+        assert!(page.len() <= 4);
+        cursor = page.last().map(LinkCursor::from);
+        pages.push(page);
+    }
+    assert_eq!(pages.len(), 3);
+    assert_eq!(pages.concat(), expected);
+}
@@ -33,7 +33,11 @@ use holochain_zome_types::ActionExt;
 use holochain_zome_types::ActionHashed;
 use holochain_zome_types::ActionUnweighed;
 use holochain_zome_types::CapAccess;
+use holochain_zome_types::CapClaimInfo;
+use holochain_zome_types::CapClaimQuery;
 use holochain_zome_types::CapGrant;
+use holochain_zome_types::CapGrantInfo;
+use holochain_zome_types::CapGrantQuery;
 use holochain_zome_types::CapSecret;
 use holochain_zome_types::CellId;
 use holochain_zome_types::ChainTopOrdering;
@@ -65,6 +69,20 @@ pub use error::*;
 
 mod error;
 
+/// Counts the actions by the chain author which update or delete `Action`.
+/// Used to only select entries from actions which are still live.
+const NOT_REFERENCED_ACTION: &str = "
+    SELECT COUNT(H_REF.hash)
+    FROM Action AS H_REF
+    JOIN DhtOp AS D_REF ON D_REF.action_hash = H_REF.hash
+    WHERE
+    H_REF.author = :author
+    AND
+    (H_REF.original_action_hash = Action.hash
+    OR
+    H_REF.deletes_action_hash = Action.hash)
+";
+
 #[derive(Clone)]
 pub struct SourceChain<AuthorDb = DbWrite<DbKindAuthored>, DhtDb = DbWrite<DbKindDht>> {
     scratch: SyncScratch,
@@ -210,6 +228,21 @@ impl SourceChain {
         chain_top_ordering: ChainTopOrdering,
         weight: W,
     ) -> SourceChainResult<ActionHash> {
+        let action = self.next_action(action_builder)?;
+        self.put_with_action(
+            action.weighed(weight).into(),
+            maybe_entry,
+            chain_top_ordering,
+        )
+        .await
+    }
+
+    /// Build the unweighed action that would be put next on the source chain,
+    /// so that it can be weighed before it's put with [`SourceChain::put_with_action`].
+    pub fn next_action<U: ActionUnweighed, B: ActionBuilder<U>>(
+        &self,
+        action_builder: B,
+    ) -> SourceChainResult<U> {
         let (prev_action, chain_head_seq, chain_head_timestamp) = self.chain_head()?;
         let action_seq = chain_head_seq + 1;
 
@@ -230,12 +263,7 @@ impl SourceChain {
             action_seq,
             prev_action,
         };
-        self.put_with_action(
-            action_builder.build(common).weighed(weight).into(),
-            maybe_entry,
-            chain_top_ordering,
-        )
-        .await
+        Ok(action_builder.build(common))
     }
 
     #[cfg(feature = "test_utils")]
@@ -586,17 +614,6 @@ where
         let valid_cap_grant = self
             .vault
             .async_reader(move |txn| {
-                let not_referenced_action = "
-            SELECT COUNT(H_REF.hash)
-            FROM Action AS H_REF
-            JOIN DhtOp AS D_REF ON D_REF.action_hash = H_REF.hash
-            WHERE
-            H_REF.author = :author
-            AND
-            (H_REF.original_action_hash = Action.hash
-            OR
-            H_REF.deletes_action_hash = Action.hash)
-            ";
                 let sql = format!(
                     "
                 SELECT DISTINCT Entry.blob
@@ -610,7 +627,7 @@ where
                 AND
                 ({}) = 0
                 ",
-                    not_referenced_action
+                    NOT_REFERENCED_ACTION
                 );
                 txn.prepare(&sql)?
                     .query_and_then(
@@ -689,6 +706,130 @@ where
         Ok(valid_cap_grant)
     }
 
+    /// The capability grants on this chain which haven't been updated or
+    /// deleted and pass the query, in chain order.
+    ///
+    /// Only grants which have been committed are returned.
+    pub async fn live_cap_grants(
+        &self,
+        query: CapGrantQuery,
+    ) -> SourceChainResult<Vec<CapGrantInfo>> {
+        let author = self.author.clone();
+        let tag = query.tag.clone();
+        let grants = self
+            .vault
+            .async_reader(move |txn| {
+                let sql = format!(
+                    "
+                SELECT DISTINCT
+                Action.hash AS action_hash, Action.blob AS action_blob,
+                Action.seq AS seq, Entry.blob AS entry_blob
+                FROM Entry
+                JOIN Action ON Action.entry_hash = Entry.hash
+                JOIN DhtOp ON Action.hash = DhtOp.action_hash
+                WHERE
+                Action.author = :author
+                AND
+                Entry.access_type IS NOT NULL
+                AND
+                (:tag IS NULL OR Entry.tag = :tag)
+                AND
+                ({}) = 0
+                ORDER BY seq ASC
+                ",
+                    NOT_REFERENCED_ACTION
+                );
+                txn.prepare(&sql)?
+                    .query_and_then(
+                        named_params! {
+                            ":author": author,
+                            ":tag": tag,
+                        },
+                        |row| {
+                            let SignedAction(action, _) = from_blob(row.get("action_blob")?)?;
+                            let cap_grant = match from_blob(row.get("entry_blob")?)? {
+                                Entry::CapGrant(cap_grant) => cap_grant,
+                                _ => return Ok(None),
+                            };
+                            StateQueryResult::Ok(Some(CapGrantInfo {
+                                cap_grant,
+                                action_hash: row.get("action_hash")?,
+                                created_at: action.timestamp(),
+                            }))
+                        },
+                    )?
+                    .filter_map(Result::transpose)
+                    .collect::<StateQueryResult<Vec<_>>>()
+            })
+            .await?;
+        Ok(grants
+            .into_iter()
+            .filter(|info| query.check(&info.cap_grant))
+            .collect())
+    }
+
+    /// The capability claims on this chain which haven't been updated or
+    /// deleted and pass the query, in chain order.
+    ///
+    /// Only claims which have been committed are returned.
+    pub async fn live_cap_claims(
+        &self,
+        query: CapClaimQuery,
+    ) -> SourceChainResult<Vec<CapClaimInfo>> {
+        let author = self.author.clone();
+        let tag = query.tag.clone();
+        let claims = self
+            .vault
+            .async_reader(move |txn| {
+                let sql = format!(
+                    "
+                SELECT DISTINCT
+                Action.hash AS action_hash, Action.blob AS action_blob,
+                Action.seq AS seq, Entry.blob AS entry_blob
+                FROM Entry
+                JOIN Action ON Action.entry_hash = Entry.hash
+                JOIN DhtOp ON Action.hash = DhtOp.action_hash
+                WHERE
+                Action.author = :author
+                AND
+                Entry.grantor IS NOT NULL
+                AND
+                (:tag IS NULL OR Entry.tag = :tag)
+                AND
+                ({}) = 0
+                ORDER BY seq ASC
+                ",
+                    NOT_REFERENCED_ACTION
+                );
+                txn.prepare(&sql)?
+                    .query_and_then(
+                        named_params! {
+                            ":author": author,
+                            ":tag": tag,
+                        },
+                        |row| {
+                            let SignedAction(action, _) = from_blob(row.get("action_blob")?)?;
+                            let cap_claim = match from_blob(row.get("entry_blob")?)? {
+                                Entry::CapClaim(cap_claim) => cap_claim,
+                                _ => return Ok(None),
+                            };
+                            StateQueryResult::Ok(Some(CapClaimInfo {
+                                cap_claim,
+                                action_hash: row.get("action_hash")?,
+                                created_at: action.timestamp(),
+                            }))
+                        },
+                    )?
+                    .filter_map(Result::transpose)
+                    .collect::<StateQueryResult<Vec<_>>>()
+            })
+            .await?;
+        Ok(claims
+            .into_iter()
+            .filter(|info| query.check(&info.cap_claim))
+            .collect())
+    }
+
     /// Query Actions in the source chain.
     /// This returns a Vec rather than an iterator because it is intended to be
     /// used by the `query` host function, which crosses the wasm boundary
@@ -1236,6 +1377,48 @@ pub async fn dump_state(
         .await?)
 }
 
+/// Every record of an author's chain in order, including private entries.
+pub async fn export_records(
+    vault: DbRead<DbKindAuthored>,
+    author: AgentPubKey,
+) -> SourceChainResult<Vec<Record>> {
+    Ok(vault
+        .async_reader(move |txn| {
+            txn.prepare(
+                "
+                SELECT DISTINCT
+                Action.blob AS action_blob, Entry.blob AS entry_blob,
+                Action.seq AS seq
+                FROM Action
+                JOIN DhtOp ON DhtOp.action_hash = Action.hash
+                LEFT JOIN Entry ON Action.entry_hash = Entry.hash
+                WHERE
+                Action.author = :author
+                ORDER BY Action.seq ASC
+                ",
+            )?
+            .query_and_then(
+                named_params! {
+                    ":author": author,
+                },
+                |row| {
+                    let action: SignedAction = from_blob(row.get("action_blob")?)?;
+                    let entry: Option<Vec<u8>> = row.get("entry_blob")?;
+                    let entry: Option<Entry> = match entry {
+                        Some(entry) => Some(from_blob(entry)?),
+                        None => None,
+                    };
+                    StateQueryResult::Ok(Record::new(
+                        SignedActionHashed::from_content_sync(action),
+                        entry,
+                    ))
+                },
+            )?
+            .collect::<StateQueryResult<Vec<_>>>()
+        })
+        .await?)
+}
+
 impl From<SourceChain> for SourceChainRead {
     fn from(chain: SourceChain) -> Self {
         SourceChainRead {
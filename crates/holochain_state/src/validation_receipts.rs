@@ -3,6 +3,7 @@
 use futures::Stream;
 use futures::StreamExt;
 use futures::TryStreamExt;
+use holo_hash::ActionHash;
 use holo_hash::AgentPubKey;
 use holo_hash::DhtOpHash;
 use holochain_keystore::AgentPubKeyExt;
@@ -12,7 +13,10 @@ use holochain_sqlite::prelude::*;
 use holochain_sqlite::rusqlite::named_params;
 use holochain_sqlite::rusqlite::OptionalExtension;
 use holochain_sqlite::rusqlite::Transaction;
+use holochain_types::dht_op::DhtOpType;
 use holochain_zome_types::signature::Signature;
+use holochain_zome_types::validate::ValidationReceiptInfo;
+use holochain_zome_types::validate::ValidationReceiptSet;
 use holochain_zome_types::Timestamp;
 use holochain_zome_types::ValidationStatus;
 use mutations::StateMutationResult;
@@ -131,6 +135,71 @@ pub fn list_receipts(
     iter.collect()
 }
 
+/// List the validation receipts for ops in the database, grouped by op.
+///
+/// The ops can be limited to those produced by a single action and to those
+/// authored by a single agent. Ops which have not received any receipts yet
+/// are included with an empty list of receipts.
+pub fn list_receipt_sets(
+    txn: &Transaction,
+    action_hash: Option<&ActionHash>,
+    author: Option<&AgentPubKey>,
+) -> StateQueryResult<Vec<ValidationReceiptSet>> {
+    let mut stmt = txn.prepare(
+        "
+        SELECT DhtOp.hash, DhtOp.type, DhtOp.receipts_complete, ValidationReceipt.blob
+        FROM DhtOp
+        JOIN Action ON DhtOp.action_hash = Action.hash
+        LEFT JOIN ValidationReceipt ON ValidationReceipt.op_hash = DhtOp.hash
+        WHERE
+        (:action_hash IS NULL OR DhtOp.action_hash = :action_hash)
+        AND
+        (:author IS NULL OR Action.author = :author)
+        ORDER BY DhtOp.rowid
+        ",
+    )?;
+    let rows = stmt.query_and_then(
+        named_params! {
+            ":action_hash": action_hash,
+            ":author": author,
+        },
+        |row| {
+            let op_hash: DhtOpHash = row.get("hash")?;
+            let op_type: DhtOpType = row.get("type")?;
+            let receipts_complete: Option<bool> = row.get("receipts_complete")?;
+            let receipt = row
+                .get::<_, Option<Vec<u8>>>("blob")?
+                .map(from_blob::<SignedValidationReceipt>)
+                .transpose()?;
+            StateQueryResult::Ok((op_hash, op_type, receipts_complete, receipt))
+        },
+    )?;
+    let mut sets: Vec<ValidationReceiptSet> = Vec::new();
+    for row in rows {
+        let (op_hash, op_type, receipts_complete, receipt) = row?;
+        let set = match sets.last_mut() {
+            Some(set) if set.op_hash == op_hash => set,
+            _ => {
+                sets.push(ValidationReceiptSet {
+                    op_hash,
+                    op_type: op_type.to_string(),
+                    receipts_complete: receipts_complete.unwrap_or(false),
+                    receipts: Vec::new(),
+                });
+                sets.last_mut().expect("Just pushed a set")
+            }
+        };
+        if let Some(SignedValidationReceipt { receipt, .. }) = receipt {
+            set.receipts.push(ValidationReceiptInfo {
+                validation_status: receipt.validation_status,
+                validators: receipt.validators,
+                when_integrated: receipt.when_integrated,
+            });
+        }
+    }
+    Ok(sets)
+}
+
 pub fn count_valid(txn: &Transaction, op_hash: &DhtOpHash) -> DatabaseResult<usize> {
     let count: usize = txn
         .query_row(
@@ -159,6 +228,7 @@ mod tests {
     use holo_hash::HasHash;
     use holochain_types::dht_op::DhtOp;
     use holochain_types::dht_op::DhtOpHashed;
+    use holochain_zome_types::action::Action;
     use holochain_zome_types::fixt::*;
 
     async fn fake_vr(
@@ -231,6 +301,86 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_list_receipt_sets_groups_by_op() -> StateMutationResult<()> {
+        observability::test_run().ok();
+
+        let test_db = crate::test_utils::test_dht_db();
+        let env = test_db.to_db();
+        let keystore = crate::test_utils::test_keystore();
+
+        let action = Action::Dna(fixt!(Dna));
+        let action_hash = ActionHash::with_data_sync(&action);
+        let author = action.author().clone();
+        let store_op = DhtOpHashed::from_content_sync(DhtOp::StoreRecord(
+            fixt!(Signature),
+            action.clone(),
+            None,
+        ));
+        let activity_op =
+            DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(fixt!(Signature), action));
+        let other_op = DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(
+            fixt!(Signature),
+            fixt!(Action),
+        ));
+
+        let vr1 = fake_vr(store_op.as_hash(), &keystore).await;
+        let vr2 = fake_vr(store_op.as_hash(), &keystore).await;
+        let vr3 = fake_vr(other_op.as_hash(), &keystore).await;
+
+        env.conn().unwrap().with_commit_sync(|txn| {
+            mutations::insert_op(txn, &store_op)?;
+            mutations::insert_op(txn, &activity_op)?;
+            mutations::insert_op(txn, &other_op)?;
+            add_if_unique(txn, vr1.clone())?;
+            add_if_unique(txn, vr2.clone())?;
+            add_if_unique(txn, vr3)?;
+            mutations::set_receipts_complete(txn, store_op.as_hash(), true)
+        })?;
+
+        let mut g = env.conn().unwrap();
+        g.with_reader_test(|reader| {
+            let sets = list_receipt_sets(&reader, Some(&action_hash), None).unwrap();
+            assert_eq!(2, sets.len());
+
+            let store_set = sets
+                .iter()
+                .find(|set| set.op_hash == *store_op.as_hash())
+                .unwrap();
+            assert_eq!("StoreRecord", store_set.op_type);
+            assert!(store_set.receipts_complete);
+            assert_eq!(2, store_set.validator_count());
+            let mut validators: Vec<_> = store_set
+                .receipts
+                .iter()
+                .flat_map(|r| r.validators.clone())
+                .collect();
+            validators.sort();
+            let mut expected: Vec<_> = [vr1, vr2]
+                .into_iter()
+                .flat_map(|vr| vr.receipt.validators)
+                .collect();
+            expected.sort();
+            assert_eq!(expected, validators);
+
+            let activity_set = sets
+                .iter()
+                .find(|set| set.op_hash == *activity_op.as_hash())
+                .unwrap();
+            assert_eq!("RegisterAgentActivity", activity_set.op_type);
+            assert!(!activity_set.receipts_complete);
+            assert!(activity_set.receipts.is_empty());
+
+            let sets = list_receipt_sets(&reader, None, Some(&author)).unwrap();
+            assert_eq!(2, sets.len());
+
+            let sets = list_receipt_sets(&reader, None, None).unwrap();
+            assert_eq!(3, sets.len());
+            assert_eq!(3, sets.iter().map(|set| set.receipts.len()).sum::<usize>());
+        });
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_try_stream_of_results() {
         let iter: Vec<futures::future::Ready<Result<i32, String>>> = vec![];
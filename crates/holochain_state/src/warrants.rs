@@ -0,0 +1,287 @@
+//! Module for items related to warrants
+
+use holo_hash::AgentPubKey;
+use holochain_keystore::AgentPubKeyExt;
+use holochain_keystore::LairResult;
+use holochain_keystore::MetaLairClient;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_sqlite::rusqlite::Transaction;
+use holochain_zome_types::warrant::SignedWarrant;
+use holochain_zome_types::warrant::Warrant;
+use holochain_zome_types::warrant::WarrantProof;
+use holochain_zome_types::ValidationStatus;
+
+use crate::prelude::from_blob;
+use crate::prelude::StateQueryResult;
+
+/// The most warrants held about a single agent which were received from
+/// the network. One proven warrant is enough to block an agent, so there is
+/// no need to hold every warrant that other agents publish.
+pub const MAX_WARRANTS_PER_WARRANTEE: usize = 8;
+
+/// Sign a warrant with the key of its author.
+pub async fn sign_warrant(
+    keystore: &MetaLairClient,
+    warrant: Warrant,
+) -> LairResult<SignedWarrant> {
+    let signature = warrant.author.sign(keystore, warrant.clone()).await?;
+    Ok(SignedWarrant::new(warrant, signature))
+}
+
+/// Check a warrant was signed by its author.
+pub async fn verify_warrant(warrant: &SignedWarrant) -> bool {
+    warrant
+        .warrant
+        .author
+        .verify_signature(&warrant.signature, warrant.warrant.clone())
+        .await
+}
+
+/// All the warrants held about an agent.
+pub fn list_warrants(
+    txn: &Transaction,
+    warrantee: &AgentPubKey,
+) -> StateQueryResult<Vec<SignedWarrant>> {
+    let mut stmt = txn.prepare(
+        "
+        SELECT blob FROM Warrant WHERE warrantee = :warrantee
+        ORDER BY timestamp
+        ",
+    )?;
+    let iter = stmt.query_and_then(
+        named_params! {
+            ":warrantee": warrantee
+        },
+        |row| from_blob::<SignedWarrant>(row.get("blob")?),
+    )?;
+    iter.collect()
+}
+
+/// The number of warrants held about an agent.
+pub fn count_warrants(txn: &Transaction, warrantee: &AgentPubKey) -> StateQueryResult<usize> {
+    Ok(txn.query_row(
+        "SELECT COUNT(*) FROM Warrant WHERE warrantee = :warrantee",
+        named_params! {
+            ":warrantee": warrantee
+        },
+        |row| row.get(0),
+    )?)
+}
+
+/// Every agent that has a warrant held about it.
+pub fn list_warranted_agents(txn: &Transaction) -> StateQueryResult<Vec<AgentPubKey>> {
+    let mut stmt = txn.prepare("SELECT DISTINCT warrantee FROM Warrant")?;
+    let iter = stmt.query_map([], |row| row.get("warrantee"))?;
+    Ok(iter.collect::<Result<_, _>>()?)
+}
+
+/// Check if the proof of a warrant is backed by data this node holds.
+///
+/// An invalid op is proven if we have rejected an op for the action.
+/// A chain fork is proven if we hold both actions and they were authored
+/// by the warrantee at the same sequence number.
+pub fn warrant_is_proven(txn: &Transaction, proof: &WarrantProof) -> StateQueryResult<bool> {
+    match proof {
+        WarrantProof::InvalidChainOp {
+            action: (hash, _), ..
+        } => Ok(txn.query_row(
+            "
+            SELECT EXISTS(
+                SELECT 1 FROM DhtOp
+                WHERE action_hash = :hash
+                AND validation_status = :rejected
+            )
+            ",
+            named_params! {
+                ":hash": hash,
+                ":rejected": ValidationStatus::Rejected,
+            },
+            |row| row.get(0),
+        )?),
+        WarrantProof::ChainFork {
+            chain_author,
+            action_pair: ((a, _), (b, _)),
+        } => {
+            if a == b {
+                return Ok(false);
+            }
+            let count: usize = txn.query_row(
+                "
+                SELECT COUNT(DISTINCT A.hash) FROM Action A
+                JOIN Action B ON A.seq = B.seq AND A.author = B.author
+                WHERE A.author = :author
+                AND A.hash IN (:a, :b)
+                AND B.hash IN (:a, :b)
+                AND A.hash != B.hash
+                ",
+                named_params! {
+                    ":author": chain_author,
+                    ":a": a,
+                    ":b": b,
+                },
+                |row| row.get(0),
+            )?;
+            Ok(count == 2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mutations;
+    use crate::mutations::StateMutationResult;
+    use ::fixt::prelude::*;
+    use holo_hash::fixt::ActionHashFixturator;
+    use holo_hash::fixt::EntryHashFixturator;
+    use holo_hash::ActionHash;
+    use holo_hash::HasHash;
+    use holochain_sqlite::prelude::*;
+    use holochain_types::dht_op::DhtOp;
+    use holochain_types::dht_op::DhtOpHashed;
+    use holochain_zome_types::fixt::CreateFixturator;
+    use holochain_zome_types::fixt::SignatureFixturator;
+    use holochain_zome_types::warrant::ValidationType;
+    use holochain_zome_types::Action;
+    use holochain_zome_types::Timestamp;
+
+    async fn fake_warrant(
+        author: &AgentPubKey,
+        warrantee: &AgentPubKey,
+        keystore: &MetaLairClient,
+    ) -> SignedWarrant {
+        let proof = WarrantProof::InvalidChainOp {
+            action_author: warrantee.clone(),
+            action: (fixt!(ActionHash), fixt!(Signature)),
+            validation_type: ValidationType::App,
+        };
+        sign_warrant(
+            keystore,
+            Warrant::new(proof, author.clone(), Timestamp::now()),
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn warrants_are_stored_once_and_listed_by_warrantee() -> StateMutationResult<()> {
+        let test_db = crate::test_utils::test_dht_db();
+        let db = test_db.to_db();
+        let keystore = crate::test_utils::test_keystore();
+        let author = keystore.new_sign_keypair_random().await.unwrap();
+        let alice = keystore.new_sign_keypair_random().await.unwrap();
+        let bob = keystore.new_sign_keypair_random().await.unwrap();
+
+        let w1 = fake_warrant(&author, &alice, &keystore).await;
+        let w2 = fake_warrant(&author, &alice, &keystore).await;
+        let w3 = fake_warrant(&author, &bob, &keystore).await;
+        assert!(verify_warrant(&w1).await);
+
+        // The same proof from the same author is a duplicate,
+        // even if it was issued at a different time.
+        let mut w1_again = w1.clone();
+        w1_again.warrant.timestamp = Timestamp::now();
+
+        let mut conn = db.conn().unwrap();
+        conn.with_commit_sync(|txn| {
+            mutations::insert_warrant(txn, w1.clone())?;
+            mutations::insert_warrant(txn, w1_again)?;
+            mutations::insert_warrant(txn, w2.clone())?;
+            mutations::insert_warrant(txn, w3.clone())
+        })?;
+
+        conn.with_reader_test(|txn| {
+            assert_eq!(count_warrants(&txn, &alice).unwrap(), 2);
+            assert_eq!(list_warrants(&txn, &alice).unwrap(), vec![w1, w2]);
+            assert_eq!(list_warrants(&txn, &bob).unwrap(), vec![w3]);
+
+            let mut agents = list_warranted_agents(&txn).unwrap();
+            agents.sort();
+            let mut expected = vec![alice, bob];
+            expected.sort();
+            assert_eq!(agents, expected);
+        });
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn tampered_warrant_fails_verification() {
+        let keystore = crate::test_utils::test_keystore();
+        let author = keystore.new_sign_keypair_random().await.unwrap();
+        let alice = keystore.new_sign_keypair_random().await.unwrap();
+        let bob = keystore.new_sign_keypair_random().await.unwrap();
+
+        let mut warrant = fake_warrant(&author, &alice, &keystore).await;
+        warrant.warrant.author = bob;
+        assert!(!verify_warrant(&warrant).await);
+    }
+
+    #[test]
+    fn warrants_are_only_proven_by_held_data() {
+        let test_db = crate::test_utils::test_dht_db();
+        let db = test_db.to_db();
+
+        let mut create = fixt!(Create);
+        create.action_seq = 3;
+        let mut fork = create.clone();
+        fork.entry_hash = fixt!(EntryHash);
+        let create = DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(
+            fixt!(Signature),
+            Action::Create(create),
+        ));
+        let fork = DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(
+            fixt!(Signature),
+            Action::Create(fork),
+        ));
+        let author = create.action().author().clone();
+        let hash = |op: &DhtOpHashed| {
+            (
+                ActionHash::with_data_sync(&op.action()),
+                op.signature().clone(),
+            )
+        };
+
+        let invalid = WarrantProof::InvalidChainOp {
+            action_author: author.clone(),
+            action: hash(&create),
+            validation_type: ValidationType::App,
+        };
+        let chain_fork = WarrantProof::ChainFork {
+            chain_author: author.clone(),
+            action_pair: (hash(&create), hash(&fork)),
+        };
+        let self_fork = WarrantProof::ChainFork {
+            chain_author: author,
+            action_pair: (hash(&create), hash(&create)),
+        };
+
+        // Nothing is held.
+        db.test_commit(|txn| {
+            assert!(!warrant_is_proven(txn, &invalid).unwrap());
+            assert!(!warrant_is_proven(txn, &chain_fork).unwrap());
+        });
+
+        db.test_commit(|txn| {
+            mutations::insert_op(txn, &create).unwrap();
+            mutations::set_validation_status(txn, create.as_hash(), ValidationStatus::Valid)
+                .unwrap();
+        });
+        db.test_commit(|txn| {
+            // The op is held but we judged it valid.
+            assert!(!warrant_is_proven(txn, &invalid).unwrap());
+            // Only one side of the fork is held.
+            assert!(!warrant_is_proven(txn, &chain_fork).unwrap());
+        });
+
+        db.test_commit(|txn| {
+            mutations::insert_op(txn, &fork).unwrap();
+            mutations::set_validation_status(txn, create.as_hash(), ValidationStatus::Rejected)
+                .unwrap();
+        });
+        db.test_commit(|txn| {
+            assert!(warrant_is_proven(txn, &invalid).unwrap());
+            assert!(warrant_is_proven(txn, &chain_fork).unwrap());
+            assert!(!warrant_is_proven(txn, &self_fork).unwrap());
+        });
+    }
+}
@@ -0,0 +1,465 @@
+//! Module for inspecting the ops waiting in the workflow queues of a cell
+
+use holo_hash::AgentPubKey;
+use holo_hash::AnyDhtHash;
+use holo_hash::DhtOpHash;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_sqlite::rusqlite::Row;
+use holochain_sqlite::rusqlite::Transaction;
+use holochain_sqlite::sql::sql_cell;
+use holochain_types::dht_op::DhtOpType;
+use holochain_zome_types::Timestamp;
+use holochain_zome_types::ValidationStatus;
+
+use crate::prelude::StateMutationResult;
+use crate::prelude::StateQueryResult;
+
+/// A queue of ops waiting to be processed by a workflow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkflowQueue {
+    /// Ops waiting for sys validation or its dependencies.
+    SysValidation,
+    /// Ops waiting for app validation or its dependencies.
+    AppValidation,
+    /// Validated ops waiting to be integrated.
+    Integration,
+    /// Authored ops which haven't got enough validation receipts yet.
+    Publish,
+}
+
+/// The ops waiting in a workflow queue.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct WorkflowQueueInfo {
+    /// The queue.
+    pub queue: WorkflowQueue,
+    /// The number of ops in the queue.
+    pub depth: usize,
+    /// How long ago the oldest op in the queue was stored, in seconds.
+    /// [`None`] if the queue is empty.
+    pub oldest_age_secs: Option<u64>,
+}
+
+/// An op which can't leave its queue until a dependency is held.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct BlockedOp {
+    /// The hash of the op.
+    pub op_hash: DhtOpHash,
+    /// The type of the op.
+    pub op_type: DhtOpType,
+    /// The queue the op is waiting in.
+    pub queue: WorkflowQueue,
+    /// The hash the op is waiting on. Ops in validation wait for the
+    /// dependency to be fetched, ops in integration wait for the
+    /// ops of the dependency to be integrated.
+    pub dependency: AnyDhtHash,
+}
+
+/// The workflow queues of a cell and the ops stuck in them.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct WorkflowQueuesDump {
+    /// Every queue of the cell. The validation and integration
+    /// queues are shared by all the cells of the same DNA.
+    pub queues: Vec<WorkflowQueueInfo>,
+    /// The ops waiting on dependencies. An op appears once for each
+    /// dependency it waits on.
+    pub blocked_ops: Vec<BlockedOp>,
+}
+
+/// The sys validation, app validation and integration queues of a DHT database.
+pub fn dht_queues(txn: &Transaction, now: Timestamp) -> StateQueryResult<Vec<WorkflowQueueInfo>> {
+    Ok(txn.query_row(sql_cell::QUEUE_DEPTHS, [], |row| {
+        Ok(vec![
+            queue_info(row, now, WorkflowQueue::SysValidation, "sys_validation")?,
+            queue_info(row, now, WorkflowQueue::AppValidation, "app_validation")?,
+            queue_info(row, now, WorkflowQueue::Integration, "integration")?,
+        ])
+    })?)
+}
+
+/// Read a queue from the `<column>` and `<column>_oldest` columns of `row`.
+fn queue_info(
+    row: &Row,
+    now: Timestamp,
+    queue: WorkflowQueue,
+    column: &str,
+) -> holochain_sqlite::rusqlite::Result<WorkflowQueueInfo> {
+    Ok(WorkflowQueueInfo {
+        queue,
+        depth: row.get(column)?,
+        oldest_age_secs: age_secs(now, row.get(format!("{}_oldest", column).as_str())?),
+    })
+}
+
+/// The queue of ops authored by `author` waiting to be published,
+/// from an authored database.
+pub fn publish_queue(
+    txn: &Transaction,
+    author: &AgentPubKey,
+    now: Timestamp,
+) -> StateQueryResult<WorkflowQueueInfo> {
+    Ok(txn.query_row(
+        sql_cell::PUBLISH_QUEUE_DEPTH,
+        named_params! {
+            ":author": author,
+            ":store_entry": DhtOpType::StoreEntry,
+        },
+        |row| {
+            Ok(WorkflowQueueInfo {
+                queue: WorkflowQueue::Publish,
+                depth: row.get("depth")?,
+                oldest_age_secs: age_secs(now, row.get("oldest")?),
+            })
+        },
+    )?)
+}
+
+/// The ops in a DHT database which are waiting on dependencies.
+pub fn blocked_ops(txn: &Transaction) -> StateQueryResult<Vec<BlockedOp>> {
+    let mut stmt = txn.prepare(sql_cell::BLOCKED_OPS)?;
+    let ops = stmt
+        .query_map([], |row| {
+            let queue = match row.get::<_, u8>("validation_stage")? {
+                0 => WorkflowQueue::SysValidation,
+                2 => WorkflowQueue::AppValidation,
+                _ => WorkflowQueue::Integration,
+            };
+            Ok(BlockedOp {
+                op_hash: row.get("hash")?,
+                op_type: row.get("type")?,
+                queue,
+                dependency: row.get("dependency")?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ops)
+}
+
+/// Put ops back at the start of the validation pipeline so they are
+/// validated again, forgetting any dependencies they were waiting on.
+///
+/// Ops which are integrated are only re-queued if their validation was
+/// abandoned, and integrated agent activity is never re-queued.
+/// Returns the hashes of the ops which were re-queued.
+pub fn requeue_ops(
+    txn: &mut Transaction,
+    hashes: &[DhtOpHash],
+) -> StateMutationResult<Vec<DhtOpHash>> {
+    let mut requeued = Vec::new();
+    for hash in hashes {
+        let changed = txn.execute(
+            sql_cell::REQUEUE_OP,
+            named_params! {
+                ":hash": hash,
+                ":abandoned": ValidationStatus::Abandoned,
+                ":activity": DhtOpType::RegisterAgentActivity,
+            },
+        )?;
+        if changed > 0 {
+            txn.execute(
+                "DELETE FROM ValidationDependency WHERE op_hash = :hash",
+                named_params! { ":hash": hash },
+            )?;
+            requeued.push(hash.clone());
+        }
+    }
+    Ok(requeued)
+}
+
+/// The hashes of the ops whose validation was abandoned.
+pub fn abandoned_ops(txn: &Transaction) -> StateQueryResult<Vec<DhtOpHash>> {
+    let mut stmt = txn.prepare(sql_cell::ABANDONED_OPS)?;
+    let hashes = stmt
+        .query_map(
+            named_params! { ":abandoned": ValidationStatus::Abandoned },
+            |row| row.get("hash"),
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(hashes)
+}
+
+fn age_secs(now: Timestamp, oldest: Option<Timestamp>) -> Option<u64> {
+    oldest.map(|oldest| (now.as_micros() - oldest.as_micros()).max(0) as u64 / 1_000_000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mutations;
+    use crate::prelude::*;
+    use ::fixt::prelude::*;
+    use holo_hash::HasHash;
+    use holochain_types::dht_op::DhtOp;
+    use holochain_types::dht_op::DhtOpHashed;
+    use holochain_zome_types::fixt::*;
+    use holochain_zome_types::Action;
+
+    fn op() -> DhtOpHashed {
+        DhtOpHashed::from_content_sync(DhtOp::RegisterAddLink(fixt!(Signature), fixt!(CreateLink)))
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn dumps_queues_and_blocked_ops() {
+        let test_db = crate::test_utils::test_dht_db();
+        let db = test_db.to_db();
+
+        // - One op pending sys validation, one waiting on a sys
+        // validation dependency and one waiting on two app validation
+        // dependencies.
+        let pending = op();
+        let awaiting_sys = op();
+        let awaiting_app = op();
+        let sys_dep = fixt!(AnyDhtHash);
+        let app_deps = vec![fixt!(AnyDhtHash), fixt!(AnyDhtHash)];
+        db.async_commit({
+            let (pending, awaiting_sys, awaiting_app) =
+                (pending.clone(), awaiting_sys.clone(), awaiting_app.clone());
+            let (sys_dep, app_deps) = (sys_dep.clone(), app_deps.clone());
+            move |txn| {
+                mutations::insert_op(txn, &pending)?;
+                mutations::insert_op(txn, &awaiting_sys)?;
+                mutations::insert_op(txn, &awaiting_app)?;
+                mutations::set_validation_stage(
+                    txn,
+                    awaiting_sys.as_hash(),
+                    ValidationLimboStatus::AwaitingSysDeps(sys_dep.clone()),
+                )?;
+                // The dependencies are replaced each time the stage is set.
+                mutations::set_validation_stage(
+                    txn,
+                    awaiting_app.as_hash(),
+                    ValidationLimboStatus::AwaitingSysDeps(sys_dep),
+                )?;
+                mutations::set_validation_stage(
+                    txn,
+                    awaiting_app.as_hash(),
+                    ValidationLimboStatus::AwaitingAppDeps(app_deps),
+                )?;
+                StateMutationResult::Ok(())
+            }
+        })
+        .await
+        .unwrap();
+
+        let (queues, mut blocked) = db
+            .async_reader(|txn| {
+                StateQueryResult::Ok((dht_queues(&txn, Timestamp::now())?, blocked_ops(&txn)?))
+            })
+            .await
+            .unwrap();
+        let depths: Vec<_> = queues.iter().map(|q| (q.queue, q.depth)).collect();
+        assert_eq!(
+            depths,
+            vec![
+                (WorkflowQueue::SysValidation, 2),
+                (WorkflowQueue::AppValidation, 1),
+                (WorkflowQueue::Integration, 0),
+            ]
+        );
+        assert!(queues[0].oldest_age_secs.is_some());
+        assert_eq!(queues[2].oldest_age_secs, None);
+
+        blocked.sort_by_key(|op| {
+            (
+                op.queue == WorkflowQueue::AppValidation,
+                op.dependency.clone(),
+            )
+        });
+        let mut expected_app_deps = app_deps.clone();
+        expected_app_deps.sort();
+        let blocked: Vec<_> = blocked
+            .into_iter()
+            .map(|op| (op.op_hash, op.queue, op.dependency))
+            .collect();
+        assert_eq!(
+            blocked,
+            vec![
+                (
+                    awaiting_sys.as_hash().clone(),
+                    WorkflowQueue::SysValidation,
+                    sys_dep
+                ),
+                (
+                    awaiting_app.as_hash().clone(),
+                    WorkflowQueue::AppValidation,
+                    expected_app_deps[0].clone()
+                ),
+                (
+                    awaiting_app.as_hash().clone(),
+                    WorkflowQueue::AppValidation,
+                    expected_app_deps[1].clone()
+                ),
+            ]
+        );
+
+        // - Once the op is validated it's no longer blocked.
+        db.async_commit({
+            let hash = awaiting_app.as_hash().clone();
+            move |txn| {
+                mutations::set_validation_stage(
+                    txn,
+                    &hash,
+                    ValidationLimboStatus::AwaitingIntegration,
+                )
+            }
+        })
+        .await
+        .unwrap();
+        let blocked = db.async_reader(|txn| blocked_ops(&txn)).await.unwrap();
+        assert_eq!(blocked.len(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn requeues_stuck_and_abandoned_ops() {
+        let test_db = crate::test_utils::test_dht_db();
+        let db = test_db.to_db();
+
+        // - An op stuck waiting on an app validation dependency, an
+        // abandoned op, a valid op and abandoned agent activity.
+        let stuck = op();
+        let abandoned = op();
+        let valid = op();
+        let abandoned_activity = DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(
+            fixt!(Signature),
+            Action::CreateLink(fixt!(CreateLink)),
+        ));
+        db.async_commit({
+            let ops = (
+                stuck.clone(),
+                abandoned.clone(),
+                valid.clone(),
+                abandoned_activity.clone(),
+            );
+            move |txn| {
+                let (stuck, abandoned, valid, abandoned_activity) = ops;
+                for op in [&stuck, &abandoned, &valid, &abandoned_activity] {
+                    mutations::insert_op(txn, op)?;
+                }
+                mutations::set_validation_stage(
+                    txn,
+                    stuck.as_hash(),
+                    ValidationLimboStatus::AwaitingAppDeps(vec![fixt!(AnyDhtHash)]),
+                )?;
+                for (op, status) in [
+                    (&abandoned, ValidationStatus::Abandoned),
+                    (&valid, ValidationStatus::Valid),
+                    (&abandoned_activity, ValidationStatus::Abandoned),
+                ] {
+                    mutations::set_validation_status(txn, op.as_hash(), status)?;
+                    mutations::set_when_integrated(txn, op.as_hash(), Timestamp::now())?;
+                }
+                StateMutationResult::Ok(())
+            }
+        })
+        .await
+        .unwrap();
+
+        let mut found = db.async_reader(|txn| abandoned_ops(&txn)).await.unwrap();
+        found.sort();
+        let mut expected = vec![
+            abandoned.as_hash().clone(),
+            abandoned_activity.as_hash().clone(),
+        ];
+        expected.sort();
+        assert_eq!(found, expected);
+
+        // - Only the stuck op and the abandoned op are re-queued.
+        let requeued = db
+            .async_commit({
+                let hashes = vec![
+                    stuck.as_hash().clone(),
+                    abandoned.as_hash().clone(),
+                    valid.as_hash().clone(),
+                    abandoned_activity.as_hash().clone(),
+                    op().as_hash().clone(),
+                ];
+                move |txn| requeue_ops(txn, &hashes)
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            requeued,
+            vec![stuck.as_hash().clone(), abandoned.as_hash().clone()]
+        );
+
+        let (queues, blocked) = db
+            .async_reader(|txn| {
+                StateQueryResult::Ok((dht_queues(&txn, Timestamp::now())?, blocked_ops(&txn)?))
+            })
+            .await
+            .unwrap();
+        assert_eq!(queues[0].queue, WorkflowQueue::SysValidation);
+        assert_eq!(queues[0].depth, 2);
+        assert!(blocked.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn dependencies_are_removed_with_the_op() {
+        let test_db = crate::test_utils::test_dht_db();
+        let db = test_db.to_db();
+
+        // - Two ops waiting on app validation dependencies.
+        let rejected = op();
+        let collected = op();
+        db.async_commit({
+            let ops = (rejected.clone(), collected.clone());
+            move |txn| {
+                for op in [&ops.0, &ops.1] {
+                    mutations::insert_op(txn, op)?;
+                    mutations::set_validation_stage(
+                        txn,
+                        op.as_hash(),
+                        ValidationLimboStatus::AwaitingAppDeps(vec![fixt!(AnyDhtHash)]),
+                    )?;
+                }
+                StateMutationResult::Ok(())
+            }
+        })
+        .await
+        .unwrap();
+
+        let count_dependencies = |hash: DhtOpHash| {
+            db.async_reader(move |txn| {
+                StateQueryResult::Ok(txn.query_row(
+                    "SELECT COUNT(*) FROM ValidationDependency WHERE op_hash = :hash",
+                    named_params! { ":hash": hash },
+                    |row| row.get::<_, usize>(0),
+                )?)
+            })
+        };
+        assert_eq!(
+            count_dependencies(rejected.as_hash().clone())
+                .await
+                .unwrap(),
+            1
+        );
+
+        // - Rejecting one op and deleting the other
+        // removes the dependencies of both.
+        db.async_commit({
+            let hashes = (rejected.as_hash().clone(), collected.as_hash().clone());
+            move |txn| {
+                mutations::set_validation_status(txn, &hashes.0, ValidationStatus::Rejected)?;
+                txn.execute(
+                    "DELETE FROM DhtOp WHERE hash = :hash",
+                    named_params! { ":hash": hashes.1 },
+                )?;
+                StateMutationResult::Ok(())
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(
+            count_dependencies(rejected.as_hash().clone())
+                .await
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            count_dependencies(collected.as_hash().clone())
+                .await
+                .unwrap(),
+            0
+        );
+    }
+}
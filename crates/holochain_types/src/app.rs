@@ -82,6 +82,26 @@ impl CreateCloneCellPayload {
     }
 }
 
+/// Identifies a clone cell of an app to archive or restore
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct ArchiveCloneCellPayload {
+    /// The App the clone cell belongs to
+    pub app_id: InstalledAppId,
+    /// The Role ID the clone cell was created under
+    pub role_id: AppRoleId,
+    /// The clone cell
+    pub clone_cell_id: CellId,
+}
+
+/// Identifies the role of an app whose archived clone cells should be deleted
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct DeleteArchivedCloneCellsPayload {
+    /// The App the clone cells belong to
+    pub app_id: InstalledAppId,
+    /// The Role ID the clone cells were created under
+    pub role_id: AppRoleId,
+}
+
 /// A collection of [DnaHash]es paired with an [AgentPubKey] and an app id
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct InstallAppPayload {
@@ -423,6 +443,21 @@ impl InstalledAppCommon {
             .flat_map(|(_, role)| &role.clones)
     }
 
+    /// The cells of other apps which this app reuses
+    pub fn existing_cells(&self) -> impl Iterator<Item = &CellId> {
+        self.role_assignments
+            .values()
+            .filter(|role| role.is_existing)
+            .map(|role| &role.base_cell_id)
+    }
+
+    /// Accessor
+    pub fn archived_clone_cells(&self) -> impl Iterator<Item = &CellId> {
+        self.role_assignments
+            .iter()
+            .flat_map(|(_, role)| &role.archived_clones)
+    }
+
     /// Iterator of all cells, both provisioned and cloned
     pub fn all_cells(&self) -> impl Iterator<Item = &CellId> {
         self.provisioned_cells()
@@ -463,7 +498,8 @@ impl InstalledAppCommon {
             role.agent_key(),
             "A clone cell must use the same agent key as the role it is added to"
         );
-        if role.clones.len() as u32 >= role.clone_limit {
+        // Archived clones can be restored, so they still count towards the limit.
+        if (role.clones.len() + role.archived_clones.len()) as u32 >= role.clone_limit {
             return Err(AppError::CloneLimitExceeded(role.clone_limit, role.clone()));
         }
         let _ = role.clones.insert(cell_id);
@@ -476,6 +512,34 @@ impl InstalledAppCommon {
         Ok(role.clones.remove(cell_id))
     }
 
+    /// Archive a cloned cell. The cell is no longer part of the app,
+    /// but it can be restored later.
+    pub fn archive_clone(&mut self, role_id: &AppRoleId, cell_id: &CellId) -> AppResult<()> {
+        let role = self.role_mut(role_id)?;
+        if !role.clones.remove(cell_id) {
+            return Err(AppError::CloneCellNotFound(cell_id.clone()));
+        }
+        let _ = role.archived_clones.insert(cell_id.clone());
+        Ok(())
+    }
+
+    /// Restore an archived clone cell to the app
+    pub fn restore_clone(&mut self, role_id: &AppRoleId, cell_id: &CellId) -> AppResult<()> {
+        let role = self.role_mut(role_id)?;
+        if !role.archived_clones.remove(cell_id) {
+            return Err(AppError::CloneCellNotFound(cell_id.clone()));
+        }
+        let _ = role.clones.insert(cell_id.clone());
+        Ok(())
+    }
+
+    /// Permanently remove all archived clone cells of a role, freeing up
+    /// their slots in the clone limit. Returns the removed cells.
+    pub fn delete_archived_clones(&mut self, role_id: &AppRoleId) -> AppResult<HashSet<CellId>> {
+        let role = self.role_mut(role_id)?;
+        Ok(std::mem::take(&mut role.archived_clones))
+    }
+
     /// Accessor
     pub fn _agent_key(&self) -> &AgentPubKey {
         &self._agent_key
@@ -529,7 +593,9 @@ impl InstalledAppCommon {
                     base_cell_id: cell_id,
                     is_provisioned: true,
                     clones: HashSet::new(),
+                    archived_clones: HashSet::new(),
                     clone_limit: 0,
+                    is_existing: false,
                 };
                 (role_id, role)
             })
@@ -759,6 +825,16 @@ pub struct AppRoleAssignment {
     /// Cells which were cloned at runtime. The length cannot grow beyond
     /// `clone_limit`
     clones: HashSet<CellId>,
+    /// Clones which were archived. They are not part of the app anymore,
+    /// but their data is kept so they can be restored.
+    /// Archived clones count towards the `clone_limit` until they are deleted.
+    #[serde(default)]
+    archived_clones: HashSet<CellId>,
+    /// Records whether the base cell was already installed by another app
+    /// and is reused by this one. The other app can't be uninstalled while
+    /// this app depends on the cell.
+    #[serde(default)]
+    is_existing: bool,
 }
 
 impl AppRoleAssignment {
@@ -769,6 +845,16 @@ impl AppRoleAssignment {
             is_provisioned,
             clone_limit,
             clones: HashSet::new(),
+            archived_clones: HashSet::new(),
+            is_existing: false,
+        }
+    }
+
+    /// Constructor for a role which reuses a cell that was installed by another app.
+    pub fn new_existing(base_cell_id: CellId, clone_limit: u32) -> Self {
+        Self {
+            is_existing: true,
+            ..Self::new(base_cell_id, true, clone_limit)
         }
     }
 
@@ -796,6 +882,11 @@ impl AppRoleAssignment {
         }
     }
 
+    /// Accessor
+    pub fn is_existing(&self) -> bool {
+        self.is_existing
+    }
+
     /// Transformer
     pub fn into_provisioned_cell(self) -> Option<CellId> {
         if self.is_provisioned {
@@ -860,4 +951,59 @@ mod tests {
             app.all_cells().collect::<HashSet<_>>()
         );
     }
+
+    #[test]
+    fn archived_clone_management() {
+        let base_cell_id = fixt!(CellId);
+        let agent = base_cell_id.agent_pubkey().clone();
+        let new_clone = || CellId::new(fixt!(DnaHash), agent.clone());
+        let role1 = AppRoleAssignment::new(base_cell_id, false, 2);
+        let agent = fixt!(AgentPubKey);
+        let role_id: AppRoleId = "role_id".into();
+        let mut app: RunningApp =
+            InstalledAppCommon::new("app", agent.clone(), vec![(role_id.clone(), role1)]).into();
+
+        let clones: Vec<_> = vec![new_clone(), new_clone(), new_clone()];
+        app.add_clone(&role_id, clones[0].clone()).unwrap();
+        app.add_clone(&role_id, clones[1].clone()).unwrap();
+
+        // Only clones can be archived
+        matches::assert_matches!(
+            app.archive_clone(&role_id, &clones[2]),
+            Err(AppError::CloneCellNotFound(_))
+        );
+
+        app.archive_clone(&role_id, &clones[0]).unwrap();
+        assert_eq!(
+            app.all_cells().collect::<HashSet<_>>(),
+            maplit::hashset! { &clones[1] }
+        );
+        assert_eq!(
+            app.archived_clone_cells().collect::<HashSet<_>>(),
+            maplit::hashset! { &clones[0] }
+        );
+
+        // Archived clones still count towards the clone limit
+        matches::assert_matches!(
+            app.add_clone(&role_id, clones[2].clone()),
+            Err(AppError::CloneLimitExceeded(2, _))
+        );
+
+        app.restore_clone(&role_id, &clones[0]).unwrap();
+        assert_eq!(app.cloned_cells().count(), 2);
+        assert_eq!(app.archived_clone_cells().count(), 0);
+        matches::assert_matches!(
+            app.restore_clone(&role_id, &clones[0]),
+            Err(AppError::CloneCellNotFound(_))
+        );
+
+        // Deleting archived clones frees up their slots
+        app.archive_clone(&role_id, &clones[0]).unwrap();
+        assert_eq!(
+            app.delete_archived_clones(&role_id).unwrap(),
+            maplit::hashset! { clones[0].clone() }
+        );
+        assert_eq!(app.archived_clone_cells().count(), 0);
+        app.add_clone(&role_id, clones[2].clone()).unwrap();
+    }
 }
@@ -2,7 +2,10 @@ use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
 use self::error::AppBundleResult;
 
-use super::{dna_gamut::DnaGamut, AppManifest, AppManifestValidated};
+use super::{
+    dna_gamut::{CellResolution, DnaGamut, DnaResolution},
+    AppManifest, AppManifestValidated,
+};
 use crate::prelude::*;
 
 #[allow(missing_docs)]
@@ -50,14 +53,14 @@ impl AppBundle {
     pub async fn resolve_cells(
         self,
         agent: AgentPubKey,
-        _gamut: DnaGamut,
+        gamut: DnaGamut,
         membrane_proofs: HashMap<AppRoleId, MembraneProof>,
     ) -> AppBundleResult<AppRoleResolution> {
         let AppManifestValidated { name: _, roles } = self.manifest().clone().validate()?;
         let bundle = Arc::new(self);
         let tasks = roles.into_iter().map(|(role_id, role)| async {
             let bundle = bundle.clone();
-            Ok((role_id, bundle.resolve_cell(role).await?))
+            Ok((role_id, bundle.resolve_cell(role, &gamut, &agent).await?))
         });
         let resolution = futures::future::join_all(tasks)
             .await
@@ -80,7 +83,7 @@ impl AppBundle {
                                 resolution.role_assignments.push((role_id, role));
                             }
                             CellProvisioningOp::Existing(cell_id, clone_limit) => {
-                                let role = AppRoleAssignment::new(cell_id, true, clone_limit);
+                                let role = AppRoleAssignment::new_existing(cell_id, clone_limit);
                                 resolution.role_assignments.push((role_id, role));
                             }
                             CellProvisioningOp::Noop(cell_id, clone_limit) => {
@@ -89,13 +92,10 @@ impl AppBundle {
                                     AppRoleAssignment::new(cell_id, false, clone_limit),
                                 ));
                             }
-                            other => {
-                                tracing::error!(
-                                    "Encountered unexpected CellProvisioningOp: {:?}",
-                                    other
-                                );
-                                unimplemented!()
+                            CellProvisioningOp::NoMatch => {
+                                return Err(AppBundleError::CellResolutionFailure(role_id));
                             }
+                            CellProvisioningOp::Conflict(conflict) => match conflict {},
                         }
                         Ok(resolution)
                     } else {
@@ -111,6 +111,8 @@ impl AppBundle {
     async fn resolve_cell(
         &self,
         role: AppRoleManifestValidated,
+        gamut: &DnaGamut,
+        agent: &AgentPubKey,
     ) -> AppBundleResult<CellProvisioningOp> {
         Ok(match role {
             AppRoleManifestValidated::Create {
@@ -125,14 +127,27 @@ impl AppBundle {
                     .await?
             }
 
-            AppRoleManifestValidated::CreateClone { .. } => {
-                unimplemented!("`create_clone` provisioning strategy is currently unimplemented")
+            AppRoleManifestValidated::CreateClone {
+                location,
+                version,
+                clone_limit,
+                properties,
+                deferred: _,
+            } => {
+                self.resolve_cell_create(
+                    &location,
+                    version.as_ref(),
+                    clone_limit,
+                    Some(random_uid()),
+                    properties,
+                )
+                .await?
             }
             AppRoleManifestValidated::UseExisting {
                 version,
                 clone_limit,
                 deferred: _,
-            } => self.resolve_cell_existing(&version, clone_limit),
+            } => self.resolve_cell_existing(gamut, version, agent, clone_limit),
             AppRoleManifestValidated::CreateIfNotExists {
                 location,
                 version,
@@ -140,7 +155,7 @@ impl AppBundle {
                 properties,
                 uid,
                 deferred: _,
-            } => match self.resolve_cell_existing(&version, clone_limit) {
+            } => match self.resolve_cell_existing(gamut, version.clone(), agent, clone_limit) {
                 op @ CellProvisioningOp::Existing(_, _) => op,
                 CellProvisioningOp::NoMatch => {
                     self.resolve_cell_create(
@@ -152,9 +167,7 @@ impl AppBundle {
                     )
                     .await?
                 }
-                CellProvisioningOp::Conflict(_) => {
-                    unimplemented!("conflicts are not handled, or even possible yet")
-                }
+                op @ CellProvisioningOp::Conflict(_) => op,
                 CellProvisioningOp::Create(_, _) => {
                     unreachable!("resolve_cell_existing will never return a Create op")
                 }
@@ -163,12 +176,15 @@ impl AppBundle {
                 }
             },
             AppRoleManifestValidated::Disabled {
-                version: _,
-                clone_limit: _,
-            } => {
-                unimplemented!("`disabled` provisioning strategy is currently unimplemented")
-                // CellProvisioningOp::Noop(clone_limit)
-            }
+                version,
+                clone_limit,
+            } => match gamut.resolve_dna(version) {
+                DnaResolution::Match(dna_hash, _) => CellProvisioningOp::Noop(
+                    CellId::new(dna_hash.into(), agent.clone()),
+                    clone_limit,
+                ),
+                DnaResolution::NoMatch | DnaResolution::Conflict => CellProvisioningOp::NoMatch,
+            },
         })
     }
 
@@ -193,10 +209,15 @@ impl AppBundle {
 
     fn resolve_cell_existing(
         &self,
-        _version: &DnaVersionSpec,
-        _clone_limit: u32,
+        gamut: &DnaGamut,
+        version: DnaVersionSpec,
+        agent: &AgentPubKey,
+        clone_limit: u32,
     ) -> CellProvisioningOp {
-        unimplemented!("Reusing existing cells is not yet implemented")
+        match gamut.resolve_cell(version, agent) {
+            CellResolution::Match(cell_id, _) => CellProvisioningOp::Existing(cell_id, clone_limit),
+            CellResolution::NoMatch | CellResolution::Conflict => CellProvisioningOp::NoMatch,
+        }
     }
 }
 
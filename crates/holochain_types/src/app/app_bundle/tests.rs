@@ -6,7 +6,7 @@ use app_manifest_v1::tests::{app_manifest_fixture, app_manifest_properties_fixtu
 
 use super::AppBundle;
 
-async fn app_bundle_fixture() -> (AppBundle, DnaFile) {
+async fn app_bundle_fixture(provisioning: CellProvisioning) -> (AppBundle, DnaFile, DnaFile) {
     let dna_wasm = DnaWasmHashed::from_content(DnaWasm::new_invalid()).await;
     let fake_wasms = vec![dna_wasm.clone().into_content()];
     let fake_zomes = vec![IntegrityZome::new(
@@ -21,18 +21,21 @@ async fn app_bundle_fixture() -> (AppBundle, DnaFile) {
 
     let path1 = PathBuf::from(format!("{}", dna1.dna_hash()));
 
-    let (manifest, _dna_hashes) = app_manifest_fixture(
+    let (mut manifest, _dna_hashes) = app_manifest_fixture(
         Some(DnaLocation::Bundled(path1.clone())),
         vec![dna1.dna_def().clone(), dna2.dna_def().clone()],
     )
     .await;
+    match &mut manifest {
+        AppManifest::V1(manifest) => manifest.roles[0].provisioning = Some(provisioning),
+    }
 
     let resources = vec![(path1, DnaBundle::from_dna_file(dna1.clone()).await.unwrap())];
 
     let bundle = AppBundle::new(manifest, resources, PathBuf::from("."))
         .await
         .unwrap();
-    (bundle, dna1)
+    (bundle, dna1, dna2)
 }
 
 /// Test that an app with a single Created cell can be provisioned
@@ -40,7 +43,7 @@ async fn app_bundle_fixture() -> (AppBundle, DnaFile) {
 async fn provisioning_1_create() {
     observability::test_run().ok();
     let agent = fixt!(AgentPubKey);
-    let (bundle, dna) = app_bundle_fixture().await;
+    let (bundle, dna, _) = app_bundle_fixture(CellProvisioning::Create { deferred: false }).await;
 
     // Apply the phenotype overrides specified in the manifest fixture
     let dna = dna
@@ -69,3 +72,163 @@ async fn provisioning_1_create() {
     };
     assert_eq!(resolution, expected);
 }
+
+/// Test that a Cell of another app can be reused, and that the installation
+/// fails if there is no Cell to reuse
+#[tokio::test]
+async fn provisioning_1_use_existing() {
+    observability::test_run().ok();
+    let agent = fixt!(AgentPubKey);
+    let (bundle, _, dna2) =
+        app_bundle_fixture(CellProvisioning::UseExisting { deferred: false }).await;
+    let cell_id = CellId::new(dna2.dna_hash().to_owned(), agent.clone());
+    let other_cell_id = CellId::new(dna2.dna_hash().to_owned(), fixt!(AgentPubKey));
+
+    let resolution = bundle
+        .resolve_cells(
+            agent.clone(),
+            DnaGamut::new(vec![other_cell_id, cell_id.clone()]),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+
+    // The Cell of the app's own agent is preferred
+    let expected = AppRoleResolution {
+        agent: agent.clone(),
+        dnas_to_register: vec![],
+        role_assignments: vec![(
+            "role_id".into(),
+            AppRoleAssignment::new_existing(cell_id, 50),
+        )],
+    };
+    assert_eq!(resolution, expected);
+
+    // The Cells of other agents are never reused
+    let (bundle, _, dna2) =
+        app_bundle_fixture(CellProvisioning::UseExisting { deferred: false }).await;
+    let other_cell_id = CellId::new(dna2.dna_hash().to_owned(), fixt!(AgentPubKey));
+    let result = bundle
+        .resolve_cells(
+            agent.clone(),
+            DnaGamut::new(vec![other_cell_id]),
+            Default::default(),
+        )
+        .await;
+    matches::assert_matches!(result, Err(AppBundleError::CellResolutionFailure(role_id)) if role_id == "role_id");
+
+    let (bundle, _, dna2) =
+        app_bundle_fixture(CellProvisioning::UseExisting { deferred: false }).await;
+    let result = bundle
+        .resolve_cells(
+            agent,
+            DnaGamut::new(vec![]).with_dnas(vec![dna2.dna_hash().to_owned()]),
+            Default::default(),
+        )
+        .await;
+    matches::assert_matches!(result, Err(AppBundleError::CellResolutionFailure(role_id)) if role_id == "role_id");
+}
+
+/// Test that an existing Cell is used if there is one, and a new Cell is
+/// created otherwise
+#[tokio::test]
+async fn provisioning_1_create_if_not_exists() {
+    observability::test_run().ok();
+    let agent = fixt!(AgentPubKey);
+    let provisioning = CellProvisioning::CreateIfNotExists { deferred: false };
+    let (bundle, _, dna2) = app_bundle_fixture(provisioning.clone()).await;
+    let cell_id = CellId::new(dna2.dna_hash().to_owned(), agent.clone());
+
+    let resolution = bundle
+        .resolve_cells(
+            agent.clone(),
+            DnaGamut::new(vec![cell_id.clone()]),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    assert!(resolution.dnas_to_register.is_empty());
+    assert_eq!(
+        resolution.role_assignments,
+        vec![(
+            "role_id".into(),
+            AppRoleAssignment::new_existing(cell_id, 50)
+        )]
+    );
+
+    let (bundle, _, _) = app_bundle_fixture(provisioning).await;
+    let resolution = bundle
+        .resolve_cells(agent, DnaGamut::new(vec![]), Default::default())
+        .await
+        .unwrap();
+    assert_eq!(resolution.dnas_to_register.len(), 1);
+    assert!(resolution.role_assignments[0]
+        .1
+        .provisioned_cell()
+        .is_some());
+    assert!(!resolution.role_assignments[0].1.is_existing());
+}
+
+/// Test that a cloned Cell gets a DNA with a unique uid
+#[tokio::test]
+async fn provisioning_1_create_clone() {
+    observability::test_run().ok();
+    let agent = fixt!(AgentPubKey);
+    let provisioning = CellProvisioning::CreateClone { deferred: false };
+    let (bundle, dna, _) = app_bundle_fixture(provisioning.clone()).await;
+
+    let resolution = bundle
+        .resolve_cells(agent.clone(), DnaGamut::new(vec![]), Default::default())
+        .await
+        .unwrap();
+
+    let (clone_dna, _) = &resolution.dnas_to_register[0];
+    assert_ne!(clone_dna.dna_hash(), dna.dna_hash());
+    assert_eq!(
+        resolution.role_assignments,
+        vec![(
+            "role_id".into(),
+            AppRoleAssignment::new(
+                CellId::new(clone_dna.dna_hash().to_owned(), agent.clone()),
+                true,
+                50
+            )
+        )]
+    );
+
+    // Every installation creates a distinct network
+    let (bundle, _, _) = app_bundle_fixture(provisioning).await;
+    let other = bundle
+        .resolve_cells(agent, DnaGamut::new(vec![]), Default::default())
+        .await
+        .unwrap();
+    assert_ne!(other.dnas_to_register[0].0.dna_hash(), clone_dna.dna_hash());
+}
+
+/// Test that a disabled role only records which DNA to clone
+#[tokio::test]
+async fn provisioning_1_disabled() {
+    observability::test_run().ok();
+    let agent = fixt!(AgentPubKey);
+    let (bundle, dna, _) = app_bundle_fixture(CellProvisioning::Disabled).await;
+    let dna_hash = DnaHash::with_data_sync(dna.dna_def());
+
+    let resolution = bundle
+        .resolve_cells(
+            agent.clone(),
+            DnaGamut::new(vec![]).with_dnas(vec![dna_hash.clone()]),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+
+    let expected = AppRoleResolution {
+        agent: agent.clone(),
+        dnas_to_register: vec![],
+        role_assignments: vec![(
+            "role_id".into(),
+            AppRoleAssignment::new(CellId::new(dna_hash, agent), false, 50),
+        )],
+    };
+    assert_eq!(resolution, expected);
+}
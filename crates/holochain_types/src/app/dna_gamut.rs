@@ -46,6 +46,14 @@ impl DnaGamut {
         Self(map)
     }
 
+    /// Add DNAs which are registered but might not be used by any Cell.
+    pub fn with_dnas<I: IntoIterator<Item = DnaHash>>(mut self, dnas: I) -> Self {
+        for dna in dnas {
+            self.0.entry(dna).or_default();
+        }
+        self
+    }
+
     #[deprecated = "Stop using the placeholder"]
     #[allow(missing_docs)]
     pub fn placeholder() -> Self {
@@ -62,18 +70,17 @@ impl DnaGamut {
         DnaResolution::NoMatch
     }
 
-    /// Given a version spec, return the best-matching CellId
-    // TODO: use DPKI to filter Cells which belong to Agents that are not
+    /// Given a version spec, return the best-matching CellId of the given agent.
+    /// The Cells of other agents are never matched, so an app can't be
+    /// bound to another agent's source chain.
+    // TODO: use DPKI to also match Cells which belong to Agents that are
     //       associated with the provided agent
-    pub fn resolve_cell(&self, spec: DnaVersionSpec, _agent: &AgentPubKey) -> CellResolution {
+    pub fn resolve_cell(&self, spec: DnaVersionSpec, agent: &AgentPubKey) -> CellResolution {
         for hash in spec.dna_hashes() {
             if let Some(agent) = self
                 .0
                 .get(hash.as_ref())
-                // TODO: this is where an agent check could go, but for now we
-                //       just return the first one available
-                .map(|agents| agents.iter().next())
-                .unwrap_or(None)
+                .and_then(|agents| agents.get(agent))
             {
                 return CellResolution::Match(
                     CellId::new(hash.clone().into(), agent.clone()),
@@ -8,6 +8,9 @@ pub enum AppError {
     #[error("Clone limit of {0} exceeded for cell: {1:?}")]
     CloneLimitExceeded(u32, AppRoleAssignment),
 
+    #[error("Clone cell not found: {0:?}")]
+    CloneCellNotFound(CellId),
+
     #[error("Tried to access missing role id: '{0}'")]
     AppRoleIdMissing(AppRoleId),
 
@@ -0,0 +1,291 @@
+//! Types for exporting a source chain and importing it into another conductor
+
+use holo_hash::ActionHash;
+use holo_hash::AgentPubKey;
+use holo_hash::DnaHash;
+use holo_hash::EntryHash;
+use holochain_keystore::AgentPubKeyExt;
+use holochain_keystore::LairResult;
+use holochain_keystore::MetaLairClient;
+use holochain_serialized_bytes::prelude::*;
+use holochain_zome_types::Action;
+use holochain_zome_types::CellId;
+use holochain_zome_types::Record;
+use holochain_zome_types::RecordEntry;
+use holochain_zome_types::Signature;
+use holochain_zome_types::Timestamp;
+use thiserror::Error;
+
+/// The version of the archive format produced by this conductor.
+/// Archives with any other version can't be imported.
+pub const SOURCE_CHAIN_ARCHIVE_VERSION: u32 = 1;
+
+/// A full copy of an agent's source chain.
+///
+/// This includes private entries and capability grants and claims,
+/// so it must be stored as carefully as the conductor's databases.
+/// It does not include the agent's private key, which must be moved
+/// to the other conductor's keystore for the agent to keep authoring.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub struct SourceChainArchive {
+    /// The version of the archive format.
+    pub version: u32,
+    /// The DNA of the cell the chain belongs to.
+    pub dna_hash: DnaHash,
+    /// The agent who authored the chain.
+    pub agent_pubkey: AgentPubKey,
+    /// When the chain was exported.
+    pub exported_at: Timestamp,
+    /// Every record of the chain in order, starting at the DNA action.
+    pub records: Vec<Record>,
+}
+
+/// A [`SourceChainArchive`] signed by the agent who authored the chain.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub struct SignedSourceChainArchive {
+    /// The archive.
+    pub archive: SourceChainArchive,
+    /// The agent's signature of the archive.
+    pub signature: Signature,
+}
+
+/// The reasons an archive can fail its integrity checks.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ChainArchiveError {
+    /// The archive was produced with a format this conductor doesn't understand.
+    #[error(
+        "Unsupported source chain archive version {0}, expected {SOURCE_CHAIN_ARCHIVE_VERSION}"
+    )]
+    UnsupportedVersion(u32),
+
+    /// The archive was not signed by the agent it belongs to
+    /// or has been changed since it was signed.
+    #[error("The source chain archive signature is not valid for agent {0}")]
+    InvalidSignature(AgentPubKey),
+
+    /// The archive has no records.
+    #[error("The source chain archive is empty")]
+    Empty,
+
+    /// The first record is not the DNA action of the archive's DNA.
+    #[error("The source chain archive does not start with the DNA action for {0}")]
+    NotFromGenesis(DnaHash),
+
+    /// A record was authored by a different agent.
+    #[error("The action {0} was not authored by the agent of the archive")]
+    WrongAuthor(ActionHash),
+
+    /// The hash of a record doesn't match its action.
+    #[error("The action {0} does not match its hash")]
+    ActionHashMismatch(ActionHash),
+
+    /// The signature of a record is not valid.
+    #[error("The action {0} has an invalid signature")]
+    InvalidActionSignature(ActionHash),
+
+    /// A record doesn't follow on from the record before it.
+    #[error("The action {0} does not follow on from the previous action in the archive")]
+    BrokenChain(ActionHash),
+
+    /// A record's entry is missing or doesn't match the action.
+    #[error("The entry of action {0} is missing or does not match the entry hash {1}")]
+    EntryMismatch(ActionHash, EntryHash),
+}
+
+#[allow(missing_docs)]
+pub type ChainArchiveResult<T> = Result<T, ChainArchiveError>;
+
+impl SourceChainArchive {
+    /// Create an archive of the records of a cell's chain.
+    pub fn new(cell_id: CellId, records: Vec<Record>) -> Self {
+        let (dna_hash, agent_pubkey) = cell_id.into_dna_and_agent();
+        Self {
+            version: SOURCE_CHAIN_ARCHIVE_VERSION,
+            dna_hash,
+            agent_pubkey,
+            exported_at: Timestamp::now(),
+            records,
+        }
+    }
+
+    /// The cell the chain belongs to.
+    pub fn cell_id(&self) -> CellId {
+        CellId::new(self.dna_hash.clone(), self.agent_pubkey.clone())
+    }
+
+    /// Sign the archive with the key of the agent who authored the chain.
+    pub async fn sign(self, keystore: &MetaLairClient) -> LairResult<SignedSourceChainArchive> {
+        let signature = self.agent_pubkey.sign(keystore, &self).await?;
+        Ok(SignedSourceChainArchive {
+            archive: self,
+            signature,
+        })
+    }
+}
+
+impl SignedSourceChainArchive {
+    /// Check the archive can be imported without producing a broken chain.
+    ///
+    /// The archive must be signed by its agent and every record must be
+    /// signed by the same agent, have the correct hash, carry its entry
+    /// and follow on from the record before it, starting at the DNA action.
+    /// This does not run validation of the records themselves.
+    pub async fn verify(&self) -> ChainArchiveResult<()> {
+        let archive = &self.archive;
+        if archive.version != SOURCE_CHAIN_ARCHIVE_VERSION {
+            return Err(ChainArchiveError::UnsupportedVersion(archive.version));
+        }
+        let agent = &archive.agent_pubkey;
+        if !agent.verify_signature(&self.signature, archive).await {
+            return Err(ChainArchiveError::InvalidSignature(agent.clone()));
+        }
+
+        match archive.records.first().map(|r| r.action()) {
+            None => return Err(ChainArchiveError::Empty),
+            Some(Action::Dna(dna)) if dna.hash == archive.dna_hash => (),
+            Some(_) => return Err(ChainArchiveError::NotFromGenesis(archive.dna_hash.clone())),
+        }
+
+        let mut prev: Option<&ActionHash> = None;
+        for (seq, record) in archive.records.iter().enumerate() {
+            let hash = record.action_address();
+            let action = record.action();
+            if action.author() != agent {
+                return Err(ChainArchiveError::WrongAuthor(hash.clone()));
+            }
+            if ActionHash::with_data_sync(action) != *hash {
+                return Err(ChainArchiveError::ActionHashMismatch(hash.clone()));
+            }
+            if !agent.verify_signature(record.signature(), action).await {
+                return Err(ChainArchiveError::InvalidActionSignature(hash.clone()));
+            }
+            if action.action_seq() as usize != seq || action.prev_action() != prev {
+                return Err(ChainArchiveError::BrokenChain(hash.clone()));
+            }
+            if let Some(entry_hash) = action.entry_hash() {
+                match record.entry() {
+                    RecordEntry::Present(entry)
+                        if EntryHash::with_data_sync(entry) == *entry_hash => {}
+                    _ => {
+                        return Err(ChainArchiveError::EntryMismatch(
+                            hash.clone(),
+                            entry_hash.clone(),
+                        ))
+                    }
+                }
+            }
+            prev = Some(record.signed_action().as_hash());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use holochain_keystore::test_keystore::spawn_test_keystore;
+
+    async fn signed_record(
+        keystore: &MetaLairClient,
+        action: Action,
+        entry: Option<Entry>,
+    ) -> Record {
+        let action = SignedActionHashed::sign(keystore, ActionHashed::from_content_sync(action))
+            .await
+            .unwrap();
+        Record::new(action, entry)
+    }
+
+    async fn test_chain(keystore: &MetaLairClient) -> (CellId, Vec<Record>) {
+        let author = keystore.new_sign_keypair_random().await.unwrap();
+        let dna_hash = fixt::fixt!(DnaHash);
+        let dna = signed_record(
+            keystore,
+            Action::Dna(Dna {
+                author: author.clone(),
+                timestamp: Timestamp::now(),
+                hash: dna_hash.clone(),
+            }),
+            None,
+        )
+        .await;
+        let entry = Entry::Agent(author.clone());
+        let create = signed_record(
+            keystore,
+            Action::Create(Create {
+                author: author.clone(),
+                timestamp: Timestamp::now(),
+                action_seq: 1,
+                prev_action: dna.action_address().clone(),
+                entry_type: EntryType::AgentPubKey,
+                entry_hash: EntryHash::with_data_sync(&entry),
+                weight: Default::default(),
+            }),
+            Some(entry),
+        )
+        .await;
+        (CellId::new(dna_hash, author), vec![dna, create])
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn archive_integrity_is_checked() {
+        let keystore = spawn_test_keystore().await.unwrap();
+        let (cell_id, records) = test_chain(&keystore).await;
+
+        let signed = SourceChainArchive::new(cell_id.clone(), records.clone())
+            .sign(&keystore)
+            .await
+            .unwrap();
+        signed.verify().await.unwrap();
+
+        // - Changing the archive after signing breaks the signature.
+        let mut tampered = signed.clone();
+        tampered.archive.records.pop();
+        assert_eq!(
+            tampered.verify().await,
+            Err(ChainArchiveError::InvalidSignature(
+                cell_id.agent_pubkey().clone()
+            ))
+        );
+
+        // - A chain with a record missing is rejected even if signed.
+        let broken = SourceChainArchive::new(cell_id.clone(), records[1..].to_vec())
+            .sign(&keystore)
+            .await
+            .unwrap();
+        assert_eq!(
+            broken.verify().await,
+            Err(ChainArchiveError::NotFromGenesis(
+                cell_id.dna_hash().clone()
+            ))
+        );
+
+        // - Records must carry their entries.
+        let (action, _) = records[1].clone().into_inner();
+        let hash = action.as_hash().clone();
+        let entry_hash = action.action().entry_hash().cloned().unwrap();
+        let missing_entry = SourceChainArchive::new(
+            cell_id.clone(),
+            vec![records[0].clone(), Record::new(action, None)],
+        )
+        .sign(&keystore)
+        .await
+        .unwrap();
+        assert_eq!(
+            missing_entry.verify().await,
+            Err(ChainArchiveError::EntryMismatch(hash, entry_hash))
+        );
+
+        // - Unknown versions are rejected.
+        let mut archive = SourceChainArchive::new(cell_id, records);
+        archive.version += 1;
+        let future = archive.sign(&keystore).await.unwrap();
+        assert_eq!(
+            future.verify().await,
+            Err(ChainArchiveError::UnsupportedVersion(
+                SOURCE_CHAIN_ARCHIVE_VERSION + 1
+            ))
+        );
+    }
+}
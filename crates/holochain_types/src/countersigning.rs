@@ -0,0 +1,30 @@
+//! Types for inspecting countersigning sessions
+
+use holo_hash::EntryHash;
+use holochain_serialized_bytes::prelude::*;
+use holochain_zome_types::countersigning::CounterSigningSessionData;
+use holochain_zome_types::CellId;
+use holochain_zome_types::Timestamp;
+
+/// A countersigning session a cell's chain is locked for.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, SerializedBytes)]
+pub struct CountersigningSessionInfo {
+    /// The cell whose chain is locked.
+    pub cell_id: CellId,
+    /// When the chain lock expires.
+    pub lock_expires: Timestamp,
+    /// The hash of the countersigned entry.
+    /// This is `None` if the preflight request has been accepted
+    /// but the entry has not been committed yet.
+    pub entry_hash: Option<EntryHash>,
+    /// The session data of the countersigned entry, if it has been committed.
+    pub session_data: Option<CounterSigningSessionData>,
+}
+
+impl CountersigningSessionInfo {
+    /// True if the chain lock has expired, in which case the chain
+    /// no longer blocks other writes and the session can't complete.
+    pub fn is_expired(&self) -> bool {
+        self.lock_expires < Timestamp::now()
+    }
+}
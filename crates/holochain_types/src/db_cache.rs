@@ -283,6 +283,20 @@ impl DhtDbQueryCache {
         .await
     }
 
+    /// Forget the activity of these authors
+    /// once it has been deleted from the database.
+    pub fn remove_activity(&self, authors: &[AgentPubKey]) {
+        // If the cache hasn't been read yet it will be
+        // filled from the database when it is.
+        if let Some(activity) = self.activity.get() {
+            activity.share_mut(|activity| {
+                for author in authors {
+                    activity.remove(author);
+                }
+            });
+        }
+    }
+
     /// Add an authors activity.
     async fn new_activity_inner(
         &self,
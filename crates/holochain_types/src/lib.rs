@@ -18,7 +18,9 @@ pub mod activity;
 pub mod app;
 pub mod autonomic;
 pub mod chain;
+pub mod chain_archive;
 pub mod combinators;
+pub mod countersigning;
 pub mod db;
 pub mod db_cache;
 pub mod dht_op;
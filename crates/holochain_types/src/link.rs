@@ -46,6 +46,105 @@ pub struct WireLinkKey {
     pub type_query: LinkTypeFilter,
     /// Optionally specify a tag for more specific queries.
     pub tag: Option<LinkTag>,
+    /// Only include links created at or after this time.
+    #[serde(default)]
+    pub after: Option<Timestamp>,
+    /// Only include links created before this time.
+    #[serde(default)]
+    pub before: Option<Timestamp>,
+    /// Only include links created by this agent.
+    #[serde(default)]
+    pub author: Option<AgentPubKey>,
+    /// Only include links which come after this cursor.
+    #[serde(default)]
+    pub cursor: Option<LinkCursor>,
+    /// The maximum number of links to include.
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+impl WireLinkKey {
+    /// Create a key for all the links on a base with these types and tag prefix.
+    pub fn new(base: AnyLinkableHash, type_query: LinkTypeFilter, tag: Option<LinkTag>) -> Self {
+        Self {
+            base,
+            type_query,
+            tag,
+            after: None,
+            before: None,
+            author: None,
+            cursor: None,
+            limit: None,
+        }
+    }
+}
+
+impl From<GetLinksInput> for WireLinkKey {
+    fn from(input: GetLinksInput) -> Self {
+        let GetLinksInput {
+            base_address,
+            link_type,
+            tag_prefix,
+            after,
+            before,
+            author,
+            cursor,
+            limit,
+        } = input;
+        Self {
+            base: base_address,
+            type_query: link_type,
+            tag: tag_prefix,
+            after,
+            before,
+            author,
+            cursor,
+            limit,
+        }
+    }
+}
+
+impl From<LinkQuery> for WireLinkKey {
+    fn from(query: LinkQuery) -> Self {
+        let LinkQuery {
+            base_address,
+            link_type,
+            tag_prefix,
+            after,
+            before,
+            author,
+        } = query;
+        Self {
+            after,
+            before,
+            author,
+            ..Self::new(base_address, link_type, tag_prefix)
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes, Default)]
+/// The hashes of the create links which match a count links request.
+///
+/// Authorities return the hashes rather than a number so the responses can
+/// be merged with the links and deletes that are held locally.
+pub struct CountLinksResponse(Vec<ActionHash>);
+
+impl CountLinksResponse {
+    /// Create a response from the hashes of the matching create links.
+    pub fn new(create_link_actions: Vec<ActionHash>) -> Self {
+        Self(create_link_actions)
+    }
+
+    /// The hashes of the matching create links.
+    pub fn create_link_actions(&self) -> &[ActionHash] {
+        &self.0
+    }
+
+    /// Take the hashes of the matching create links.
+    pub fn into_create_link_actions(self) -> Vec<ActionHash> {
+        self.0
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes, Default)]
@@ -3,7 +3,7 @@
 use holo_hash::ActionHash;
 use holochain_serialized_bytes::prelude::*;
 pub use holochain_zome_types::metadata::EntryDhtStatus;
-use holochain_zome_types::{ActionHashed, Timestamp};
+use holochain_zome_types::{ActionHashed, Timestamp, ValidationStatus};
 use std::collections::BTreeSet;
 
 /// Timestamp of when the action was created with the actions hash.
@@ -17,13 +17,13 @@ pub struct TimedActionHash {
 
 /// Metadata returned from a GetMeta request.
 /// The Ord derive on TimedActionHash means each set is ordered by time.
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, SerializedBytes)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, SerializedBytes)]
 pub struct MetadataSet {
     /// Actions that created or updated an entry.
     /// These are the actions that show the entry exists.
     pub actions: BTreeSet<TimedActionHash>,
-    // TODO: Implement after validation
-    /// Placeholder
+    /// Actions that created or updated an entry
+    /// but were rejected by validation.
     pub invalid_actions: BTreeSet<TimedActionHash>,
     /// Deletes on an action
     pub deletes: BTreeSet<TimedActionHash>,
@@ -33,6 +33,58 @@ pub struct MetadataSet {
     /// This is simply a faster way of determining if
     /// there are any live actions on an entry.
     pub entry_dht_status: Option<EntryDhtStatus>,
+    /// Links on a base that have not been deleted.
+    #[serde(default)]
+    pub links: BTreeSet<TimedActionHash>,
+    /// The create link actions on a base which have been deleted.
+    /// These are kept so a link which is live in one source
+    /// but deleted in another is removed when merging.
+    #[serde(default)]
+    pub deleted_links: BTreeSet<ActionHash>,
+    /// The validation status of an action.
+    #[serde(default)]
+    pub validation_status: Option<ValidationStatus>,
+}
+
+impl MetadataSet {
+    /// Merge the metadata from another source into this set.
+    ///
+    /// An entry is live if any source has seen it live, otherwise
+    /// the first known status is kept. Links deleted in any source
+    /// are removed.
+    pub fn merge(&mut self, other: MetadataSet) {
+        let MetadataSet {
+            actions,
+            invalid_actions,
+            deletes,
+            updates,
+            entry_dht_status,
+            links,
+            deleted_links,
+            validation_status,
+        } = other;
+        self.actions.extend(actions);
+        self.invalid_actions.extend(invalid_actions);
+        self.deletes.extend(deletes);
+        self.updates.extend(updates);
+        self.deleted_links.extend(deleted_links);
+        let deleted_links = &self.deleted_links;
+        self.links.extend(links);
+        self.links
+            .retain(|link| !deleted_links.contains(&link.action_hash));
+        self.entry_dht_status = match (self.entry_dht_status, entry_dht_status) {
+            (Some(EntryDhtStatus::Live), _) | (_, Some(EntryDhtStatus::Live)) => {
+                Some(EntryDhtStatus::Live)
+            }
+            (current, other) => current.or(other),
+        };
+        self.validation_status = self.validation_status.or(validation_status);
+    }
+
+    /// Has a valid delete been seen for this hash.
+    pub fn is_deleted(&self) -> bool {
+        !self.deletes.is_empty()
+    }
 }
 
 impl From<ActionHashed> for TimedActionHash {
@@ -1,5 +1,7 @@
 //! defines a builder-style config struct for setting up websockets
 
+use std::collections::HashSet;
+
 /// A builder-style config struct for setting up websockets.
 #[derive(Debug)]
 pub struct WebsocketConfig {
@@ -26,6 +28,11 @@ pub struct WebsocketConfig {
 
     /// Maximum number of pending new incoming connections. [default = 255]
     pub max_pending_connections: usize,
+
+    /// If set, incoming connections are rejected during the handshake
+    /// unless they send an `Origin` header from this set.
+    /// [default = None (any origin)]
+    pub allowed_origins: Option<HashSet<String>>,
 }
 
 impl Default for WebsocketConfig {
@@ -38,6 +45,7 @@ impl Default for WebsocketConfig {
             max_message_size: 64 << 20,
             max_frame_size: 16 << 20,
             max_pending_connections: 255,
+            allowed_origins: None,
         }
     }
 }
@@ -78,6 +86,21 @@ impl WebsocketConfig {
         self.max_frame_size = max;
         self
     }
+
+    /// Builder-style setter.
+    pub fn allowed_origins(mut self, origins: Option<HashSet<String>>) -> Self {
+        self.allowed_origins = origins;
+        self
+    }
+
+    /// Check whether a connection with the given `Origin` header may be accepted.
+    pub fn is_origin_allowed(&self, origin: Option<&str>) -> bool {
+        match (&self.allowed_origins, origin) {
+            (None, _) => true,
+            (Some(allowed), Some(origin)) => allowed.contains(origin),
+            (Some(_), None) => false,
+        }
+    }
 }
 
 /// internal helper to convert our configs into tungstenite configs
@@ -241,8 +241,32 @@ async fn connect(
         message = "accepted incoming raw socket",
         remote_addr = %socket.peer_addr()?,
     );
-    let socket = tokio_tungstenite::accept_async_with_config(
+    let check_origin = {
+        let config = config.clone();
+        move |request: &tungstenite::handshake::server::Request,
+              response: tungstenite::handshake::server::Response| {
+            let origin = request
+                .headers()
+                .get(tungstenite::http::header::ORIGIN)
+                .and_then(|origin| origin.to_str().ok());
+            if config.is_origin_allowed(origin) {
+                Ok(response)
+            } else {
+                tracing::warn!(
+                    ?origin,
+                    "rejected websocket connection from disallowed origin"
+                );
+                let mut response = tungstenite::handshake::server::ErrorResponse::new(Some(
+                    "Origin not allowed".to_string(),
+                ));
+                *response.status_mut() = tungstenite::http::StatusCode::FORBIDDEN;
+                Err(response)
+            }
+        }
+    };
+    let socket = tokio_tungstenite::accept_hdr_async_with_config(
         socket,
+        check_origin,
         Some(tungstenite::protocol::WebSocketConfig {
             max_send_queue: Some(config.max_send_queue),
             max_message_size: Some(config.max_message_size),
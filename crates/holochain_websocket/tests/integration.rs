@@ -121,6 +121,40 @@ async fn can_connect() {
         .expect("Failed to connect to server");
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn rejects_disallowed_origins() {
+    observability::test_run().ok();
+    let (handle, mut listener) = WebsocketListener::bind_with_handle(
+        url2!("ws://127.0.0.1:0"),
+        Arc::new(
+            WebsocketConfig::default()
+                .allowed_origins(Some(["http://allowed".to_string()].into_iter().collect())),
+        ),
+    )
+    .await
+    .unwrap();
+    tokio::task::spawn(async move { while listener.next().await.is_some() {} });
+    let binding = handle.local_addr().clone();
+
+    let connect_with_origin = |origin: &str| {
+        let request = tungstenite::http::Request::builder()
+            .uri(binding.as_str())
+            .header("Origin", origin)
+            .body(())
+            .unwrap();
+        tokio_tungstenite::connect_async(request)
+    };
+
+    assert!(connect_with_origin("http://allowed").await.is_ok());
+    assert!(connect_with_origin("http://not-allowed").await.is_err());
+    // Clients which don't send an origin are rejected too.
+    assert!(
+        connect(binding.clone(), Arc::new(WebsocketConfig::default()))
+            .await
+            .is_err()
+    );
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn can_send_signal() {
     observability::test_run().ok();
@@ -1,5 +1,5 @@
 use crate::{judged::Judged, ActionType};
-use crate::{EntryType, SignedAction};
+use crate::{EntryType, SignedAction, SignedActionHashed};
 use holo_hash::ActionHash;
 use holochain_serialized_bytes::prelude::*;
 
@@ -49,3 +49,16 @@ impl DeterministicGetAgentActivityResponse {
         Self { chain }
     }
 }
+
+/// The result of walking a range of an agent's source chain
+/// for `must_get_agent_activity`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum MustGetAgentActivityResponse {
+    /// The actions in the range, from the chain top down.
+    Activity(Vec<SignedActionHashed>),
+    /// The chain top could not be found.
+    ChainTopNotFound(ActionHash),
+    /// The chain top was found but this action below it in the range
+    /// could not be found.
+    IncompleteChain(ActionHash),
+}
@@ -0,0 +1,91 @@
+use super::*;
+use crate::Timestamp;
+use holo_hash::ActionHash;
+use holo_hash::AgentPubKey;
+use holochain_serialized_bytes::prelude::*;
+
+/// Filter for the capability grants returned by `capability_grants`.
+/// Every filter that is set must match for a grant to be returned.
+#[derive(Default, PartialEq, Eq, Debug, Clone, Serialize, Deserialize, SerializedBytes)]
+pub struct CapGrantQuery {
+    /// Only return grants with exactly this tag.
+    pub tag: Option<String>,
+    /// Only return grants which give access to this function.
+    pub function: Option<GrantedFunction>,
+    /// Only return [`CapAccess::Assigned`] grants which list this agent as an assignee.
+    pub assignee: Option<AgentPubKey>,
+}
+
+impl CapGrantQuery {
+    /// Check if a grant passes this filter.
+    pub fn check(&self, grant: &ZomeCallCapGrant) -> bool {
+        self.tag.as_ref().map_or(true, |tag| *tag == grant.tag)
+            && self
+                .function
+                .as_ref()
+                .map_or(true, |function| grant.functions.contains(function))
+            && self
+                .assignee
+                .as_ref()
+                .map_or(true, |assignee| match &grant.access {
+                    CapAccess::Assigned { assignees, .. } => assignees.contains(assignee),
+                    _ => false,
+                })
+    }
+}
+
+/// Filter for the capability claims returned by `capability_claims`.
+/// Every filter that is set must match for a claim to be returned.
+#[derive(Default, PartialEq, Eq, Debug, Clone, Serialize, Deserialize, SerializedBytes)]
+pub struct CapClaimQuery {
+    /// Only return claims with exactly this tag.
+    pub tag: Option<String>,
+    /// Only return claims on grants made by this agent.
+    pub grantor: Option<AgentPubKey>,
+}
+
+impl CapClaimQuery {
+    /// Check if a claim passes this filter.
+    pub fn check(&self, claim: &CapClaim) -> bool {
+        self.tag.as_ref().map_or(true, |tag| *tag == claim.tag)
+            && self
+                .grantor
+                .as_ref()
+                .map_or(true, |grantor| *grantor == claim.grantor)
+    }
+}
+
+/// A capability grant that is live on the local source chain.
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize, SerializedBytes)]
+pub struct CapGrantInfo {
+    /// The grant itself.
+    pub cap_grant: ZomeCallCapGrant,
+    /// The action that created the grant.
+    /// Pass this to `update_cap_grant` or `delete_cap_grant` to change or revoke it.
+    pub action_hash: ActionHash,
+    /// When the grant was created.
+    pub created_at: Timestamp,
+}
+
+/// A capability claim that is live on the local source chain.
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize, SerializedBytes)]
+pub struct CapClaimInfo {
+    /// The claim itself.
+    pub cap_claim: CapClaim,
+    /// The action that created the claim.
+    pub action_hash: ActionHash,
+    /// When the claim was created.
+    pub created_at: Timestamp,
+}
+
+/// The capability that authorized the current zome call.
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize, SerializedBytes)]
+pub struct CapInfo {
+    /// The agent who made the call.
+    pub provenance: AgentPubKey,
+    /// The grant that authorized the call.
+    pub cap_grant: CapGrant,
+    /// The action that created the grant.
+    /// This is `None` for the implicit [`CapGrant::ChainAuthor`] grant.
+    pub action_hash: Option<ActionHash>,
+}
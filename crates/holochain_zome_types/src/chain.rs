@@ -0,0 +1,3 @@
+//! Types for reading a deterministic range of an agent's source chain.
+
+pub use holochain_integrity_types::chain::*;
@@ -18,6 +18,7 @@ pub mod bytes;
 pub mod call;
 pub mod capability;
 pub mod cell;
+pub mod chain;
 pub mod countersigning;
 #[allow(missing_docs)]
 pub mod crdt;
@@ -83,6 +83,22 @@ pub struct GetLinksInput {
     /// The link types to include in this get.
     pub link_type: LinkTypeFilter,
     pub tag_prefix: Option<crate::link::LinkTag>,
+    /// Only include links created at or after this time.
+    #[serde(default)]
+    pub after: Option<crate::Timestamp>,
+    /// Only include links created before this time.
+    #[serde(default)]
+    pub before: Option<crate::Timestamp>,
+    /// Only include links created by this agent.
+    #[serde(default)]
+    pub author: Option<holo_hash::AgentPubKey>,
+    /// Only include links which come after this cursor.
+    /// Links are ordered by their timestamp then by the hash of their create action.
+    #[serde(default)]
+    pub cursor: Option<LinkCursor>,
+    /// The maximum number of links to get.
+    #[serde(default)]
+    pub limit: Option<u32>,
 }
 
 impl GetLinksInput {
@@ -95,8 +111,116 @@ impl GetLinksInput {
             base_address,
             link_type,
             tag_prefix,
+            after: None,
+            before: None,
+            author: None,
+            cursor: None,
+            limit: None,
         }
     }
+
+    /// Only include links created at or after this time.
+    pub fn after(mut self, after: crate::Timestamp) -> Self {
+        self.after = Some(after);
+        self
+    }
+
+    /// Only include links created before this time.
+    pub fn before(mut self, before: crate::Timestamp) -> Self {
+        self.before = Some(before);
+        self
+    }
+
+    /// Only include links created by this agent.
+    pub fn author(mut self, author: holo_hash::AgentPubKey) -> Self {
+        self.author = Some(author);
+        self
+    }
+
+    /// Get the page of links which comes after this cursor.
+    pub fn cursor(mut self, cursor: impl Into<LinkCursor>) -> Self {
+        self.cursor = Some(cursor.into());
+        self
+    }
+
+    /// Get at most this many links.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+}
+
+/// A position in the links on a base, which are ordered by their timestamp
+/// then by the hash of their create action.
+///
+/// The cursor of the last link of a page gets the next page.
+#[derive(PartialEq, Eq, Clone, Debug, Hash, Serialize, Deserialize)]
+pub struct LinkCursor {
+    /// The timestamp of the link.
+    pub timestamp: crate::Timestamp,
+    /// The hash of the link's create action.
+    pub create_link_hash: ActionHash,
+}
+
+impl From<&Link> for LinkCursor {
+    fn from(link: &Link) -> Self {
+        Self {
+            timestamp: link.timestamp,
+            create_link_hash: link.create_link_hash.clone(),
+        }
+    }
+}
+
+/// Zome IO inner type for counting links.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct LinkQuery {
+    pub base_address: holo_hash::AnyLinkableHash,
+    /// The link types to include in this count.
+    pub link_type: LinkTypeFilter,
+    pub tag_prefix: Option<crate::link::LinkTag>,
+    /// Only include links created at or after this time.
+    pub after: Option<crate::Timestamp>,
+    /// Only include links created before this time.
+    pub before: Option<crate::Timestamp>,
+    /// Only include links created by this agent.
+    pub author: Option<holo_hash::AgentPubKey>,
+}
+
+impl LinkQuery {
+    pub fn new(base_address: holo_hash::AnyLinkableHash, link_type: LinkTypeFilter) -> Self {
+        Self {
+            base_address,
+            link_type,
+            tag_prefix: None,
+            after: None,
+            before: None,
+            author: None,
+        }
+    }
+
+    /// Only include links with a tag that starts with this prefix.
+    pub fn tag_prefix(mut self, tag_prefix: crate::link::LinkTag) -> Self {
+        self.tag_prefix = Some(tag_prefix);
+        self
+    }
+
+    /// Only include links created at or after this time.
+    pub fn after(mut self, after: crate::Timestamp) -> Self {
+        self.after = Some(after);
+        self
+    }
+
+    /// Only include links created before this time.
+    pub fn before(mut self, before: crate::Timestamp) -> Self {
+        self.before = Some(before);
+        self
+    }
+
+    /// Only include links created by this agent.
+    pub fn author(mut self, author: holo_hash::AgentPubKey) -> Self {
+        self.author = Some(author);
+        self
+    }
 }
 
 type CreateLinkWithDeleteLinks = Vec<(SignedActionHashed, Vec<SignedActionHashed>)>;
@@ -1,3 +1,16 @@
 //! Types for rate limiting
 
 pub use holochain_integrity_types::rate_limit::*;
+
+use crate::CallbackResult;
+use holochain_wasmer_common::WasmError;
+
+impl CallbackResult for RateLimitsCallbackResult {
+    fn is_definitive(&self) -> bool {
+        false
+    }
+    fn try_from_wasm_error(wasm_error: WasmError) -> Result<Self, WasmError> {
+        // There is no concept of rate limits failing, other than normal error handling.
+        Err(wasm_error)
+    }
+}
@@ -21,6 +21,9 @@ pub struct MetadataRequest {
     /// This is faster then getting all the actions
     /// and checking for live actions.
     pub entry_dht_status: bool,
+    /// Get the links on a base that have not been deleted.
+    #[serde(default)]
+    pub all_links: bool,
 }
 
 impl Default for MetadataRequest {
@@ -32,6 +35,7 @@ impl Default for MetadataRequest {
             all_updates: true,
             follow_redirects: false,
             entry_dht_status: false,
+            all_links: false,
         }
     }
 }
@@ -1,6 +1,10 @@
 use crate::record::Record;
+use crate::timestamp::Timestamp;
 use crate::CallbackResult;
+use holo_hash::ActionHash;
+use holo_hash::AgentPubKey;
 use holo_hash::AnyDhtHash;
+use holo_hash::DhtOpHash;
 use holochain_serialized_bytes::prelude::*;
 use holochain_wasmer_common::*;
 
@@ -26,6 +30,61 @@ pub enum ValidationStatus {
     Abandoned = 2,
 }
 
+/// Input to the `get_validation_receipts` host function.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, SerializedBytes)]
+pub struct GetValidationReceiptsInput {
+    /// The action to get validation receipts for.
+    pub action_hash: ActionHash,
+}
+
+impl GetValidationReceiptsInput {
+    /// Create the input for getting the validation receipts of an action.
+    pub fn new(action_hash: ActionHash) -> Self {
+        Self { action_hash }
+    }
+}
+
+/// A validation receipt received from the validators of an op,
+/// stripped of its signatures.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, SerializedBytes)]
+pub struct ValidationReceiptInfo {
+    /// The status the validators gave to the op.
+    pub validation_status: ValidationStatus,
+    /// The validators that signed this receipt.
+    pub validators: Vec<AgentPubKey>,
+    /// When the validators integrated the op.
+    pub when_integrated: Timestamp,
+}
+
+/// All the validation receipts received for one of the ops
+/// produced by an action.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, SerializedBytes)]
+pub struct ValidationReceiptSet {
+    /// The op the receipts are for.
+    pub op_hash: DhtOpHash,
+    /// The type of the op, e.g. `StoreRecord`.
+    pub op_type: String,
+    /// Whether enough receipts have been received that the op
+    /// is no longer being published.
+    pub receipts_complete: bool,
+    /// The receipts received so far.
+    pub receipts: Vec<ValidationReceiptInfo>,
+}
+
+impl ValidationReceiptSet {
+    /// The number of distinct validators that have sent a receipt for this op.
+    pub fn validator_count(&self) -> usize {
+        let mut validators: Vec<_> = self
+            .receipts
+            .iter()
+            .flat_map(|receipt| receipt.validators.iter())
+            .collect();
+        validators.sort_unstable();
+        validators.dedup();
+        validators.len()
+    }
+}
+
 impl CallbackResult for ValidateCallbackResult {
     fn is_definitive(&self) -> bool {
         matches!(self, ValidateCallbackResult::Invalid(_))
@@ -1,6 +1,112 @@
 //! Types for warrants
+use crate::signature::Signature;
+use crate::timestamp::Timestamp;
+use holo_hash::ActionHash;
+use holo_hash::AgentPubKey;
 pub use holochain_serialized_bytes::prelude::*;
 
-#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
-/// Placeholder for warrant type
-pub struct Warrant;
+/// A proof that an agent broke the rules of a DNA, issued by the
+/// authority that discovered it.
+///
+/// Warrants are held by the agent activity authorities of the warrantee
+/// and returned alongside the warrantee's agent activity.
+#[derive(
+    Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, SerializedBytes,
+)]
+pub struct Warrant {
+    /// The proof of invalid behaviour.
+    pub proof: WarrantProof,
+    /// The agent that issued this warrant.
+    pub author: AgentPubKey,
+    /// When this warrant was issued.
+    pub timestamp: Timestamp,
+}
+
+impl Warrant {
+    /// Constructor
+    pub fn new(proof: WarrantProof, author: AgentPubKey, timestamp: Timestamp) -> Self {
+        Self {
+            proof,
+            author,
+            timestamp,
+        }
+    }
+
+    /// The agent this warrant is about.
+    pub fn warrantee(&self) -> &AgentPubKey {
+        self.proof.warrantee()
+    }
+}
+
+/// The kinds of invalid behaviour a warrant can prove.
+#[derive(
+    Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, SerializedBytes,
+)]
+pub enum WarrantProof {
+    /// An action on the author's chain failed validation.
+    InvalidChainOp {
+        /// The author of the invalid action.
+        action_author: AgentPubKey,
+        /// The hash of the invalid action and the author's signature of it.
+        action: (ActionHash, Signature),
+        /// Which validation the action failed.
+        validation_type: ValidationType,
+    },
+    /// The author signed two different actions at the same
+    /// sequence number, forking their chain.
+    ChainFork {
+        /// The author of the forked chain.
+        chain_author: AgentPubKey,
+        /// The two actions at the fork and the author's signatures of them.
+        action_pair: ((ActionHash, Signature), (ActionHash, Signature)),
+    },
+}
+
+impl WarrantProof {
+    /// The agent this proof is about.
+    pub fn warrantee(&self) -> &AgentPubKey {
+        match self {
+            WarrantProof::InvalidChainOp { action_author, .. } => action_author,
+            WarrantProof::ChainFork { chain_author, .. } => chain_author,
+        }
+    }
+}
+
+/// The validation that an action failed.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, SerializedBytes,
+)]
+pub enum ValidationType {
+    /// System validation, which is the same for every DNA.
+    Sys,
+    /// App validation, defined by the integrity zomes of the DNA.
+    App,
+}
+
+/// A warrant signed by its author.
+#[derive(
+    Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, SerializedBytes,
+)]
+pub struct SignedWarrant {
+    /// The warrant.
+    pub warrant: Warrant,
+    /// The author's signature of the warrant.
+    pub signature: Signature,
+}
+
+impl SignedWarrant {
+    /// Constructor
+    pub fn new(warrant: Warrant, signature: Signature) -> Self {
+        Self { warrant, signature }
+    }
+
+    /// The warrant.
+    pub fn warrant(&self) -> &Warrant {
+        &self.warrant
+    }
+
+    /// The author's signature of the warrant.
+    pub fn signature(&self) -> &Signature {
+        &self.signature
+    }
+}
@@ -4,6 +4,7 @@ use std::sync::atomic::AtomicUsize;
 use kitsune_p2p_types::codec::rmp_decode;
 use kitsune_p2p_types::codec::rmp_encode;
 use store::Store;
+pub use store::StoreConfig;
 use warp::{hyper::body::Bytes, Filter};
 
 static NOW: AtomicUsize = AtomicUsize::new(0);
@@ -42,14 +43,28 @@ pub async fn run_with_prune_freq(
     proxy_list: Vec<String>,
     prune_frequency: std::time::Duration,
 ) -> Result<(BootstrapDriver, SocketAddr, BootstrapShutdown), String> {
-    let store = Store::new(proxy_list);
+    run_with_store(addr, proxy_list, prune_frequency, StoreConfig::Memory).await
+}
+
+/// Run a bootstrap server with a set prune frequency,
+/// keeping agent infos in the configured store.
+pub async fn run_with_store(
+    addr: impl Into<SocketAddr> + 'static,
+    proxy_list: Vec<String>,
+    prune_frequency: std::time::Duration,
+    store_config: StoreConfig,
+) -> Result<(BootstrapDriver, SocketAddr, BootstrapShutdown), String> {
+    let store = Store::from_config(&store_config, proxy_list)
+        .map_err(|e| format!("Failed to open store {:?}: {}", store_config, e))?;
 
     {
         let store = store.clone();
         tokio::task::spawn(async move {
             loop {
                 tokio::time::sleep(prune_frequency).await;
-                store.prune();
+                if let Err(e) = store.prune() {
+                    tracing::error!(?e, "Failed to prune expired agent infos");
+                }
             }
         });
     }
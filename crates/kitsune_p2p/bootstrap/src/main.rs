@@ -12,10 +12,17 @@ struct Args {
     /// multiple times
     #[clap(short, long, verbatim_doc_comment)]
     proxy: Vec<String>,
+
+    /// keep agent infos in a SQLite database at
+    /// this path, so they survive restarts,
+    /// instead of in memory
+    #[clap(long, verbatim_doc_comment)]
+    sqlite: Option<std::path::PathBuf>,
 }
 
 #[tokio::main(flavor = "multi_thread")]
 async fn main() {
+    observability::init_fmt(observability::Output::Log).ok();
     let args = Args::parse();
 
     use std::net::ToSocketAddrs;
@@ -27,7 +34,19 @@ async fn main() {
         .next()
         .unwrap();
 
-    match kitsune_p2p_bootstrap::run(addr, args.proxy).await {
+    let store = match args.sqlite {
+        Some(path) => kitsune_p2p_bootstrap::StoreConfig::Sqlite(path),
+        None => kitsune_p2p_bootstrap::StoreConfig::Memory,
+    };
+
+    match kitsune_p2p_bootstrap::run_with_store(
+        addr,
+        args.proxy,
+        kitsune_p2p_bootstrap::PRUNE_EXPIRED_FREQ,
+        store,
+    )
+    .await
+    {
         Ok((driver, addr, _shutdown)) => {
             println!("http://{}", addr);
             driver.await;
@@ -20,7 +20,10 @@ async fn put_info(peer: Bytes, store: Store) -> Result<impl warp::Reply, warp::R
         rmp_decode(&mut AsRef::<[u8]>::as_ref(&peer)).map_err(|_| warp::reject())?;
     // TODO: Return rejection if agent info was invalid?
     if valid(&peer) {
-        store.put(peer);
+        store.put(peer).map_err(|e| {
+            tracing::error!(?e, "Failed to store agent info");
+            warp::reject()
+        })?;
     }
     PUT.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
     let mut buf = Vec::with_capacity(1);
@@ -1,94 +1,101 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
 use kitsune_p2p_types::{
     agent_info::AgentInfoSigned,
     bin_types::{KitsuneAgent, KitsuneSpace},
     bootstrap::RandomQuery,
-    codec::rmp_encode,
 };
-use parking_lot::RwLock;
-use rand::seq::IteratorRandom;
+
+mod memory;
+mod sqlite;
+
+use memory::MemoryStore;
+use sqlite::SqliteStore;
 
 type AgentMap = HashMap<Arc<KitsuneAgent>, AgentInfoSigned>;
 type SpaceMap = HashMap<Arc<KitsuneSpace>, AgentMap>;
 
+pub(crate) type StoreResult<T> = Result<T, rusqlite::Error>;
+
+/// Where the bootstrap server keeps the agent infos it is sent.
 #[derive(Clone, Debug)]
-pub(crate) struct Store(Arc<RwLock<SpaceMap>>, Arc<Vec<String>>);
+pub enum StoreConfig {
+    /// Keep agent infos in memory, so they are lost when the server stops.
+    Memory,
+    /// Keep agent infos in a SQLite database at this path,
+    /// which is created if it doesn't exist.
+    Sqlite(PathBuf),
+}
+
+/// A backend holding the agent infos of every space.
+pub(crate) trait AgentInfoStore: std::fmt::Debug + Send + Sync {
+    /// Remove every agent info which expired before `now`.
+    fn prune(&self, now: u64) -> StoreResult<()>;
+
+    /// Store an agent info, unless one signed at the same time
+    /// or later is already stored for the agent.
+    fn put(&self, info: AgentInfoSigned) -> StoreResult<()>;
+
+    /// Up to `limit` randomly chosen, encoded agent infos of a space
+    /// which haven't expired by `now` and have urls.
+    fn random(&self, space: &KitsuneSpace, limit: usize, now: u64) -> StoreResult<Vec<Vec<u8>>>;
+
+    /// Remove every agent info.
+    fn clear(&self) -> StoreResult<()>;
+
+    /// All stored agent infos.
+    #[cfg(test)]
+    fn all(&self) -> StoreResult<SpaceMap>;
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Store(Arc<dyn AgentInfoStore>, Arc<Vec<String>>);
 
 impl Store {
+    #[cfg(test)]
     pub fn new(proxy_list: Vec<String>) -> Self {
-        Self(Arc::new(RwLock::new(HashMap::new())), Arc::new(proxy_list))
+        Self(Arc::new(MemoryStore::default()), Arc::new(proxy_list))
+    }
+
+    pub fn from_config(config: &StoreConfig, proxy_list: Vec<String>) -> StoreResult<Self> {
+        let backend: Arc<dyn AgentInfoStore> = match config {
+            StoreConfig::Memory => Arc::new(MemoryStore::default()),
+            StoreConfig::Sqlite(path) => Arc::new(SqliteStore::open(path)?),
+        };
+        Ok(Self(backend, Arc::new(proxy_list)))
     }
 
     pub fn proxy_list(&self) -> Arc<Vec<String>> {
         self.1.clone()
     }
 
-    pub fn prune(&self) {
-        let now = std::time::UNIX_EPOCH
-            .elapsed()
-            .expect("Bootstrap server time set before epoch")
-            .as_millis() as u64;
-
-        self.0.write().retain(|_, map| {
-            map.retain(|_, info| info.expires_at_ms >= now);
-            !map.is_empty()
-        });
+    pub fn prune(&self) -> StoreResult<()> {
+        self.0.prune(now())
     }
 
-    pub fn put(&self, info: AgentInfoSigned) {
-        let mut lock = self.0.write();
-        let space_map = lock.entry(info.space.clone()).or_insert_with(HashMap::new);
-        match space_map.entry(info.agent.clone()) {
-            std::collections::hash_map::Entry::Occupied(mut e) => {
-                if info.signed_at_ms > e.get().signed_at_ms {
-                    e.insert(info);
-                }
-            }
-            std::collections::hash_map::Entry::Vacant(e) => {
-                e.insert(info);
-            }
-        }
+    pub fn put(&self, info: AgentInfoSigned) -> StoreResult<()> {
+        self.0.put(info)
     }
 
-    pub fn random(&self, query: RandomQuery) -> Vec<Vec<u8>> {
+    pub fn random(&self, query: RandomQuery) -> StoreResult<Vec<Vec<u8>>> {
         // TODO: Max this limit
         let limit = query.limit.0 as usize;
-        let mut rng = rand::thread_rng();
-        let now = std::time::UNIX_EPOCH
-            .elapsed()
-            .expect("Bootstrap server time set before epoch")
-            .as_millis() as u64;
-        self.0
-            .read()
-            .get(query.space.as_ref())
-            .map(|space| {
-                space
-                    .values()
-                    .filter_map(|i| {
-                        if i.expires_at_ms <= now {
-                            return None;
-                        }
-                        if i.url_list.is_empty() {
-                            return None;
-                        }
-                        let mut buf = Vec::new();
-                        match rmp_encode(&mut buf, i) {
-                            Ok(_) => Some(buf),
-                            Err(_) => None,
-                        }
-                    })
-                    .choose_multiple(&mut rng, limit)
-            })
-            .unwrap_or_default()
+        self.0.random(query.space.as_ref(), limit, now())
     }
 
-    pub fn clear(&self) {
-        self.0.write().clear()
+    pub fn clear(&self) -> StoreResult<()> {
+        self.0.clear()
     }
 
     #[cfg(test)]
     pub fn all(&self) -> HashMap<Arc<KitsuneSpace>, HashMap<Arc<KitsuneAgent>, AgentInfoSigned>> {
-        self.0.read().clone()
+        self.0.all().unwrap()
     }
 }
+
+fn now() -> u64 {
+    std::time::UNIX_EPOCH
+        .elapsed()
+        .expect("Bootstrap server time set before epoch")
+        .as_millis() as u64
+}
@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+
+use kitsune_p2p_types::{agent_info::AgentInfoSigned, bin_types::KitsuneSpace, codec::rmp_encode};
+use parking_lot::RwLock;
+use rand::seq::IteratorRandom;
+
+use super::{AgentInfoStore, SpaceMap, StoreResult};
+
+/// Keeps agent infos in a `HashMap`, so they are lost on restart.
+#[derive(Debug, Default)]
+pub(crate) struct MemoryStore(RwLock<SpaceMap>);
+
+impl AgentInfoStore for MemoryStore {
+    fn prune(&self, now: u64) -> StoreResult<()> {
+        self.0.write().retain(|_, map| {
+            map.retain(|_, info| info.expires_at_ms >= now);
+            !map.is_empty()
+        });
+        Ok(())
+    }
+
+    fn put(&self, info: AgentInfoSigned) -> StoreResult<()> {
+        let mut lock = self.0.write();
+        let space_map = lock.entry(info.space.clone()).or_insert_with(HashMap::new);
+        match space_map.entry(info.agent.clone()) {
+            std::collections::hash_map::Entry::Occupied(mut e) => {
+                if info.signed_at_ms > e.get().signed_at_ms {
+                    e.insert(info);
+                }
+            }
+            std::collections::hash_map::Entry::Vacant(e) => {
+                e.insert(info);
+            }
+        }
+        Ok(())
+    }
+
+    fn random(&self, space: &KitsuneSpace, limit: usize, now: u64) -> StoreResult<Vec<Vec<u8>>> {
+        let mut rng = rand::thread_rng();
+        Ok(self
+            .0
+            .read()
+            .get(space)
+            .map(|space| {
+                space
+                    .values()
+                    .filter_map(|i| {
+                        if i.expires_at_ms <= now {
+                            return None;
+                        }
+                        if i.url_list.is_empty() {
+                            return None;
+                        }
+                        let mut buf = Vec::new();
+                        match rmp_encode(&mut buf, i) {
+                            Ok(_) => Some(buf),
+                            Err(_) => None,
+                        }
+                    })
+                    .choose_multiple(&mut rng, limit)
+            })
+            .unwrap_or_default())
+    }
+
+    fn clear(&self) -> StoreResult<()> {
+        self.0.write().clear();
+        Ok(())
+    }
+
+    #[cfg(test)]
+    fn all(&self) -> StoreResult<SpaceMap> {
+        Ok(self.0.read().clone())
+    }
+}
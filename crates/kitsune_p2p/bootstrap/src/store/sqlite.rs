@@ -0,0 +1,230 @@
+use std::path::Path;
+
+use kitsune_p2p_types::{
+    agent_info::AgentInfoSigned, bin_types::KitsuneBinType, bin_types::KitsuneSpace,
+    codec::rmp_encode,
+};
+use parking_lot::Mutex;
+use rusqlite::{named_params, Connection};
+
+use super::{AgentInfoStore, StoreResult};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS agent_info (
+        space           BLOB    NOT NULL,
+        agent           BLOB    NOT NULL,
+        signed_at_ms    INTEGER NOT NULL,
+        expires_at_ms   INTEGER NOT NULL,
+        has_urls        INTEGER NOT NULL,
+        encoded         BLOB    NOT NULL,
+        PRIMARY KEY (space, agent)
+    );
+    CREATE INDEX IF NOT EXISTS agent_info_expires_at_ms ON agent_info (expires_at_ms);
+";
+
+/// Only replace a stored agent info with one signed later,
+/// the same as the in-memory store.
+const PUT: &str = "
+    INSERT INTO agent_info (space, agent, signed_at_ms, expires_at_ms, has_urls, encoded)
+    VALUES (:space, :agent, :signed_at_ms, :expires_at_ms, :has_urls, :encoded)
+    ON CONFLICT (space, agent) DO UPDATE SET
+        signed_at_ms = excluded.signed_at_ms,
+        expires_at_ms = excluded.expires_at_ms,
+        has_urls = excluded.has_urls,
+        encoded = excluded.encoded
+    WHERE excluded.signed_at_ms > agent_info.signed_at_ms
+";
+
+const RANDOM: &str = "
+    SELECT encoded FROM agent_info
+    WHERE space = :space AND expires_at_ms > :now AND has_urls
+    ORDER BY RANDOM()
+    LIMIT :limit
+";
+
+/// Keeps agent infos in a SQLite database, so they survive restarts.
+#[derive(Debug)]
+pub(crate) struct SqliteStore(Mutex<Connection>);
+
+impl SqliteStore {
+    /// Open the database at this path, creating it if it doesn't exist.
+    pub fn open(path: &Path) -> StoreResult<Self> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    fn from_connection(conn: Connection) -> StoreResult<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self(Mutex::new(conn)))
+    }
+}
+
+impl AgentInfoStore for SqliteStore {
+    fn prune(&self, now: u64) -> StoreResult<()> {
+        self.0.lock().execute(
+            "DELETE FROM agent_info WHERE expires_at_ms < :now",
+            named_params! { ":now": now as i64 },
+        )?;
+        Ok(())
+    }
+
+    fn put(&self, info: AgentInfoSigned) -> StoreResult<()> {
+        let mut encoded = Vec::new();
+        rmp_encode(&mut encoded, &info)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        self.0.lock().execute(
+            PUT,
+            named_params! {
+                ":space": info.space.get_bytes(),
+                ":agent": info.agent.get_bytes(),
+                ":signed_at_ms": info.signed_at_ms as i64,
+                ":expires_at_ms": info.expires_at_ms as i64,
+                ":has_urls": !info.url_list.is_empty(),
+                ":encoded": encoded,
+            },
+        )?;
+        Ok(())
+    }
+
+    fn random(&self, space: &KitsuneSpace, limit: usize, now: u64) -> StoreResult<Vec<Vec<u8>>> {
+        let conn = self.0.lock();
+        let mut stmt = conn.prepare_cached(RANDOM)?;
+        let rows = stmt.query_map(
+            named_params! {
+                ":space": space.get_bytes(),
+                ":now": now as i64,
+                ":limit": limit as i64,
+            },
+            |row| row.get(0),
+        )?;
+        rows.collect()
+    }
+
+    fn clear(&self) -> StoreResult<()> {
+        self.0.lock().execute("DELETE FROM agent_info", [])?;
+        Ok(())
+    }
+
+    #[cfg(test)]
+    fn all(&self) -> StoreResult<super::SpaceMap> {
+        let conn = self.0.lock();
+        let mut stmt = conn.prepare("SELECT encoded FROM agent_info")?;
+        let mut all = super::SpaceMap::new();
+        for encoded in stmt.query_map([], |row| row.get::<_, Vec<u8>>(0))? {
+            let info: AgentInfoSigned =
+                kitsune_p2p_types::codec::rmp_decode(&mut encoded?.as_slice()).map_err(|e| {
+                    rusqlite::Error::FromSqlConversionFailure(
+                        0,
+                        rusqlite::types::Type::Blob,
+                        Box::new(e),
+                    )
+                })?;
+            all.entry(info.space.clone())
+                .or_default()
+                .insert(info.agent.clone(), info);
+        }
+        Ok(all)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use fixt::prelude::*;
+    use kitsune_p2p::fixt::*;
+    use kitsune_p2p_types::bin_types::KitsuneAgent;
+
+    fn now() -> u64 {
+        std::time::UNIX_EPOCH.elapsed().unwrap().as_millis() as u64
+    }
+
+    async fn agent_info(
+        space: Arc<KitsuneSpace>,
+        agent: Arc<KitsuneAgent>,
+        signed_at_ms: u64,
+        expires_at_ms: u64,
+    ) -> AgentInfoSigned {
+        AgentInfoSigned::sign(
+            space,
+            agent,
+            u32::MAX / 4,
+            vec!["fake:".into()],
+            signed_at_ms,
+            expires_at_ms,
+            |_| async move { Ok(Arc::new(fixt!(KitsuneSignature, Unpredictable))) },
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_put_keeps_latest_signed() {
+        let store = SqliteStore::from_connection(Connection::open_in_memory().unwrap()).unwrap();
+        let space = Arc::new(fixt!(KitsuneSpace, Unpredictable));
+        let agent = Arc::new(fixt!(KitsuneAgent, Unpredictable));
+        let expires_at_ms = now() + 60_000_000;
+
+        let older = agent_info(space.clone(), agent.clone(), 1, expires_at_ms).await;
+        let newer = agent_info(space.clone(), agent.clone(), 2, expires_at_ms).await;
+
+        store.put(newer.clone()).unwrap();
+        store.put(older).unwrap();
+        assert_eq!(store.all().unwrap()[&space][&agent], newer);
+
+        let newest = agent_info(space.clone(), agent.clone(), 3, expires_at_ms).await;
+        store.put(newest.clone()).unwrap();
+        assert_eq!(store.all().unwrap()[&space][&agent], newest);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_random_and_prune_skip_expired() {
+        let store = SqliteStore::from_connection(Connection::open_in_memory().unwrap()).unwrap();
+        let space = Arc::new(fixt!(KitsuneSpace, Unpredictable));
+        let now = now();
+
+        let live = agent_info(
+            space.clone(),
+            Arc::new(fixt!(KitsuneAgent, Unpredictable)),
+            0,
+            now + 60_000_000,
+        )
+        .await;
+        let expired = agent_info(
+            space.clone(),
+            Arc::new(fixt!(KitsuneAgent, Unpredictable)),
+            0,
+            now - 1,
+        )
+        .await;
+        store.put(live.clone()).unwrap();
+        store.put(expired).unwrap();
+
+        let mut live_encoded = Vec::new();
+        rmp_encode(&mut live_encoded, &live).unwrap();
+        assert_eq!(store.random(&space, 10, now).unwrap(), vec![live_encoded]);
+        let other_space = fixt!(KitsuneSpace, Unpredictable);
+        assert!(store.random(&other_space, 10, now).unwrap().is_empty());
+
+        store.prune(now).unwrap();
+        assert_eq!(store.all().unwrap()[&space].len(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_agent_infos_survive_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bootstrap.sqlite3");
+        let info = agent_info(
+            Arc::new(fixt!(KitsuneSpace, Unpredictable)),
+            Arc::new(fixt!(KitsuneAgent, Unpredictable)),
+            0,
+            now() + 60_000_000,
+        )
+        .await;
+
+        SqliteStore::open(&path).unwrap().put(info.clone()).unwrap();
+
+        let store = SqliteStore::open(&path).unwrap();
+        assert_eq!(store.all().unwrap()[&info.space][&info.agent], info);
+    }
+}
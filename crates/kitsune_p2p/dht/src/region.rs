@@ -17,9 +17,13 @@
 
 mod region_coords;
 mod region_data;
+mod region_tree;
 
 pub use region_coords::*;
 pub use region_data::*;
+pub use region_tree::*;
+
+pub(crate) use region_tree::QuantumRect;
 
 use num_traits::Zero;
 use std::ops::{AddAssign, Sub};
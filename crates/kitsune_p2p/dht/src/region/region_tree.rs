@@ -0,0 +1,406 @@
+use kitsune_p2p_timestamp::Timestamp;
+
+use crate::{
+    region_set::{RegionCoordSetLtcs, RegionSetLtcs},
+    spacetime::{Quantum, SpaceQuantum, SpacetimeQuantumCoords, Topology},
+    Loc,
+};
+
+use super::{RegionBounds, RegionCoords, RegionData, RegionDataConstraints};
+
+/// The number of distinct quanta a leaf can hold before it gets split.
+const LEAF_CAPACITY: usize = 16;
+
+/// An in-memory index of the [`RegionData`] of every quantum of spacetime
+/// which contains ops, which can be updated one op at a time and can answer
+/// the data for any quantum-aligned region without touching the database.
+///
+/// The tree is a sparse binary partition of quantized spacetime: each node
+/// covers a power-of-two rectangle of quanta and holds the sum of all data
+/// inside of it, and each branch splits its rectangle in half along its
+/// longer dimension. Queries are answered by summing the largest nodes which
+/// fit inside the queried region, so the cost of a query depends on the
+/// length of its boundary rather than on the number of ops inside of it.
+#[derive(Clone, Debug)]
+pub struct RegionTree<D: RegionDataConstraints = RegionData> {
+    topo: Topology,
+    root: Node<D>,
+}
+
+impl<D: RegionDataConstraints> RegionTree<D> {
+    /// An empty tree for this topology
+    pub fn new(topo: Topology) -> Self {
+        Self {
+            topo,
+            root: Node::empty(),
+        }
+    }
+
+    /// The topology which the tree is quantized by
+    pub fn topo(&self) -> &Topology {
+        &self.topo
+    }
+
+    /// Add the data of an op at this location and time.
+    /// Ops authored before the origin of time, or too far after it to have
+    /// a time quantum, are not part of any region, so they are ignored.
+    pub fn insert(&mut self, loc: Loc, timestamp: Timestamp, data: D) {
+        let t = timestamp.as_micros() - self.topo.time_origin.as_micros();
+        let t = t / self.topo.time.quantum as i64;
+        if t < 0 || t > u32::MAX as i64 {
+            return;
+        }
+        let coords = SpacetimeQuantumCoords {
+            space: self.topo.space_quantum(loc),
+            time: (t as u32).into(),
+        };
+        self.insert_quantum(&coords, data);
+    }
+
+    /// Add data to a single quantum of spacetime
+    pub fn insert_quantum(&mut self, coords: &SpacetimeQuantumCoords, data: D) {
+        let x = coords.space.normalized(&self.topo).inner();
+        let t = coords.time.inner();
+        let root = QuantumRect::root(&self.topo);
+        debug_assert!(root.contains_point(x as u64, t as u64));
+        self.root.insert(root, x as u64, t as u64, data);
+    }
+
+    /// The sum of all data in the region with these coords
+    pub fn region_data(&self, coords: &RegionCoords) -> D {
+        let root = QuantumRect::root(&self.topo);
+        QuantumRect::from_coords(&self.topo, coords)
+            .into_iter()
+            .map(|rect| self.root.query(root, &rect))
+            .fold(D::zero(), |sum, d| sum + d)
+    }
+
+    /// The sum of all data within these absolute bounds, if they align with
+    /// quantum boundaries. Unaligned bounds can't be answered from quantized
+    /// data, so they return None.
+    pub fn query(&self, bounds: &RegionBounds) -> Option<D> {
+        let root = QuantumRect::root(&self.topo);
+        Some(
+            QuantumRect::from_bounds(&self.topo, bounds)?
+                .into_iter()
+                .map(|rect| self.root.query(root, &rect))
+                .fold(D::zero(), |sum, d| sum + d),
+        )
+    }
+
+    /// Compute the data for every region in the set of coords
+    pub fn region_set(&self, coords: RegionCoordSetLtcs) -> RegionSetLtcs<D> {
+        coords.into_region_set_infallible(|(_, coords)| self.region_data(&coords))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Node<D> {
+    data: D,
+    children: Children<D>,
+}
+
+#[derive(Clone, Debug)]
+enum Children<D> {
+    /// The data of each distinct quantum in this node
+    Leaf(Vec<(u64, u64, D)>),
+    /// The two halves of this node, in the order given by [`QuantumRect::halves`]
+    Branch(Box<[Node<D>; 2]>),
+}
+
+impl<D: RegionDataConstraints> Node<D> {
+    fn empty() -> Self {
+        Self {
+            data: D::zero(),
+            children: Children::Leaf(Vec::new()),
+        }
+    }
+
+    fn insert(&mut self, rect: QuantumRect, x: u64, t: u64, data: D) {
+        self.data += data.clone();
+        match &mut self.children {
+            Children::Leaf(entries) => {
+                match entries.iter_mut().find(|(ex, et, _)| *ex == x && *et == t) {
+                    Some((_, _, d)) => *d += data,
+                    None => entries.push((x, t, data)),
+                }
+                if entries.len() > LEAF_CAPACITY {
+                    if let Some(halves) = rect.halves() {
+                        let mut children = [Self::empty(), Self::empty()];
+                        for (x, t, d) in std::mem::take(entries) {
+                            let i = if halves[0].contains_point(x, t) { 0 } else { 1 };
+                            children[i].insert(halves[i], x, t, d);
+                        }
+                        self.children = Children::Branch(Box::new(children));
+                    }
+                }
+            }
+            Children::Branch(children) => {
+                let halves = rect.halves().expect("a branch can always be split");
+                let i = if halves[0].contains_point(x, t) { 0 } else { 1 };
+                children[i].insert(halves[i], x, t, data);
+            }
+        }
+    }
+
+    fn query(&self, rect: QuantumRect, query: &QuantumRect) -> D {
+        if self.data.is_zero() || !query.intersects(&rect) {
+            D::zero()
+        } else if query.contains_rect(&rect) {
+            self.data.clone()
+        } else {
+            match &self.children {
+                Children::Leaf(entries) => entries
+                    .iter()
+                    .filter(|(x, t, _)| query.contains_point(*x, *t))
+                    .fold(D::zero(), |sum, (_, _, d)| sum + d.clone()),
+                Children::Branch(children) => {
+                    let halves = rect.halves().expect("a branch can always be split");
+                    children[0].query(halves[0], query) + children[1].query(halves[1], query)
+                }
+            }
+        }
+    }
+}
+
+/// A rectangle of spacetime quanta, with inclusive bounds in each dimension.
+/// The space range never wraps: wrapping ranges are represented by two rects.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct QuantumRect {
+    pub(crate) x: (u64, u64),
+    pub(crate) t: (u64, u64),
+}
+
+impl QuantumRect {
+    /// All of quantized spacetime
+    fn root(topo: &Topology) -> Self {
+        Self {
+            x: (0, SpaceQuantum::max_value(topo).inner() as u64),
+            t: (0, u32::MAX as u64),
+        }
+    }
+
+    /// The rects covered by these region coords
+    pub(crate) fn from_coords(topo: &Topology, coords: &RegionCoords) -> Vec<Self> {
+        let n = coords.time.num_quanta();
+        let t0 = n * *coords.time.offset as u64;
+        if t0 > u32::MAX as u64 {
+            return vec![];
+        }
+        let t = (t0, (t0 + n - 1).min(u32::MAX as u64));
+        let (x0, x1) = coords.space.quantum_bounds(topo);
+        Self::split_space(topo, (x0.inner() as u64, x1.inner() as u64), t)
+    }
+
+    /// The rects covered by these absolute bounds, if they are quantum-aligned
+    pub(crate) fn from_bounds(topo: &Topology, bounds: &RegionBounds) -> Option<Vec<Self>> {
+        let xq = topo.space.quantum as u64;
+        let (x0, x1) = (bounds.x.0.as_u32() as u64, bounds.x.1.as_u32() as u64);
+        if x0 % xq != 0 || (x1 + 1) % xq != 0 {
+            return None;
+        }
+
+        let tq = topo.time.quantum as i64;
+        let origin = topo.time_origin.as_micros();
+        let (t0, t1) = (
+            bounds.t.0.as_micros().checked_sub(origin)?,
+            bounds.t.1.as_micros().checked_sub(origin)?,
+        );
+        if t0 < 0 || t1 < t0 || t0 % tq != 0 || (t1 + 1) % tq != 0 {
+            return None;
+        }
+        let t = (t0 / tq, t1 / tq);
+        if t.0 > u32::MAX as i64 {
+            return Some(vec![]);
+        }
+        let t = (t.0 as u64, t.1.min(u32::MAX as i64) as u64);
+
+        let max = SpaceQuantum::max_value(topo).inner() as u64;
+        let (x0, x1) = ((x0 / xq).min(max), (x1 / xq).min(max));
+        Some(Self::split_space(topo, (x0, x1), t))
+    }
+
+    /// Split a possibly wrapping space range into non-wrapping rects
+    fn split_space(topo: &Topology, (x0, x1): (u64, u64), t: (u64, u64)) -> Vec<Self> {
+        if x0 <= x1 {
+            vec![Self { x: (x0, x1), t }]
+        } else {
+            let max = SpaceQuantum::max_value(topo).inner() as u64;
+            vec![Self { x: (x0, max), t }, Self { x: (0, x1), t }]
+        }
+    }
+
+    /// The number of quanta in the rect
+    pub(crate) fn area(&self) -> u128 {
+        (self.x.1 - self.x.0 + 1) as u128 * (self.t.1 - self.t.0 + 1) as u128
+    }
+
+    pub(crate) fn contains_point(&self, x: u64, t: u64) -> bool {
+        self.x.0 <= x && x <= self.x.1 && self.t.0 <= t && t <= self.t.1
+    }
+
+    pub(crate) fn contains_rect(&self, other: &Self) -> bool {
+        self.x.0 <= other.x.0
+            && other.x.1 <= self.x.1
+            && self.t.0 <= other.t.0
+            && other.t.1 <= self.t.1
+    }
+
+    pub(crate) fn intersects(&self, other: &Self) -> bool {
+        self.x.0 <= other.x.1
+            && other.x.0 <= self.x.1
+            && self.t.0 <= other.t.1
+            && other.t.0 <= self.t.1
+    }
+
+    /// Split the rect in half along its longer dimension, preferring space.
+    /// A single quantum can't be split.
+    fn halves(&self) -> Option<[Self; 2]> {
+        let xlen = self.x.1 - self.x.0 + 1;
+        let tlen = self.t.1 - self.t.0 + 1;
+        if xlen >= tlen && xlen > 1 {
+            let mid = self.x.0 + xlen / 2;
+            Some([
+                Self {
+                    x: (self.x.0, mid - 1),
+                    t: self.t,
+                },
+                Self {
+                    x: (mid, self.x.1),
+                    t: self.t,
+                },
+            ])
+        } else if tlen > 1 {
+            let mid = self.t.0 + tlen / 2;
+            Some([
+                Self {
+                    x: self.x,
+                    t: (self.t.0, mid - 1),
+                },
+                Self {
+                    x: self.x,
+                    t: (mid, self.t.1),
+                },
+            ])
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "test_utils")]
+mod tests {
+    use num_traits::Zero;
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::{
+        op::OpRegion,
+        persistence::AccessOpStore,
+        spacetime::{SpaceSegment, TimeSegment},
+        test_utils::{OpData, OpStore},
+    };
+
+    fn ops_strategy() -> impl Strategy<Value = Vec<(u32, i64, u32)>> {
+        // Use a small range of times so that ops share quanta and regions
+        proptest::collection::vec((any::<u32>(), 0..2000i64, 1..1000u32), 0..300)
+    }
+
+    /// Build a tree and an op store with the same ops, spacing op times
+    /// `scale` microseconds apart
+    fn fill(topo: &Topology, scale: i64, ops: &[(u32, i64, u32)]) -> (RegionTree, OpStore) {
+        let mut tree = RegionTree::new(topo.clone());
+        let mut store = OpStore::new(topo.clone(), crate::spacetime::GossipParams::zero());
+        let ops: Vec<_> = ops
+            .iter()
+            .map(|&(x, t, size)| {
+                let t = Timestamp::from_micros(topo.time_origin.as_micros() + t * scale);
+                OpData::fake(x.into(), t, size)
+            })
+            .collect();
+        for op in ops.iter() {
+            tree.insert(op.loc, op.timestamp, op.region_data());
+        }
+        store.integrate_ops(ops.into_iter());
+        (tree, store)
+    }
+
+    #[test]
+    fn empty_tree_is_zero() {
+        let topo = Topology::standard_epoch_full();
+        let tree: RegionTree = RegionTree::new(topo.clone());
+        let coords = RegionCoords::new(SpaceSegment::new(20, 0), TimeSegment::new(32, 0));
+        assert!(tree.region_data(&coords).is_zero());
+        assert!(tree.query(&coords.to_bounds(&topo)).unwrap().is_zero());
+    }
+
+    #[test]
+    fn unaligned_bounds_are_not_answered() {
+        let topo = Topology::standard_epoch_full();
+        let tree: RegionTree = RegionTree::new(topo.clone());
+        let bounds =
+            RegionCoords::new(SpaceSegment::new(4, 3), TimeSegment::new(2, 5)).to_bounds(&topo);
+        assert!(tree.query(&bounds).is_some());
+        let unaligned = RegionBounds::new((bounds.x.0.as_u32() + 1, bounds.x.1.as_u32()), bounds.t);
+        assert!(tree.query(&unaligned).is_none());
+        let before_origin = RegionBounds::new(bounds.x, (Timestamp::from_micros(0), bounds.t.1));
+        assert!(tree.query(&before_origin).is_none());
+    }
+
+    proptest! {
+        #[test]
+        fn region_data_matches_op_store(
+            ops in ops_strategy(),
+            xp in 0u8..=20, xo in any::<u32>(),
+            tp in 0u8..8, to in 0u32..64,
+        ) {
+            let topo = Topology::standard_epoch_full();
+            let (tree, store) = fill(&topo, 1_000_000_000, &ops);
+            let coords = RegionCoords::new(
+                SpaceSegment::new(xp, xo % (1u32 << (20 - xp))),
+                TimeSegment::new(tp, to),
+            );
+            prop_assert_eq!(tree.region_data(&coords), store.query_region_data(&coords));
+            prop_assert_eq!(
+                tree.query(&coords.to_bounds(&topo)).unwrap(),
+                store.query_region_data(&coords)
+            );
+        }
+
+        #[test]
+        fn wrapping_query_matches_op_store(
+            ops in ops_strategy(),
+            x0 in any::<u32>(), x1 in any::<u32>(),
+            t0 in 0i64..100, tlen in 1i64..100,
+        ) {
+            let topo = Topology::unit_zero();
+            let (tree, store) = fill(&topo, 1000, &ops);
+            let bounds = RegionBounds::new(
+                (x0, x1),
+                (
+                    Timestamp::from_micros(t0 * 20_000),
+                    Timestamp::from_micros((t0 + tlen) * 20_000 - 1),
+                ),
+            );
+            let expected = store
+                .ops
+                .iter()
+                .filter(|o| bounds.contains(&o.loc, &o.timestamp))
+                .map(|o| o.region_data())
+                .fold(RegionData::zero(), |sum, d| sum + d);
+            prop_assert_eq!(tree.query(&bounds).unwrap(), expected);
+        }
+
+        #[test]
+        fn region_set_matches_op_store(ops in ops_strategy(), power in 12u8..=15, now in 0u32..8000) {
+            use crate::{arq::*, spacetime::TelescopingTimes};
+            let topo = Topology::standard_epoch_full();
+            let (tree, store) = fill(&topo, 1_000_000_000, &ops);
+            let arq_set = ArqBoundsSet::new(vec![Arq::new(power, 0.into(), 8.into())]);
+            let coords = RegionCoordSetLtcs::new(TelescopingTimes::new(now.into()), arq_set);
+            let expected = RegionSetLtcs::from_store(&store, coords.clone());
+            prop_assert_eq!(tree.region_set(coords), expected);
+        }
+    }
+}
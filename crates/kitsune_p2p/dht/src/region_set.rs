@@ -14,7 +14,9 @@ pub use ltcs::*;
 
 use crate::{error::GossipResult, spacetime::*};
 
-use crate::region::{Region, RegionBounds, RegionCoords, RegionData, RegionDataConstraints};
+use crate::region::{
+    QuantumRect, Region, RegionBounds, RegionCoords, RegionData, RegionDataConstraints,
+};
 
 /// The generic definition of a set of Regions.
 /// The current representation is very specific to our current algorithm,
@@ -49,15 +51,50 @@ impl<D: RegionDataConstraints> RegionSet<D> {
     ///
     /// This allows agents with differently computed RegionSets to still engage
     /// in gossip without needing to recompute regions.
-    pub fn query(&self, _bounds: &RegionBounds) -> ! {
-        unimplemented!("only implement after trying naive database-only approach")
+    ///
+    /// Returns None if the bounds are not quantum-aligned, or if no set of
+    /// nonoverlapping regions in this set exactly covers them.
+    pub fn query(&self, topo: &Topology, bounds: &RegionBounds) -> Option<D> {
+        let targets = QuantumRect::from_bounds(topo, bounds)?;
+        let target_area: u128 = targets.iter().map(|r| r.area()).sum();
+
+        // Try the largest regions first, so that as few as possible are used.
+        let mut candidates: Vec<(Vec<QuantumRect>, Region<D>)> = self
+            .regions()
+            .map(|r| (QuantumRect::from_coords(topo, &r.coords), r))
+            .filter(|(rects, _)| {
+                rects
+                    .iter()
+                    .all(|rect| targets.iter().any(|t| t.contains_rect(rect)))
+            })
+            .collect();
+        candidates.sort_by_key(|(rects, _)| {
+            std::cmp::Reverse(rects.iter().map(|r| r.area()).sum::<u128>())
+        });
+
+        let mut used: Vec<QuantumRect> = vec![];
+        let mut area = 0;
+        let mut sum = D::zero();
+        for (rects, region) in candidates {
+            if rects.iter().any(|r| used.iter().any(|u| u.intersects(r))) {
+                continue;
+            }
+            area += rects.iter().map(|r| r.area()).sum::<u128>();
+            used.extend(rects);
+            sum += region.data;
+        }
+        (area == target_area).then_some(sum)
     }
 
     /// In order for this RegionSet to be queryable, new data needs to be
     /// integrated into it to avoid needing to recompute it from the database
     /// on each query.
-    pub fn update(&self, _c: SpacetimeQuantumCoords, _d: D) -> ! {
-        unimplemented!("only implement after trying naive database-only approach")
+    ///
+    /// The data is added to every region which contains the coords.
+    pub fn update(&mut self, topo: &Topology, c: &SpacetimeQuantumCoords, d: D) {
+        match self {
+            Self::Ltcs(set) => set.update(topo, c, d),
+        }
     }
 
     /// Find a set of Regions which represents the intersection of the two
@@ -232,6 +269,51 @@ mod tests {
         assert_eq!(tt_b.segments()[0..nt], rset_b.coords.times.segments());
     }
 
+    #[test]
+    fn test_update_and_query() {
+        use num_traits::Zero;
+        let topo = Topology::unit_zero();
+        let arq = Arq::new(8, 0u32.into(), 4.into()).to_bounds(&topo);
+        let mut store = OpStore::new(topo.clone(), GossipParams::zero());
+        let ops = op_grid(&topo, &arq, 10..20);
+        store.integrate_ops(ops.clone().into_iter());
+
+        let times = TelescopingTimes::new(TimeQuantum::from(20));
+        let coords = RegionCoordSetLtcs::new(times, ArqBoundsSet::single(arq));
+        let expected: RegionSet = RegionSetLtcs::from_store(&store, coords.clone()).into();
+
+        // Updating an empty set with every op gives the same set as
+        // computing it from the store
+        let mut rset: RegionSet = coords
+            .into_region_set_infallible(|_| RegionData::zero())
+            .into();
+        for op in ops.iter() {
+            rset.update(&topo, &op.coords(&topo), op.region_data());
+        }
+        assert_eq!(
+            rset.regions().map(|r| r.data).collect::<Vec<_>>(),
+            expected.regions().map(|r| r.data).collect::<Vec<_>>(),
+        );
+
+        // Each region can be queried by its own bounds
+        for region in rset.regions() {
+            assert_eq!(
+                rset.query(&topo, &region.coords.to_bounds(&topo)),
+                Some(region.data)
+            );
+        }
+
+        // The whole set can be queried by composing every region
+        let end = times.segments().last().unwrap().timestamp_bounds(&topo).1;
+        let all = RegionBounds::new(arq.to_edge_locs(&topo), (Timestamp::from_micros(0), end));
+        let total = ops.iter().map(|op| op.region_data()).sum::<RegionData>();
+        assert_eq!(rset.query(&topo, &all), Some(total));
+
+        // Anything smaller than the smallest region can't be answered
+        let small = RegionBounds::new((0u32, 0u32), (Timestamp::from_micros(0), end));
+        assert_eq!(rset.query(&topo, &small), None);
+    }
+
     #[test]
     fn test_diff() {
         let topo = Topology::unit_zero();
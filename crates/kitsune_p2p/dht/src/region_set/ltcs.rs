@@ -171,6 +171,16 @@ impl<D: RegionDataConstraints> RegionSetLtcs<D> {
             })
     }
 
+    /// Add data to every region in the set which contains the coords
+    pub fn update(&mut self, topo: &Topology, c: &SpacetimeQuantumCoords, d: D) {
+        let Self { coords, data, .. } = self;
+        for ((ia, ix, it), region) in coords.region_coords_flat() {
+            if region.contains(topo, c) {
+                data[ia][ix][it] += d.clone();
+            }
+        }
+    }
+
     /// Reshape the two region sets so that both match, omitting or merging
     /// regions as needed
     pub fn rectify(&mut self, other: &mut Self) -> GossipResult<()> {
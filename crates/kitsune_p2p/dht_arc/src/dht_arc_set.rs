@@ -159,6 +159,28 @@ impl DhtArcSet {
         }
     }
 
+    /// The set of locations which are not in this set.
+    pub fn complement(&self) -> Self {
+        match self {
+            Self::Full => Self::new_empty(),
+            Self::Partial(intervals) => {
+                let mut gaps: Vec<(DhtLocation, DhtLocation)> = Vec::new();
+                let mut next = MIN as u64;
+                for i in intervals.iter() {
+                    let (lower, upper) = (i.lower().as_u32() as u64, i.upper().as_u32() as u64);
+                    if lower > next {
+                        gaps.push(((next as u32).into(), ((lower - 1) as u32).into()));
+                    }
+                    next = upper + 1;
+                }
+                if next <= MAX as u64 {
+                    gaps.push(((next as u32).into(), MAX.into()));
+                }
+                Self::Partial(gaps.to_interval_set()).normalized()
+            }
+        }
+    }
+
     pub fn size(&self) -> u32 {
         match self {
             Self::Full => u32::MAX,
@@ -233,3 +255,24 @@ fn fullness() {
         DhtArcSet::Full,
     );
 }
+
+#[test]
+fn complement() {
+    assert_eq!(DhtArcSet::new_full().complement(), DhtArcSet::new_empty());
+    assert_eq!(DhtArcSet::new_empty().complement(), DhtArcSet::Full);
+
+    let set = DhtArcSet::from(vec![(10, 20), (30, 40)]);
+    let complement = set.complement();
+    assert_eq!(
+        complement,
+        DhtArcSet::from(vec![(0, 9), (21, 29), (41, u32::MAX - 1)])
+    );
+    assert!(!complement.overlap(&set));
+    assert_eq!(complement.union(&set), DhtArcSet::Full);
+
+    // - A wrapping arc leaves a single gap in the middle.
+    assert_eq!(
+        DhtArcSet::from(vec![(u32::MAX - 10, 10)]).complement(),
+        DhtArcSet::from(vec![(11, u32::MAX - 11)])
+    );
+}
@@ -245,6 +245,10 @@ impl ShardedGossip {
         let timeout = self.gossip.tuning_params.implicit_timeout();
 
         self.bandwidth.outgoing_bytes(bytes).await;
+        self.gossip.inner.share_mut(|i, _| {
+            i.metrics.write().record_bytes_out(bytes);
+            Ok(())
+        })?;
 
         let con = match how.clone() {
             HowToConnect::Con(con, remote_url) => {
@@ -266,6 +270,10 @@ impl ShardedGossip {
         let (incoming, outgoing) = self.pop_queues()?;
         if let Some((con, remote_url, msg, bytes)) = incoming {
             self.bandwidth.incoming_bytes(bytes).await;
+            self.gossip.inner.share_mut(|i, _| {
+                i.metrics.write().record_bytes_in(bytes);
+                Ok(())
+            })?;
             let outgoing = match self.gossip.process_incoming(con.peer_cert(), msg).await {
                 Ok(r) => r,
                 Err(e) => {
@@ -137,6 +137,28 @@ pub struct Metrics {
     // Number of times we need to force initiate
     // the next round.
     force_initiates: u8,
+
+    /// Totals of the gossip in this space.
+    gossip_totals: GossipTotals,
+}
+
+/// Running totals of the gossip rounds and bytes of a space
+/// since it was joined.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GossipTotals {
+    /// Rounds initiated by us.
+    pub rounds_initiated: u64,
+    /// Rounds initiated by a remote node.
+    pub rounds_accepted: u64,
+    /// Rounds which completed successfully.
+    pub rounds_succeeded: u64,
+    /// Rounds which finished with an error.
+    pub rounds_failed: u64,
+    /// Bytes of gossip received.
+    pub bytes_in: u64,
+    /// Bytes of gossip sent.
+    pub bytes_out: u64,
 }
 
 /// Outcome of a gossip round.
@@ -236,6 +258,7 @@ impl Metrics {
 
         serde_json::json!({
             "aggExtrapCov": *self.agg_extrap_cov,
+            "gossipTotals": self.gossip_totals,
             "agents": agents,
         })
     }
@@ -295,6 +318,7 @@ impl Metrics {
         T: Into<AgentLike<'a>>,
         I: IntoIterator<Item = T>,
     {
+        self.gossip_totals.rounds_initiated += 1;
         for agent_info in remote_agent_list {
             let info = self
                 .map
@@ -311,6 +335,7 @@ impl Metrics {
         T: Into<AgentLike<'a>>,
         I: IntoIterator<Item = T>,
     {
+        self.gossip_totals.rounds_accepted += 1;
         for agent_info in remote_agent_list {
             let info = self
                 .map
@@ -327,6 +352,7 @@ impl Metrics {
         T: Into<AgentLike<'a>>,
         I: IntoIterator<Item = T>,
     {
+        self.gossip_totals.rounds_succeeded += 1;
         let mut should_dec_force_initiates = false;
 
         for agent_info in remote_agent_list {
@@ -353,6 +379,7 @@ impl Metrics {
         T: Into<AgentLike<'a>>,
         I: IntoIterator<Item = T>,
     {
+        self.gossip_totals.rounds_failed += 1;
         for agent_info in remote_agent_list {
             let info = self
                 .map
@@ -364,6 +391,21 @@ impl Metrics {
         }
     }
 
+    /// Record bytes of gossip received.
+    pub fn record_bytes_in(&mut self, bytes: usize) {
+        self.gossip_totals.bytes_in += bytes as u64;
+    }
+
+    /// Record bytes of gossip sent.
+    pub fn record_bytes_out(&mut self, bytes: usize) {
+        self.gossip_totals.bytes_out += bytes as u64;
+    }
+
+    /// The totals of the gossip in this space.
+    pub fn gossip_totals(&self) -> GossipTotals {
+        self.gossip_totals
+    }
+
     /// Record that we should force initiate the next few rounds.
     pub fn record_force_initiate(&mut self) {
         self.force_initiates = MAX_TRIGGERS;
@@ -658,4 +700,31 @@ mod tests {
         a5.push_n(1, 255);
         assert_eq!(1.0, *a5);
     }
+
+    #[test]
+    fn test_gossip_totals() {
+        let agents = vec![Arc::new(KitsuneAgent::new(vec![0; 36]))];
+        let mut metrics = Metrics::default();
+        metrics.record_initiate(&agents);
+        metrics.record_success(&agents);
+        metrics.record_remote_round(&agents);
+        metrics.record_error(&agents);
+        metrics.record_bytes_in(10);
+        metrics.record_bytes_out(20);
+        metrics.record_bytes_out(5);
+
+        let totals = GossipTotals {
+            rounds_initiated: 1,
+            rounds_accepted: 1,
+            rounds_succeeded: 1,
+            rounds_failed: 1,
+            bytes_in: 10,
+            bytes_out: 25,
+        };
+        assert_eq!(metrics.gossip_totals(), totals);
+        assert_eq!(
+            metrics.dump()["gossipTotals"],
+            serde_json::to_value(totals).unwrap()
+        );
+    }
 }
@@ -901,6 +901,22 @@ impl KitsuneP2pHandler for KitsuneP2pActor {
         .boxed()
         .into())
     }
+
+    fn handle_gossip_totals(
+        &mut self,
+        space: Arc<KitsuneSpace>,
+    ) -> KitsuneP2pHandlerResult<Option<crate::metrics::GossipTotals>> {
+        let space_sender = match self.spaces.get_mut(&space) {
+            None => return Ok(async move { Ok(None) }.boxed().into()),
+            Some(space) => space.get(),
+        };
+        Ok(async move {
+            let (space_sender, _) = space_sender.await;
+            space_sender.gossip_totals(space).await
+        }
+        .boxed()
+        .into())
+    }
 }
 
 #[cfg(any(test, feature = "test_utils"))]
@@ -1062,6 +1062,14 @@ impl KitsuneP2pHandler for Space {
         .boxed()
         .into())
     }
+
+    fn handle_gossip_totals(
+        &mut self,
+        _space: Arc<KitsuneSpace>,
+    ) -> KitsuneP2pHandlerResult<Option<crate::metrics::GossipTotals>> {
+        let totals = self.ro_inner.metrics.read().gossip_totals();
+        Ok(async move { Ok(Some(totals)) }.boxed().into())
+    }
 }
 
 pub(crate) struct SpaceReadOnlyInner {
@@ -141,5 +141,9 @@ ghost_actor::ghost_chan! {
         fn dump_network_metrics(
             space: KSpaceOpt,
         ) -> serde_json::Value;
+
+        /// The totals of the gossip in a space since it was joined,
+        /// or `None` if the space hasn't been joined.
+        fn gossip_totals(space: KSpace) -> Option<crate::metrics::GossipTotals>;
     }
 }
@@ -51,6 +51,7 @@ pub enum TestWasm {
     XSalsa20Poly1305,
     SerRegression,
     Sign,
+    Sleep,
     SysTime,
     TheIncredibleHalt,
     Update,
@@ -147,6 +148,7 @@ impl From<TestWasm> for ZomeName {
             TestWasm::XSalsa20Poly1305 => "x_salsa20_poly1305",
             TestWasm::SerRegression => "ser_regression",
             TestWasm::Sign => "sign",
+            TestWasm::Sleep => "sleep",
             TestWasm::SysTime => "sys_time",
             TestWasm::TheIncredibleHalt => "the_incredible_halt",
             TestWasm::Update => "update_entry",
@@ -228,6 +230,7 @@ impl From<TestWasm> for PathBuf {
                 "wasm32-unknown-unknown/release/test_wasm_ser_regression.wasm"
             }
             TestWasm::Sign => "wasm32-unknown-unknown/release/test_wasm_sign.wasm",
+            TestWasm::Sleep => "wasm32-unknown-unknown/release/test_wasm_sleep.wasm",
             TestWasm::SysTime => "wasm32-unknown-unknown/release/test_wasm_sys_time.wasm",
             TestWasm::TheIncredibleHalt => {
                 "wasm32-unknown-unknown/release/test_wasm_the_incredible_halt.wasm"
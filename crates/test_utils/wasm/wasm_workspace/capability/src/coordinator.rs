@@ -111,3 +111,18 @@ fn send_assigned_cap_claim(agent: AgentPubKey) -> ExternResult<()> {
     )?;
     Ok(())
 }
+
+#[hdk_extern]
+fn list_cap_grants(query: CapGrantQuery) -> ExternResult<Vec<CapGrantInfo>> {
+    capability_grants(query)
+}
+
+#[hdk_extern]
+fn list_cap_claims(query: CapClaimQuery) -> ExternResult<Vec<CapClaimInfo>> {
+    capability_claims(query)
+}
+
+#[hdk_extern]
+fn cap_info(_: ()) -> ExternResult<CapInfo> {
+    capability_info()
+}
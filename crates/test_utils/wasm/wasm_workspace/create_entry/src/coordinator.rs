@@ -69,6 +69,11 @@ fn get_post(hash: ActionHash) -> ExternResult<Option<Record>> {
     get(hash, GetOptions::content())
 }
 
+#[hdk_extern]
+fn get_validation_receipts(action_hash: ActionHash) -> ExternResult<Vec<ValidationReceiptSet>> {
+    hdk::prelude::get_validation_receipts(GetValidationReceiptsInput::new(action_hash))
+}
+
 #[hdk_extern]
 fn create_msg(_: ()) -> ExternResult<ActionHash> {
     use EntryTypes::*;
@@ -188,6 +188,20 @@ fn get_link_details_bidi(_: ()) -> ExternResult<Vec<LinkDetails>> {
     })
 }
 
+#[hdk_extern]
+fn count_links(_: ()) -> ExternResult<usize> {
+    hdk::prelude::count_links(LinkQuery::new(base()?, LinkTypes::SomeLinks.try_into()?))
+}
+
+#[hdk_extern]
+fn get_links_page(cursor: Option<LinkCursor>) -> ExternResult<Vec<Link>> {
+    let mut input = GetLinksInput::new(base()?, LinkTypes::SomeLinks.try_into()?, None).limit(2);
+    if let Some(cursor) = cursor {
+        input = input.cursor(cursor);
+    }
+    hdk::prelude::get_links_with(input)
+}
+
 #[hdk_extern]
 fn delete_all_links(_: ()) -> ExternResult<()> {
     for link in hdk::prelude::get_links(base()?, LinkTypes::SomeLinks, None)? {
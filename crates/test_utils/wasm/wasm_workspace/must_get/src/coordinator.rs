@@ -14,3 +14,10 @@ fn must_get_action(action_hash: ActionHash) -> ExternResult<SignedActionHashed>
 fn must_get_entry(entry_hash: EntryHash) -> ExternResult<EntryHashed> {
     hdk::prelude::must_get_entry(entry_hash)
 }
+
+#[hdk_extern]
+fn must_get_agent_activity(
+    input: MustGetAgentActivityInput,
+) -> ExternResult<Vec<SignedActionHashed>> {
+    hdk::prelude::must_get_agent_activity(input.author, input.chain_filter)
+}
@@ -0,0 +1,38 @@
+use hdk::prelude::*;
+
+#[hdk_extern]
+fn sleep(millis: u64) -> ExternResult<()> {
+    hdk::prelude::sleep(std::time::Duration::from_millis(millis))
+}
+
+/// Keep sleeping until the host stops us.
+#[hdk_extern]
+fn sleep_loop(millis: u64) -> ExternResult<()> {
+    loop {
+        hdk::prelude::sleep(std::time::Duration::from_millis(millis))?;
+    }
+}
+
+#[cfg(all(test, feature = "mock"))]
+pub mod test {
+    use hdk::prelude::*;
+
+    #[test]
+    fn sleep_smoke() {
+        let mut mock_hdk = hdk::prelude::MockHdkT::new();
+
+        mock_hdk
+            .expect_sleep()
+            .with(hdk::prelude::mockall::predicate::eq(
+                std::time::Duration::from_millis(5),
+            ))
+            .times(1)
+            .return_once(|_| Ok(()));
+
+        hdk::prelude::set_hdk(mock_hdk);
+
+        let result = super::sleep(5);
+
+        assert_eq!(result, Ok(()))
+    }
+}